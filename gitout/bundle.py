@@ -0,0 +1,215 @@
+"""``git bundle`` exports of the mirrors (``[bundle]``).
+
+A bundle is a single file holding every ref and object of a repository
+(``git bundle create --all``); it can be copied to tape or object storage as is and
+cloned from directly (``git clone repo.bundle``). After each successful sync the
+engine writes ``<bundle.directory>/<mirror path>.bundle`` — e.g.
+``bundles/github/clone/octocat/hello.bundle`` — into a temporary file, checks it with
+``git bundle verify`` and only then replaces the previous bundle. With
+``keep_mirrors = false`` the mirror is deleted once its bundle is written, so the
+destination holds bundles only (and every sync clones afresh). A bundle that cannot be
+written fails the repository in the run's outcome and keeps its mirror. LFS objects
+are not part of a bundle.
+
+With ``incremental = true`` each mirror gets a chain instead:
+``<bundle.directory>/<mirror path>.bundles/`` holds one full bundle followed by
+incremental ones carrying only the objects since the previous tips (``git bundle
+create --all --not <tips>``), so an offsite copy only ships deltas. ``manifest.json``
+ties the chain together: per bundle its file, kind, creation time, prerequisites (the
+previous tips) and the complete ref map after it. A sync that changed no ref adds
+nothing; one that only deleted or rewound refs adds an entry without a file. A new
+chain (and the old one's removal) starts after ``full_every`` incrementals, or when a
+previous tip is no longer in the mirror. To restore, fetch every bundle in order into
+an empty repository and set the refs of the last entry.
+"""
+
+from __future__ import annotations
+
+import json
+import logging
+from collections.abc import Awaitable, Callable
+from dataclasses import dataclass, field
+from datetime import datetime
+from pathlib import Path
+from typing import Any
+
+from gitout.ref_attic import parse_refs
+
+log = logging.getLogger(__name__)
+
+BUNDLE_SUFFIX = ".bundle"
+CHAIN_SUFFIX = ".bundles"
+BUNDLE_MANIFEST = "manifest.json"
+BUNDLE_MANIFEST_VERSION = 1
+# What ``git bundle create`` says about a repository without any ref.
+_EMPTY_BUNDLE = "refusing to create empty bundle"
+
+# (argv, cwd) -> (exit code, combined output)
+BundleGitRunner = Callable[[list[str], Path], Awaitable[tuple[int, str]]]
+
+
+def bundle_root(destination: Path, directory: str) -> Path:
+    """``bundle.directory``, relative to the destination unless absolute."""
+    return destination / Path(directory).expanduser()
+
+
+def bundle_path(root: Path, destination: Path, mirror: Path) -> Path:
+    """Where the bundle of ``mirror`` (a path under ``destination``) lives."""
+    relative = mirror.relative_to(destination)
+    return root / relative.parent / f"{relative.name}{BUNDLE_SUFFIX}"
+
+
+def chain_path(root: Path, destination: Path, mirror: Path) -> Path:
+    """The directory holding the incremental bundle chain of ``mirror``."""
+    relative = mirror.relative_to(destination)
+    return root / relative.parent / f"{relative.name}{CHAIN_SUFFIX}"
+
+
+async def write_bundle(
+    git: list[str], mirror: Path, target: Path, run_git: BundleGitRunner, *, verify: bool
+) -> bool:
+    """Bundle ``mirror`` into ``target``; False (old bundle kept) when git fails.
+
+    ``git`` is the git argv prefix (executable and ``-c`` options). A repository
+    without refs has nothing to bundle and counts as written.
+    """
+    target.parent.mkdir(parents=True, exist_ok=True)
+    partial = target.with_name(target.name + ".tmp")
+    create = [*git, "bundle", "create", "--quiet", str(partial), "--all"]
+    code, output = await run_git(create, mirror)
+    if code != 0:
+        partial.unlink(missing_ok=True)
+        if _EMPTY_BUNDLE in output.lower():
+            log.debug("Not bundling %s: it has no refs yet", mirror)
+            return True
+        log.warning("Bundling %s failed: %s", mirror, output.strip())
+        return False
+    if verify:
+        code, output = await run_git([*git, "bundle", "verify", "--quiet", str(partial)], mirror)
+        if code != 0:
+            partial.unlink(missing_ok=True)
+            log.warning("The new bundle of %s does not verify: %s", mirror, output.strip())
+            return False
+    partial.replace(target)
+    return True
+
+
+@dataclass(frozen=True)
+class ChainEntry:
+    file: str | None  # None: refs were only deleted or rewound, no new objects
+    kind: str  # "full" or "incremental"
+    created: str
+    prerequisites: list[str] = field(default_factory=list)
+    refs: dict[str, str] = field(default_factory=dict)
+
+    def to_dict(self) -> dict[str, Any]:
+        return {
+            "file": self.file,
+            "kind": self.kind,
+            "created": self.created,
+            "prerequisites": list(self.prerequisites),
+            "refs": dict(self.refs),
+        }
+
+    @staticmethod
+    def from_dict(data: dict[str, Any]) -> ChainEntry:
+        return ChainEntry(
+            file=data.get("file"),
+            kind=str(data.get("kind", "full")),
+            created=str(data.get("created", "")),
+            prerequisites=list(data.get("prerequisites") or []),
+            refs=dict(data.get("refs") or {}),
+        )
+
+
+def load_chain(directory: Path) -> list[ChainEntry]:
+    """The entries of ``directory``'s manifest; empty when missing or unreadable."""
+    try:
+        data = json.loads((directory / BUNDLE_MANIFEST).read_text())
+        return [ChainEntry.from_dict(entry) for entry in data.get("bundles", [])]
+    except (OSError, ValueError, AttributeError, TypeError):
+        return []
+
+
+def _save_chain(directory: Path, chain: list[ChainEntry]) -> None:
+    payload = {"version": BUNDLE_MANIFEST_VERSION, "bundles": [e.to_dict() for e in chain]}
+    partial = directory / (BUNDLE_MANIFEST + ".tmp")
+    partial.write_text(json.dumps(payload, indent=2))
+    partial.replace(directory / BUNDLE_MANIFEST)
+
+
+async def _create_chain_bundle(
+    git: list[str],
+    mirror: Path,
+    target: Path,
+    run_git: BundleGitRunner,
+    *,
+    basis: list[str],
+    verify: bool,
+) -> tuple[bool, str]:
+    """``(written, git output)``; an empty bundle is not written but not an error."""
+    partial = target.with_name(target.name + ".tmp")
+    create = [*git, "bundle", "create", "--quiet", str(partial), "--all"]
+    if basis:
+        create += ["--not", *basis]
+    code, output = await run_git(create, mirror)
+    if code == 0 and verify:
+        code, output = await run_git([*git, "bundle", "verify", "--quiet", str(partial)], mirror)
+    if code != 0:
+        partial.unlink(missing_ok=True)
+        return False, output
+    partial.replace(target)
+    return True, output
+
+
+async def write_incremental_bundle(
+    git: list[str],
+    mirror: Path,
+    directory: Path,
+    run_git: BundleGitRunner,
+    *,
+    verify: bool,
+    full_every: int,
+    now: datetime,
+) -> bool:
+    """Extend ``mirror``'s bundle chain in ``directory``; False when git fails."""
+    code, output = await run_git(
+        [*git, "for-each-ref", "--format=%(objectname) %(refname)"], mirror
+    )
+    if code != 0:
+        log.warning("Listing the refs of %s failed: %s", mirror, output.strip())
+        return False
+    refs = parse_refs(output)
+    chain = load_chain(directory)
+    if (chain and chain[-1].refs == refs) or (not chain and not refs):
+        return True
+    directory.mkdir(parents=True, exist_ok=True)
+    stamp = now.strftime("%Y%m%dT%H%M%S%fZ")
+    created = now.isoformat()
+
+    if chain and not (full_every > 0 and len(chain) - 1 >= full_every):
+        basis = sorted(set(chain[-1].refs.values()))
+        name = f"{stamp}-incremental{BUNDLE_SUFFIX}"
+        written, output = await _create_chain_bundle(
+            git, mirror, directory / name, run_git, basis=basis, verify=verify
+        )
+        if written or _EMPTY_BUNDLE in output.lower():
+            entry = ChainEntry(name if written else None, "incremental", created, basis, refs)
+            _save_chain(directory, [*chain, entry])
+            return True
+        log.info("Starting a new bundle chain for %s: %s", mirror, output.strip())
+
+    if not refs:
+        return True
+    name = f"{stamp}-full{BUNDLE_SUFFIX}"
+    written, output = await _create_chain_bundle(
+        git, mirror, directory / name, run_git, basis=[], verify=verify
+    )
+    if not written:
+        log.warning("Bundling %s failed: %s", mirror, output.strip())
+        return False
+    _save_chain(directory, [ChainEntry(name, "full", created, [], refs)])
+    for old in chain:
+        if old.file is not None:
+            (directory / old.file).unlink(missing_ok=True)
+    return True
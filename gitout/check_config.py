@@ -0,0 +1,147 @@
+"""Configuration checks without a sync (``gitout check-config CONFIG``).
+
+:func:`check_text` parses the document, reports every key :func:`gitout.config.parse`
+would silently ignore (typos such as ``startred``) as a warning and every
+:func:`gitout.config.validate` failure — URLs, patterns, ranges — as an error.
+
+:func:`check_online` (``--online``) then talks to the remotes: the GitHub token is
+resolved and checked against ``GET /user`` with the scopes the config needs, each
+``owner/repo`` in ``github.clone.repos`` and ``github.archive.repos`` must be readable
+with it, and every ``[git.repos]`` URL must answer ``git ls-remote`` with its login.
+The HTTP client, token checker and git runner are injectable.
+"""
+
+from __future__ import annotations
+
+import contextlib
+from collections.abc import Mapping
+from dataclasses import dataclass
+from pathlib import Path
+
+import httpx
+
+from gitout.config import Config, format_for_path, parse, unknown_keys, validate
+from gitout.engine import (
+    GitRunner,
+    configured_github_token,
+    credential_origin,
+    default_git_runner,
+    git_repo_password,
+    local_git,
+    resolve_github_token,
+    write_credentials,
+)
+from gitout.github_client import api_headers, rest_url
+from gitout.token_check import TokenChecker, TokenCheckError, check_token, required_scopes
+
+SEVERITIES = ("ok", "warning", "error")
+
+
+@dataclass(frozen=True)
+class Finding:
+    severity: str  # one of SEVERITIES
+    message: str
+
+
+def check_text(text: str, source: str) -> tuple[Config | None, list[Finding]]:
+    """The parsed config (None when it does not parse) and what is wrong with it."""
+    fmt = format_for_path(source)
+    try:
+        config = parse(text, fmt)
+        unknown = unknown_keys(text, fmt)
+    except (ValueError, TypeError, AttributeError) as exc:
+        return None, [Finding("error", f"Cannot parse {source}: {exc}")]
+    findings = [Finding("warning", f"Unknown key {key} (ignored)") for key in unknown]
+    findings += [Finding("error", error.message) for error in validate(config)]
+    return config, findings
+
+
+async def _check_github(
+    config: Config,
+    environ: Mapping[str, str],
+    token_checker: TokenChecker,
+    client: httpx.AsyncClient,
+) -> list[Finding]:
+    github = config.github
+    if github is None:
+        return []
+    try:
+        token = resolve_github_token(
+            configured_github_token(github), environ, api_url=github.api_url
+        )
+        login = await token_checker(token, required_scopes(github))
+    except (ValueError, TokenCheckError, httpx.HTTPError) as exc:
+        return [Finding("error", f"GitHub token: {exc}")]
+    findings = [Finding("ok", f"GitHub token belongs to {login}")]
+    headers = api_headers(
+        token, user_agent=config.http.user_agent, extra_headers=config.http.headers
+    )
+    names = dict.fromkeys(n for n in [*github.clone.repos, *github.archive.repos] if "/" in n)
+    for name in names:
+        try:
+            url = f"{rest_url(github.api_url)}/repos/{name}"
+            response = await client.get(url, headers=headers)
+        except httpx.HTTPError as exc:
+            findings.append(Finding("error", f"GitHub repository {name}: {exc}"))
+            continue
+        if response.is_success:
+            findings.append(Finding("ok", f"GitHub repository {name} is accessible"))
+        else:
+            reason = f"not accessible (HTTP {response.status_code})"
+            findings.append(Finding("error", f"GitHub repository {name} is {reason}"))
+    return findings
+
+
+async def _check_git_repo(
+    name: str,
+    url: str,
+    config: Config,
+    environ: Mapping[str, str],
+    git_runner: GitRunner,
+    timeout_seconds: float,
+) -> Finding:
+    # core.askPass=true answers any prompt with nothing instead of waiting on the terminal.
+    options = ["-c", "core.askPass=true"]
+    credentials: Path | None = None
+    login = config.git.credentials.get(name)
+    try:
+        if login is not None:
+            password = git_repo_password(name, login, environ)
+            credentials = write_credentials(login.username, password, credential_origin(url))
+            helper = f"credential.helper=store --file={credentials}"
+            options += ["-c", "credential.helper=", "-c", helper]
+        argv = local_git(*options, "ls-remote", "--heads", "--", url)
+        code, output = await git_runner(argv, Path.cwd(), timeout_seconds)
+    except (ValueError, OSError, RuntimeError) as exc:
+        return Finding("error", f"git.repos {name}: {exc}")
+    finally:
+        if credentials is not None:
+            with contextlib.suppress(OSError):
+                credentials.unlink()
+    if code != 0:
+        detail = output.strip().splitlines()
+        return Finding("error", f"git.repos {name}: {detail[-1] if detail else f'exit {code}'}")
+    return Finding("ok", f"git.repos {name} is reachable")
+
+
+async def check_online(
+    config: Config,
+    environ: Mapping[str, str],
+    *,
+    token_checker: TokenChecker = check_token,
+    client: httpx.AsyncClient | None = None,
+    git_runner: GitRunner = default_git_runner,
+    timeout_seconds: float = 60.0,
+) -> list[Finding]:
+    """What the remotes say about the token and each configured repository."""
+    http = client or httpx.AsyncClient(timeout=30.0)
+    try:
+        findings = await _check_github(config, environ, token_checker, http)
+    finally:
+        if client is None:
+            await http.aclose()
+    for name, url in config.git.repos.items():
+        findings.append(
+            await _check_git_repo(name, url, config, environ, git_runner, timeout_seconds)
+        )
+    return findings
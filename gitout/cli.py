@@ -1,9 +1,20 @@
 """Typer CLI entry point (port of main.kt + SearchCommand + IndexCommand).
 
 Subcommands:
-  gitout sync CONFIG DESTINATION [--dry-run]   back up repositories
+  gitout sync CONFIG DESTINATION [--dry-run]   back up repositories (CONFIG: path/URL)
+  gitout tui CONFIG DESTINATION                sync with a live terminal dashboard
   gitout search QUERY CONFIG DESTINATION       semantic search
   gitout index CONFIG DESTINATION              (re)index for semantic search
+  gitout export FORMAT DESTINATION             cgit/gitolite/manifest config for a backup
+  gitout check-config CONFIG [--online]        validate a config without syncing
+  gitout doctor [CONFIG] [DESTINATION]         diagnose git, TLS, network, token and storage
+  gitout man [-o FILE]                         gitout(1) man page from the CLI and config model
+  gitout verify DESTINATION                    git fsck every mirror in a backup
+  gitout restore SOURCE URL                    push a backed-up repository to a new remote
+  gitout serve DESTINATION                     read-only smart-HTTP git server over a backup
+  gitout unlock CONFIG                         release repository locks left by migrations
+  gitout state export CONFIG DESTINATION FILE  export state files to portable JSON
+  gitout state import DESTINATION FILE         restore exported state files
 
 Note: unlike the Kotlin CLI (``gitout CONFIG DEST``), sync is an explicit
 subcommand here — Typer/Click cannot mix positional root args with subcommands.
@@ -12,51 +23,259 @@ subcommand here — Typer/Click cannot mix positional root args with subcommands
 from __future__ import annotations
 
 import asyncio
+import dataclasses
+import functools
+import json
 import logging
 import os
 import sys
-from datetime import datetime
+from datetime import date, datetime
 from pathlib import Path
+from typing import Any
 
+import httpx
 import typer
 
 from gitout import __version__
 from gitout import config as config_module
-from gitout.cron import run_cron
-from gitout.engine import Engine, dry_run_line
+from gitout.check_config import check_online, check_text
+from gitout.cron import format_duration, parse_duration, run_cron, run_interval
+from gitout.device_flow import (
+    DEFAULT_SCOPES,
+    GITHUB_WEB_URL,
+    DeviceCode,
+    DeviceFlowError,
+    device_login,
+)
+from gitout.doctor import diagnose
+from gitout.engine import (
+    Engine,
+    RepoLoader,
+    SyncOutcome,
+    auto_worker_count,
+    configured_github_token,
+    dry_run_line,
+    git_repo_password,
+    resolve_github_tokens,
+)
+from gitout.estimate import estimate_run, format_estimate
+from gitout.fanout import FanoutResult
+from gitout.frontends import FRONTEND_FORMATS, render
 from gitout.gemini_key import resolve_gemini_api_key
-from gitout.github_client import load_repositories
-from gitout.health_check import DEFAULT_HEALTHCHECK_HOST, HealthCheckService
+from gitout.github_app import load_installation_repositories
+from gitout.github_archive import MigrationArchiver
+from gitout.github_client import TokenPool, load_repositories
+from gitout.health_check import DEFAULT_HEALTHCHECK_HOST, HealthCheck, HealthCheckService
+from gitout.keyring_store import (
+    PROVIDERS,
+    KeyringUnavailableError,
+    delete_secret,
+    set_secret,
+)
+from gitout.log_format import (
+    DEFAULT_LOG_BACKUPS,
+    HTTP_URL_FILTER,
+    LOG_FORMATS,
+    LOG_LEVELS,
+    TRACE_LOGGERS,
+    RepositoryBufferingHandler,
+    file_handler,
+    formatter_for,
+    resolve_levels,
+)
+from gitout.manpage import render_manpage
+from gitout.push_mirror import push_password
+from gitout.pushgateway import Pushgateway
+from gitout.quarantine import quarantine
+from gitout.remote_config import RemoteConfigError, load_config_text
+from gitout.resources import end_cycle, parse_size
+from gitout.restore import RestoreError, Restorer
+from gitout.retry import BackoffStrategy, RetryPolicy
 from gitout.search.gemini import GeminiEmbeddingClient
 from gitout.search.index_service import SearchIndexService
 from gitout.search.qdrant import QdrantClient
 from gitout.search.readme_extractor import ReadmeExtractor
+from gitout.serve import DEFAULT_BIND, GitHttpServer, parse_bind
+from gitout.ssh import is_ssh_url
+from gitout.state_export import export_state, import_state
 from gitout.state_tracker import RepositoryStateTracker
 from gitout.telegram import TelegramNotificationService
+from gitout.token_check import (
+    TokenChecker,
+    TokenCheckError,
+    check_token,
+    recommended_scopes,
+)
+from gitout.verify import run_verification_pass, verify_destination
+
+# Exit codes of sync and tui (2 is Click's usage error, also used for invalid flags).
+EXIT_PARTIAL_FAILURE = 1  # some repositories (or fan-out copies) failed
+EXIT_USAGE_ERROR = 2
+EXIT_CONFIG_ERROR = 3  # the configuration cannot be loaded, is invalid or lacks what a flag names
+EXIT_TOTAL_FAILURE = 4  # the run could not start, or no repository synced
+
+COLOR_MODES = ("auto", "always", "never")
+_STATUS_COLORS = {"OK": "green", "SKIPPED": "yellow", "FAILED": "red"}
 
 app = typer.Typer(
     add_completion=False,
     help="Back up Git repositories from GitHub or any git host.",
+    # Locals include the environment (tokens, passwords); never print them.
+    pretty_exceptions_show_locals=False,
 )
 
+state_app = typer.Typer(help="Export or import the destination's state files.")
+app.add_typer(state_app, name="state")
+auth_app = typer.Typer(help="Store credentials in the OS keyring.")
+app.add_typer(auth_app, name="auth")
 
-def _configure_logging(verbose: int, quiet: bool) -> None:
-    """Configure the root logger for the application.
 
-    Level mapping: quiet -> WARNING; default -> INFO; verbose>=1 -> DEBUG.
-    quiet wins over verbose when both are set.
+def _check_passwords(cfg: config_module.Config) -> None:
+    """Resolve the configured password sources up front; ValueError names a missing one."""
+    for name, login in cfg.git.credentials.items():
+        git_repo_password(name, login, os.environ)
+    push = cfg.push_mirror
+    if push is not None and push.username and not is_ssh_url(push.url):
+        push_password(push, os.environ)
+
+
+def _parse_config_file(path: Path) -> config_module.Config:
+    """Parse a local config file in the format its extension names."""
+    return config_module.parse(path.read_text(), config_module.format_for_path(str(path)))
+
+
+def _load_sync_config(
+    source: str, sha256: str | None, verify_signature: bool
+) -> config_module.Config:
+    """Load, parse and validate a sync CONFIG source; exit ``EXIT_CONFIG_ERROR`` with the reason."""
+    try:
+        config_text = load_config_text(
+            source, environ=os.environ, sha256=sha256, verify_signature=verify_signature
+        )
+    except RemoteConfigError as exc:
+        typer.echo(str(exc), err=True)
+        raise typer.Exit(code=EXIT_CONFIG_ERROR) from exc
+    try:
+        cfg = config_module.parse(config_text, config_module.format_for_path(source))
+    except ValueError as exc:
+        typer.echo(f"Invalid configuration: {exc}", err=True)
+        raise typer.Exit(code=EXIT_CONFIG_ERROR) from exc
+    errors = config_module.validate(cfg)
+    if errors:
+        typer.echo("Configuration validation failed:", err=True)
+        for error in errors:
+            typer.echo(f"  - {error.message}", err=True)
+        raise typer.Exit(code=EXIT_CONFIG_ERROR)
+    return cfg
+
+
+def _repo_loader(cfg: config_module.Config) -> RepoLoader:
+    """``load_repositories`` bound to the config's HTTP and discovery options.
+
+    With ``[github.app]`` the installation's repositories are listed instead.
     """
-    if quiet:
-        level = logging.WARNING
-    elif verbose >= 1:
-        level = logging.DEBUG
-    else:
-        level = logging.INFO
-    logging.basicConfig(
-        format="%(asctime)s %(levelname)s %(name)s: %(message)s",
-        stream=sys.stderr,
+    loader_options: dict[str, Any] = {}
+    if cfg.http.user_agent is not None or cfg.http.headers:
+        loader_options.update(user_agent=cfg.http.user_agent, extra_headers=cfg.http.headers)
+    if cfg.github is not None and cfg.github.api_url != config_module.GitHubConfig.api_url:
+        loader_options["api_url"] = cfg.github.api_url
+    if cfg.github is not None and cfg.github.app is not None:
+        return functools.partial(load_installation_repositories, **loader_options)
+    if cfg.github is not None and cfg.github.clone.gists_starred:
+        loader_options["starred_gists"] = True
+    if not loader_options:
+        return load_repositories
+    return functools.partial(load_repositories, **loader_options)
+
+
+def _token_checker(cfg: config_module.Config) -> TokenChecker:
+    """``check_token`` bound to the config's API URL and HTTP options."""
+    options: dict[str, Any] = {
+        "user_agent": cfg.http.user_agent,
+        "extra_headers": cfg.http.headers,
+    }
+    if cfg.github is not None:
+        options["api_url"] = cfg.github.api_url
+        options["recommended"] = recommended_scopes(cfg.github)
+    return functools.partial(check_token, **options)
+
+
+def _runtime_timeout(cfg: config_module.Config, timeout: float | None) -> float:
+    """``--timeout``, else ``runtime.timeout``, else 600 seconds."""
+    if timeout is not None:
+        return timeout
+    return parse_duration(cfg.runtime.timeout) if cfg.runtime.timeout else 600.0
+
+
+def _retry_policy(cfg: config_module.Config) -> RetryPolicy:
+    """The per-repository retry policy of ``[runtime]``."""
+    runtime = cfg.runtime
+    return RetryPolicy(
+        max_attempts=runtime.retry_attempts,
+        base_delay_ms=round(parse_duration(runtime.retry_delay) * 1000),
+        backoff_strategy=BackoffStrategy[runtime.retry_backoff.upper()],
     )
+
+
+def _configure_logging(
+    verbose: int,
+    quiet: bool,
+    log_format: str = "text",
+    log_level: str | None = None,
+    log_file: logging.Handler | None = None,
+    *,
+    per_repository: bool = False,
+) -> None:
+    """Configure the root logger for the application.
+
+    Levels come from :func:`gitout.log_format.resolve_levels` (``--log-level`` wins,
+    then quiet -> WARNING; default -> INFO; -v -> DEBUG; -vv -> DEBUG plus git and HTTP
+    traces). ``log_format`` is one of :data:`gitout.log_format.LOG_FORMATS`; ``log_file``
+    (``--log-file``) receives every line as well. With ``per_repository`` (``sync``; not
+    the dashboard) each repository's lines are written together when it finishes.
+    """
+    level, trace_level = resolve_levels(verbose, quiet, log_level)
+
+    def prepared(handler: logging.Handler) -> logging.Handler:
+        handler.setFormatter(formatter_for(log_format))
+        return RepositoryBufferingHandler(handler) if per_repository else handler
+
+    logging.basicConfig(handlers=[prepared(logging.StreamHandler(sys.stderr))])
+    if log_file is not None:
+        logging.getLogger().addHandler(prepared(log_file))
     logging.getLogger().setLevel(level)
+    for name in TRACE_LOGGERS:
+        logging.getLogger(name).setLevel(trace_level)
+    logging.getLogger("httpx").addFilter(HTTP_URL_FILTER)
+
+
+def _duration(value: str) -> float:
+    """``--interval``/``--timeout`` values: seconds or e.g. ``10m``, ``1h30m``, ``1d``."""
+    try:
+        return parse_duration(value)
+    except ValueError as exc:
+        raise typer.BadParameter(str(exc)) from exc
+
+
+def _size(value: str) -> int:
+    """``--log-max-size`` values such as ``10M`` or ``1G``."""
+    try:
+        return parse_size(value)
+    except ValueError as exc:
+        raise typer.BadParameter(str(exc)) from exc
+
+
+def _workers(value: str) -> int:
+    """``--workers`` values: a count, or ``auto``/``0`` for :func:`auto_worker_count`."""
+    if str(value).strip().lower() == "auto":
+        return 0
+    try:
+        count = int(value)
+    except ValueError as exc:
+        raise typer.BadParameter(f"expected a number or auto, got {value!r}") from exc
+    if count < 0:
+        raise typer.BadParameter(f"must not be negative, got {count}")
+    return count
 
 
 def _version_callback(value: bool) -> None:
@@ -76,16 +295,32 @@ def _root(
 
 @app.command()
 def sync(
-    config: Path = typer.Argument(..., exists=True, dir_okay=False, help="Configuration TOML"),
-    destination: Path = typer.Argument(..., help="Backup directory"),
+    config: str = typer.Argument(
+        ...,
+        envvar="GITOUT_CONFIG",
+        help="Configuration (TOML, JSON or YAML): a path, an https:// URL, "
+        "or git+<url>#<path>[@<ref>]",
+    ),
+    destination: Path = typer.Argument(..., envvar="GITOUT_DEST", help="Backup directory"),
     dry_run: bool = typer.Option(
         False, "--dry-run", "-n", envvar="GITOUT_DRY_RUN", help="Print actions, do not run them"
     ),
     workers: int | None = typer.Option(
-        None, "--workers", envvar="GITOUT_WORKERS", help="Parallel worker count"
+        None,
+        "--workers",
+        envvar="GITOUT_WORKERS",
+        parser=_workers,
+        metavar="N|auto",
+        help="Parallel worker count; auto (or 0) picks one from the CPU and repository counts",
     ),
-    timeout: float = typer.Option(
-        600.0, "--timeout", help="Per-repository git timeout in seconds"
+    timeout: float | None = typer.Option(
+        None,
+        "--timeout",
+        envvar="GITOUT_TIMEOUT",
+        parser=_duration,
+        metavar="DURATION",
+        help="Per-repository git timeout (seconds, or e.g. 10m, 1h30m; default runtime.timeout "
+        "or 600)",
     ),
     hc_id: str | None = typer.Option(
         None, "--hc-id", envvar="GITOUT_HC_ID", help="Healthchecks.io check id to ping"
@@ -93,23 +328,279 @@ def sync(
     hc_host: str = typer.Option(
         DEFAULT_HEALTHCHECK_HOST, "--hc-host", envvar="GITOUT_HC_HOST", help="Healthchecks.io host"
     ),
+    pushgateway_url: str | None = typer.Option(
+        None,
+        "--pushgateway",
+        envvar="GITOUT_PUSHGATEWAY",
+        metavar="URL",
+        help="Push the run's metrics to this Prometheus Pushgateway (default "
+        "metrics.pushgateway_url)",
+    ),
     cron: str | None = typer.Option(
         None, "--cron", envvar="GITOUT_CRON", help="Run forever, syncing on this cron schedule"
     ),
+    interval: float | None = typer.Option(
+        None,
+        "--interval",
+        envvar="GITOUT_INTERVAL",
+        parser=_duration,
+        metavar="DURATION",
+        help="Run forever, syncing now and then again this long after each sync (e.g. 1h30m, 1d)",
+    ),
+    fail_fast: bool = typer.Option(
+        False,
+        "--fail-fast",
+        envvar="GITOUT_FAIL_FAST",
+        help="Stop at the first repository that fails and exit 1",
+    ),
+    report: Path | None = typer.Option(
+        None,
+        "--report",
+        envvar="GITOUT_REPORT",
+        metavar="PATH",
+        help="Also write each run's JSON report to PATH (replaced every run)",
+    ),
+    once: bool = typer.Option(
+        False,
+        "--once",
+        envvar="GITOUT_ONCE",
+        help="Sync once even when the config sets runtime.interval or runtime.cron",
+    ),
     verbose: int = typer.Option(
-        0, "--verbose", "-v", count=True, help="Increase log verbosity (-v for debug)"
+        0,
+        "--verbose",
+        "-v",
+        count=True,
+        envvar="GITOUT_VERBOSE",
+        help="Increase log verbosity (-v for debug, -vv to also trace git commands and API "
+        "requests)",
+    ),
+    quiet: bool = typer.Option(
+        False,
+        "--quiet",
+        "-q",
+        envvar="GITOUT_QUIET",
+        help="Only log warnings and errors, and print only failures and the final summary",
+    ),
+    log_level: str | None = typer.Option(
+        None,
+        "--log-level",
+        envvar="GITOUT_LOG_LEVEL",
+        metavar="LEVEL",
+        help=f"Log level, overriding -v/-q: {', '.join(LOG_LEVELS)}",
+    ),
+    color: str = typer.Option(
+        "auto",
+        "--color",
+        envvar="GITOUT_COLOR",
+        metavar="auto|always|never",
+        help="Color the per-repository OK/SKIPPED/FAILED lines (auto: only on a terminal)",
+    ),
+    log_format: str = typer.Option(
+        "text",
+        "--log-format",
+        envvar="GITOUT_LOG_FORMAT",
+        metavar="text|json",
+        help="Log lines as readable text or as one JSON object per line",
+    ),
+    log_file: Path | None = typer.Option(
+        None,
+        "--log-file",
+        envvar="GITOUT_LOG_FILE",
+        dir_okay=False,
+        help="Also append log lines to this file, rotating it (see --log-max-size)",
+    ),
+    log_max_size: int | None = typer.Option(
+        None,
+        "--log-max-size",
+        envvar="GITOUT_LOG_MAX_SIZE",
+        parser=_size,
+        metavar="SIZE",
+        help="Rotate --log-file when it reaches this size (default 10M)",
+    ),
+    log_rotate_every: float | None = typer.Option(
+        None,
+        "--log-rotate-every",
+        envvar="GITOUT_LOG_ROTATE_EVERY",
+        parser=_duration,
+        metavar="DURATION",
+        help="Rotate --log-file after this long (e.g. 1d) instead of by size",
+    ),
+    log_backups: int = typer.Option(
+        DEFAULT_LOG_BACKUPS,
+        "--log-backups",
+        envvar="GITOUT_LOG_BACKUPS",
+        min=0,
+        help="Rotated log files to keep",
+    ),
+    max_rss: str | None = typer.Option(
+        None,
+        "--max-rss",
+        envvar="GITOUT_MAX_RSS",
+        help="With --cron/--interval, warn when resident memory exceeds this size after a cycle "
+        "(e.g. 512M)",
+    ),
+    config_sha256: str | None = typer.Option(
+        None,
+        "--config-sha256",
+        envvar="GITOUT_CONFIG_SHA256",
+        help="Refuse to run unless the configuration has this SHA-256 digest",
+    ),
+    config_verify_signature: bool = typer.Option(
+        False,
+        "--config-verify-signature",
+        envvar="GITOUT_CONFIG_VERIFY_SIGNATURE",
+        help="Require a valid signature on the commit of a git+ configuration source",
+    ),
+    group: list[str] = typer.Option(
+        [],
+        "--group",
+        "-g",
+        envvar="GITOUT_GROUP",
+        help="Only sync repositories in this [groups] entry (repeatable)",
+    ),
+    only: str | None = typer.Option(
+        None,
+        "--only",
+        envvar="GITOUT_ONLY",
+        metavar="REPO",
+        help="Only sync this repository (owner/repo, gist:<id> or a [git.repos] name)",
+    ),
+    include: list[str] = typer.Option(
+        [],
+        "--filter",
+        envvar="GITOUT_FILTER",
+        metavar="PATTERN",
+        help="Only sync repositories matching this glob or re:<regex> (repeatable)",
+    ),
+    exclude: list[str] = typer.Option(
+        [],
+        "--exclude",
+        envvar="GITOUT_EXCLUDE",
+        metavar="PATTERN",
+        help="Skip repositories matching this glob or re:<regex> (repeatable)",
+    ),
+    owned: bool | None = typer.Option(
+        None,
+        "--owned/--no-owned",
+        envvar="GITOUT_OWNED",
+        help="Override github.clone.owned for this run",
+    ),
+    starred: bool | None = typer.Option(
+        None,
+        "--starred/--no-starred",
+        envvar="GITOUT_STARRED",
+        help="Override github.clone.starred for this run",
+    ),
+    watched: bool | None = typer.Option(
+        None,
+        "--watched/--no-watched",
+        envvar="GITOUT_WATCHED",
+        help="Override github.clone.watched for this run",
+    ),
+    gists: bool | None = typer.Option(
+        None,
+        "--gists/--no-gists",
+        envvar="GITOUT_GISTS",
+        help="Override github.clone.gists for this run",
+    ),
+    verify_cron: str | None = typer.Option(
+        None,
+        "--verify-cron",
+        envvar="GITOUT_VERIFY_CRON",
+        help="With --cron/--interval, also run a full integrity verification on this schedule",
+    ),
+    also: list[Path] = typer.Option(
+        [],
+        "--also",
+        envvar="GITOUT_ALSO",
+        help="After the sync, copy the destination to this directory too (repeatable)",
     ),
-    quiet: bool = typer.Option(False, "--quiet", "-q", help="Only log warnings and errors"),
 ) -> None:
     """Back up repositories described by the config into the destination."""
-    _configure_logging(verbose, quiet)
-    cfg = config_module.parse(config.read_text())
-    errors = config_module.validate(cfg)
-    if errors:
-        typer.echo("Configuration validation failed:", err=True)
-        for error in errors:
-            typer.echo(f"  - {error.message}", err=True)
-        raise typer.Exit(code=1)
+    if log_format not in LOG_FORMATS:
+        typer.echo(f"--log-format must be one of {', '.join(LOG_FORMATS)}", err=True)
+        raise typer.Exit(code=EXIT_USAGE_ERROR)
+    if pushgateway_url is not None and not pushgateway_url.startswith(("https://", "http://")):
+        typer.echo("--pushgateway must be an http(s) URL", err=True)
+        raise typer.Exit(code=EXIT_USAGE_ERROR)
+    if color not in COLOR_MODES:
+        typer.echo(f"--color must be one of {', '.join(COLOR_MODES)}", err=True)
+        raise typer.Exit(code=EXIT_USAGE_ERROR)
+    if log_level is not None and log_level.lower() not in LOG_LEVELS:
+        typer.echo(f"--log-level must be one of {', '.join(LOG_LEVELS)}", err=True)
+        raise typer.Exit(code=EXIT_USAGE_ERROR)
+    handler = None
+    if log_file is not None:
+        try:
+            handler = file_handler(
+                log_file,
+                max_bytes=log_max_size,
+                rotate_every_seconds=log_rotate_every,
+                backups=log_backups,
+            )
+        except OSError as exc:
+            typer.echo(f"Cannot open --log-file {log_file}: {exc}", err=True)
+            raise typer.Exit(code=EXIT_USAGE_ERROR) from exc
+    _configure_logging(
+        verbose,
+        quiet,
+        log_format,
+        log_level.lower() if log_level else None,
+        handler,
+        per_repository=True,
+    )
+    if cron and interval:
+        typer.echo("--cron and --interval are mutually exclusive", err=True)
+        raise typer.Exit(code=EXIT_USAGE_ERROR)
+    try:
+        max_rss_bytes = parse_size(max_rss) if max_rss is not None else None
+    except ValueError as exc:
+        typer.echo(str(exc), err=True)
+        raise typer.Exit(code=EXIT_USAGE_ERROR) from exc
+    cfg = _load_sync_config(config, config_sha256, config_verify_signature)
+    runtime = cfg.runtime
+    if not (cron or interval or once or dry_run):
+        cron = runtime.cron
+        interval = parse_duration(runtime.interval) if runtime.interval else None
+    if verify_cron and not (cron or interval):
+        typer.echo("--verify-cron needs --cron or --interval", err=True)
+        raise typer.Exit(code=EXIT_USAGE_ERROR)
+    if workers is None:
+        workers = runtime.workers
+    timeout = _runtime_timeout(cfg, timeout)
+    clone_overrides = {
+        name: value
+        for name, value in {
+            "owned": owned,
+            "starred": starred,
+            "watched": watched,
+            "gists": gists,
+        }.items()
+        if value is not None
+    }
+    if clone_overrides:
+        if cfg.github is None:
+            flags = " ".join(f"--{'' if v else 'no-'}{name}" for name, v in clone_overrides.items())
+            typer.echo(f"{flags}: the config has no [github] section", err=True)
+            raise typer.Exit(code=EXIT_CONFIG_ERROR)
+        clone = dataclasses.replace(cfg.github.clone, **clone_overrides)
+        cfg = dataclasses.replace(cfg, github=dataclasses.replace(cfg.github, clone=clone))
+    unknown_groups = sorted(set(group) - set(cfg.groups))
+    if unknown_groups:
+        typer.echo(f"Unknown group(s): {', '.join(unknown_groups)}", err=True)
+        raise typer.Exit(code=EXIT_CONFIG_ERROR)
+    invalid_patterns = [p for p in [*include, *exclude] if not config_module.is_valid_pattern(p)]
+    if invalid_patterns:
+        typer.echo(f"Invalid pattern(s): {', '.join(invalid_patterns)}", err=True)
+        raise typer.Exit(code=EXIT_USAGE_ERROR)
+
+    if not dry_run:
+        try:
+            _check_passwords(cfg)
+        except ValueError as exc:
+            typer.echo(str(exc), err=True)
+            raise typer.Exit(code=EXIT_CONFIG_ERROR) from exc
 
     search_service: SearchIndexService | None = None
     if cfg.search.enabled and not dry_run:
@@ -128,7 +619,13 @@ def sync(
                 err=True,
             )
 
-    health_check = HealthCheckService(hc_host).new_check(hc_id) if hc_id else None
+    health_check = None
+    if hc_id:
+        health_check = HealthCheckService(hc_host).new_check(hc_id)
+    elif cfg.health_check.ping_url:
+        health_check = HealthCheck(cfg.health_check.ping_url.rstrip("/"), None)
+    gateway_url = pushgateway_url or cfg.metrics.pushgateway_url
+    pushgateway = Pushgateway(gateway_url, cfg.metrics.pushgateway_job) if gateway_url else None
 
     telegram = (
         TelegramNotificationService(
@@ -141,43 +638,241 @@ def sync(
         else None
     )
 
-    engine = Engine(
-        config=cfg,
-        destination=destination,
-        repo_loader=load_repositories,
-        environ=os.environ,
-        workers=workers,
-        timeout_seconds=timeout,
-        search_index_service=search_service,
-        health_check=health_check,
-        telegram=telegram,
-    )
+    repo_loader = _repo_loader(cfg)
 
-    if cron:
+    def build_engine() -> Engine:
+        return Engine(
+            config=cfg,
+            destination=destination,
+            repo_loader=repo_loader,
+            token_checker=_token_checker(cfg),
+            environ=os.environ,
+            workers=workers,
+            timeout_seconds=timeout,
+            retry_policy=_retry_policy(cfg),
+            search_index_service=search_service,
+            health_check=health_check,
+            pushgateway=pushgateway,
+            telegram=telegram,
+            groups=frozenset(group),
+            only=only,
+            include_patterns=tuple(include),
+            exclude_patterns=tuple(exclude),
+            extra_destinations=tuple(also),
+            fail_fast=fail_fast,
+            report_path=report,
+        )
 
-        async def scheduled() -> None:
+    if cron or interval:
+
+        async def run_cycle() -> None:
+            # A fresh engine per cycle: nothing discovered or built during one sync
+            # (repo lists, collaborators, tasks) outlives it.
             try:
-                await engine.perform_sync(dry_run=dry_run)
+                await build_engine().perform_sync(dry_run=dry_run)
             except Exception as exc:  # noqa: BLE001 - keep the schedule alive across failures
                 typer.echo(f"Scheduled sync failed: {exc}", err=True)
 
-        typer.echo(f"Running on schedule: {cron}")
-        asyncio.run(run_cron(cron, scheduled, sleep=asyncio.sleep, now=datetime.now))
+        # A verification pass never overlaps a sync (or another pass).
+        busy = asyncio.Lock()
+
+        async def scheduled() -> None:
+            async with busy:
+                await run_cycle()
+                end_cycle(max_rss_bytes)
+
+        async def verification() -> None:
+            async with busy:
+                try:
+                    await run_verification_pass(
+                        cfg,
+                        destination,
+                        workers=workers,
+                        timeout_seconds=timeout,
+                        telegram=telegram,
+                    )
+                except Exception as exc:  # noqa: BLE001 - keep the schedule alive across failures
+                    typer.echo(f"Scheduled verification failed: {exc}", err=True)
+
+        async def schedules() -> None:
+            if interval is not None:
+                runs = [run_interval(interval, scheduled, sleep=asyncio.sleep)]
+            else:
+                runs = [run_cron(cron or "", scheduled, sleep=asyncio.sleep, now=datetime.now)]
+            if verify_cron:
+                runs.append(
+                    run_cron(verify_cron, verification, sleep=asyncio.sleep, now=datetime.now)
+                )
+            await asyncio.gather(*runs)
+
+        if interval is not None:
+            typer.echo(f"Running every {format_duration(interval)}")
+        else:
+            typer.echo(f"Running on schedule: {cron}")
+        if verify_cron:
+            typer.echo(f"Verifying on schedule: {verify_cron}")
+        asyncio.run(schedules())
         return
 
-    outcomes = asyncio.run(engine.perform_sync(dry_run=dry_run))
+    engine = build_engine()
+    try:
+        outcomes = asyncio.run(engine.perform_sync(dry_run=dry_run))
+    except ValueError as exc:  # incl. DestinationConflictError, UnknownRepositoryError
+        typer.echo(str(exc), err=True)
+        raise typer.Exit(code=EXIT_CONFIG_ERROR) from exc
+    except TokenCheckError as exc:
+        typer.echo(str(exc), err=True)
+        raise typer.Exit(code=EXIT_TOTAL_FAILURE) from exc
 
     if dry_run:
         for outcome in outcomes:
             typer.echo(dry_run_line(outcome.task, cfg))
+        for line in format_estimate(estimate_run([o.task for o in outcomes], cfg)):
+            typer.echo(line)
         return
 
+    use_color = _use_color(color)
+    for outcome in outcomes:
+        if not quiet or not outcome.ok:
+            _echo_outcome(outcome, use_color)
     failures = [o for o in outcomes if not o.ok]
-    for failure in failures:
-        typer.echo(f"FAILED {failure.task.url}: {failure.error}", err=True)
     typer.echo(f"Synced {len(outcomes) - len(failures)}/{len(outcomes)} repositories.")
-    if failures and cfg.exit_on_failure:
-        raise typer.Exit(code=1)
+    if engine.aborted:
+        typer.echo("Stopped after the first failure (--fail-fast).", err=True)
+        raise typer.Exit(code=_sync_exit_code(outcomes) or EXIT_PARTIAL_FAILURE)
+    copies_failed = _echo_fanout(engine.fanout_results, use_color, quiet=quiet)
+    code = _sync_exit_code(outcomes, copies_failed=copies_failed)
+    if code and cfg.exit_on_failure:
+        raise typer.Exit(code=code)
+
+
+def _sync_exit_code(outcomes: list[SyncOutcome], *, copies_failed: bool = False) -> int:
+    """0, ``EXIT_PARTIAL_FAILURE`` or (nothing synced) ``EXIT_TOTAL_FAILURE`` for a run."""
+    if any(not outcome.ok for outcome in outcomes):
+        if not any(outcome.ok for outcome in outcomes):
+            return EXIT_TOTAL_FAILURE
+        return EXIT_PARTIAL_FAILURE
+    return EXIT_PARTIAL_FAILURE if copies_failed else 0
+
+
+def _use_color(color: str) -> bool | None:
+    """``color=`` for :func:`typer.echo`: forced on or off, or None to ask the stream.
+
+    ``auto`` is off when ``NO_COLOR`` is set (https://no-color.org); otherwise Click
+    keeps the colors only when the stream is a terminal.
+    """
+    if color == "always":
+        return True
+    if color == "never" or os.environ.get("NO_COLOR"):
+        return False
+    return None
+
+
+def _echo_status(status: str, text: str, use_color: bool | None, *, err: bool = False) -> None:
+    label = typer.style(status, fg=_STATUS_COLORS[status], bold=status == "FAILED" or None)
+    typer.echo(f"{label} {text}", err=err, color=use_color)
+
+
+def _echo_outcome(outcome: SyncOutcome, use_color: bool | None) -> None:
+    """``OK``, ``SKIPPED`` or ``FAILED`` and the repository; failures go to stderr."""
+    if not outcome.ok:
+        status = "SKIPPED" if outcome.skipped else "FAILED"
+        _echo_status(status, f"{outcome.task.url}: {outcome.error}", use_color, err=True)
+    elif outcome.skipped:
+        _echo_status("SKIPPED", outcome.task.name, use_color)
+    else:
+        _echo_status("OK", outcome.task.name, use_color)
+
+
+def _echo_fanout(
+    results: list[FanoutResult], use_color: bool | None = None, *, quiet: bool = False
+) -> bool:
+    """Print a line per fan-out destination (quiet: failures only); whether any failed."""
+    for result in results:
+        if result.ok and quiet:
+            continue
+        if result.ok:
+            typer.echo(
+                f"Copied to {result.destination}: "
+                f"{result.copied} updated, {result.removed} removed."
+            )
+        else:
+            text = f"copy to {result.destination}: {result.error}"
+            _echo_status("FAILED", text, use_color, err=True)
+    return any(not result.ok for result in results)
+
+
+@app.command()
+def tui(
+    config: str = typer.Argument(
+        ...,
+        envvar="GITOUT_CONFIG",
+        help="Configuration: a path, an https:// URL, or git+<url>#<path>[@<ref>]",
+    ),
+    destination: Path = typer.Argument(..., envvar="GITOUT_DEST", help="Backup directory"),
+    workers: int | None = typer.Option(
+        None,
+        "--workers",
+        envvar="GITOUT_WORKERS",
+        parser=_workers,
+        metavar="N|auto",
+        help="Parallel worker count; auto (or 0) picks one from the CPU and repository counts",
+    ),
+    timeout: float | None = typer.Option(
+        None,
+        "--timeout",
+        envvar="GITOUT_TIMEOUT",
+        parser=_duration,
+        metavar="DURATION",
+        help="Per-repository git timeout (seconds, or e.g. 10m, 1h30m; default runtime.timeout "
+        "or 600)",
+    ),
+    verbose: int = typer.Option(
+        0,
+        "--verbose",
+        "-v",
+        count=True,
+        envvar="GITOUT_VERBOSE",
+        help="Show debug messages in the event log",
+    ),
+    config_sha256: str | None = typer.Option(
+        None,
+        "--config-sha256",
+        envvar="GITOUT_CONFIG_SHA256",
+        help="Refuse to run unless the configuration has this SHA-256 digest",
+    ),
+) -> None:
+    """Run one sync with a live dashboard of workers, events and failures."""
+    from gitout.tui import Dashboard, run_dashboard
+
+    _configure_logging(verbose, quiet=False)
+    cfg = _load_sync_config(config, config_sha256, False)
+    dashboard = Dashboard()
+    engine = Engine(
+        config=cfg,
+        destination=destination,
+        repo_loader=_repo_loader(cfg),
+        token_checker=_token_checker(cfg),
+        environ=os.environ,
+        workers=workers if workers is not None else cfg.runtime.workers,
+        timeout_seconds=_runtime_timeout(cfg, timeout),
+        retry_policy=_retry_policy(cfg),
+        on_event=dashboard.handle,
+    )
+    try:
+        outcomes = run_dashboard(dashboard, engine.perform_sync)
+    except ValueError as exc:  # incl. DestinationConflictError, UnknownRepositoryError
+        typer.echo(str(exc), err=True)
+        raise typer.Exit(code=EXIT_CONFIG_ERROR) from exc
+    except TokenCheckError as exc:
+        typer.echo(str(exc), err=True)
+        raise typer.Exit(code=EXIT_TOTAL_FAILURE) from exc
+
+    failures = [o for o in outcomes if not o.ok]
+    typer.echo(f"Synced {len(outcomes) - len(failures)}/{len(outcomes)} repositories.")
+    code = _sync_exit_code(outcomes)
+    if code and cfg.exit_on_failure:
+        raise typer.Exit(code=code)
 
 
 def _build_search_service(cfg: config_module.Config) -> SearchIndexService | None:
@@ -200,11 +895,13 @@ def _build_search_service(cfg: config_module.Config) -> SearchIndexService | Non
 @app.command()
 def search(
     query: str = typer.Argument(..., help="Natural language search query"),
-    config: Path = typer.Argument(..., exists=True, dir_okay=False, help="Configuration TOML"),
+    config: Path = typer.Argument(
+        ..., exists=True, dir_okay=False, help="Configuration file (TOML, JSON or YAML)"
+    ),
     destination: Path = typer.Argument(..., help="Backup directory"),
 ) -> None:
     """Search backed-up repositories by natural-language query."""
-    cfg = config_module.parse(config.read_text())
+    cfg = _parse_config_file(config)
     service = _build_search_service(cfg)
     if service is None:
         return
@@ -233,11 +930,13 @@ def search(
 
 @app.command()
 def index(
-    config: Path = typer.Argument(..., exists=True, dir_okay=False, help="Configuration TOML"),
+    config: Path = typer.Argument(
+        ..., exists=True, dir_okay=False, help="Configuration file (TOML, JSON or YAML)"
+    ),
     destination: Path = typer.Argument(..., help="Backup directory"),
 ) -> None:
     """Index all backed-up repositories for semantic search."""
-    cfg = config_module.parse(config.read_text())
+    cfg = _parse_config_file(config)
     service = _build_search_service(cfg)
     if service is None:
         return
@@ -254,5 +953,400 @@ def index(
     typer.echo("Indexing complete.")
 
 
+@app.command("export")
+def export_frontend(
+    fmt: str = typer.Argument(..., metavar="FORMAT", help=f"One of {', '.join(FRONTEND_FORMATS)}"),
+    destination: Path = typer.Argument(..., help="Backup directory"),
+    output: Path | None = typer.Option(
+        None, "--output", "-o", dir_okay=False, help="Write to a file instead of stdout"
+    ),
+) -> None:
+    """Print cgit, gitolite or manifest configuration for the mirrors in DESTINATION."""
+    try:
+        text = render(fmt, destination)
+    except ValueError as exc:
+        typer.echo(str(exc), err=True)
+        raise typer.Exit(code=1) from exc
+    if output is None:
+        typer.echo(text, nl=False)
+    else:
+        output.write_text(text)
+
+
+@app.command()
+def verify(
+    destination: Path = typer.Argument(..., help="Backup directory"),
+    workers: int = typer.Option(
+        4,
+        "--workers",
+        envvar="GITOUT_WORKERS",
+        parser=_workers,
+        metavar="N|auto",
+        help="Repositories checked in parallel; auto (or 0) uses the CPU count",
+    ),
+    timeout: float = typer.Option(
+        600.0,
+        "--timeout",
+        parser=_duration,
+        metavar="DURATION",
+        help="Per-repository git timeout (seconds, or e.g. 10m, 1h30m)",
+    ),
+    connectivity_only: bool = typer.Option(
+        False, "--connectivity-only", help="Only check that every ref has a complete history"
+    ),
+    quarantine_failed: bool = typer.Option(
+        False,
+        "--quarantine",
+        help="Rename mirrors fsck rejects to <repo>.corrupt-<date> so the next sync "
+        "re-clones them",
+    ),
+) -> None:
+    """Run git fsck over every mirror in DESTINATION; exit 1 if any fails."""
+    try:
+        results = asyncio.run(
+            verify_destination(
+                destination,
+                workers=workers or auto_worker_count(()),
+                timeout_seconds=timeout,
+                connectivity_only=connectivity_only,
+            )
+        )
+    except ValueError as exc:
+        typer.echo(str(exc), err=True)
+        raise typer.Exit(code=1) from exc
+    failures = [r for r in results if not r.ok]
+    for result in results:
+        typer.echo(f"{'OK' if result.ok else 'FAILED'} {result.name}")
+    for failure in failures:
+        typer.echo(f"FAILED {failure.name}:\n{failure.output}", err=True)
+        if quarantine_failed and failure.corrupt:
+            moved = quarantine(failure.path, date.today())
+            typer.echo(f"Moved {failure.name} to {moved.name}; the next sync clones it again")
+        elif quarantine_failed:
+            typer.echo(f"Kept {failure.name}: its check did not finish, so it is not quarantined")
+    typer.echo(f"Verified {len(results) - len(failures)}/{len(results)} repositories.")
+    if failures:
+        raise typer.Exit(code=1)
+
+
+@app.command("check-config")
+def check_config(
+    config: str = typer.Argument(
+        ...,
+        help="Configuration (TOML, JSON or YAML): a path, an https:// URL, "
+        "or git+<url>#<path>[@<ref>]",
+    ),
+    online: bool = typer.Option(
+        False, "--online", help="Also check the token and that every repository is reachable"
+    ),
+    strict: bool = typer.Option(
+        False, "--strict", help="Treat every warning (such as an unknown key) as an error"
+    ),
+) -> None:
+    """Check CONFIG without syncing: unknown keys, invalid values and (--online) access."""
+    try:
+        text = load_config_text(config, environ=os.environ)
+    except RemoteConfigError as exc:
+        typer.echo(str(exc), err=True)
+        raise typer.Exit(code=1) from exc
+    cfg, findings = check_text(text, config)
+    if cfg is not None and online and not any(f.severity == "error" for f in findings):
+        findings += asyncio.run(check_online(cfg, os.environ, token_checker=_token_checker(cfg)))
+    for finding in findings:
+        typer.echo(f"{finding.severity.upper()} {finding.message}", err=finding.severity != "ok")
+    errors = sum(f.severity == "error" for f in findings)
+    warnings = sum(f.severity == "warning" for f in findings)
+    if errors or (strict and warnings):
+        typer.echo(f"{config}: {errors} error(s), {warnings} warning(s).", err=True)
+        raise typer.Exit(code=1)
+    typer.echo(f"{config} is valid ({warnings} warning(s)).")
+
+
+@app.command()
+def doctor(
+    config: Path | None = typer.Argument(
+        None, exists=True, dir_okay=False, help="Configuration file (TOML, JSON or YAML)"
+    ),
+    destination: Path | None = typer.Argument(None, help="Backup directory"),
+) -> None:
+    """Check git, TLS, network access, the token and DESTINATION; exit 1 on any failure."""
+    cfg = _parse_config_file(config) if config is not None else None
+    token_checker = _token_checker(cfg) if cfg is not None else check_token
+    results = asyncio.run(diagnose(cfg, destination, token_checker=token_checker))
+    for result in results:
+        typer.echo(f"{result.status.upper():7} {result.check:11} {result.detail}")
+    failures = sum(result.status == "fail" for result in results)
+    if failures:
+        typer.echo(f"{failures} check(s) failed.", err=True)
+        raise typer.Exit(code=1)
+    typer.echo("All checks passed.")
+
+
+@app.command()
+def restore(
+    source: Path = typer.Argument(
+        ..., exists=True, help="Bare mirror, bundle, bundle chain or migration archive"
+    ),
+    url: str = typer.Argument(..., help="Remote to push to, e.g. an empty repository"),
+    repository: str | None = typer.Option(
+        None, "--repository", help="OWNER/REPO to restore from a multi-repository archive"
+    ),
+    all_refs: bool = typer.Option(
+        False, "--all-refs", help="Push every ref (git push --mirror), not just branches and tags"
+    ),
+    force: bool = typer.Option(False, "--force", help="Overwrite diverged refs on the remote"),
+    dry_run: bool = typer.Option(
+        False, "--dry-run", help="Read SOURCE and count its refs without pushing"
+    ),
+    timeout: float = typer.Option(
+        3600.0,
+        "--timeout",
+        parser=_duration,
+        metavar="DURATION",
+        help="Per-git-command timeout (seconds, or e.g. 10m, 1h30m)",
+    ),
+) -> None:
+    """Push a backed-up repository to URL, recreating its branches and tags."""
+    try:
+        result = asyncio.run(
+            Restorer(timeout_seconds=timeout).restore(
+                source,
+                url,
+                repository=repository,
+                all_refs=all_refs,
+                force=force,
+                dry_run=dry_run,
+            )
+        )
+    except RestoreError as exc:
+        typer.echo(str(exc), err=True)
+        raise typer.Exit(code=1) from exc
+    verb = "Would restore" if dry_run else "Restored"
+    typer.echo(f"{verb} {result.branches} branches and {result.tags} tags to {url}.")
+
+
+@app.command()
+def serve(
+    destination: Path = typer.Argument(..., exists=True, file_okay=False, help="Backup directory"),
+    bind: str = typer.Option(
+        DEFAULT_BIND, "--bind", envvar="GITOUT_SERVE_BIND", help="HOST:PORT to listen on"
+    ),
+    token: str | None = typer.Option(
+        None,
+        "--token",
+        envvar="GITOUT_SERVE_TOKEN",
+        help="Require this token (Bearer, or as the basic-auth password)",
+    ),
+    prefix: str = typer.Option(
+        "", "--prefix", envvar="GITOUT_SERVE_PREFIX", help="URL path to serve under, e.g. /git"
+    ),
+) -> None:
+    """Serve the mirrors in DESTINATION read-only over smart HTTP."""
+    try:
+        server = GitHttpServer(parse_bind(bind), destination, token=token, prefix=prefix)
+    except (ValueError, OSError) as exc:
+        typer.echo(str(exc), err=True)
+        raise typer.Exit(code=1) from exc
+    typer.echo(f"Serving {destination} read-only at {server.url}")
+    try:
+        server.serve_forever()
+    except KeyboardInterrupt:
+        pass
+    finally:
+        server.server_close()
+
+
+@app.command("man")
+def man_page(
+    output: Path | None = typer.Option(
+        None, "--output", "-o", dir_okay=False, help="Write to a file instead of stdout"
+    ),
+) -> None:
+    """Print the gitout(1) man page (roff), generated from the commands and config model."""
+    text = render_manpage(typer.main.get_command(app), __version__)
+    if output is None:
+        typer.echo(text, nl=False)
+    else:
+        output.write_text(text)
+
+
+@app.command()
+def unlock(
+    config: Path = typer.Argument(
+        ..., exists=True, dir_okay=False, help="Configuration file (TOML, JSON or YAML)"
+    ),
+) -> None:
+    """Unlock repositories still locked by migrations (after an interrupted archive run)."""
+    cfg = _parse_config_file(config)
+    if cfg.github is None:
+        typer.echo("No [github] section in config; nothing to unlock.", err=True)
+        raise typer.Exit(code=1)
+    try:
+        tokens = resolve_github_tokens(
+            configured_github_token(cfg.github), os.environ, api_url=cfg.github.api_url
+        )
+    except ValueError as exc:
+        typer.echo(str(exc), err=True)
+        raise typer.Exit(code=1) from exc
+    archiver = MigrationArchiver(
+        tokens[0] if len(tokens) == 1 else TokenPool(tokens),  # each token's migrations
+        api_url=cfg.github.api_url,
+        user_agent=cfg.http.user_agent,
+        extra_headers=cfg.http.headers,
+    )
+    try:
+        unlocked = asyncio.run(archiver.unlock_all())
+    except httpx.HTTPError as exc:
+        typer.echo(f"Failed to unlock repositories: {exc}", err=True)
+        raise typer.Exit(code=1) from exc
+    for name in unlocked:
+        typer.echo(f"Unlocked {name}")
+    typer.echo(f"Unlocked {len(unlocked)} repositories.")
+
+
+@state_app.command("export")
+def state_export(
+    config: Path = typer.Argument(
+        ..., exists=True, dir_okay=False, help="Configuration file (TOML, JSON or YAML)"
+    ),
+    destination: Path = typer.Argument(..., help="Backup directory"),
+    output: Path = typer.Argument(..., dir_okay=False, help="File to write the export to"),
+) -> None:
+    """Export sync state (repo snapshot, exclusions, failure history) to a JSON file."""
+    cfg = _parse_config_file(config)
+    try:
+        payload = export_state(cfg, destination)
+    except ValueError as exc:
+        typer.echo(str(exc), err=True)
+        raise typer.Exit(code=1) from exc
+    output.write_text(json.dumps(payload, indent=2))
+    typer.echo(f"Exported {len(payload['files'])} state file(s) to {output}")
+
+
+@state_app.command("import")
+def state_import(
+    destination: Path = typer.Argument(..., help="Backup directory"),
+    source: Path = typer.Argument(..., exists=True, dir_okay=False, help="Exported state file"),
+    force: bool = typer.Option(False, "--force", help="Overwrite existing state files"),
+) -> None:
+    """Import state files previously written by `gitout state export`."""
+    try:
+        written = import_state(json.loads(source.read_text()), destination, force=force)
+    except ValueError as exc:
+        typer.echo(str(exc), err=True)
+        raise typer.Exit(code=1) from exc
+    for relative in written:
+        typer.echo(f"Imported {relative}")
+
+
+def _check_provider(provider: str) -> None:
+    if provider not in PROVIDERS:
+        expected = ", ".join(PROVIDERS)
+        typer.echo(f"Unknown provider {provider!r}; expected one of {expected}", err=True)
+        raise typer.Exit(code=1)
+
+
+@auth_app.command("set")
+def auth_set(
+    provider: str = typer.Argument(..., help="Credential to store: github"),
+    stdin: bool = typer.Option(False, "--stdin", help="Read the token from standard input"),
+) -> None:
+    """Store a token in the OS keyring; syncs use it when no other token is configured."""
+    _check_provider(provider)
+    if stdin:
+        token = sys.stdin.read().strip()
+    else:
+        token = typer.prompt(f"{provider} token", hide_input=True).strip()
+    if not token:
+        typer.echo("No token given; nothing stored.", err=True)
+        raise typer.Exit(code=1)
+    try:
+        set_secret(provider, token)
+    except KeyringUnavailableError as exc:
+        typer.echo(str(exc), err=True)
+        raise typer.Exit(code=1) from exc
+    typer.echo(f"Stored the {provider} token in the OS keyring")
+
+
+@auth_app.command("delete")
+def auth_delete(provider: str = typer.Argument(..., help="Credential to remove: github")) -> None:
+    """Remove a token from the OS keyring."""
+    _check_provider(provider)
+    try:
+        deleted = delete_secret(provider)
+    except KeyringUnavailableError as exc:
+        typer.echo(str(exc), err=True)
+        raise typer.Exit(code=1) from exc
+    typer.echo(f"Removed the {provider} token" if deleted else f"No {provider} token stored")
+
+
+LOGIN_STORES = ("keyring", "file")
+
+
+def _write_token_file(path: Path, token: str) -> None:
+    """Write ``token`` readable by the owner only."""
+    path.parent.mkdir(parents=True, exist_ok=True)
+    fd = os.open(path, os.O_WRONLY | os.O_CREAT | os.O_TRUNC, 0o600)
+    with open(fd, "w") as handle:
+        handle.write(token + "\n")
+    path.chmod(0o600)
+
+
+@app.command()
+def login(
+    client_id: str = typer.Option(
+        ...,
+        "--client-id",
+        envvar="GITOUT_OAUTH_CLIENT_ID",
+        help="Client ID of an OAuth App with device flow enabled",
+    ),
+    store: str = typer.Option(
+        "keyring", "--store", help="Where to keep the token: keyring or file"
+    ),
+    token_file: Path | None = typer.Option(
+        None, "--token-file", dir_okay=False, help="File written by --store file"
+    ),
+    scopes: list[str] | None = typer.Option(
+        None,
+        "--scope",
+        help=f"OAuth scope to request (repeatable; default: {' '.join(DEFAULT_SCOPES)})",
+    ),
+    web_url: str = typer.Option(
+        GITHUB_WEB_URL, "--web-url", help="GitHub web root, e.g. https://github.example.com"
+    ),
+) -> None:
+    """Log in to GitHub with the OAuth device flow and store the token."""
+    if store not in LOGIN_STORES:
+        typer.echo(f"--store must be one of {', '.join(LOGIN_STORES)}", err=True)
+        raise typer.Exit(code=1)
+    if store == "file" and token_file is None:
+        typer.echo("--store file needs --token-file", err=True)
+        raise typer.Exit(code=1)
+
+    def show(code: DeviceCode) -> None:
+        typer.echo(f"Open {code.verification_uri} and enter the code {code.user_code}")
+
+    try:
+        token = asyncio.run(
+            device_login(
+                client_id, show, scopes=tuple(scopes or DEFAULT_SCOPES), web_url=web_url
+            )
+        )
+    except (DeviceFlowError, httpx.HTTPError) as exc:
+        typer.echo(f"Login failed: {exc}", err=True)
+        raise typer.Exit(code=1) from exc
+
+    if store == "file" and token_file is not None:
+        _write_token_file(token_file, token)
+        typer.echo(f"Token written to {token_file}; set github.token_file to use it")
+        return
+    try:
+        set_secret("github", token)
+    except KeyringUnavailableError as exc:
+        typer.echo(str(exc), err=True)
+        raise typer.Exit(code=1) from exc
+    typer.echo("Logged in; the token is stored in the OS keyring")
+
+
 if __name__ == "__main__":
     app()
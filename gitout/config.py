@@ -1,50 +1,116 @@
-"""Configuration model and TOML parsing.
+"""Configuration model and TOML/JSON/YAML parsing.
 
 Port of ``Config.kt``. The dataclasses and their defaults are the spec (they
 mirror the Kotlin ``@Serializable`` classes, with field names matching the TOML
 / ``@SerialName`` keys). ``parse`` loads and coerces a TOML file into the
 dataclass tree, ``validate`` enforces cross-field invariants, and
 ``to_normalized_dict`` serialises the config for logging and diagnostics.
+JSON and YAML documents (picked by file extension, see ``format_for_path``) use
+the same key layout as the TOML tables; YAML needs the optional PyYAML extra.
 """
 
 from __future__ import annotations
 
+import json
 import re
+import string
 import tomllib
 from collections.abc import Callable
-from dataclasses import asdict, dataclass, field, fields
-from pathlib import Path
-from typing import Any
+from dataclasses import asdict, dataclass, field, fields, is_dataclass
+from datetime import date
+from fnmatch import fnmatchcase
+from pathlib import Path, PurePosixPath
+from types import UnionType
+from typing import Any, get_args, get_origin, get_type_hints
+
+from gitout.cron import CronExpression, parse_duration
 
 DEFAULT_TELEGRAM_PROGRESS_STEP_PERCENT = 10
+MIGRATION_EXCLUDES = ("repositories",)  # values the migrations API accepts in "exclude"
+FORK_MODES = ("include", "exclude", "only")
+CLONE_PROTOCOLS = ("https", "ssh")  # ssh clones git@github.com:owner/repo.git
+MIRROR_REFSPEC = "+refs/*:refs/*"  # every ref, as git clone --mirror configures
+# all: every tag; auto: only tags pointing into fetched history (git's default); none
+FETCH_TAGS = ("all", "auto", "none")
+REGEX_PREFIX = "re:"  # repository patterns starting with this are regular expressions
 
 
 @dataclass
 class GitHubArchive:
-    owned: bool = False
+    owned: bool = False  # migration archive (issues, PRs, releases, ...) of every owned repo
+    repos: list[str] = field(default_factory=list)  # extra "owner/repo" to archive
+    retention: int | None = None  # keep N dated archives per repo; None overwrites one
+    exclude_attachments: bool = False
+    exclude_releases: bool = False
+    exclude_metadata: bool = False  # git data only (issues, PRs, ... are left out)
+    lock_repositories: bool = False  # lock the repos on GitHub while exporting
+    exclude: list[str] = field(default_factory=list)  # e.g. ["repositories"]
 
 
 @dataclass
 class GitHubClone:
+    owned: bool = True  # repositories the user owns (or the App installation can see)
     starred: bool = False
     watched: bool = False
     gists: bool = True
+    gists_starred: bool = False  # gists the token owner has starred
     repos: list[str] = field(default_factory=list)
-    ignore: list[str] = field(default_factory=list)
+    ignore: list[str] = field(default_factory=list)  # names, globs or "re:<regex>"
     single_branch_only: bool = False
+    forks: str = "include"  # one of FORK_MODES; explicit `repos` entries are always kept
+    include_archived: bool = True  # False skips repos GitHub marks archived
+    topics: list[str] = field(default_factory=list)  # keep only repos with one of these
+    ignored_topics: list[str] = field(default_factory=list)
+    languages: list[str] = field(default_factory=list)  # keep only these primary languages
+    ignored_languages: list[str] = field(default_factory=list)
+    skip_unchanged: bool = False  # skip repos whose pushedAt matches the last sync
+    protocol: str = "https"  # one of CLONE_PROTOCOLS; ssh authenticates with [ssh]
+
+
+@dataclass
+class GitHubMetadata:
+    settings: bool = False  # branch protection, merge settings, rulesets (admin repos only)
+    community: bool = False  # dated stargazer/watcher snapshots of owned repos
+
+
+@dataclass
+class GitHubApp:
+    id: int = 0
+    private_key_file: str = ""
+    installation_id: int | None = None  # looked up from github.user when unset
 
 
 @dataclass
 class GitHubConfig:
     user: str
-    token: str | None = None
+    token: str | list[str] | None = None  # several tokens rotate when one runs out
+    token_file: str | None = None  # e.g. a Docker secret; read and trimmed at startup
+    token_cmd: str | None = None  # shell command printing the token, e.g. "pass show gh"
+    # GitHub Enterprise Server: https://<host>/api (REST, GraphQL and clone URLs derive from it)
+    api_url: str = "https://api.github.com"
     archive: GitHubArchive = field(default_factory=GitHubArchive)
     clone: GitHubClone = field(default_factory=GitHubClone)
+    metadata: GitHubMetadata = field(default_factory=GitHubMetadata)
+    app: GitHubApp | None = None  # authenticate as a GitHub App installation
+
+
+@dataclass
+class GitRepoCredentials:
+    """HTTP(S) login for one ``[git.repos]`` entry given in table form."""
+
+    username: str = ""
+    password_env: str | None = None  # environment variable holding the password/token
+    password_file: str | None = None  # or a file holding it (trimmed)
 
 
 @dataclass
 class GitConfig:
-    repos: dict[str, str] = field(default_factory=dict)
+    repos: dict[str, str] = field(default_factory=dict)  # name -> URL
+    skip_unchanged: bool = False  # `git ls-remote --heads` first; skip when heads match
+    # name -> login, from entries written as { url = ..., username = ..., password_env = ... }
+    credentials: dict[str, GitRepoCredentials] = field(default_factory=dict)
+    # name -> refspecs, from entries with ``refspecs = [...]``; replaces fetch.refspecs
+    refspecs: dict[str, list[str]] = field(default_factory=dict)
 
 
 @dataclass
@@ -53,6 +119,15 @@ class Ssl:
     verify_certificates: bool = True
 
 
+@dataclass
+class Ssh:
+    key_file: str | None = None  # private key for SSH remotes; default: ssh's own choice
+    passphrase_file: str | None = None  # file holding the key's passphrase
+    passphrase_env: str | None = None  # or the environment variable holding it
+    agent: bool = True  # offer ssh-agent keys before key_file
+    agent_socket: str | None = None  # agent to use when SSH_AUTH_SOCK is not inherited
+
+
 @dataclass
 class Http:
     version: str = "HTTP/1.1"
@@ -60,6 +135,31 @@ class Http:
     adaptive_fallback: bool = True
     low_speed_limit: int = 1000  # bytes/second
     low_speed_time: int = 60  # seconds
+    # Applied to GitHub API requests (not git transport); None keeps gitout/<version>.
+    user_agent: str | None = None
+    headers: dict[str, str] = field(default_factory=dict)
+
+
+@dataclass
+class Transfer:
+    # Pack tuning passed to every git clone/fetch/repack as ``-c`` options.
+    pack_threads: int | None = None  # pack.threads; 0 lets git use every core
+    window_memory: str | None = None  # pack.windowMemory, e.g. "256m" on small devices
+
+
+@dataclass
+class Fetch:
+    """How updates fetch into existing mirrors."""
+
+    prune: bool = True  # drop refs deleted upstream (fetch --prune)
+    attic: bool = False  # keep pruned refs under refs/attic/<date>/ instead of losing them
+    keep_rewritten: bool = False  # keep force-pushed tips under refs/attic/<date>/forced/
+    # Refspecs mirror updates fetch; empty falls back to the origin remote's own.
+    refspecs: list[str] = field(default_factory=lambda: [MIRROR_REFSPEC])
+    tags: str = "all"  # one of FETCH_TAGS
+    depth: int = 0  # commits of history to keep per ref (clone/fetch --depth); 0 = all
+    filter: str | None = None  # partial clone filter such as "blob:none" (clone --filter)
+    reclone_corrupt: bool = True  # set a corrupt mirror aside and clone it again
 
 
 @dataclass
@@ -77,11 +177,28 @@ class Parallelism:
     priorities: list[PriorityPattern] = field(default_factory=list)
 
 
+RETRY_BACKOFFS = ("linear", "exponential", "constant")
+
+
+@dataclass
+class Runtime:
+    # How `gitout sync` runs; each key is a default for the matching CLI flag, which wins.
+    workers: int | None = None  # --workers; replaces parallelism.workers; 0 = auto
+    interval: str | None = None  # --interval, e.g. "6h"; ignored by --dry-run and --once
+    cron: str | None = None  # --cron; mutually exclusive with interval
+    timeout: str | None = None  # --timeout, e.g. "10m" (default 600 seconds)
+    retry_attempts: int = 6  # tries per repository before it counts as failed
+    retry_delay: str = "5s"  # base delay between tries
+    retry_backoff: str = "linear"  # one of RETRY_BACKOFFS
+
+
 @dataclass
 class Metrics:
     enabled: bool = True
     format: str = "console"
     export_path: str | None = None
+    pushgateway_url: str | None = None  # push the run's metrics here after each sync
+    pushgateway_job: str = "gitout"  # the `job` grouping label of the pushed metrics
 
 
 @dataclass(kw_only=True)
@@ -127,6 +244,9 @@ class HealthCheckConfig:
     preflight_timeout_seconds: int = 5
     circuit_breaker_enabled: bool = True
     circuit_breaker_threshold: int = 10
+    # Pinged at the start, success and failure of each sync, e.g.
+    # "https://hc-ping.com/<uuid>" (sync --hc-id wins).
+    ping_url: str | None = None
 
 
 @dataclass
@@ -137,6 +257,9 @@ class Maintenance:
     repack_window: int = 50
     repack_depth: int = 50
     write_commit_graph: bool = True
+    write_multi_pack_index: bool = False  # git multi-pack-index write after every sync
+    gc_every: int = 0  # git gc every N syncs of a repository; 0 = never
+    gc_prune: str = "2.weeks.ago"  # git gc --prune: unreachable objects older than this
 
 
 @dataclass
@@ -153,14 +276,251 @@ class Search:
     auto_index: bool = True
 
 
+@dataclass
+class Reports:
+    enabled: bool = False
+    directory: str = "reports"  # relative to the destination unless absolute
+    retention: int = 30  # newest report files kept
+
+
+@dataclass
+class Bundle:
+    enabled: bool = False  # write a `git bundle` of each mirror after it syncs
+    directory: str = "bundles"  # relative to the destination unless absolute
+    keep_mirrors: bool = True  # false: delete each mirror once its bundle is written
+    verify: bool = True  # `git bundle verify` a new bundle before it replaces the old one
+    incremental: bool = False  # a chain of bundles with only the objects since the last one
+    full_every: int = 0  # incremental bundles before a new full chain starts; 0 = never
+
+
+@dataclass
+class Tarball:
+    enabled: bool = False  # export HEAD of every repository; [[overrides]] tarball per repo
+    directory: str = "tarballs"  # relative to the destination unless absolute
+    keep: int = 1  # newest tarballs kept per repository; 0 = all (e.g. WORM storage)
+
+
+SNAPSHOT_MODES = ("bundle", "hardlink")
+
+
+@dataclass
+class Snapshots:
+    """Point-in-time snapshots of the destination, thinned out over time."""
+
+    enabled: bool = False
+    mode: str = "bundle"  # "bundle": a bundle per mirror; "hardlink": a linked tree copy
+    directory: str = "snapshots"  # relative to the destination unless absolute
+    interval_hours: int = 24  # a sync takes a snapshot once the newest is this old
+    verify: bool = True  # `git bundle verify` every bundle of a snapshot (bundle mode)
+    keep_daily: int = 7  # newest snapshot of each of the last 7 days...
+    keep_weekly: int = 4  # ...ISO weeks...
+    keep_monthly: int = 12  # ...months...
+    keep_yearly: int = 0  # ...and years (the newest snapshot is always kept)
+
+
+OFFSITE_BACKENDS = ("s3", "webdav")
+# S3 rejects multipart parts smaller than this (except the last one).
+S3_MIN_PART_MB = 5
+
+
+@dataclass
+class S3Storage:
+    bucket: str = ""
+    prefix: str = ""  # key prefix, e.g. "gitout/"
+    endpoint_url: str | None = None  # B2, MinIO, ...: the provider's S3 API endpoint
+    region: str | None = None
+    profile: str | None = None  # ~/.aws profile; otherwise the default credential chain
+    storage_class: str | None = None  # e.g. "STANDARD_IA", "GLACIER_IR"
+    multipart_threshold_mb: int = 64  # larger files are uploaded in parts
+    multipart_chunk_mb: int = 64
+    max_attempts: int = 5  # per request, with the SDK's backoff
+
+
+@dataclass
+class WebDav:
+    url: str = ""  # the collection to upload into (Nextcloud: .../remote.php/dav/files/<user>/x)
+    username: str = ""
+    password_env: str | None = None  # environment variable holding the password
+    password_file: str | None = None  # or a file holding it (trimmed)
+    timeout_seconds: float = 300.0  # per request
+    max_attempts: int = 5  # per file, on network errors and 5xx/429 answers
+
+
+@dataclass
+class Offsite:
+    """Copies of the destination's artifacts on a remote storage backend."""
+
+    backend: str = "s3"  # one of OFFSITE_BACKENDS
+    # Destination-relative directories (or files) uploaded after every sync.
+    paths: list[str] = field(default_factory=lambda: ["bundles", "tarballs", "github/archive"])
+    mirrors: bool = False  # also upload every file of the bare mirrors
+    s3: S3Storage = field(default_factory=S3Storage)
+    webdav: WebDav = field(default_factory=WebDav)
+
+
+RCLONE_MODES = ("sync", "copy")
+
+
+@dataclass
+class Rclone:
+    """Replication of the whole destination tree to an rclone remote after every sync."""
+
+    remote: str = ""  # e.g. "b2:bucket/gitout" (configured with `rclone config`)
+    mode: str = "sync"  # "sync" mirrors (deletes remote extras); "copy" only adds
+    include: list[str] = field(default_factory=list)  # rclone filter patterns
+    exclude: list[str] = field(default_factory=list)
+    flags: list[str] = field(default_factory=list)  # extra rclone arguments
+    binary: str = "rclone"
+    timeout_seconds: float = 21600.0
+
+
+@dataclass
+class Replicate:
+    """rsync of the destination to SSH targets after a sync without failures."""
+
+    targets: list[str] = field(default_factory=list)  # e.g. "backup@nas2:/srv/gitout"
+    delete: bool = True  # remove files gone from the destination (e.g. repacked packs)
+    ssh_command: str = "ssh -o BatchMode=yes"  # rsync -e
+    bandwidth_limit_kbps: int = 0  # rsync --bwlimit; 0 = unlimited
+    flags: list[str] = field(default_factory=list)  # extra rsync arguments
+    binary: str = "rsync"
+    timeout_seconds: float = 21600.0  # per target
+
+
+@dataclass
+class Tracing:
+    """An OpenTelemetry collector the spans of every run are exported to (OTLP/HTTP, JSON)."""
+
+    endpoint: str = ""  # e.g. "http://otel-collector:4318"; spans go to {endpoint}/v1/traces
+    service_name: str = "gitout"  # the service.name resource attribute
+    headers: dict[str, str] = field(default_factory=dict)  # e.g. a hosted backend's API key
+    timeout_seconds: float = 10.0
+
+
+WEBHOOK_EVENTS = ("success", "failure")
+# The $placeholders a [[webhooks]] template may use (see gitout.webhooks).
+WEBHOOK_PLACEHOLDERS = frozenset(
+    {
+        "event",
+        "summary",
+        "attempted",
+        "succeeded",
+        "failed",
+        "skipped",
+        "duration_seconds",
+        "started_at",
+        "finished_at",
+        "error",
+        "failures",
+        "version",
+    }
+)
+
+
+@dataclass
+class Webhook:
+    """An HTTP endpoint every run's outcome is POSTed to (see gitout.webhooks)."""
+
+    url: str
+    events: list[str] = field(default_factory=lambda: list(WEBHOOK_EVENTS))
+    # Body with $placeholders (string.Template); None posts the JSON summary
+    template: str | None = None
+    content_type: str = "application/json"
+    headers: dict[str, str] = field(default_factory=dict)
+    timeout_seconds: float = 10.0
+
+
+EMAIL_SECURITY = ("starttls", "ssl", "none")
+
+
+@dataclass
+class EmailNotify:
+    """An SMTP server the summary of every run is mailed through (see gitout.notify)."""
+
+    server: str = ""  # SMTP host
+    port: int = 587
+    security: str = "starttls"  # one of EMAIL_SECURITY ("ssl" is implicit TLS, port 465)
+    username: str = ""  # SMTP login; none when blank
+    password_env: str | None = None  # environment variable holding the password
+    password_file: str | None = None  # or a file holding it (trimmed)
+    sender: str = ""  # From address
+    recipients: list[str] = field(default_factory=list)
+    only_on_failure: bool = False  # mail only runs where a repository (or the run) failed
+    subject_prefix: str = "[gitout]"
+    timeout_seconds: float = 30.0
+
+
+@dataclass
+class ChatNotify:
+    """A chat service's incoming webhook the run summary is posted to (see gitout.notify)."""
+
+    webhook_url: str = ""  # from the service's incoming-webhook setup
+    only_on_failure: bool = False  # post only when a repository (or the run) failed
+    timeout_seconds: float = 10.0
+
+
+@dataclass
+class Notify:
+    """Run notifications ([notify.email], [notify.slack], [notify.discord])."""
+
+    email: EmailNotify | None = None
+    slack: ChatNotify | None = None
+    discord: ChatNotify | None = None
+
+
+@dataclass
+class PushMirror:
+    """A secondary git server every mirror is pushed to after it syncs."""
+
+    url: str = ""  # template, e.g. "https://gitea.example/backup/{repo}.git" (see push_mirror)
+    username: str = ""  # HTTP(S) login; SSH URLs use [ssh]
+    password_env: str | None = None  # environment variable holding the password/token
+    password_file: str | None = None  # or a file holding it (trimmed)
+    refspecs: list[str] = field(
+        default_factory=lambda: ["+refs/heads/*:refs/heads/*", "+refs/tags/*:refs/tags/*"]
+    )
+    prune: bool = True  # delete branches and tags that are gone from the mirror
+
+
+@dataclass
+class Fanout:
+    """Further destinations kept as copies of the synced one (repositories sync once)."""
+
+    destinations: list[str] = field(default_factory=list)  # absolute paths (or ~/...)
+    delete: bool = True  # remove files that are gone from the synced destination
+
+
+@dataclass
+class RepoOverride:
+    """Per-repository options for every task whose name matches ``pattern`` (a glob)."""
+
+    pattern: str
+    since: str | None = None  # "YYYY-MM-DD": clone only history newer than this date
+    prune: bool | None = None  # overrides fetch.prune
+    depth: int | None = None  # overrides fetch.depth (0 = full history)
+    filter: str | None = None  # overrides fetch.filter ("" = full clone)
+    # Mirror only these branches (names or globs such as "release/*"); see branch_refspecs
+    branches: list[str] | None = None
+    tarball: bool | None = None  # overrides tarball.enabled
+
+
+@dataclass
+class RepoGroup:
+    repos: list[str] = field(default_factory=list)  # names or globs
+
+
 @dataclass
 class Config:
     version: int
     github: GitHubConfig | None = None
     git: GitConfig = field(default_factory=GitConfig)
     ssl: Ssl = field(default_factory=Ssl)
+    ssh: Ssh = field(default_factory=Ssh)
     http: Http = field(default_factory=Http)
+    transfer: Transfer = field(default_factory=Transfer)
+    fetch: Fetch = field(default_factory=Fetch)
     parallelism: Parallelism = field(default_factory=Parallelism)
+    runtime: Runtime = field(default_factory=Runtime)
     metrics: Metrics = field(default_factory=Metrics)
     telegram: Telegram | None = None
     large_repos: LargeRepoConfig = field(default_factory=LargeRepoConfig)
@@ -170,6 +530,20 @@ class Config:
     lfs: Lfs = field(default_factory=Lfs)
     exit_on_failure: bool = True
     search: Search = field(default_factory=Search)
+    reports: Reports = field(default_factory=Reports)
+    bundle: Bundle = field(default_factory=Bundle)
+    tarball: Tarball = field(default_factory=Tarball)
+    snapshots: Snapshots = field(default_factory=Snapshots)
+    offsite: Offsite | None = None
+    rclone: Rclone | None = None
+    replicate: Replicate | None = None
+    fanout: Fanout = field(default_factory=Fanout)
+    push_mirror: PushMirror | None = None
+    tracing: Tracing | None = None
+    webhooks: list[Webhook] = field(default_factory=list)
+    notify: Notify = field(default_factory=Notify)
+    overrides: list[RepoOverride] = field(default_factory=list)
+    groups: dict[str, RepoGroup] = field(default_factory=dict)
 
 
 def _build_message_map() -> dict[str, Callable[[dict[str, Any]], str]]:
@@ -186,9 +560,36 @@ def _build_message_map() -> dict[str, Callable[[dict[str, Any]], str]]:
     return {
         "InvalidVersion": _fmt("version must be >= 0, got {version}"),
         "EmptyGitHubUser": lambda d: "github.user must not be empty",
+        "EmptyGitHubTokenFile": lambda d: "github.token_file must not be blank",
+        "InvalidGitHubAppId": _fmt("github.app.id must be a positive App ID, got {id}"),
+        "EmptyGitHubAppKey": lambda d: "github.app.private_key_file must not be blank",
+        "InvalidGitHubInstallationId": _fmt(
+            "github.app.installation_id must be a positive installation ID, got {id}"
+        ),
+        "EmptyGitHubTokenCmd": lambda d: "github.token_cmd must not be blank",
+        "EmptyGitHubToken": lambda d: (
+            "github.token must be a token or a non-empty list of tokens without blank entries"
+        ),
+        "InvalidGitHubApiUrl": _fmt('github.api_url must be an http(s) URL, got "{url}"'),
+        "InvalidArchiveRetention": _fmt(
+            "github.archive.retention must be at least 1, got {count}"
+        ),
+        "InvalidArchiveExclude": _fmt(
+            'github.archive.exclude entries must be one of {allowed}, got "{value}"'
+        ),
+        "EmptyTopic": lambda d: "github.clone.topics/ignored_topics must not contain blanks",
+        "EmptyLanguage": lambda d: (
+            "github.clone.languages/ignored_languages must not contain blanks"
+        ),
+        "InvalidForkMode": _fmt(
+            'github.clone.forks must be one of {allowed}, got "{mode}"'
+        ),
+        "InvalidCloneProtocol": _fmt(
+            'github.clone.protocol must be one of {allowed}, got "{protocol}"'
+        ),
         "NoGitHubCloneOptionsEnabled": lambda d: (
             "github.clone has no clone options enabled "
-            "(set starred, watched, gists, or repos)"
+            "(set starred, watched, gists, gists_starred, or repos)"
         ),
         "EmptyGitRepoName": _fmt("git.repos has an entry with a blank name (url: {url})"),
         "InvalidRepositoryName": _fmt(
@@ -197,11 +598,57 @@ def _build_message_map() -> dict[str, Callable[[dict[str, Any]], str]]:
         ),
         "EmptyGitRepoUrl": _fmt('git.repos "{name}" has a blank URL'),
         "InvalidGitUrl": _fmt('git.repos "{name}" has an invalid URL: {url}'),
+        "EmptyGitRepoUsername": _fmt('git.repos "{name}" has a blank username'),
+        "MissingGitRepoPassword": _fmt(
+            'git.repos "{name}" needs password_env or password_file with its username'
+        ),
+        "ConflictingGitRepoPassword": _fmt(
+            'git.repos "{name}": password_env and password_file are mutually exclusive'
+        ),
+        "EmptyGitRepoRefspecs": _fmt(
+            'git.repos "{name}" has an empty refspecs list (omit it to use fetch.refspecs)'
+        ),
+        "InvalidGitRepoRefspec": _fmt(
+            'git.repos "{name}" refspec "{refspec}" is not a refspec such as '
+            '"+refs/heads/main:refs/heads/main"'
+        ),
+        "InvalidGitRepoPasswordEnv": _fmt(
+            'git.repos "{name}" password_env must be an environment variable name, '
+            'got "{env}"'
+        ),
+        "GitRepoCredentialsNeedHttp": _fmt(
+            'git.repos "{name}" has a username/password but is not an http(s) URL: {url}'
+        ),
         "CertFileNotFound": _fmt("ssl.cert_file not found: {path}"),
+        "EmptySshKeyFile": lambda d: "ssh.key_file must not be blank",
+        "EmptySshPassphraseFile": lambda d: "ssh.passphrase_file must not be blank",
+        "InvalidSshPassphraseEnv": _fmt(
+            'ssh.passphrase_env must be an environment variable name, got "{name}"'
+        ),
+        "EmptySshAgentSocket": lambda d: "ssh.agent_socket must not be blank",
+        "ConflictingSshPassphrase": lambda d: (
+            "ssh.passphrase_file and ssh.passphrase_env are mutually exclusive"
+        ),
         "InvalidWorkerCount": _fmt(
             "parallelism.workers must be at least 1, got {count}"
         ),
         "TooManyWorkers": _fmt("parallelism.workers must be at most 32, got {count}"),
+        "InvalidRuntimeWorkers": _fmt(
+            "runtime.workers must be between 0 (auto) and 32, got {count}"
+        ),
+        "InvalidRuntimeDuration": _fmt(
+            'runtime.{field} must be a duration such as "90s", "10m" or "1h30m", got {value!r}'
+        ),
+        "InvalidRuntimeCron": _fmt("runtime.cron is not a valid cron expression: {reason}"),
+        "ConflictingRuntimeSchedule": lambda d: (
+            "runtime: interval and cron are mutually exclusive"
+        ),
+        "InvalidRetryAttempts": _fmt("runtime.retry_attempts must be at least 1, got {count}"),
+        "InvalidRetryBackoff": _fmt(
+            "runtime.retry_backoff must be one of "
+            + ", ".join(RETRY_BACKOFFS)
+            + ", got {backoff!r}"
+        ),
         "InvalidProgressInterval": _fmt(
             "parallelism.progress_interval_ms must be at least 100 ms, got {interval}"
         ),
@@ -218,6 +665,13 @@ def _build_message_map() -> dict[str, Callable[[dict[str, Any]], str]]:
             'metrics.format must be one of "console", "json", "csv", got "{format}"'
         ),
         "EmptyMetricsExportPath": lambda d: "metrics.export_path must not be blank",
+        "InvalidPushgatewayUrl": _fmt(
+            'metrics.pushgateway_url must be an http(s) URL, got "{url}"'
+        ),
+        "EmptyPushgatewayJob": lambda d: "metrics.pushgateway_job must not be blank",
+        "InvalidHealthCheckPingUrl": _fmt(
+            'health_check.ping_url must be an http(s) URL, got "{url}"'
+        ),
         "EmptyTelegramChatId": lambda d: "telegram.chat_id must not be empty",
         "InvalidTelegramProgressStep": _fmt(
             "telegram.notify_progress_step_percent must be between 1 and 100, got {step}"
@@ -234,6 +688,31 @@ def _build_message_map() -> dict[str, Callable[[dict[str, Any]], str]]:
         "InvalidLowSpeedTime": _fmt(
             "http.low_speed_time must be at least 1 second, got {time}"
         ),
+        "EmptyUserAgent": lambda d: "http.user_agent must not be blank",
+        "InvalidHttpHeader": _fmt(
+            'http.headers "{name}" is not a valid custom header '
+            "(token characters only, no line breaks, not Authorization/User-Agent)"
+        ),
+        "EmptyOverrideBranches": _fmt(
+            'overrides pattern "{pattern}" has an empty branches list (omit it to mirror all)'
+        ),
+        "InvalidOverrideBranch": _fmt(
+            'overrides pattern "{pattern}" branch "{branch}" must be a branch name or glob '
+            '(without "refs/heads/")'
+        ),
+        "InvalidCloneFilter": _fmt(
+            '{field} must be a partial clone filter such as "blob:none", '
+            '"blob:limit=1m" or "tree:0", got "{filter}"'
+        ),
+        "InvalidDepth": _fmt("{field} must be >= 0 (0 keeps all history), got {depth}"),
+        "InvalidFetchTags": _fmt('fetch.tags must be one of {allowed}, got "{tags}"'),
+        "InvalidFetchRefspec": _fmt(
+            'fetch.refspecs entry "{refspec}" is not a refspec such as "+refs/*:refs/*"'
+        ),
+        "InvalidPackThreads": _fmt("transfer.pack_threads must be >= 0, got {count}"),
+        "InvalidWindowMemory": _fmt(
+            'transfer.window_memory must be a git size such as "256m" or "1g", got "{size}"'
+        ),
         "InvalidLargeRepoThreshold": _fmt(
             "large_repos.size_threshold_kb must be at least 1024 KB, got {threshold}"
         ),
@@ -262,6 +741,13 @@ def _build_message_map() -> dict[str, Callable[[dict[str, Any]], str]]:
         "InvalidRepackWindow": _fmt(
             "maintenance.repack_window must be at least 1, got {window}"
         ),
+        "InvalidGcInterval": _fmt(
+            "maintenance.gc_every must be >= 0 (0 disables the periodic gc), got {count}"
+        ),
+        "InvalidGcPrune": _fmt(
+            'maintenance.gc_prune must be a git date such as "2.weeks.ago" or "now", '
+            'got "{prune}"'
+        ),
         "InvalidRepackDepth": _fmt(
             "maintenance.repack_depth must be at least 1, got {depth}"
         ),
@@ -270,6 +756,125 @@ def _build_message_map() -> dict[str, Callable[[dict[str, Any]], str]]:
         ),
         "EmptyQdrantUrl": lambda d: "search.qdrant_url must not be blank",
         "EmptyCollectionName": lambda d: "search.collection_name must not be blank",
+        "EmptyReportsDirectory": lambda d: "reports.directory must not be blank",
+        "EmptyBundleDirectory": lambda d: "bundle.directory must not be blank",
+        "InvalidBundleFullEvery": _fmt("bundle.full_every must not be negative, got {count}"),
+        "EmptyTarballDirectory": lambda d: "tarball.directory must not be blank",
+        "InvalidTarballKeep": _fmt("tarball.keep must not be negative, got {count}"),
+        "EmptySnapshotsDirectory": lambda d: "snapshots.directory must not be blank",
+        "InvalidSnapshotsMode": _fmt(
+            "snapshots.mode must be one of " + ", ".join(SNAPSHOT_MODES) + ", got {mode!r}"
+        ),
+        "InvalidSnapshotsInterval": _fmt(
+            "snapshots.interval_hours must not be negative, got {hours}"
+        ),
+        "InvalidSnapshotsKeep": _fmt("snapshots.{field} must not be negative, got {count}"),
+        "NoSnapshotsKept": lambda d: (
+            "snapshots: set at least one of keep_daily, keep_weekly, keep_monthly, keep_yearly"
+        ),
+        "InvalidOffsiteBackend": _fmt(
+            "offsite.backend must be one of " + ", ".join(OFFSITE_BACKENDS) + ", got {backend!r}"
+        ),
+        "InvalidOffsitePath": _fmt(
+            "offsite.paths entries must be relative paths inside the destination, got {path!r}"
+        ),
+        "EmptyS3Bucket": lambda d: "offsite.s3.bucket must be set for the s3 backend",
+        "InvalidS3PartSize": _fmt(
+            "offsite.s3.{field} must be at least " + str(S3_MIN_PART_MB) + " (MB), got {size}"
+        ),
+        "InvalidS3Attempts": _fmt("offsite.s3.max_attempts must be at least 1, got {count}"),
+        "InvalidWebDavUrl": _fmt("offsite.webdav.url must be an http(s) URL, got {url!r}"),
+        "ConflictingWebDavPassword": lambda d: (
+            "offsite.webdav: password_env and password_file are mutually exclusive"
+        ),
+        "InvalidWebDavPasswordEnv": _fmt(
+            "offsite.webdav.password_env must be an environment variable name, got {env!r}"
+        ),
+        "InvalidWebDavAttempts": _fmt(
+            "offsite.webdav.max_attempts must be at least 1, got {count}"
+        ),
+        "EmptyRcloneRemote": lambda d: 'rclone.remote must be set, e.g. "b2:bucket/gitout"',
+        "InvalidRcloneMode": _fmt(
+            "rclone.mode must be one of " + ", ".join(RCLONE_MODES) + ", got {mode!r}"
+        ),
+        "EmptyRcloneFilter": _fmt("rclone.{field} entries must not be blank"),
+        "InvalidRcloneTimeout": _fmt("rclone.timeout_seconds must be positive, got {seconds}"),
+        "EmptyReplicateTargets": lambda d: "replicate.targets must list at least one target",
+        "InvalidReplicateTarget": _fmt(
+            'replicate.targets entries must be "[user@]host:path" SSH targets, got {target!r}'
+        ),
+        "EmptyReplicateSshCommand": lambda d: "replicate.ssh_command must not be blank",
+        "InvalidReplicateBandwidth": _fmt(
+            "replicate.bandwidth_limit_kbps must not be negative, got {kbps}"
+        ),
+        "InvalidReplicateTimeout": _fmt(
+            "replicate.timeout_seconds must be positive, got {seconds}"
+        ),
+        "InvalidPushMirrorUrl": _fmt(
+            "push_mirror.url must be a git URL template using only {{name}}, {{owner}}, "
+            "{{repo}} and {{path}}, got {url!r}"
+        ),
+        "PushMirrorPasswordWithoutUsername": lambda d: (
+            "push_mirror: password_env/password_file need a username"
+        ),
+        "ConflictingPushMirrorPassword": lambda d: (
+            "push_mirror: password_env and password_file are mutually exclusive"
+        ),
+        "InvalidPushMirrorRefspec": _fmt("push_mirror.refspecs has an invalid refspec {refspec!r}"),
+        "InvalidTracingEndpoint": _fmt('tracing.endpoint must be an http(s) URL, got "{url}"'),
+        "EmptyTracingServiceName": lambda d: "tracing.service_name must not be blank",
+        "InvalidTracingTimeout": _fmt(
+            "tracing.timeout_seconds must be greater than 0, got {seconds}"
+        ),
+        "InvalidWebhookUrl": _fmt('webhooks.url must be an http(s) URL, got "{url}"'),
+        "InvalidWebhookEvent": _fmt(
+            'webhooks "{url}" events must be among {allowed}, got "{event}"'
+        ),
+        "InvalidWebhookTemplate": _fmt('webhooks "{url}" template is invalid: {reason}'),
+        "InvalidWebhookTimeout": _fmt(
+            'webhooks "{url}" timeout_seconds must be greater than 0, got {seconds}'
+        ),
+        "EmptyEmailServer": lambda d: "notify.email.server must not be blank",
+        "InvalidEmailPort": _fmt("notify.email.port must be between 1 and 65535, got {port}"),
+        "InvalidEmailSecurity": _fmt(
+            'notify.email.security must be one of {allowed}, got "{security}"'
+        ),
+        "InvalidEmailAddress": _fmt('notify.email.{field} is not an email address: "{address}"'),
+        "EmptyEmailRecipients": lambda d: "notify.email.recipients must list at least one address",
+        "EmailPasswordWithoutUsername": lambda d: (
+            "notify.email: password_env/password_file need a username"
+        ),
+        "ConflictingEmailPassword": lambda d: (
+            "notify.email: password_env and password_file are mutually exclusive"
+        ),
+        "InvalidEmailTimeout": _fmt(
+            "notify.email.timeout_seconds must be greater than 0, got {seconds}"
+        ),
+        "InvalidChatWebhookUrl": _fmt(
+            'notify.{service}.webhook_url must be an http(s) URL, got "{url}"'
+        ),
+        "InvalidChatTimeout": _fmt(
+            "notify.{service}.timeout_seconds must be greater than 0, got {seconds}"
+        ),
+        "InvalidFanoutDestination": _fmt(
+            "fanout.destinations entries must be absolute paths, got {path!r}"
+        ),
+        "DuplicateFanoutDestination": _fmt("fanout.destinations lists {path!r} twice"),
+        "InvalidReportsRetention": _fmt(
+            "reports.retention must be at least 1, got {count}"
+        ),
+        "InvalidGroupName": _fmt(
+            'groups "{name}" is not a valid group name (letters, digits, "-" and "_")'
+        ),
+        "EmptyGroup": _fmt('groups "{name}" has no repos'),
+        "InvalidPattern": _fmt('{field} has an invalid regular expression "{pattern}"'),
+        "EmptyOverridePattern": lambda d: "overrides has an entry with a blank pattern",
+        "InvalidSinceDate": _fmt(
+            'overrides pattern "{pattern}" since must be a YYYY-MM-DD date, got "{since}"'
+        ),
+        "DepthWithSince": _fmt(
+            'overrides pattern "{pattern}" sets both depth and since; git accepts only one'
+        ),
     }
 
 
@@ -303,9 +908,55 @@ def _known_kwargs(cls: type, data: dict[str, Any]) -> dict[str, Any]:
     return {k: v for k, v in data.items() if k in names}
 
 
-def parse(toml_text: str) -> Config:
-    """Parse TOML into a :class:`Config`, ignoring unknown keys (lenient, like ktoml)."""
-    raw = tomllib.loads(toml_text)
+CONFIG_FORMATS = ("toml", "json", "yaml")
+
+
+def format_for_path(source: str) -> str:
+    """Config format from a path or URL extension (``.json``, ``.yaml``/``.yml``, else TOML).
+
+    For ``git+<url>#<path>[@<ref>]`` sources the in-repo path decides.
+    """
+    if "#" in source:
+        name = source.split("#", 1)[1].partition("@")[0]
+    else:
+        name = source.split("?", 1)[0]
+    suffix = PurePosixPath(name).suffix.lower()
+    if suffix == ".json":
+        return "json"
+    if suffix in (".yaml", ".yml"):
+        return "yaml"
+    return "toml"
+
+
+def _load_raw(text: str, fmt: str) -> dict[str, Any]:
+    if fmt == "toml":
+        return tomllib.loads(text)
+    if fmt == "json":
+        raw = json.loads(text)
+    elif fmt == "yaml":
+        try:
+            import yaml
+        except ImportError as exc:
+            raise ValueError("YAML configs need PyYAML (pip install 'gitout[yaml]')") from exc
+        try:
+            raw = yaml.safe_load(text)
+        except yaml.YAMLError as exc:
+            raise ValueError(f"Invalid YAML config: {exc}") from exc
+    else:
+        raise ValueError(f"Unknown config format {fmt!r} (expected one of {CONFIG_FORMATS})")
+    if raw is None:
+        return {}
+    if not isinstance(raw, dict):
+        raise ValueError(f"The {fmt.upper()} config must be a mapping at the top level")
+    return raw
+
+
+def parse(text: str, fmt: str = "toml") -> Config:
+    """Parse a TOML (or ``fmt``) document into a :class:`Config`, ignoring unknown keys.
+
+    Unknown keys are dropped (lenient, like ktoml).
+    """
+    raw = _load_raw(text, fmt)
 
     github: GitHubConfig | None = None
     gh = raw.get("github")
@@ -313,8 +964,13 @@ def parse(toml_text: str) -> Config:
         github = GitHubConfig(
             user=gh.get("user", ""),
             token=gh.get("token"),
+            token_file=gh.get("token_file"),
+            token_cmd=gh.get("token_cmd"),
+            api_url=gh.get("api_url", GitHubConfig.api_url),
             archive=GitHubArchive(**_known_kwargs(GitHubArchive, gh.get("archive", {}))),
             clone=GitHubClone(**_known_kwargs(GitHubClone, gh.get("clone", {}))),
+            metadata=GitHubMetadata(**_known_kwargs(GitHubMetadata, gh.get("metadata", {}))),
+            app=GitHubApp(**_known_kwargs(GitHubApp, gh["app"])) if "app" in gh else None,
         )
 
     parallelism_raw = raw.get("parallelism", {})
@@ -337,13 +993,38 @@ def parse(toml_text: str) -> Config:
             ]
         telegram = Telegram(**telegram_kwargs)
 
+    offsite: Offsite | None = None
+    off = raw.get("offsite")
+    if off is not None:
+        offsite_kwargs = _known_kwargs(Offsite, off)
+        offsite_kwargs["s3"] = S3Storage(**_known_kwargs(S3Storage, off.get("s3", {})))
+        offsite_kwargs["webdav"] = WebDav(**_known_kwargs(WebDav, off.get("webdav", {})))
+        offsite = Offsite(**offsite_kwargs)
+
+    rclone_raw = raw.get("rclone")
+    rclone = Rclone(**_known_kwargs(Rclone, rclone_raw)) if rclone_raw is not None else None
+    push_raw = raw.get("push_mirror")
+    push_mirror = (
+        PushMirror(**_known_kwargs(PushMirror, push_raw)) if push_raw is not None else None
+    )
+    tracing_raw = raw.get("tracing")
+    tracing = Tracing(**_known_kwargs(Tracing, tracing_raw)) if tracing_raw is not None else None
+    replicate_raw = raw.get("replicate")
+    replicate = (
+        Replicate(**_known_kwargs(Replicate, replicate_raw)) if replicate_raw is not None else None
+    )
+
     return Config(
         version=raw.get("version", 0),
         github=github,
-        git=GitConfig(repos=dict(raw.get("git", {}).get("repos", {}))),
+        git=_parse_git(raw.get("git", {})),
         ssl=Ssl(**_known_kwargs(Ssl, raw.get("ssl", {}))),
-        http=Http(**_known_kwargs(Http, raw.get("http", {}))),
+        ssh=Ssh(**_known_kwargs(Ssh, raw.get("ssh", {}))),
+        http=_parse_http(raw.get("http", {})),
+        transfer=Transfer(**_known_kwargs(Transfer, raw.get("transfer", {}))),
+        fetch=Fetch(**_known_kwargs(Fetch, raw.get("fetch", {}))),
         parallelism=parallelism,
+        runtime=Runtime(**_known_kwargs(Runtime, raw.get("runtime", {}))),
         metrics=Metrics(**_known_kwargs(Metrics, raw.get("metrics", {}))),
         telegram=telegram,
         large_repos=LargeRepoConfig(**_known_kwargs(LargeRepoConfig, raw.get("large_repos", {}))),
@@ -357,6 +1038,112 @@ def parse(toml_text: str) -> Config:
         lfs=Lfs(**_known_kwargs(Lfs, raw.get("lfs", {}))),
         exit_on_failure=raw.get("exit_on_failure", True),
         search=Search(**_known_kwargs(Search, raw.get("search", {}))),
+        reports=Reports(**_known_kwargs(Reports, raw.get("reports", {}))),
+        bundle=Bundle(**_known_kwargs(Bundle, raw.get("bundle", {}))),
+        tarball=Tarball(**_known_kwargs(Tarball, raw.get("tarball", {}))),
+        snapshots=Snapshots(**_known_kwargs(Snapshots, raw.get("snapshots", {}))),
+        offsite=offsite,
+        rclone=rclone,
+        replicate=replicate,
+        fanout=Fanout(**_known_kwargs(Fanout, raw.get("fanout", {}))),
+        push_mirror=push_mirror,
+        tracing=tracing,
+        webhooks=[Webhook(**_known_kwargs(Webhook, w)) for w in raw.get("webhooks", [])],
+        notify=_parse_notify(raw.get("notify", {})),
+        overrides=[
+            RepoOverride(**_known_kwargs(RepoOverride, o)) for o in raw.get("overrides", [])
+        ],
+        groups={
+            str(name): RepoGroup(**_known_kwargs(RepoGroup, group))
+            for name, group in raw.get("groups", {}).items()
+        },
+    )
+
+
+# Keys a [git.repos] entry in table form may have besides GitRepoCredentials' fields.
+_GIT_REPO_TABLE_KEYS = frozenset({"url", "refspecs"})
+
+
+def _dataclass_of(hint: Any) -> type | None:
+    """The dataclass in a field type such as ``Offsite | None``, if any."""
+    candidates = get_args(hint) if isinstance(hint, UnionType) else (hint,)
+    for candidate in candidates:
+        if is_dataclass(candidate):
+            return candidate if isinstance(candidate, type) else None
+    return None
+
+
+def _unknown_in(cls: type, data: dict[str, Any], path: str) -> list[str]:
+    hints = get_type_hints(cls)
+    found: list[str] = []
+    for key, value in data.items():
+        where = f"{path}.{key}" if path else str(key)
+        if key not in hints:
+            found.append(where)
+        elif cls is GitConfig and key == "repos" and isinstance(value, dict):
+            allowed = _GIT_REPO_TABLE_KEYS | {f.name for f in fields(GitRepoCredentials)}
+            for name, entry in value.items():
+                if isinstance(entry, dict):
+                    found += [f"{where}.{name}.{k}" for k in entry if k not in allowed]
+        elif (nested := _dataclass_of(hints[key])) is not None and isinstance(value, dict):
+            found += _unknown_in(nested, value, where)
+        elif get_origin(hints[key]) is list and isinstance(value, list):
+            item = _dataclass_of(get_args(hints[key])[0])
+            for index, entry in enumerate(value):
+                if item is not None and isinstance(entry, dict):
+                    found += _unknown_in(item, entry, f"{where}[{index}]")
+        elif get_origin(hints[key]) is dict and isinstance(value, dict):
+            item = _dataclass_of(get_args(hints[key])[1])
+            for name, entry in value.items():
+                if item is not None and isinstance(entry, dict):
+                    found += _unknown_in(item, entry, f"{where}.{name}")
+    return found
+
+
+def unknown_keys(text: str, fmt: str = "toml") -> list[str]:
+    """Dotted paths of the keys :func:`parse` ignores, e.g. ``github.clone.startred``."""
+    return _unknown_in(Config, _load_raw(text, fmt), "")
+
+
+def _parse_git(data: dict[str, Any]) -> GitConfig:
+    """``[git]``; a ``repos`` entry is a URL or a table with ``url``, a login and
+    ``refspecs``."""
+    repos: dict[str, str] = {}
+    credentials: dict[str, GitRepoCredentials] = {}
+    refspecs: dict[str, list[str]] = {}
+    for name, entry in data.get("repos", {}).items():
+        if isinstance(entry, dict):
+            repos[name] = entry.get("url", "")
+            login = _known_kwargs(GitRepoCredentials, entry)
+            if login:
+                credentials[name] = GitRepoCredentials(**login)
+            if "refspecs" in entry:
+                refspecs[name] = entry["refspecs"]
+        else:
+            repos[name] = entry
+    return GitConfig(
+        repos=repos,
+        skip_unchanged=data.get("skip_unchanged", False),
+        credentials=credentials,
+        refspecs=refspecs,
+    )
+
+
+def _parse_http(data: dict[str, Any]) -> Http:
+    http_kwargs = _known_kwargs(Http, data)
+    if "headers" in http_kwargs:
+        http_kwargs["headers"] = {str(k): str(v) for k, v in http_kwargs["headers"].items()}
+    return Http(**http_kwargs)
+
+
+def _parse_notify(data: dict[str, Any]) -> Notify:
+    email = data.get("email")
+    slack = data.get("slack")
+    discord = data.get("discord")
+    return Notify(
+        email=EmailNotify(**_known_kwargs(EmailNotify, email)) if email is not None else None,
+        slack=ChatNotify(**_known_kwargs(ChatNotify, slack)) if slack is not None else None,
+        discord=ChatNotify(**_known_kwargs(ChatNotify, discord)) if discord is not None else None,
     )
 
 
@@ -368,16 +1155,75 @@ def to_normalized_dict(config: Config) -> dict[str, Any]:
 _GIT_URL_RE = re.compile(r"^(https?://|git@|git://|ssh://|file://).*")
 _SCP_URL_RE = re.compile(r"^[\w.-]+@[\w.-]+:.*")
 _REPO_NAME_RE = re.compile(r"^[a-zA-Z0-9._/-]+$")
+_HEADER_NAME_RE = re.compile(r"^[!#$%&'*+.^_`|~0-9A-Za-z-]+$")
+_RESERVED_HEADERS = frozenset({"authorization", "user-agent"})
+_GIT_SIZE_RE = re.compile(r"^\d+[kKmMgG]?$")
+# git's --filter specs (see git rev-list --filter); combine: is passed through as is.
+_CLONE_FILTER_RE = re.compile(
+    r"^(blob:none|blob:limit=\d+[kKmMgG]?|tree:\d+|object:type=(blob|tree|commit|tag)"
+    r"|sparse:oid=\S+|combine:\S+)$"
+)
+# A branch name or glob below refs/heads/ (no whitespace, ":" or leading "refs/").
+_BRANCH_RE = re.compile(r"^(?!refs/)(?!-)[^\s:^~?\\]+$")
+# [+]<src>[:<dst>] or a negative ^<src>; no whitespace.
+_REFSPEC_RE = re.compile(r"^(\+?[^\s:^+][^\s:]*(:[^\s:]*)?|\^[^\s:]+)$")
+_GROUP_NAME_RE = re.compile(r"^[A-Za-z0-9_-]+$")
+# rsync over ssh: "[user@]host:path" (a single colon; "::" would be an rsync daemon).
+_RSYNC_SSH_TARGET_RE = re.compile(r"^(?:[\w.-]+@)?[\w.-]+:(?!:)\S.*$")
+_ENV_NAME_RE = re.compile(r"^[A-Za-z_][A-Za-z0-9_]*$")
+_DATE_RE = re.compile(r"^\d{4}-\d{2}-\d{2}$")
+_EMAIL_RE = re.compile(r"^[^@\s<>]+@[^@\s<>]+\.[^@\s<>]+$")
 
 
 def _blank(value: str) -> bool:
     return not value.strip()
 
 
+def _is_duration(value: str) -> bool:
+    try:
+        parse_duration(value)
+    except ValueError:
+        return False
+    return True
+
+
+def is_valid_pattern(pattern: str) -> bool:
+    if not pattern.startswith(REGEX_PREFIX):
+        return True
+    try:
+        re.compile(pattern.removeprefix(REGEX_PREFIX))
+    except re.error:
+        return False
+    return True
+
+
 def _is_valid_git_url(url: str) -> bool:
     return bool(_GIT_URL_RE.match(url) or _SCP_URL_RE.match(url))
 
 
+def _is_valid_push_url(template: str) -> bool:
+    """A git URL once its {name}/{owner}/{repo}/{path} fields are filled in."""
+    try:
+        fields = {f for _, f, _, _ in string.Formatter().parse(template) if f is not None}
+        if not fields <= {"name", "owner", "repo", "path"}:
+            return False
+        url = template.format(name="o/r", owner="o", repo="r", path="git/o/r")
+    except (ValueError, IndexError):
+        return False
+    return _is_valid_git_url(url)
+
+
+def _webhook_template_error(template: str) -> str | None:
+    """Why a [[webhooks]] template cannot be filled in, or None when it can."""
+    parsed = string.Template(template)
+    if not parsed.is_valid():
+        return 'a "$" must start a placeholder (write "$$" for a literal "$")'
+    unknown = sorted(set(parsed.get_identifiers()) - WEBHOOK_PLACEHOLDERS)
+    if unknown:
+        return "unknown placeholder " + ", ".join(f"${name}" for name in unknown)
+    return None
+
+
 def _is_valid_repository_name(name: str) -> bool:
     if ".." in name:
         return False
@@ -388,6 +1234,22 @@ def _is_valid_repository_name(name: str) -> bool:
     return bool(_REPO_NAME_RE.match(name))
 
 
+def _is_valid_date(value: str) -> bool:
+    if not _DATE_RE.match(value):
+        return False
+    try:
+        date.fromisoformat(value)
+    except ValueError:
+        return False
+    return True
+
+
+def _is_valid_header(name: str, value: str) -> bool:
+    if not _HEADER_NAME_RE.match(name) or name.lower() in _RESERVED_HEADERS:
+        return False
+    return "\r" not in value and "\n" not in value
+
+
 def validate(config: Config) -> list[ValidationError]:
     """Return validation errors for ``config`` (empty list when valid)."""
     errors: list[ValidationError] = []
@@ -402,8 +1264,43 @@ def validate(config: Config) -> list[ValidationError]:
     if gh is not None:
         if _blank(gh.user):
             err("EmptyGitHubUser")
+        if isinstance(gh.token, list) and (
+            not gh.token or any(not isinstance(t, str) or _blank(t) for t in gh.token)
+        ):
+            err("EmptyGitHubToken")
+        if gh.token_file is not None and _blank(gh.token_file):
+            err("EmptyGitHubTokenFile")
+        if gh.token_cmd is not None and _blank(gh.token_cmd):
+            err("EmptyGitHubTokenCmd")
+        if gh.app is not None:
+            if gh.app.id < 1:
+                err("InvalidGitHubAppId", id=gh.app.id)
+            if _blank(gh.app.private_key_file):
+                err("EmptyGitHubAppKey")
+            if gh.app.installation_id is not None and gh.app.installation_id < 1:
+                err("InvalidGitHubInstallationId", id=gh.app.installation_id)
+        if not gh.api_url.startswith(("https://", "http://")):
+            err("InvalidGitHubApiUrl", url=gh.api_url)
+        if gh.archive.retention is not None and gh.archive.retention < 1:
+            err("InvalidArchiveRetention", count=gh.archive.retention)
+        for value in gh.archive.exclude:
+            if value not in MIGRATION_EXCLUDES:
+                err("InvalidArchiveExclude", value=value, allowed=", ".join(MIGRATION_EXCLUDES))
         c = gh.clone
-        if not c.starred and not c.watched and not c.gists and not c.repos:
+        for pattern in c.ignore:
+            if not is_valid_pattern(pattern):
+                err("InvalidPattern", field="github.clone.ignore", pattern=pattern)
+        for topic in [*c.topics, *c.ignored_topics]:
+            if _blank(topic):
+                err("EmptyTopic")
+        for language in [*c.languages, *c.ignored_languages]:
+            if _blank(language):
+                err("EmptyLanguage")
+        if c.forks not in FORK_MODES:
+            err("InvalidForkMode", mode=c.forks, allowed=", ".join(FORK_MODES))
+        if c.protocol not in CLONE_PROTOCOLS:
+            err("InvalidCloneProtocol", protocol=c.protocol, allowed=", ".join(CLONE_PROTOCOLS))
+        if not (c.starred or c.watched or c.gists or c.gists_starred or c.repos):
             err("NoGitHubCloneOptionsEnabled")
 
     for name, url in config.git.repos.items():
@@ -415,11 +1312,41 @@ def validate(config: Config) -> list[ValidationError]:
             err("EmptyGitRepoUrl", name=name)
         if not _is_valid_git_url(url):
             err("InvalidGitUrl", name=name, url=url)
+    for name, login in config.git.credentials.items():
+        if _blank(login.username):
+            err("EmptyGitRepoUsername", name=name)
+        if login.password_env is None and login.password_file is None:
+            err("MissingGitRepoPassword", name=name)
+        elif login.password_env is not None and login.password_file is not None:
+            err("ConflictingGitRepoPassword", name=name)
+        if login.password_env is not None and not _ENV_NAME_RE.match(login.password_env):
+            err("InvalidGitRepoPasswordEnv", name=name, env=login.password_env)
+        url = config.git.repos.get(name, "")
+        if not url.startswith(("https://", "http://")):
+            err("GitRepoCredentialsNeedHttp", name=name, url=url)
+    for name, specs in config.git.refspecs.items():
+        if not specs:
+            err("EmptyGitRepoRefspecs", name=name)
+        for refspec in specs:
+            if not isinstance(refspec, str) or not _REFSPEC_RE.match(refspec):
+                err("InvalidGitRepoRefspec", name=name, refspec=refspec)
 
     cert = config.ssl.cert_file
     if cert is not None and not _blank(cert) and not Path(cert).exists():
         err("CertFileNotFound", path=cert)
 
+    ssh = config.ssh
+    if ssh.key_file is not None and _blank(ssh.key_file):
+        err("EmptySshKeyFile")
+    if ssh.passphrase_file is not None and _blank(ssh.passphrase_file):
+        err("EmptySshPassphraseFile")
+    if ssh.passphrase_env is not None and not _ENV_NAME_RE.match(ssh.passphrase_env):
+        err("InvalidSshPassphraseEnv", name=ssh.passphrase_env)
+    if ssh.passphrase_file is not None and ssh.passphrase_env is not None:
+        err("ConflictingSshPassphrase")
+    if ssh.agent_socket is not None and _blank(ssh.agent_socket):
+        err("EmptySshAgentSocket")
+
     p = config.parallelism
     if p.workers < 1:
         err("InvalidWorkerCount", count=p.workers)
@@ -435,10 +1362,37 @@ def validate(config: Config) -> list[ValidationError]:
         if pattern.timeout is not None and pattern.timeout < 1:
             err("InvalidPriorityTimeout", pattern=pattern.pattern, timeout=pattern.timeout)
 
+    rt = config.runtime
+    if rt.workers is not None and not 0 <= rt.workers <= 32:
+        err("InvalidRuntimeWorkers", count=rt.workers)
+    for name in ("interval", "timeout", "retry_delay"):
+        value = getattr(rt, name)
+        if value is not None and not _is_duration(value):
+            err("InvalidRuntimeDuration", field=name, value=value)
+    if rt.cron is not None:
+        try:
+            CronExpression.parse(rt.cron)
+        except ValueError as exc:
+            err("InvalidRuntimeCron", reason=str(exc))
+        if rt.interval is not None:
+            err("ConflictingRuntimeSchedule")
+    if rt.retry_attempts < 1:
+        err("InvalidRetryAttempts", count=rt.retry_attempts)
+    if rt.retry_backoff not in RETRY_BACKOFFS:
+        err("InvalidRetryBackoff", backoff=rt.retry_backoff)
+
     if config.metrics.format not in ("console", "json", "csv"):
         err("InvalidMetricsFormat", format=config.metrics.format)
     if config.metrics.export_path is not None and _blank(config.metrics.export_path):
         err("EmptyMetricsExportPath")
+    gateway = config.metrics.pushgateway_url
+    if gateway is not None and not gateway.startswith(("https://", "http://")):
+        err("InvalidPushgatewayUrl", url=gateway)
+    if _blank(config.metrics.pushgateway_job):
+        err("EmptyPushgatewayJob")
+    ping_url = config.health_check.ping_url
+    if ping_url is not None and not ping_url.startswith(("https://", "http://")):
+        err("InvalidHealthCheckPingUrl", url=ping_url)
 
     tg = config.telegram
     if tg is not None:
@@ -456,6 +1410,27 @@ def validate(config: Config) -> list[ValidationError]:
         err("InvalidLowSpeedLimit", limit=h.low_speed_limit)
     if h.low_speed_time < 1:
         err("InvalidLowSpeedTime", time=h.low_speed_time)
+    if h.user_agent is not None and _blank(h.user_agent):
+        err("EmptyUserAgent")
+    for name, value in h.headers.items():
+        if not _is_valid_header(name, value):
+            err("InvalidHttpHeader", name=name)
+
+    t = config.transfer
+    if t.pack_threads is not None and t.pack_threads < 0:
+        err("InvalidPackThreads", count=t.pack_threads)
+    if t.window_memory is not None and not _GIT_SIZE_RE.match(t.window_memory):
+        err("InvalidWindowMemory", size=t.window_memory)
+
+    if config.fetch.filter is not None and not _CLONE_FILTER_RE.match(config.fetch.filter):
+        err("InvalidCloneFilter", field="fetch.filter", filter=config.fetch.filter)
+    if config.fetch.depth < 0:
+        err("InvalidDepth", field="fetch.depth", depth=config.fetch.depth)
+    if config.fetch.tags not in FETCH_TAGS:
+        err("InvalidFetchTags", tags=config.fetch.tags, allowed=", ".join(FETCH_TAGS))
+    for refspec in config.fetch.refspecs:
+        if not isinstance(refspec, str) or not _REFSPEC_RE.match(refspec):
+            err("InvalidFetchRefspec", refspec=refspec)
 
     lr = config.large_repos
     if lr.size_threshold_kb < 1024:
@@ -482,6 +1457,10 @@ def validate(config: Config) -> list[ValidationError]:
         err("InvalidRepackWindow", window=m.repack_window)
     if m.repack_depth < 1:
         err("InvalidRepackDepth", depth=m.repack_depth)
+    if m.gc_every < 0:
+        err("InvalidGcInterval", count=m.gc_every)
+    if _blank(m.gc_prune) or any(c.isspace() for c in m.gc_prune):
+        err("InvalidGcPrune", prune=m.gc_prune)
 
     s = config.search
     if s.top_k < 1 or s.top_k > 100:
@@ -492,4 +1471,251 @@ def validate(config: Config) -> list[ValidationError]:
         if _blank(s.collection_name):
             err("EmptyCollectionName")
 
+    r = config.reports
+    if _blank(r.directory):
+        err("EmptyReportsDirectory")
+    if r.retention < 1:
+        err("InvalidReportsRetention", count=r.retention)
+
+    if _blank(config.bundle.directory):
+        err("EmptyBundleDirectory")
+    if config.bundle.full_every < 0:
+        err("InvalidBundleFullEvery", count=config.bundle.full_every)
+    if _blank(config.tarball.directory):
+        err("EmptyTarballDirectory")
+    if config.tarball.keep < 0:
+        err("InvalidTarballKeep", count=config.tarball.keep)
+
+    snaps = config.snapshots
+    if _blank(snaps.directory):
+        err("EmptySnapshotsDirectory")
+    if snaps.mode not in SNAPSHOT_MODES:
+        err("InvalidSnapshotsMode", mode=snaps.mode)
+    if snaps.interval_hours < 0:
+        err("InvalidSnapshotsInterval", hours=snaps.interval_hours)
+    keeps = ("keep_daily", "keep_weekly", "keep_monthly", "keep_yearly")
+    for name in keeps:
+        if getattr(snaps, name) < 0:
+            err("InvalidSnapshotsKeep", field=name, count=getattr(snaps, name))
+    if snaps.enabled and not any(getattr(snaps, name) > 0 for name in keeps):
+        err("NoSnapshotsKept")
+
+    off = config.offsite
+    if off is not None:
+        if off.backend not in OFFSITE_BACKENDS:
+            err("InvalidOffsiteBackend", backend=off.backend)
+        for path in off.paths:
+            parts = PurePosixPath(path.strip()).parts
+            if not parts or path.strip().startswith("/") or ".." in parts:
+                err("InvalidOffsitePath", path=path)
+        if off.backend == "s3":
+            if _blank(off.s3.bucket):
+                err("EmptyS3Bucket")
+            for name in ("multipart_threshold_mb", "multipart_chunk_mb"):
+                size = getattr(off.s3, name)
+                if size < S3_MIN_PART_MB:
+                    err("InvalidS3PartSize", field=name, size=size)
+            if off.s3.max_attempts < 1:
+                err("InvalidS3Attempts", count=off.s3.max_attempts)
+        if off.backend == "webdav":
+            dav = off.webdav
+            if not dav.url.startswith(("https://", "http://")):
+                err("InvalidWebDavUrl", url=dav.url)
+            if dav.password_env is not None and dav.password_file is not None:
+                err("ConflictingWebDavPassword")
+            if dav.password_env is not None and not _ENV_NAME_RE.match(dav.password_env):
+                err("InvalidWebDavPasswordEnv", env=dav.password_env)
+            if dav.max_attempts < 1:
+                err("InvalidWebDavAttempts", count=dav.max_attempts)
+
+    rc = config.rclone
+    if rc is not None:
+        if _blank(rc.remote):
+            err("EmptyRcloneRemote")
+        if rc.mode not in RCLONE_MODES:
+            err("InvalidRcloneMode", mode=rc.mode)
+        for name in ("include", "exclude"):
+            if any(_blank(pattern) for pattern in getattr(rc, name)):
+                err("EmptyRcloneFilter", field=name)
+        if rc.timeout_seconds <= 0:
+            err("InvalidRcloneTimeout", seconds=rc.timeout_seconds)
+
+    rep = config.replicate
+    if rep is not None:
+        if not rep.targets:
+            err("EmptyReplicateTargets")
+        for target in rep.targets:
+            if not _RSYNC_SSH_TARGET_RE.match(target):
+                err("InvalidReplicateTarget", target=target)
+        if _blank(rep.ssh_command):
+            err("EmptyReplicateSshCommand")
+        if rep.bandwidth_limit_kbps < 0:
+            err("InvalidReplicateBandwidth", kbps=rep.bandwidth_limit_kbps)
+        if rep.timeout_seconds <= 0:
+            err("InvalidReplicateTimeout", seconds=rep.timeout_seconds)
+
+    push = config.push_mirror
+    if push is not None:
+        if not _is_valid_push_url(push.url):
+            err("InvalidPushMirrorUrl", url=push.url)
+        has_password = push.password_env is not None or push.password_file is not None
+        if has_password and _blank(push.username):
+            err("PushMirrorPasswordWithoutUsername")
+        if push.password_env is not None and push.password_file is not None:
+            err("ConflictingPushMirrorPassword")
+        for refspec in push.refspecs:
+            if not _REFSPEC_RE.match(refspec):
+                err("InvalidPushMirrorRefspec", refspec=refspec)
+
+    tracing = config.tracing
+    if tracing is not None:
+        if not tracing.endpoint.startswith(("https://", "http://")):
+            err("InvalidTracingEndpoint", url=tracing.endpoint)
+        if _blank(tracing.service_name):
+            err("EmptyTracingServiceName")
+        if tracing.timeout_seconds <= 0:
+            err("InvalidTracingTimeout", seconds=tracing.timeout_seconds)
+
+    for hook in config.webhooks:
+        if not hook.url.startswith(("https://", "http://")):
+            err("InvalidWebhookUrl", url=hook.url)
+        for event in hook.events:
+            if event not in WEBHOOK_EVENTS:
+                err(
+                    "InvalidWebhookEvent",
+                    url=hook.url,
+                    event=event,
+                    allowed=", ".join(WEBHOOK_EVENTS),
+                )
+        if hook.template is not None:
+            reason = _webhook_template_error(hook.template)
+            if reason is not None:
+                err("InvalidWebhookTemplate", url=hook.url, reason=reason)
+        if hook.timeout_seconds <= 0:
+            err("InvalidWebhookTimeout", url=hook.url, seconds=hook.timeout_seconds)
+
+    email = config.notify.email
+    if email is not None:
+        if _blank(email.server):
+            err("EmptyEmailServer")
+        if not 0 < email.port < 65536:
+            err("InvalidEmailPort", port=email.port)
+        if email.security not in EMAIL_SECURITY:
+            err(
+                "InvalidEmailSecurity",
+                security=email.security,
+                allowed=", ".join(EMAIL_SECURITY),
+            )
+        if not _EMAIL_RE.match(email.sender):
+            err("InvalidEmailAddress", field="sender", address=email.sender)
+        if not email.recipients:
+            err("EmptyEmailRecipients")
+        for address in email.recipients:
+            if not _EMAIL_RE.match(address):
+                err("InvalidEmailAddress", field="recipients", address=address)
+        has_password = email.password_env is not None or email.password_file is not None
+        if has_password and _blank(email.username):
+            err("EmailPasswordWithoutUsername")
+        if email.password_env is not None and email.password_file is not None:
+            err("ConflictingEmailPassword")
+        if email.timeout_seconds <= 0:
+            err("InvalidEmailTimeout", seconds=email.timeout_seconds)
+
+    for service, chat in (("slack", config.notify.slack), ("discord", config.notify.discord)):
+        if chat is None:
+            continue
+        if not chat.webhook_url.startswith(("https://", "http://")):
+            err("InvalidChatWebhookUrl", service=service, url=chat.webhook_url)
+        if chat.timeout_seconds <= 0:
+            err("InvalidChatTimeout", service=service, seconds=chat.timeout_seconds)
+
+    seen_fanout: set[str] = set()
+    for path in config.fanout.destinations:
+        expanded = str(Path(path.strip()).expanduser()) if path.strip() else ""
+        if not expanded or not Path(expanded).is_absolute():
+            err("InvalidFanoutDestination", path=path)
+        elif expanded.rstrip("/") in seen_fanout:
+            err("DuplicateFanoutDestination", path=path)
+        seen_fanout.add(expanded.rstrip("/"))
+
+    for override in config.overrides:
+        if _blank(override.pattern):
+            err("EmptyOverridePattern")
+        elif not is_valid_pattern(override.pattern):
+            err("InvalidPattern", field="overrides.pattern", pattern=override.pattern)
+        if override.since is not None and not _is_valid_date(override.since):
+            err("InvalidSinceDate", pattern=override.pattern, since=override.since)
+        if override.since is not None and override.depth:
+            err("DepthWithSince", pattern=override.pattern)
+        if override.filter and not _CLONE_FILTER_RE.match(override.filter):
+            field_name = f'overrides "{override.pattern}" filter'
+            err("InvalidCloneFilter", field=field_name, filter=override.filter)
+        if override.branches is not None:
+            if not override.branches:
+                err("EmptyOverrideBranches", pattern=override.pattern)
+            for branch in override.branches:
+                if not isinstance(branch, str) or not _BRANCH_RE.match(branch):
+                    err("InvalidOverrideBranch", pattern=override.pattern, branch=branch)
+        if override.depth is not None and override.depth < 0:
+            field_name = f'overrides "{override.pattern}" depth'
+            err("InvalidDepth", field=field_name, depth=override.depth)
+
+    for name, group in config.groups.items():
+        if not _GROUP_NAME_RE.match(name):
+            err("InvalidGroupName", name=name)
+        if not [pattern for pattern in group.repos if not _blank(pattern)]:
+            err("EmptyGroup", name=name)
+        for pattern in group.repos:
+            if not is_valid_pattern(pattern):
+                err("InvalidPattern", field=f"groups.{name}.repos", pattern=pattern)
+
     return errors
+
+
+def transfer_options(config: Config) -> list[str]:
+    """``key=value`` git config entries for the ``[transfer]`` pack tuning."""
+    options: list[str] = []
+    if config.transfer.pack_threads is not None:
+        options.append(f"pack.threads={config.transfer.pack_threads}")
+    if config.transfer.window_memory is not None:
+        options.append(f"pack.windowMemory={config.transfer.window_memory}")
+    return options
+
+
+def matches_pattern(pattern: str, name: str) -> bool:
+    """Whether ``name`` matches a repository pattern: a name or glob, or ``re:<regex>``.
+
+    Regular expressions are searched (anchor them with ``^``/``$`` as needed).
+    """
+    if pattern.startswith(REGEX_PREFIX):
+        return re.search(pattern.removeprefix(REGEX_PREFIX), name) is not None
+    return fnmatchcase(name, pattern)
+
+
+def is_ignored(config: Config, name: str) -> bool:
+    """Whether ``name`` matches a ``github.clone.ignore`` pattern."""
+    if config.github is None:
+        return False
+    return any(matches_pattern(pattern, name) for pattern in config.github.clone.ignore)
+
+
+def groups_for(config: Config, name: str) -> list[str]:
+    """Names of the ``[groups.<name>]`` whose repos (patterns) match ``name``."""
+    return [
+        group_name
+        for group_name, group in config.groups.items()
+        if any(matches_pattern(pattern, name) for pattern in group.repos)
+    ]
+
+
+def branch_refspecs(branches: list[str]) -> list[str]:
+    """The refspecs mirroring exactly ``branches`` (``main`` -> ``+refs/heads/main:...``)."""
+    return [f"+refs/heads/{branch}:refs/heads/{branch}" for branch in branches]
+
+
+def overrides_for(config: Config, name: str) -> list[RepoOverride]:
+    """The ``[[overrides]]`` entries whose pattern matches ``name``, in config order.
+
+    Later entries take precedence, so a broad pattern can be refined by a narrower one.
+    """
+    return [o for o in config.overrides if matches_pattern(o.pattern, name)]
@@ -4,10 +4,15 @@ Supports the standard 5-field expression ``minute hour day-of-month month day-of
 with ``*``, lists (``a,b``), ranges (``a-b``), and steps (``*/n``, ``a-b/n``). Day-of-week
 is 0-6 with Sunday=0 (7 also accepted for Sunday). When both day-of-month and day-of-week
 are restricted, a timestamp matches if EITHER does (standard cron semantics).
+
+Fixed intervals (``--interval 1h30m``) use :func:`parse_duration`, :func:`format_duration` and
+:func:`run_interval`.
 """
 
 from __future__ import annotations
 
+import math
+import re
 from collections.abc import Awaitable, Callable
 from dataclasses import dataclass
 from datetime import datetime, timedelta
@@ -15,6 +20,41 @@ from datetime import datetime, timedelta
 # Search bound for next_after: ~4 years of minutes (guards against impossible expressions).
 _MAX_LOOKAHEAD_MINUTES = 4 * 366 * 24 * 60
 
+_DURATION_UNITS = {"w": 604800, "d": 86400, "h": 3600, "m": 60, "s": 1, "ms": 0.001}
+_DURATION_PART_RE = re.compile(r"(\d+(?:\.\d+)?)(ms|[wdhms])", re.IGNORECASE)
+
+
+def parse_duration(text: str) -> float:
+    """Seconds in a duration such as ``"1h30m"``, ``"1d"``, ``"90s"`` or plain ``"600"``.
+
+    Units are ``w``, ``d``, ``h``, ``m``, ``s`` and ``ms``; a bare number is seconds.
+    ``inf``, ``nan`` and numbers too large for a float are rejected.
+    """
+    spec = str(text).strip().replace(" ", "")
+    try:
+        seconds = float(spec)
+    except ValueError:
+        parts = _DURATION_PART_RE.findall(spec)
+        if not parts or "".join(n + u for n, u in parts) != spec:
+            raise ValueError(f"Invalid duration: {text!r} (expected e.g. 90, 10m or 1h30m)")
+        seconds = sum(float(number) * _DURATION_UNITS[unit.lower()] for number, unit in parts)
+    if not math.isfinite(seconds):
+        raise ValueError(f"Duration must be finite: {text!r}")
+    if seconds <= 0:
+        raise ValueError(f"Duration must be positive: {text!r}")
+    return seconds
+
+
+def format_duration(seconds: float) -> str:
+    """``5400`` -> ``"1h30m"``: the largest whole units, down to seconds."""
+    remaining = round(seconds)
+    parts = []
+    for unit in ("w", "d", "h", "m", "s"):
+        count, remaining = divmod(remaining, int(_DURATION_UNITS[unit]))
+        if count:
+            parts.append(f"{count}{unit}")
+    return "".join(parts) or f"{seconds:g}s"
+
 
 def _parse_field(spec: str, lo: int, hi: int) -> set[int]:
     values: set[int] = set()
@@ -117,3 +157,22 @@ async def run_cron(
         await sleep((upcoming - current).total_seconds())
         await action()
         count += 1
+
+
+async def run_interval(
+    seconds: float,
+    action: Callable[[], Awaitable[None]],
+    *,
+    sleep: Callable[[float], Awaitable[None]],
+    iterations: int | None = None,
+) -> None:
+    """Run ``action`` now and then again ``seconds`` after each run finishes.
+
+    Loops forever unless ``iterations`` is set; ``sleep`` is injected for tests.
+    """
+    count = 0
+    while iterations is None or count < iterations:
+        if count:
+            await sleep(seconds)
+        await action()
+        count += 1
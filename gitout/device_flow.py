@@ -0,0 +1,124 @@
+"""GitHub OAuth device flow for ``gitout login``.
+
+The device flow obtains a user token without a browser redirect: gitout asks GitHub for a
+one-time code, the user enters it at ``https://github.com/login/device``, and gitout
+polls until GitHub hands out the token (or the code expires). It needs the client ID of
+an OAuth App with device flow enabled. The httpx client, sleep function and clock are
+injectable for tests.
+"""
+
+from __future__ import annotations
+
+import asyncio
+import time
+from collections.abc import Awaitable, Callable
+from dataclasses import dataclass
+from typing import Any
+
+import httpx
+
+GITHUB_WEB_URL = "https://github.com"
+# Private repositories and migrations need ``repo``; starred gists need ``gist``.
+DEFAULT_SCOPES = ("repo", "gist", "read:user")
+DEVICE_GRANT_TYPE = "urn:ietf:params:oauth:grant-type:device_code"
+# GitHub asks clients that poll too fast to back off by this much.
+_SLOW_DOWN_SECONDS = 5.0
+
+
+class DeviceFlowError(RuntimeError):
+    """The device flow was refused, denied or expired."""
+
+
+@dataclass(frozen=True)
+class DeviceCode:
+    device_code: str
+    user_code: str
+    verification_uri: str
+    interval: float
+    expires_in: float
+
+
+async def _post(http: httpx.AsyncClient, url: str, data: dict[str, str]) -> dict[str, Any]:
+    response = await http.post(url, data=data, headers={"Accept": "application/json"})
+    response.raise_for_status()
+    body = response.json()
+    if not isinstance(body, dict):
+        raise DeviceFlowError(f"Unexpected response from {url}")
+    return body
+
+
+async def request_device_code(
+    http: httpx.AsyncClient,
+    client_id: str,
+    scopes: tuple[str, ...] = DEFAULT_SCOPES,
+    *,
+    web_url: str = GITHUB_WEB_URL,
+) -> DeviceCode:
+    body = await _post(
+        http,
+        f"{web_url.rstrip('/')}/login/device/code",
+        {"client_id": client_id, "scope": " ".join(scopes)},
+    )
+    if "error" in body:
+        raise DeviceFlowError(body.get("error_description") or body["error"])
+    return DeviceCode(
+        device_code=body["device_code"],
+        user_code=body["user_code"],
+        verification_uri=body["verification_uri"],
+        interval=float(body.get("interval", 5)),
+        expires_in=float(body.get("expires_in", 900)),
+    )
+
+
+async def poll_for_token(
+    http: httpx.AsyncClient,
+    client_id: str,
+    code: DeviceCode,
+    *,
+    web_url: str = GITHUB_WEB_URL,
+    sleep: Callable[[float], Awaitable[None]] = asyncio.sleep,
+    clock: Callable[[], float] = time.monotonic,
+) -> str:
+    """Poll until the user authorises ``code``; returns the access token."""
+    url = f"{web_url.rstrip('/')}/login/oauth/access_token"
+    data = {
+        "client_id": client_id,
+        "device_code": code.device_code,
+        "grant_type": DEVICE_GRANT_TYPE,
+    }
+    interval = code.interval
+    deadline = clock() + code.expires_in
+    while clock() < deadline:
+        await sleep(interval)
+        body = await _post(http, url, data)
+        token = body.get("access_token")
+        if token:
+            return str(token)
+        error = body.get("error")
+        if error == "authorization_pending":
+            continue
+        if error == "slow_down":
+            interval = float(body.get("interval", interval + _SLOW_DOWN_SECONDS))
+            continue
+        raise DeviceFlowError(body.get("error_description") or error or "no token returned")
+    raise DeviceFlowError("The device code expired before it was authorised")
+
+
+async def device_login(
+    client_id: str,
+    on_code: Callable[[DeviceCode], None],
+    *,
+    scopes: tuple[str, ...] = DEFAULT_SCOPES,
+    web_url: str = GITHUB_WEB_URL,
+    client: httpx.AsyncClient | None = None,
+    sleep: Callable[[float], Awaitable[None]] = asyncio.sleep,
+) -> str:
+    """Run the whole flow: request a code, hand it to ``on_code``, poll for the token."""
+    http = client or httpx.AsyncClient(timeout=30.0)
+    try:
+        code = await request_device_code(http, client_id, scopes, web_url=web_url)
+        on_code(code)
+        return await poll_for_token(http, client_id, code, web_url=web_url, sleep=sleep)
+    finally:
+        if client is None:
+            await http.aclose()
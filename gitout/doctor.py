@@ -0,0 +1,167 @@
+"""Environment diagnostics (``gitout doctor [CONFIG] [DESTINATION]``).
+
+Most failed runs are environmental: no ``git`` on ``PATH``, a CA bundle that does not
+exist, a proxy swallowing ``api.github.com``, an expired token or a read-only volume.
+:func:`diagnose` checks each of these and returns :class:`Diagnosis` lines grouped by
+check, in a fixed order, so the CLI can print a readable report:
+
+* ``git`` — the CLI runs (``git --version``), plus ``git lfs`` when ``lfs.fetch_lfs``;
+* ``tls`` — git's ``http.sslBackend`` and every CA source in effect (``ssl.cert_file``,
+  ``GIT_SSL_CAINFO``, ``SSL_CERT_FILE``, ``http.sslCAInfo``; each must exist), Python's
+  default verify paths, and a warning when ``ssl.verify_certificates`` is off;
+* ``network`` — the GitHub API root answers at all (any HTTP status will do);
+* ``token`` — the token resolves and ``GET /user`` accepts it with the scopes the
+  config needs (without a config, only whether a token can be found);
+* ``destination`` — a sentinel can be written and read back, and how much space is free.
+
+Nothing is changed. The HTTP client, token checker and git runner are injectable.
+"""
+
+from __future__ import annotations
+
+import os
+import shutil
+import ssl
+import time
+from collections.abc import Mapping
+from dataclasses import dataclass
+from pathlib import Path
+
+import httpx
+
+from gitout.config import Config
+from gitout.engine import (
+    GitRunner,
+    configured_github_token,
+    default_git_runner,
+    local_git,
+    preflight_storage_check,
+    resolve_github_token,
+)
+from gitout.github_client import GITHUB_API_URL, rest_url
+from gitout.token_check import TokenChecker, TokenCheckError, check_token, required_scopes
+
+DIAGNOSIS_STATUSES = ("ok", "warning", "fail")
+_GIT_TIMEOUT_SECONDS = 30.0
+_STORAGE_TIMEOUT_MS = 10_000
+
+
+@dataclass(frozen=True)
+class Diagnosis:
+    check: str  # "git", "tls", "network", "token" or "destination"
+    status: str  # one of DIAGNOSIS_STATUSES
+    detail: str
+
+
+async def _git_output(git_runner: GitRunner, *args: str) -> tuple[int, str]:
+    try:
+        return await git_runner(local_git(*args), Path.cwd(), _GIT_TIMEOUT_SECONDS)
+    except (OSError, RuntimeError) as exc:
+        return 127, str(exc)
+
+
+async def _check_git(config: Config | None, git_runner: GitRunner) -> list[Diagnosis]:
+    code, output = await _git_output(git_runner, "--version")
+    if code != 0:
+        return [Diagnosis("git", "fail", f"git does not run: {output.strip() or f'exit {code}'}")]
+    found = [Diagnosis("git", "ok", output.strip())]
+    if config is not None and config.lfs.fetch_lfs:
+        code, output = await _git_output(git_runner, "lfs", "version")
+        if code != 0:
+            found.append(Diagnosis("git", "fail", "lfs.fetch_lfs is on but git lfs is missing"))
+        else:
+            found.append(Diagnosis("git", "ok", output.strip()))
+    return found
+
+
+async def _check_tls(
+    config: Config | None, environ: Mapping[str, str], git_runner: GitRunner
+) -> list[Diagnosis]:
+    code, output = await _git_output(git_runner, "config", "--get", "http.sslBackend")
+    backend = output.strip() if code == 0 and output.strip() else "git's build default"
+    found = [Diagnosis("tls", "ok", f"git TLS backend: {backend}")]
+    sources: list[tuple[str, str]] = []
+    if config is not None and config.ssl.cert_file:
+        sources.append(("ssl.cert_file", config.ssl.cert_file))
+    for name in ("GIT_SSL_CAINFO", "SSL_CERT_FILE"):
+        if environ.get(name):
+            sources.append((name, environ[name]))
+    code, output = await _git_output(git_runner, "config", "--get", "http.sslCAInfo")
+    if code == 0 and output.strip():
+        sources.append(("http.sslCAInfo", output.strip()))
+    for name, value in sources:
+        if Path(value).expanduser().is_file():
+            found.append(Diagnosis("tls", "ok", f"CA bundle from {name}: {value}"))
+        else:
+            found.append(Diagnosis("tls", "fail", f"CA bundle from {name} does not exist: {value}"))
+    paths = ssl.get_default_verify_paths()
+    default = paths.cafile or paths.capath or "none found"
+    found.append(Diagnosis("tls", "ok", f"Python default CA paths: {default}"))
+    if config is not None and not config.ssl.verify_certificates:
+        found.append(Diagnosis("tls", "warning", "ssl.verify_certificates is off"))
+    return found
+
+
+async def _check_network(api_url: str, client: httpx.AsyncClient) -> Diagnosis:
+    started = time.perf_counter()
+    try:
+        response = await client.get(api_url)
+    except httpx.HTTPError as exc:
+        return Diagnosis("network", "fail", f"{api_url} is unreachable: {exc}")
+    elapsed_ms = (time.perf_counter() - started) * 1000
+    detail = f"{api_url} answered HTTP {response.status_code} in {elapsed_ms:.0f}ms"
+    return Diagnosis("network", "ok", detail)
+
+
+async def _check_token(
+    config: Config | None, environ: Mapping[str, str], token_checker: TokenChecker
+) -> Diagnosis:
+    github = config.github if config is not None else None
+    api_url = github.api_url if github is not None else GITHUB_API_URL
+    try:
+        configured = configured_github_token(github) if github is not None else None
+        token = resolve_github_token(configured, environ, api_url=api_url)
+    except ValueError as exc:
+        status = "fail" if github is not None else "warning"
+        return Diagnosis("token", status, str(exc))
+    if github is None:
+        return Diagnosis("token", "ok", "A GitHub token is available (no config to check)")
+    try:
+        login = await token_checker(token, required_scopes(github))
+    except (TokenCheckError, httpx.HTTPError) as exc:
+        return Diagnosis("token", "fail", str(exc))
+    return Diagnosis("token", "ok", f"Token of {login or 'an installation'} has the needed scopes")
+
+
+async def _check_destination(destination: Path) -> Diagnosis:
+    failure = await preflight_storage_check(destination, _STORAGE_TIMEOUT_MS)
+    if failure is not None:
+        return Diagnosis("destination", "fail", failure)
+    free = shutil.disk_usage(destination).free
+    return Diagnosis("destination", "ok", f"{destination} is writable, {free / 1e9:.1f} GB free")
+
+
+async def diagnose(
+    config: Config | None,
+    destination: Path | None,
+    *,
+    environ: Mapping[str, str] | None = None,
+    client: httpx.AsyncClient | None = None,
+    token_checker: TokenChecker = check_token,
+    git_runner: GitRunner = default_git_runner,
+) -> list[Diagnosis]:
+    """Run every check; the token and destination checks need their inputs."""
+    env = os.environ if environ is None else environ
+    found = await _check_git(config, git_runner)
+    found += await _check_tls(config, env, git_runner)
+    api_url = config.github.api_url if config is not None and config.github else GITHUB_API_URL
+    http = client or httpx.AsyncClient(timeout=15.0)
+    try:
+        found.append(await _check_network(rest_url(api_url), http))
+    finally:
+        if client is None:
+            await http.aclose()
+    found.append(await _check_token(config, env, token_checker))
+    if destination is not None:
+        found.append(await _check_destination(destination))
+    return found
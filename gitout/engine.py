@@ -5,7 +5,8 @@ sync-task collection, the ``DRY RUN`` plan line, and parallel execution with an
 ``asyncio.Semaphore`` worker pool and per-repo retry. Lifecycle wiring includes
 repository state tracking and exclusions, failure tracking, circuit breaker and
 storage pre-flight, large-repo/shallow-clone heuristics, LFS, maintenance,
-health checks, Telegram notifications, and search indexing.
+health checks, Telegram notifications, repository settings and community
+snapshot export, migration archives, per-cycle run reports, and search indexing.
 """
 
 from __future__ import annotations
@@ -14,37 +15,107 @@ import asyncio
 import contextlib
 import logging
 import os
+import re
+import shlex
+import shutil
+import subprocess
 import tempfile
 import time
-from collections.abc import AsyncIterator, Awaitable, Callable, Mapping
-from dataclasses import dataclass, field
+from collections.abc import AsyncIterator, Awaitable, Callable, Mapping, Sequence
+from dataclasses import dataclass, field, replace
+from datetime import UTC, datetime
 from pathlib import Path
-from urllib.parse import quote
-
+from urllib.parse import quote, urlsplit
+
+from gitout.bundle import (
+    bundle_path,
+    bundle_root,
+    chain_path,
+    write_bundle,
+    write_incremental_bundle,
+)
 from gitout.circuit_breaker import StorageCircuitBreaker
-from gitout.config import Config
+from gitout.config import (
+    Config,
+    GitHubApp,
+    GitHubClone,
+    GitHubConfig,
+    GitRepoCredentials,
+    Offsite,
+    PushMirror,
+    Rclone,
+    Replicate,
+    branch_refspecs,
+    groups_for,
+    is_ignored,
+    matches_pattern,
+    overrides_for,
+    transfer_options,
+)
 from gitout.errors import ErrorCategory, classify, display_name
+from gitout.events import (
+    EventListener,
+    RepoFinished,
+    RepoStarted,
+    RunFinished,
+    RunStarted,
+    SyncEvent,
+)
 from gitout.failure_tracker import FailureTracker
+from gitout.fanout import FanoutResult, fan_out
 from gitout.git_commands import build_git_command
 from gitout.git_exec import resolve_git_executable
 from gitout.github import UserRepositories
-from gitout.health_check import HealthCheck
+from gitout.github_app import INSTALLATION_GIT_USER, GitHubAppAuth
+from gitout.github_archive import MigrationArchiver, MigrationRequest, archive_targets
+from gitout.github_client import GITHUB_API_URL, RateLimiter, TokenPool, web_url
+from gitout.github_metadata import CommunitySnapshotExporter, RepositorySettingsExporter
+from gitout.health_check import HealthCheck, failure_log
+from gitout.http_cache import HTTP_CACHE_FILE, HttpCache
+from gitout.keyring_store import get_secret
 from gitout.lfs import LfsSupport
-from gitout.maintenance import RepositoryMaintenance
+from gitout.log_format import repository_logs
+from gitout.maintenance import MAINTENANCE_STATE_FILE, RepositoryMaintenance
+from gitout.netrc_lookup import netrc_login
+from gitout.notify import ChatNotifier, EmailNotifier, chat_notifiers
+from gitout.offsite.backend import OffsiteError, StorageBackend, build_backend
+from gitout.offsite.rclone import RcloneRunner, replicate
+from gitout.offsite.rsync import RsyncRunner, replicate_to_targets
+from gitout.offsite.uploader import OffsiteUploader
+from gitout.push_mirror import push_argv, push_password, push_url
+from gitout.pushgateway import Pushgateway, render_metrics
+from gitout.quarantine import is_corruption, quarantine
+from gitout.ref_attic import attic_ref, forced_ref, moved_refs, parse_refs, pruned_refs
+from gitout.reports import build_report, outcome_label, write_report, write_report_file
 from gitout.retry import RetryContext, RetryPolicy, SyncFailureException
 from gitout.search.index_service import SearchIndexService
+from gitout.snapshots import prune_snapshots, snapshot_due, take_snapshot
+from gitout.ssh import default_login, is_ssh_url, ssh_command, write_askpass
 from gitout.state_tracker import ExcludedRepo, RepositoryStateTracker
+from gitout.sync_state import SYNC_STATE_FILE, SyncStateStore, read_heads
+from gitout.tarball import tarball_dir, write_tarball
 from gitout.telegram import FailedRepoSummary, TelegramNotificationService
+from gitout.token_check import TokenChecker, TokenCheckError, required_scopes
+from gitout.tracing import AttributeValue, Span, Tracer
+from gitout.webhooks import WebhookNotifier, build_payload
 
 logger = logging.getLogger(__name__)
+# Every git command of default_git_runner, at DEBUG (sync -vv / --log-level trace).
+git_logger = logging.getLogger("gitout.git")
 
 __all__ = [
     "Engine",
     "SyncTask",
     "SyncOutcome",
+    "DestinationConflictError",
     "collect_sync_tasks",
+    "merge_duplicate_destinations",
+    "apply_overrides",
     "dry_run_line",
+    "configured_github_token",
     "resolve_github_token",
+    "resolve_github_tokens",
+    "git_repo_password",
     "resolve_git_executable",
     "preflight_storage_check",
     "default_git_runner",
@@ -52,8 +123,11 @@ __all__ = [
 
 # (argv, cwd, timeout_seconds) -> (exit_code, combined_output)
 GitRunner = Callable[[list[str], Path, float], Awaitable[tuple[int, str]]]
-# (user, token) -> discovered repositories
-RepoLoader = Callable[[str, str], Awaitable[UserRepositories]]
+# (user, token or rotating token pool) -> discovered repositories
+RepoLoader = Callable[[str, str | TokenPool], Awaitable[UserRepositories]]
+
+# (name_with_owner, target_dir) -> written
+MetadataExport = Callable[[str, Path], Awaitable[bool]]
 
 
 @dataclass(frozen=True)
@@ -67,6 +141,26 @@ class SyncTask:
     default_branch: str | None = None
     size_kb: int | None = None
     is_large_repo: bool = False
+    shallow_since: str | None = None
+    groups: tuple[str, ...] = ()  # [groups.<name>] this repository belongs to
+    pushed_at: str | None = None  # GitHub pushedAt at discovery (skip_unchanged)
+    ls_remote_check: bool = False  # compare remote heads before fetching (git.skip_unchanged)
+    ssh_command: str | None = None  # core.sshCommand for SSH remotes (see gitout.ssh)
+    prune: bool = True  # updates drop refs deleted upstream (fetch.prune / overrides)
+    depth: int = 0  # history kept per ref, 0 = all (fetch.depth / overrides)
+    clone_filter: str | None = None  # partial clone filter (fetch.filter / overrides)
+    # Only these refs are mirrored ([git.repos] refspecs); None means fetch.refspecs.
+    refspecs: tuple[str, ...] | None = None
+    tarball: bool = False  # export HEAD as a tarball after syncing (tarball / overrides)
+
+    @property
+    def provider(self) -> str:
+        return provider_host(self.url)
+
+    @property
+    def identity(self) -> str:
+        """Host-qualified name, unique across providers (failure state, reports, dedup)."""
+        return f"{self.provider}/{self.name.removeprefix('gist:')}"
 
 
 @dataclass(frozen=True)
@@ -77,10 +171,142 @@ class SyncOutcome:
     skipped: bool = False
     category: ErrorCategory | None = None
     attempts: int = 1
+    duration_seconds: float = 0.0
+    rewritten_refs: tuple[str, ...] = ()  # refs force-pushed upstream (fetch.keep_rewritten)
+
+
+# How long github.token_cmd may take (a password manager may prompt to unlock).
+TOKEN_CMD_TIMEOUT_SECONDS = 120.0
+
+
+def configured_github_token(github: GitHubConfig) -> str | list[str] | None:
+    """The token the config supplies: ``token`` > ``token_file`` > ``token_cmd``.
+
+    The file contents and the command's stdout (run through the shell) are trimmed.
+    Raises ValueError when the file or command is needed but fails or yields nothing;
+    the command's output is never included in the error.
+    """
+    token = github.token
+    if isinstance(token, list) or (token is not None and token.strip()):
+        return token
+    if github.token_file is not None:
+        path = Path(github.token_file)
+        try:
+            contents = path.read_text().strip()
+        except OSError as exc:
+            raise ValueError(f"Cannot read github.token_file {path}: {exc.strerror}") from exc
+        if not contents:
+            raise ValueError(f"github.token_file {path} is empty")
+        return contents
+    if github.token_cmd is not None:
+        return _run_token_cmd(github.token_cmd)
+    return token
+
+
+def _run_token_cmd(command: str) -> str:
+    try:
+        proc = subprocess.run(  # noqa: S602 - the command comes from the user's own config
+            command,
+            shell=True,
+            capture_output=True,
+            text=True,
+            timeout=TOKEN_CMD_TIMEOUT_SECONDS,
+            check=False,
+        )
+    except (OSError, subprocess.SubprocessError) as exc:
+        raise ValueError(f"github.token_cmd failed: {exc}") from exc
+    if proc.returncode != 0:
+        detail = proc.stderr.strip().splitlines()
+        reason = f": {detail[-1]}" if detail else ""
+        raise ValueError(f"github.token_cmd exited with code {proc.returncode}{reason}")
+    token = proc.stdout.strip()
+    if not token:
+        raise ValueError("github.token_cmd printed no token")
+    return token
+
+
+def git_repo_password(name: str, login: GitRepoCredentials, environ: Mapping[str, str]) -> str:
+    """The password of a ``[git.repos]`` login, from its variable or file (trimmed)."""
+    if login.password_env is not None:
+        password = environ.get(login.password_env, "").strip()
+        if not password:
+            raise ValueError(f'git.repos "{name}": ${login.password_env} is not set')
+        return password
+    if login.password_file is None:
+        raise ValueError(f'git.repos "{name}" has a username but no password source')
+    path = Path(login.password_file)
+    try:
+        password = path.read_text().strip()
+    except OSError as exc:
+        raise ValueError(f'git.repos "{name}": cannot read {path}: {exc.strerror}') from exc
+    if not password:
+        raise ValueError(f'git.repos "{name}": {path} is empty')
+    return password
+
+
+def credential_origin(url: str) -> str:
+    """``scheme://host[:port]`` of ``url``, without any user info."""
+    parts = urlsplit(url.strip())
+    host = parts.hostname or ""
+    return f"{parts.scheme}://{host}:{parts.port}" if parts.port else f"{parts.scheme}://{host}"
+
+
+async def usable_tokens(
+    checker: TokenChecker, tokens: list[str], required: Mapping[str, str]
+) -> list[str]:
+    """The tokens ``checker`` accepts; a rejected token of a pool is skipped, not fatal.
+
+    Raises the checker's TokenCheckError when no token is left.
+    """
+    usable: list[str] = []
+    for index, token in enumerate(tokens, 1):
+        try:
+            login = await checker(token, required)
+        except TokenCheckError as exc:
+            if not usable and index == len(tokens):
+                raise
+            logger.warning("Skipping GitHub token %d of %d: %s", index, len(tokens), exc)
+            continue
+        logger.debug("GitHub token %d authenticates as %s", index, login)
+        usable.append(token)
+    return usable
+
+
+def resolve_github_tokens(
+    config_token: str | list[str] | None,
+    environ: Mapping[str, str],
+    *,
+    api_url: str = GITHUB_API_URL,
+) -> list[str]:
+    """Every configured GitHub token, in rotation order (see :func:`resolve_github_token`)."""
+    if isinstance(config_token, list):
+        tokens = [t.strip() for t in config_token if t.strip()]
+        if tokens:
+            return tokens
+        config_token = None
+    return [resolve_github_token(config_token, environ, api_url=api_url)]
+
+
+def _netrc_hosts(api_url: str) -> list[str]:
+    """``.netrc`` machines that may hold the token for ``api_url``: API host, then web."""
+    host = provider_host(api_url)
+    return [host, host.removeprefix("api.")] if host.startswith("api.") else [host]
 
 
-def resolve_github_token(config_token: str | None, environ: Mapping[str, str]) -> str:
-    """Resolve a GitHub token: config (trimmed) > GITHUB_TOKEN_FILE > GITHUB_TOKEN."""
+def resolve_github_token(
+    config_token: str | list[str] | None,
+    environ: Mapping[str, str],
+    *,
+    api_url: str = GITHUB_API_URL,
+) -> str:
+    """Resolve a GitHub token: config (trimmed) > GITHUB_TOKEN_FILE > GITHUB_TOKEN >
+    the OS keyring (``gitout auth set github``) > the ``.netrc`` entry for the API host
+    (``api.github.com``, then ``github.com``).
+
+    A list of tokens in the config resolves to its first entry.
+    """
+    if isinstance(config_token, list):
+        return resolve_github_tokens(config_token, environ, api_url=api_url)[0]
     if config_token is not None and config_token.strip():
         return config_token.strip()
 
@@ -96,23 +322,180 @@ def resolve_github_token(config_token: str | None, environ: Mapping[str, str]) -
     if token_env and token_env.strip():
         return token_env.strip()
 
+    stored = get_secret("github")
+    if stored is not None:
+        return stored
+
+    login = netrc_login(_netrc_hosts(api_url), environ)
+    if login is not None:
+        return login[1].strip()
+
     raise ValueError(
         "GitHub token not found. Provide it via: (1) config.toml [github] token, "
-        "(2) GITHUB_TOKEN_FILE, or (3) GITHUB_TOKEN."
+        "(2) GITHUB_TOKEN_FILE, (3) GITHUB_TOKEN, (4) `gitout auth set github`, "
+        "or (5) a machine entry in ~/.netrc."
     )
 
 
+_SCP_HOST_RE = re.compile(r"^[\w.-]+@([\w.-]+):")
+
+
+def provider_host(url: str) -> str:
+    """The host a clone URL points at (casefolded); ``local`` for file:// and paths."""
+    parts = urlsplit(url.strip())
+    if parts.scheme == "file":
+        return "local"
+    if parts.hostname:
+        return parts.hostname
+    scp = _SCP_HOST_RE.match(url.strip())
+    return scp.group(1).casefold() if scp else "local"
+
+
+def summary_names(tasks: list[SyncTask]) -> dict[SyncTask, str]:
+    """Names for user-facing summaries: host-qualified once tasks span several hosts."""
+    qualify = len({t.provider for t in tasks}) > 1
+    return {t: t.identity if qualify else t.name for t in tasks}
+
+
+def legacy_failure_aliases(tasks: list[SyncTask]) -> dict[str, str]:
+    """Map pre-namespacing failure-state keys (bare names) to task identities.
+
+    A bare name shared by several tasks is ambiguous and left alone.
+    """
+    by_name: dict[str, list[SyncTask]] = {}
+    for task in tasks:
+        by_name.setdefault(task.name, []).append(task)
+    return {name: group[0].identity for name, group in by_name.items() if len(group) == 1}
+
+
+class DestinationConflictError(ValueError):
+    """Two sync tasks with different origins would write into the same directory."""
+
+
+class UnknownRepositoryError(ValueError):
+    """``--only`` names no repository of this configuration."""
+
+
+def is_named(task_name: str, only: str) -> bool:
+    """Whether ``only`` (CLI ``--only``) names a task; GitHub names ignore case."""
+    return task_name == only or ("/" in only and task_name.casefold() == only.casefold())
+
+
+# Most parallel clones --workers auto opens against one git host; hosts throttle (or
+# refuse) a single client beyond a handful of concurrent connections.
+AUTO_WORKERS_PER_HOST = 8
+
+
+def auto_worker_count(tasks: Sequence[SyncTask], cpu_count: int | None = None) -> int:
+    """Workers for ``--workers auto``: the CPU count, capped per git host and by the tasks."""
+    cpus = cpu_count if cpu_count is not None else (os.cpu_count() or 1)
+    hosts = {task.provider for task in tasks}
+    count = min(cpus, AUTO_WORKERS_PER_HOST * max(len(hosts), 1))
+    if tasks:
+        count = min(count, len(tasks))
+    return max(count, 1)
+
+
+def _origin_key(url: str) -> str:
+    key = url.strip().casefold().rstrip("/")
+    return key.removesuffix(".git")
+
+
+def merge_duplicate_destinations(tasks: list[SyncTask]) -> list[SyncTask]:
+    """Collapse tasks that resolve to the same destination directory.
+
+    Destinations compare case-insensitively (GitHub names are case-insensitive and
+    backup volumes often are too). Duplicates from the same origin are merged into
+    the first task, unioning their selection reasons; duplicates from different
+    origins raise :class:`DestinationConflictError` listing every conflict.
+    """
+    merged: dict[str, SyncTask] = {}
+    conflicts: list[str] = []
+    for task in tasks:
+        key = str(task.destination).casefold()
+        existing = merged.get(key)
+        if existing is None:
+            merged[key] = task
+        elif _origin_key(existing.url) == _origin_key(task.url):
+            if task.reasons:
+                merged[key] = replace(
+                    existing, reasons=(existing.reasons or frozenset()) | task.reasons
+                )
+        else:
+            conflicts.append(
+                f"{task.destination}: {existing.identity} ({existing.url}) "
+                f"and {task.identity} ({task.url})"
+            )
+    if conflicts:
+        raise DestinationConflictError(
+            "Multiple repositories resolve to the same destination:\n  "
+            + "\n  ".join(conflicts)
+        )
+    return list(merged.values())
+
+
+def _filtered_out(
+    reasons: dict[str, set[str]], user_repos: UserRepositories, clone: GitHubClone
+) -> list[str]:
+    """Names dropped by ``github.clone`` forks/include_archived/topic/language filters.
+
+    Explicitly listed repos are never dropped.
+    """
+    wanted_topics = {t.lower() for t in clone.topics}
+    ignored_topics = {t.lower() for t in clone.ignored_topics}
+    wanted_languages = {lang.lower() for lang in clone.languages}
+    ignored_languages = {lang.lower() for lang in clone.ignored_languages}
+    dropped = []
+    for name, why in reasons.items():
+        metadata = user_repos.metadata.get(name)
+        if "explicit" in why or metadata is None:
+            continue
+        topics = {t.lower() for t in metadata.topics}
+        language = (metadata.language or "").lower()
+        if (
+            (clone.forks != "include" and metadata.is_fork == (clone.forks == "exclude"))
+            or (metadata.is_archived and not clone.include_archived)
+            or (wanted_topics and not topics & wanted_topics)
+            or topics & ignored_topics
+            or (wanted_languages and language not in wanted_languages)
+            or language in ignored_languages
+        ):
+            dropped.append(name)
+    return dropped
+
+
+def github_clone_urls(api_url: str, over_ssh: bool) -> tuple[str, str]:
+    """Repository and gist clone URL templates (``{}``: name) on ``api_url``'s server.
+
+    GitHub serves gists from ``gist.github.com``; Enterprise Server under ``/gist/``.
+    """
+    web = web_url(api_url)
+    host = urlsplit(web).hostname or ""
+    if host == "github.com":
+        if over_ssh:
+            return "git@github.com:{}.git", "git@gist.github.com:{}.git"
+        return "https://github.com/{}.git", "https://gist.github.com/{}.git"
+    if over_ssh:
+        return f"git@{host}:{{}}.git", f"git@{host}:gist/{{}}.git"
+    return f"{web}/{{}}.git", f"{web}/gist/{{}}.git"
+
+
 def collect_sync_tasks(
     config: Config,
     destination: Path,
     user_repos: UserRepositories | None,
     credentials_path: str | None = None,
     excluded: set[str] | None = None,
+    ssh_askpass: str | None = None,
+    repo_credentials: Mapping[str, str] | None = None,
 ) -> list[SyncTask]:
     """Build the ordered list of repositories to sync from config + discovered repos.
 
     ``excluded`` names (deleted/inaccessible repos from the state tracker) are dropped,
-    just like the config's ``ignore`` list.
+    just like the config's ``ignore`` list. Destination collisions are merged or
+    rejected by :func:`merge_duplicate_destinations`. Tasks whose URL is an SSH one get
+    the ``[ssh]`` command (with the ``ssh_askpass`` helper, see :mod:`gitout.ssh`).
+    ``repo_credentials`` maps ``[git.repos]`` names to their credential-store files.
     """
     excluded = excluded or set()
     tasks: list[SyncTask] = []
@@ -122,8 +505,9 @@ def collect_sync_tasks(
         github_destination = destination / "github"
         reasons: dict[str, set[str]] = {}
 
-        for name in user_repos.owned:
-            reasons.setdefault(name, set()).add("owned")
+        if github.clone.owned:
+            for name in user_repos.owned:
+                reasons.setdefault(name, set()).add("owned")
         for name in github.clone.repos:
             reasons.setdefault(name, set()).add("explicit")
         if github.clone.starred:
@@ -133,11 +517,14 @@ def collect_sync_tasks(
             for name in user_repos.watching:
                 reasons.setdefault(name, set()).add("watching")
 
-        for ignore in github.clone.ignore:
-            reasons.pop(ignore, None)
+        for name in [n for n in reasons if is_ignored(config, n)]:
+            reasons.pop(name)
+        for name in _filtered_out(reasons, user_repos, github.clone):
+            reasons.pop(name)
         for name in excluded:
             reasons.pop(name, None)
 
+        repo_url, gist_url = github_clone_urls(github.api_url, github.clone.protocol == "ssh")
         clone_destination = github_destination / "clone"
         threshold = config.large_repos.size_threshold_kb
         for name_and_owner, why in reasons.items():
@@ -146,7 +533,7 @@ def collect_sync_tasks(
             tasks.append(
                 SyncTask(
                     name=name_and_owner,
-                    url=f"https://github.com/{name_and_owner}.git",
+                    url=repo_url.format(name_and_owner),
                     destination=clone_destination / name_and_owner,
                     credentials_path=credentials_path,
                     reasons=frozenset(why),
@@ -154,26 +541,115 @@ def collect_sync_tasks(
                     default_branch=metadata.default_branch if metadata else None,
                     size_kb=size_kb,
                     is_large_repo=size_kb is not None and size_kb >= threshold,
+                    pushed_at=metadata.pushed_at if metadata else None,
                 )
             )
 
+        gists_destination = github_destination / "gists"
         if github.clone.gists:
-            gists_destination = github_destination / "gists"
             for gist in user_repos.gists:
                 tasks.append(
                     SyncTask(
                         name=f"gist:{gist}",
-                        url=f"https://gist.github.com/{gist}.git",
+                        url=gist_url.format(gist),
+                        destination=gists_destination / gist,
+                        credentials_path=credentials_path,
+                    )
+                )
+        if github.clone.gists_starred:
+            for gist in user_repos.starred_gists - excluded:
+                tasks.append(
+                    SyncTask(
+                        name=f"gist:{gist}",
+                        url=gist_url.format(gist),
                         destination=gists_destination / gist,
                         credentials_path=credentials_path,
+                        reasons=frozenset({"starred"}),
                     )
                 )
 
     git_destination = destination / "git"
     for name, url in config.git.repos.items():
-        tasks.append(SyncTask(name=name, url=url, destination=git_destination / name))
+        tasks.append(
+            SyncTask(
+                name=name,
+                url=url,
+                destination=git_destination / name,
+                credentials_path=(repo_credentials or {}).get(name),
+                ls_remote_check=config.git.skip_unchanged,
+                refspecs=(
+                    tuple(config.git.refspecs[name]) if name in config.git.refspecs else None
+                ),
+            )
+        )
+
+    tasks = [
+        replace(
+            t, ssh_command=ssh_command(config.ssh, ssh_askpass, login=default_login(t.url))
+        )
+        if is_ssh_url(t.url)
+        else t
+        for t in tasks
+    ]
+    return [apply_overrides(task, config) for task in merge_duplicate_destinations(tasks)]
+
 
-    return tasks
+def apply_overrides(task: SyncTask, config: Config) -> SyncTask:
+    """Tag ``task`` with its groups and ``[fetch]`` options, then apply the matching
+    ``[[overrides]]`` (later win).
+
+    git refuses ``--depth`` together with ``--shallow-since``, so ``since`` replaces an
+    inherited depth and a later depth replaces ``since``.
+    """
+    groups = tuple(groups_for(config, task.name))
+    if groups:
+        task = replace(task, groups=groups)
+    if not config.fetch.prune:
+        task = replace(task, prune=False)
+    if config.fetch.depth:
+        task = replace(task, depth=config.fetch.depth)
+    if config.fetch.filter:
+        task = replace(task, clone_filter=config.fetch.filter)
+    if config.tarball.enabled:
+        task = replace(task, tarball=True)
+    for override in overrides_for(config, task.name):
+        if override.since is not None:
+            task = replace(task, shallow_since=override.since, depth=0)
+        if override.prune is not None:
+            task = replace(task, prune=override.prune)
+        if override.depth is not None:
+            since = None if override.depth else task.shallow_since
+            task = replace(task, depth=override.depth, shallow_since=since)
+        if override.filter is not None:
+            task = replace(task, clone_filter=override.filter or None)
+        if override.branches is not None:
+            task = replace(task, refspecs=tuple(branch_refspecs(override.branches)))
+        if override.tarball is not None:
+            task = replace(task, tarball=override.tarball)
+    return task
+
+
+def _git_options(task: SyncTask, config: Config) -> list[str]:
+    options = transfer_options(config)
+    if task.ssh_command is not None:
+        options.append(f"core.sshCommand={task.ssh_command}")
+    if config.fetch.tags != "auto":
+        # tagOpt applies to ``fetch`` and ``remote update`` alike.
+        tag_opt = "--tags" if config.fetch.tags == "all" else "--no-tags"
+        options.append(f"remote.origin.tagOpt={tag_opt}")
+    return options
+
+
+def _lfs_options(task: SyncTask, config: Config) -> list[str]:
+    """The sync's credentials and transport for ``git lfs fetch`` on ``task``."""
+    options: list[str] = []
+    if not config.ssl.verify_certificates:
+        options.append("http.sslVerify=false")
+    if task.credentials_path is not None:
+        options.append(f"credential.helper=store --file={task.credentials_path}")
+    if task.ssh_command is not None:
+        options.append(f"core.sshCommand={task.ssh_command}")
+    return options
 
 
 def _build_argv(
@@ -183,12 +659,15 @@ def _build_argv(
     force_http1: bool = False,
     use_shallow_clone: bool = False,
     show_progress: bool = False,
+    ls_remote: bool = False,
+    ls_remote_heads: bool = True,
 ) -> list[str]:
-    repo_exists = task.destination.exists()
+    # A mirror with its own refspecs is never cloned: it is created empty and fetched.
+    repo_exists = task.destination.exists() or task.refspecs is not None
     is_clone = not repo_exists
     return build_git_command(
         repo_exists=repo_exists,
-        url=task.url if is_clone else None,
+        url=task.url if is_clone or ls_remote else None,
         repo_name=task.destination.name if is_clone else None,
         git_executable=resolve_git_executable(),
         verify_certificates=config.ssl.verify_certificates,
@@ -202,12 +681,43 @@ def _build_argv(
         show_progress=show_progress,
         single_branch_only=task.single_branch_only,
         default_branch=task.default_branch,
+        shallow_since=task.shallow_since,
+        extra_config=_git_options(task, config),
+        ls_remote=ls_remote,
+        prune=task.prune,
+        ls_remote_heads=ls_remote_heads,
+        refspecs=_refspecs(task, config),
+        depth=task.depth or None,
+        clone_filter=task.clone_filter,
     )
 
 
+def _refspecs(task: SyncTask, config: Config) -> Sequence[str]:
+    if task.refspecs is not None:
+        return task.refspecs
+    if (task.destination / ".git").exists():
+        return ()  # a working-tree clone (shallow fallback) cannot fetch into its branch
+    return config.fetch.refspecs
+
+
+def local_git(*args: str) -> list[str]:
+    """argv for a git command on the mirror itself (no network options needed)."""
+    return [resolve_git_executable(), "-c", "safe.directory=*", *args]
+
+
+def parse_ls_remote_heads(output: str) -> dict[str, str]:
+    """Branch name -> SHA from ``git ls-remote --heads`` output."""
+    heads: dict[str, str] = {}
+    for line in output.splitlines():
+        sha, _, ref = line.strip().partition("\t")
+        if ref.startswith("refs/heads/"):
+            heads[ref.removeprefix("refs/heads/")] = sha
+    return dict(sorted(heads.items()))
+
+
 def dry_run_line(task: SyncTask, config: Config) -> str:
     """Render the ``DRY RUN <directory> <git argv>`` line (matches Engine.kt)."""
-    repo_exists = task.destination.exists()
+    repo_exists = task.destination.exists() or task.refspecs is not None
     directory = task.destination if repo_exists else task.destination.parent
     argv = _build_argv(task, config)
     return f"DRY RUN {directory} {' '.join(argv)}"
@@ -247,10 +757,11 @@ async def preflight_storage_check(root: Path, timeout_ms: int) -> str | None:
     return None
 
 
-def _write_credentials(user: str, token: str) -> Path:
-    """Write a git credential-store file with the GitHub https URL (deleted after sync)."""
+def write_credentials(user: str, token: str, origin: str) -> Path:
+    """Write a git credential-store file for ``origin`` (deleted after sync)."""
     fd, path = tempfile.mkstemp(prefix="gitout-credentials-")
-    url = f"https://{quote(user, safe='')}:{quote(token, safe='')}@github.com"
+    scheme, _, host = origin.partition("://")
+    url = f"{scheme}://{quote(user, safe='')}:{quote(token, safe='')}@{host}"
     with open(fd, "w") as handle:
         handle.write(url)
     return Path(path)
@@ -269,8 +780,24 @@ async def default_git_runner(argv: list[str], cwd: Path, timeout_seconds: float)
     except TimeoutError:
         process.kill()
         await process.wait()
+        git_logger.debug("%s timed out after %ss", shlex.join(argv), timeout_seconds)
         raise RuntimeError(f"git operation timed out after {timeout_seconds}s") from None
-    return process.returncode or 0, stdout.decode(errors="replace")
+    output = stdout.decode(errors="replace")
+    if git_logger.isEnabledFor(logging.DEBUG):
+        git_logger.debug(
+            "%s (in %s) exited %d\n%s", shlex.join(argv), cwd, process.returncode, output.rstrip()
+        )
+    return process.returncode or 0, output
+
+
+async def _export_each(
+    names: list[str], export_to: MetadataExport, target_dir: Path, semaphore: asyncio.Semaphore
+) -> int:
+    async def run(name: str) -> bool:
+        async with semaphore:
+            return await export_to(name, target_dir)
+
+    return sum(await asyncio.gather(*(run(n) for n in names)))
 
 
 @dataclass
@@ -278,9 +805,12 @@ class Engine:
     config: Config
     destination: Path
     repo_loader: RepoLoader | None = None
+    # Validates each token (and its scopes) before discovery; the CLI binds
+    # gitout.token_check.check_token. None skips the check.
+    token_checker: TokenChecker | None = None
     git_runner: GitRunner = default_git_runner
     environ: Mapping[str, str] = field(default_factory=dict)
-    workers: int | None = None
+    workers: int | None = None  # None: parallelism.workers; 0: auto_worker_count
     timeout_seconds: float = 600.0
     credentials_path: str | None = None
     retry_policy: RetryPolicy = field(default_factory=RetryPolicy)
@@ -293,8 +823,78 @@ class Engine:
     # Lifecycle collaborators (built by the CLI when configured).
     search_index_service: SearchIndexService | None = None
     health_check: HealthCheck | None = None
+    # Receives the run's metrics after a real sync ([metrics] pushgateway_url / CLI
+    # --pushgateway).
+    pushgateway: Pushgateway | None = None
+    # Built from [tracing] when configured; inject one in tests.
+    tracer: Tracer | None = None
+    # Built from [[webhooks]] when any is configured; inject one in tests.
+    webhooks: WebhookNotifier | None = None
+    # Built from [notify.email] when it is configured; inject one in tests.
+    email: EmailNotifier | None = None
+    # Built from [notify.slack] / [notify.discord]; inject fakes in tests.
+    chat: list[ChatNotifier] | None = None
     telegram: TelegramNotificationService | None = None
+    # Built from the resolved token when the [github.metadata] exports are enabled.
+    settings_exporter: RepositorySettingsExporter | None = None
+    community_exporter: CommunitySnapshotExporter | None = None
+    # Built from the resolved token when [github.archive] selects any repository.
+    archiver: MigrationArchiver | None = None
+    # Built from [github.app] (App ID + private key) when it is configured.
+    app_auth: GitHubAppAuth | None = None
+    # Built from [offsite] when it is configured.
+    offsite_backend: StorageBackend | None = None
+    # Runs rclone for [rclone]; inject a fake in tests.
+    rclone_runner: RcloneRunner = default_git_runner
+    # Runs rsync for [replicate]; inject a fake in tests.
+    rsync_runner: RsyncRunner = default_git_runner
+    # Copied to after the sync, besides [fanout] destinations (CLI --also).
+    extra_destinations: tuple[Path, ...] = ()
+    # Only sync repositories in these [groups] (CLI --group); empty means everything.
+    groups: frozenset[str] = frozenset()
+    # Only sync the repository of this name (CLI --only); naming a [git.repos] entry
+    # also skips GitHub discovery.
+    only: str | None = None
+    # Only sync repositories matching one of these patterns (CLI --filter) and none of
+    # exclude_patterns (CLI --exclude); names, globs or "re:<regex>" as in the config.
+    include_patterns: tuple[str, ...] = ()
+    exclude_patterns: tuple[str, ...] = ()
+    # Start no further repository after the first failure and skip the post-sync steps
+    # (CLI --fail-fast); repositories already syncing finish.
+    fail_fast: bool = False
+    # Also write each run report here, with or without [reports] (CLI --report).
+    report_path: Path | None = None
+    # Live observer of the run (see gitout.events), e.g. the TUI dashboard.
+    on_event: EventListener | None = None
+    # One per fan-out destination after a real run, for the CLI summary.
+    fanout_results: list[FanoutResult] = field(default_factory=list, init=False)
+    # Set when fail_fast stopped the run early.
+    aborted: bool = field(default=False, init=False)
     _token: str | None = field(default=None, init=False, repr=False)
+    # What the GitHub API collaborators authenticate with: the token, or a pool of them.
+    _auth: str | TokenPool | None = field(default=None, init=False, repr=False)
+    _git_user: str | None = field(default=None, init=False, repr=False)
+    # The temporary git credential-store file of the current run, if one was written.
+    _credentials_file: Path | None = field(default=None, init=False, repr=False)
+    # The auto_worker_count of this run's tasks (workers == 0).
+    _auto_workers: int | None = field(default=None, init=False, repr=False)
+    # git -c options for [push_mirror] pushes, set for the duration of a run.
+    _push_options: list[str] = field(default_factory=list, init=False, repr=False)
+    # The tracer of the current real run (tracer, or one built from [tracing]).
+    _active_tracer: Tracer | None = field(default=None, init=False, repr=False)
+
+    def _worker_count(self) -> int:
+        if self.workers == 0:
+            return self._auto_workers or auto_worker_count(())
+        return self.workers or self.config.parallelism.workers
+
+    def _emit(self, event: SyncEvent) -> None:
+        if self.on_event is None:
+            return
+        try:
+            self.on_event(event)
+        except Exception:  # noqa: BLE001 - an observer must never break the sync
+            logger.warning("Event listener failed on %s", type(event).__name__, exc_info=True)
 
     def _apply_state_tracking(self, user_repos: UserRepositories) -> set[str]:
         """Detect repo state changes, maintain the exclusion list, persist state.
@@ -326,14 +926,74 @@ class Engine:
         tracker.save_state(metadata, excluded)
         return set(excluded)
 
+    def _matches_cli_patterns(self, name: str) -> bool:
+        """Whether ``name`` passes ``--filter`` (any, when given) and ``--exclude`` (none)."""
+        if self.include_patterns and not any(
+            matches_pattern(pattern, name) for pattern in self.include_patterns
+        ):
+            return False
+        return not any(matches_pattern(pattern, name) for pattern in self.exclude_patterns)
+
     async def _discover(self) -> UserRepositories | None:
         github = self.config.github
         if github is None:
             return None
         if self.repo_loader is None:
             raise RuntimeError("repo_loader is required when [github] is configured")
-        self._token = resolve_github_token(github.token, self.environ)
-        return await self.repo_loader(github.user, self._token)
+        if github.app is not None:
+            app_auth = self._app_auth(github.app, github.api_url)
+            self._token = await app_auth.installation_token(github.user)
+            self._auth = self._token
+            self._git_user = INSTALLATION_GIT_USER
+            return await self.repo_loader(github.user, self._auth)
+        tokens = resolve_github_tokens(
+            configured_github_token(github), self.environ, api_url=github.api_url
+        )
+        if self.token_checker is not None:
+            tokens = await usable_tokens(self.token_checker, tokens, required_scopes(github))
+        self._token = tokens[0]  # git credentials use the first token
+        self._auth = tokens[0] if len(tokens) == 1 else TokenPool(tokens)
+        self._git_user = github.user
+        return await self.repo_loader(github.user, self._auth)
+
+    async def _refresh_app_token(self) -> None:
+        """Re-mint the ``[github.app]`` installation token when it nears its one-hour expiry.
+
+        Called before each repository sync and each export phase. A new token replaces
+        the one the API collaborators and the git credential-store file use; when minting
+        fails the current token is kept, with a warning.
+        """
+        github = self.config.github
+        if self.app_auth is None or github is None or github.app is None or not self._token:
+            return
+        try:
+            token = await self.app_auth.installation_token(github.user)
+        except Exception as exc:  # noqa: BLE001 - the current token is valid a while longer
+            logger.warning("Cannot refresh the GitHub App installation token: %s", exc)
+            return
+        if token == self._token:
+            return
+        self._token = token
+        self._auth = token
+        if self._credentials_file is not None:
+            git_user = self._git_user or github.user
+            try:
+                credentials = write_credentials(git_user, token, web_url(github.api_url))
+                os.replace(credentials, self._credentials_file)
+            except OSError as exc:
+                logger.warning("Cannot update the git credentials with the new token: %s", exc)
+
+    def _app_auth(self, app: GitHubApp, api_url: str) -> GitHubAppAuth:
+        if self.app_auth is None:
+            self.app_auth = GitHubAppAuth.from_key_file(
+                app.id,
+                app.private_key_file,
+                installation_id=app.installation_id,
+                api_url=api_url,
+                user_agent=self.config.http.user_agent,
+                extra_headers=self.config.http.headers,
+            )
+        return self.app_auth
 
     def _build_collaborators(
         self,
@@ -358,13 +1018,18 @@ class Engine:
         maint = self.maintenance
         if maint is None and self.config.maintenance.enabled:
             maint = RepositoryMaintenance(
-                self.config.maintenance, timeout_seconds=self.timeout_seconds
+                self.config.maintenance,
+                timeout_seconds=self.timeout_seconds,
+                git_config=transfer_options(self.config),
+                state_path=self.destination / MAINTENANCE_STATE_FILE,
             )
 
         lfs = self.lfs
         if lfs is None and self.config.lfs.fetch_lfs:
             candidate = LfsSupport(timeout_seconds=self.timeout_seconds)
             lfs = candidate if candidate.is_lfs_available() else None
+            if lfs is None:
+                logger.warning("lfs.fetch_lfs is set but git-lfs is not installed")
 
         return breaker, tracker, maint, lfs
 
@@ -396,15 +1061,91 @@ class Engine:
             and self._token
             and credentials_path is None
         ):
-            temp_credentials = _write_credentials(self.config.github.user, self._token)
+            github = self.config.github
+            git_user = self._git_user or github.user
+            temp_credentials = write_credentials(git_user, self._token, web_url(github.api_url))
             credentials_path = str(temp_credentials)
+        self._credentials_file = temp_credentials
         try:
             yield credentials_path
         finally:
+            self._credentials_file = None
             if temp_credentials is not None:
                 with contextlib.suppress(OSError):
                     temp_credentials.unlink()
 
+    @contextlib.asynccontextmanager
+    async def _repo_credentials_scope(self, dry_run: bool) -> AsyncIterator[dict[str, str]]:
+        """Yield a credential-store file per ``[git.repos]`` login; deleted on exit.
+
+        Each repository gets its own file, so logins for the same host never clash.
+        HTTP(S) entries without a configured login use their host's ``.netrc`` entry.
+        Nothing is written (or resolved) during a dry run.
+        """
+        files: dict[str, Path] = {}
+        try:
+            if not dry_run:
+                git = self.config.git
+                for name, url in git.repos.items():
+                    origin = credential_origin(url)
+                    login = git.credentials.get(name)
+                    if login is not None:
+                        password = git_repo_password(name, login, self.environ)
+                        files[name] = write_credentials(login.username, password, origin)
+                    elif origin.startswith(("https://", "http://")):
+                        found = netrc_login([provider_host(url)], self.environ)
+                        if found is not None:
+                            files[name] = write_credentials(*found, origin)
+            yield {name: str(path) for name, path in files.items()}
+        finally:
+            for path in files.values():
+                with contextlib.suppress(OSError):
+                    path.unlink()
+
+    @contextlib.asynccontextmanager
+    async def _ssh_scope(self, dry_run: bool) -> AsyncIterator[str | None]:
+        """Yield the ``SSH_ASKPASS`` helper for the ``[ssh]`` passphrase, if any.
+
+        Like the credentials file, the helper is only written for real runs and is
+        deleted on exit.
+        """
+        askpass = None if dry_run else write_askpass(self.config.ssh)
+        try:
+            yield str(askpass) if askpass else None
+        finally:
+            if askpass is not None:
+                with contextlib.suppress(OSError):
+                    askpass.unlink()
+
+    @contextlib.asynccontextmanager
+    async def _push_scope(self, dry_run: bool, ssh_askpass: str | None) -> AsyncIterator[None]:
+        """Set the git ``-c`` options for [push_mirror] (credential file deleted on exit)."""
+        push = self.config.push_mirror
+        credentials: Path | None = None
+        options: list[str] = []
+        try:
+            if push is not None and not dry_run:
+                if not self.config.ssl.verify_certificates:
+                    options.append("http.sslVerify=false")
+                if is_ssh_url(push.url):
+                    command = ssh_command(self.config.ssh, ssh_askpass)
+                    if command is not None:
+                        options.append(f"core.sshCommand={command}")
+                elif push.username:
+                    password = push_password(push, self.environ)
+                    origin = credential_origin(push.url)
+                    credentials = write_credentials(push.username, password, origin)
+                    # Reset inherited helpers so only this login is offered.
+                    helper = f"credential.helper=store --file={credentials}"
+                    options += ["credential.helper=", helper]
+            self._push_options = options
+            yield
+        finally:
+            self._push_options = []
+            if credentials is not None:
+                with contextlib.suppress(OSError):
+                    credentials.unlink()
+
     async def _run_workers(
         self,
         tasks: list[SyncTask],
@@ -412,32 +1153,81 @@ class Engine:
         tracker: FailureTracker | None,
         maint: RepositoryMaintenance | None,
         lfs: LfsSupport | None,
+        sync_state: SyncStateStore | None = None,
     ) -> list[SyncOutcome]:
-        """Run all sync tasks in parallel under the configured semaphore limits."""
-        worker_count = self.workers or self.config.parallelism.workers
+        """Run all sync tasks in parallel under the configured semaphore limits.
+
+        With a ``sync_state``, repositories it reports unchanged are skipped.
+        """
+        if self.workers == 0:
+            self._auto_workers = auto_worker_count(tasks)
+        worker_count = self._worker_count()
         semaphore = asyncio.Semaphore(worker_count)
         large_repo_semaphore = asyncio.Semaphore(self.config.large_repos.max_parallel)
 
         logger.info("Starting sync of %d repositories with %d workers", len(tasks), worker_count)
         if self.telegram is not None:
             self.telegram.notify_sync_start(len(tasks), worker_count)
+        self._emit(RunStarted(total=len(tasks), workers=worker_count))
         start_time = time.monotonic()
+        free_slots = list(range(worker_count, 0, -1))  # pop() hands out 1 first
+
+        def cancelled(task: SyncTask) -> SyncOutcome:
+            outcome = SyncOutcome(
+                task=task, ok=False, skipped=True, error="not started: an earlier repository failed"
+            )
+            self._emit(RepoFinished(outcome))
+            return outcome
 
         async def run(task: SyncTask) -> SyncOutcome:
+            if self.aborted:
+                return cancelled(task)
             # Checked before acquiring a permit so queued tasks skip once tripped.
             if breaker is not None and breaker.is_open():
                 logger.warning("Skipping %s: storage circuit breaker is open", task.name)
-                return SyncOutcome(
+                skipped = SyncOutcome(
                     task=task, ok=False, skipped=True, error="storage circuit breaker open"
                 )
-            if tracker is not None and tracker.should_skip(task.name):
-                return SyncOutcome(task=task, ok=True, skipped=True)
+                self._emit(RepoFinished(skipped))
+                return skipped
+            if tracker is not None and tracker.should_skip(task.identity):
+                skipped = SyncOutcome(task=task, ok=True, skipped=True)
+                self._emit(RepoFinished(skipped))
+                return skipped
+            if sync_state is not None and sync_state.is_unchanged(task):
+                logger.debug("Skipping %s: not pushed since the last sync", task.name)
+                skipped = SyncOutcome(task=task, ok=True, skipped=True)
+                self._emit(RepoFinished(skipped))
+                return skipped
             async with semaphore:
-                return await self._sync_one(
-                    task, breaker, tracker, maint, lfs, large_repo_semaphore
-                )
+                if self.aborted:
+                    return cancelled(task)
+                slot = free_slots.pop()
+                self._emit(RepoStarted(task=task, worker=slot))
+                started = time.monotonic()
+                try:
+                    with (
+                        repository_logs(task.name),
+                        self._span("repository", **{"gitout.repository": task.identity}) as span,
+                    ):
+                        outcome = await self._sync_one(
+                            task, breaker, tracker, maint, lfs, large_repo_semaphore
+                        )
+                        if span is not None:
+                            span.attributes["gitout.outcome"] = outcome_label(outcome)
+                            span.attributes["gitout.attempts"] = outcome.attempts
+                            span.error = outcome.error if not outcome.ok else None
+                finally:
+                    free_slots.append(slot)
+                outcome = replace(outcome, duration_seconds=time.monotonic() - started)
+                self._emit(RepoFinished(outcome, worker=slot))
+                if self.fail_fast and not outcome.ok and not self.aborted:
+                    logger.error("Stopping the run: %s failed (fail-fast)", task.name)
+                    self.aborted = True
+                return outcome
 
         results = list(await asyncio.gather(*(run(t) for t in tasks)))
+        self._emit(RunFinished(results))
         successful = sum(1 for r in results if r.ok)
         logger.info("Sync complete: %d/%d repositories succeeded", successful, len(results))
         self._report_to_telegram(results, start_time)
@@ -453,12 +1243,16 @@ class Engine:
             return
         successful = sum(1 for outcome in results if outcome.ok)
         self.telegram.notify_sync_completion(
-            successful, len(results) - successful, int(time.monotonic() - start_time)
+            successful,
+            len(results) - successful,
+            int(time.monotonic() - start_time),
+            [f"{o.task.identity}: {o.error}" for o in results if not o.ok],
         )
+        labels = summary_names([outcome.task for outcome in results])
         self.telegram.record_failures(
             [
                 FailedRepoSummary(
-                    name=outcome.task.name,
+                    name=labels[outcome.task],
                     url=outcome.task.url,
                     error_message=outcome.error or "",
                     category=display_name(outcome.category)
@@ -471,6 +1265,99 @@ class Engine:
             ]
         )
 
+    async def _export_metadata(
+        self,
+        tasks: list[SyncTask],
+        cache: HttpCache | None = None,
+        rate_limiter: RateLimiter | None = None,
+    ) -> None:
+        """Export settings and community snapshots of owned repos (best effort)."""
+        github = self.config.github
+        if github is None:
+            return
+        names = [t.name for t in tasks if t.reasons and "owned" in t.reasons]
+        github_dir = self.destination / "github"
+        http = self.config.http
+        semaphore = asyncio.Semaphore(self._worker_count())
+
+        exports: list[tuple[str, MetadataExport, Path]] = []
+        if github.metadata.settings:
+            settings = self.settings_exporter
+            if settings is None and self._auth:
+                settings = RepositorySettingsExporter(
+                    self._auth,
+                    api_url=github.api_url,
+                    user_agent=http.user_agent,
+                    extra_headers=http.headers,
+                    cache=cache,
+                    rate_limiter=rate_limiter,
+                )
+            if settings is not None:
+                exports.append(("settings", settings.export_to, github_dir / "settings"))
+        if github.metadata.community:
+            community = self.community_exporter
+            if community is None and self._auth:
+                community = CommunitySnapshotExporter(
+                    self._auth,
+                    api_url=github.api_url,
+                    user_agent=http.user_agent,
+                    extra_headers=http.headers,
+                    cache=cache,
+                    rate_limiter=rate_limiter,
+                )
+            if community is not None:
+                target = github_dir / "community"
+                exports.append(("community snapshots", community.export_to, target))
+
+        for what, export_to, target_dir in exports:
+            exported = await _export_each(names, export_to, target_dir, semaphore)
+            logger.info("Exported %s of %d/%d owned repositories", what, exported, len(names))
+
+    async def _archive_repositories(
+        self,
+        user_repos: UserRepositories | None,
+        cache: HttpCache | None = None,
+        rate_limiter: RateLimiter | None = None,
+    ) -> None:
+        """Download migration archives for [github.archive] repos (best effort)."""
+        github = self.config.github
+        if github is None or user_repos is None:
+            return
+        owned = {name for name in user_repos.owned if not is_ignored(self.config, name)}
+        names = archive_targets(owned, github.archive.repos, include_owned=github.archive.owned)
+        if self.groups:
+            names = [n for n in names if self.groups.intersection(groups_for(self.config, n))]
+        if self.only is not None:
+            names = [n for n in names if is_named(n, self.only)]
+        names = [n for n in names if self._matches_cli_patterns(n)]
+        if not names:
+            return
+        archiver = self.archiver
+        if archiver is None:
+            if not self._auth:
+                return
+            http = self.config.http
+            archiver = MigrationArchiver(
+                self._auth,
+                api_url=github.api_url,
+                user_agent=http.user_agent,
+                extra_headers=http.headers,
+                retention=github.archive.retention,
+                request=MigrationRequest(
+                    exclude_attachments=github.archive.exclude_attachments,
+                    exclude_releases=github.archive.exclude_releases,
+                    exclude_metadata=github.archive.exclude_metadata,
+                    lock_repositories=github.archive.lock_repositories,
+                    exclude=tuple(github.archive.exclude),
+                ),
+                cache=cache,
+                rate_limiter=rate_limiter,
+            )
+        semaphore = asyncio.Semaphore(self._worker_count())
+        archive_dir = self.destination / "github" / "archive"
+        archived = await _export_each(names, archiver.archive_to, archive_dir, semaphore)
+        logger.info("Archived %d/%d repositories", archived, len(names))
+
     async def _finalize(
         self,
         results: list[SyncOutcome],
@@ -478,12 +1365,34 @@ class Engine:
         maint: RepositoryMaintenance | None,
         user_repos: UserRepositories | None,
     ) -> None:
-        """Persist failure state, run full repack if due, and auto-index for search."""
+        """Export metadata and archives, persist failure state, repack if due, auto-index."""
+        await self._refresh_app_token()
+        cache = HttpCache(self.destination / HTTP_CACHE_FILE)
+        # One REST budget (per token) shared by the exporters and the archiver.
+        tokens = self._auth if isinstance(self._auth, TokenPool) else None
+        rate_limiter = RateLimiter(tokens=tokens)
+        await self._export_metadata([r.task for r in results], cache, rate_limiter)
+        await self._refresh_app_token()
+        with self._span("archive"):
+            await self._archive_repositories(user_repos, cache, rate_limiter)
+        if cache.hits:
+            logger.debug("Answered %d GitHub REST requests from the ETag cache", cache.hits)
+        cache.save()
         if tracker is not None:
             tracker.save_state()
+        if maint is not None:
+            maint.save_state()
         if maint is not None and maint.register_sync_and_check_repack():
             logger.info("Running full repack of %s", self.destination)
             await asyncio.to_thread(maint.run_full_repack, self.destination)
+        if self.config.snapshots.enabled:
+            await self._take_snapshot()
+        if self.config.offsite is not None:
+            await self._upload_offsite(self.config.offsite)
+        if self.config.rclone is not None:
+            await self._replicate_rclone(self.config.rclone, results)
+        if self.config.replicate is not None:
+            await self._replicate_rsync(self.config.replicate, results)
         if (
             self.search_index_service is not None
             and self.config.search.auto_index
@@ -496,41 +1405,472 @@ class Engine:
             )
             logger.info("Search auto-indexing complete")
 
+    async def _take_snapshot(self) -> None:
+        """Snapshot every mirror when the newest snapshot is old enough, then thin them out."""
+        snapshots = self.config.snapshots
+        root = bundle_root(self.destination, snapshots.directory)
+        now = datetime.now(UTC)
+        if snapshot_due(root, now, snapshots.interval_hours):
+
+            async def run(argv: list[str], cwd: Path) -> tuple[int, str]:
+                return await self.git_runner(argv, cwd, self.timeout_seconds)
+
+            taken = await take_snapshot(
+                local_git(),
+                self.destination,
+                root,
+                run,
+                now=now,
+                verify=snapshots.verify,
+                workers=self._worker_count(),
+                mode=snapshots.mode,
+            )
+            if taken is not None:
+                logger.info("Took snapshot %s", taken)
+        for removed in await asyncio.to_thread(prune_snapshots, root, snapshots):
+            logger.info("Removed snapshot %s", removed.name)
+
+    async def _upload_offsite(self, offsite: Offsite) -> None:
+        """Copy the destination's artifacts to the [offsite] backend (never fatal)."""
+        try:
+            backend = self.offsite_backend or build_backend(offsite, self.environ)
+        except OffsiteError as exc:
+            logger.warning("Skipping the off-site upload: %s", exc)
+            return
+        uploader = OffsiteUploader(offsite, self.destination, backend)
+        summary = await asyncio.to_thread(uploader.upload)
+        logger.info(
+            "Uploaded %d file(s) to %s (%d unchanged, %d failed)",
+            summary.uploaded,
+            summary.target,
+            summary.unchanged,
+            summary.failed,
+        )
+
+    async def _replicate_rclone(self, rclone: Rclone, results: list[SyncOutcome]) -> None:
+        """Mirror the destination to the [rclone] remote and report failures (never fatal).
+
+        After a run where a repository failed, ``sync`` mode falls back to ``copy``, so a
+        mirror missing locally is never deleted from the remote as well.
+        """
+        failed = sum(1 for outcome in results if not outcome.ok)
+        if failed and rclone.mode == "sync":
+            logger.warning(
+                "%d repositories failed to sync; replicating with rclone copy instead", failed
+            )
+            rclone = replace(rclone, mode="copy")
+        logger.info(
+            "Replicating %s to %s (rclone %s)", self.destination, rclone.remote, rclone.mode
+        )
+        report = await replicate(
+            self.destination, rclone, self.rclone_runner, now=lambda: datetime.now(UTC)
+        )
+        if report.ok:
+            logger.info("Replicated %s to %s", self.destination, rclone.remote)
+            return
+        failed = [failure.describe() for failure in report.failures]
+        for description in failed:
+            logger.warning("rclone: %s", description)
+        if self.telegram is not None:
+            self.telegram.notify_replication_failure(rclone.remote, report.exit_code, failed)
+
+    async def _replicate_rsync(self, replicate: Replicate, results: list[SyncOutcome]) -> None:
+        """rsync the destination to the [replicate] targets unless a repository failed."""
+        failed = sum(1 for outcome in results if not outcome.ok)
+        if failed:
+            logger.warning("Not replicating: %d repositories failed to sync", failed)
+            return
+        for result in await replicate_to_targets(self.destination, replicate, self.rsync_runner):
+            if result.ok:
+                logger.info("Replicated %s to %s", self.destination, result.target)
+                continue
+            logger.warning(
+                "rsync to %s failed (%d): %s", result.target, result.exit_code, result.output
+            )
+            if self.telegram is not None:
+                self.telegram.notify_replication_failure(
+                    result.target, result.exit_code, result.output.splitlines()
+                )
+
+    async def _fan_out(self) -> list[FanoutResult]:
+        """Copy the destination to every [fanout] / --also destination, concurrently."""
+        targets = [Path(path).expanduser() for path in self.config.fanout.destinations]
+        targets += [path for path in self.extra_destinations if path not in targets]
+        delete = self.config.fanout.delete
+        results = await asyncio.gather(
+            *(asyncio.to_thread(fan_out, self.destination, t, delete=delete) for t in targets)
+        )
+        for result in results:
+            if result.ok:
+                logger.info(
+                    "Copied to %s: %d updated, %d removed",
+                    result.destination,
+                    result.copied,
+                    result.removed,
+                )
+            else:
+                logger.warning("Copying to %s failed: %s", result.destination, result.error)
+        return list(results)
+
+    def _write_report(self, results: list[SyncOutcome], started_at: datetime) -> None:
+        """Write the per-cycle JSON report when [reports] is enabled and to ``report_path``.
+
+        Never fatal: a report that cannot be written is logged.
+        """
+        reports = self.config.reports
+        if not reports.enabled and self.report_path is None:
+            return
+        report = build_report(results, started_at=started_at, finished_at=datetime.now(UTC))
+        try:
+            if reports.enabled:
+                path = write_report(
+                    report,
+                    self.destination / reports.directory,
+                    retention=reports.retention,
+                    keep=self.report_path,
+                )
+                logger.info("Wrote run report %s", path)
+            if self.report_path is not None:
+                logger.info("Wrote run report %s", write_report_file(report, self.report_path))
+        except OSError as exc:
+            logger.warning("Failed to write run report: %s", exc)
+
+    async def _push_metrics(self, results: list[SyncOutcome], started_at: datetime) -> None:
+        """Push the run's metrics to the Pushgateway when there is one (never fatal)."""
+        if self.pushgateway is None:
+            return
+        metrics = render_metrics(results, started_at=started_at, finished_at=datetime.now(UTC))
+        if await self.pushgateway.push(metrics):
+            logger.info("Pushed metrics to %s", self.pushgateway.url)
+
+    def _span(
+        self, name: str, **attributes: AttributeValue
+    ) -> contextlib.AbstractContextManager[Span | None]:
+        """A span of the current run's trace; a no-op without [tracing]."""
+        if self._active_tracer is None:
+            return contextlib.nullcontext()
+        return self._active_tracer.span(name, **attributes)
+
     async def perform_sync(self, dry_run: bool = False) -> list[SyncOutcome]:
+        tracing = self.config.tracing
+        tracer = self.tracer
+        if tracer is None and tracing is not None:
+            tracer = Tracer(
+                tracing.endpoint,
+                service_name=tracing.service_name,
+                headers=tracing.headers,
+                timeout_seconds=tracing.timeout_seconds,
+            )
+        self._active_tracer = None if dry_run else tracer
+        if self._active_tracer is None:
+            return await self._perform_sync(dry_run)
+        try:
+            with self._active_tracer.span(
+                "sync", **{"gitout.destination": str(self.destination)}
+            ) as span:
+                results = await self._perform_sync(dry_run)
+                span.attributes["gitout.repositories"] = len(results)
+                span.attributes["gitout.failed"] = sum(1 for r in results if not r.ok)
+                return results
+        finally:
+            await self._active_tracer.export()
+            self._active_tracer = None
+
+    async def _perform_sync(self, dry_run: bool) -> list[SyncOutcome]:
+        """Run :meth:`_run_sync` between the health check pings, then send the notifications."""
         if self.config.version != 0:
             raise ValueError("Only version 0 of the config is supported at this time")
         if not dry_run and not self.destination.is_dir():
             raise ValueError("Destination must exist and must be a directory")
+        if dry_run:
+            return await self._run_sync(dry_run)
 
-        started_check = None
-        if not dry_run and self.health_check is not None:
-            started_check = await self.health_check.start()
+        started_at = datetime.now(UTC)
+        started_check = await self.health_check.start() if self.health_check else None
+        try:
+            results = await self._run_sync(dry_run)
+        except Exception as exc:
+            if started_check is not None:
+                await started_check.fail(f"gitout sync failed: {exc}")
+            await self._notify([], started_at, error=str(exc))
+            raise
+        if started_check is not None:
+            if all(outcome.ok for outcome in results):
+                await started_check.complete()
+            else:
+                await started_check.fail(failure_log(results))
+        await self._notify(results, started_at)
+        return results
+
+    async def _notify(
+        self, results: list[SyncOutcome], started_at: datetime, *, error: str | None = None
+    ) -> None:
+        """Send the run's summary to the [[webhooks]] and the [notify] channels (never fatal)."""
+        webhooks = self.webhooks
+        if webhooks is None and self.config.webhooks:
+            webhooks = WebhookNotifier(self.config.webhooks)
+        email = self.email
+        if email is None and self.config.notify.email is not None:
+            email = EmailNotifier(self.config.notify.email)
+        chat = self.chat if self.chat is not None else chat_notifiers(self.config.notify)
+        if webhooks is None and email is None and not chat:
+            return
+        payload = build_payload(
+            results, started_at=started_at, finished_at=datetime.now(UTC), error=error
+        )
+        if webhooks is not None:
+            delivered = await webhooks.notify(payload)
+            if delivered:
+                logger.info(
+                    "Sent the %s notification to %d webhook(s)", payload["event"], delivered
+                )
+        if email is not None and await email.notify(payload):
+            logger.info("Mailed the run summary to %s", ", ".join(email.config.recipients))
+        for notifier in chat:
+            if await notifier.notify(payload):
+                logger.info("Posted the run summary to %s", notifier.service)
 
+    async def _run_sync(self, dry_run: bool) -> list[SyncOutcome]:
         if not dry_run:
             await self._run_preflight()
 
-        user_repos = await self._discover()
+        only_git_repo = self.only is not None and self.only in self.config.git.repos
+        with self._span("discover"):
+            user_repos = None if only_git_repo else await self._discover()
 
         excluded_names: set[str] = set()
         if not dry_run and self.config.github is not None and user_repos is not None:
             excluded_names = self._apply_state_tracking(user_repos)
 
-        async with self._credentials_scope(dry_run) as credentials_path:
+        async with (
+            self._credentials_scope(dry_run) as credentials_path,
+            self._ssh_scope(dry_run) as ssh_askpass,
+            self._repo_credentials_scope(dry_run) as repo_credentials,
+            self._push_scope(dry_run, ssh_askpass),
+        ):
             tasks = collect_sync_tasks(
-                self.config, self.destination, user_repos, credentials_path, excluded_names
+                self.config,
+                self.destination,
+                user_repos,
+                credentials_path,
+                excluded_names,
+                ssh_askpass=ssh_askpass,
+                repo_credentials=repo_credentials,
             )
+            if self.groups:
+                tasks = [t for t in tasks if self.groups.intersection(t.groups)]
+            if self.only is not None:
+                tasks = [t for t in tasks if is_named(t.name, self.only)]
+                if not tasks:
+                    raise UnknownRepositoryError(f"No repository named {self.only} to sync")
+            tasks = [t for t in tasks if self._matches_cli_patterns(t.name)]
 
             if dry_run:
                 return [SyncOutcome(task=t, ok=True) for t in tasks]
 
             breaker, tracker, maint, lfs = self._build_collaborators()
-            results = await self._run_workers(tasks, breaker, tracker, maint, lfs)
-            await self._finalize(results, tracker, maint, user_repos)
-
-            if started_check is not None:
-                await started_check.complete()
+            if tracker is not None:
+                tracker.adopt_legacy_names(legacy_failure_aliases(tasks))
+            sync_state = SyncStateStore(self.destination / SYNC_STATE_FILE)
+            github = self.config.github
+            skip_unchanged = github is not None and github.clone.skip_unchanged
+            started_at = datetime.now(UTC)
+            results = await self._run_workers(
+                tasks, breaker, tracker, maint, lfs, sync_state if skip_unchanged else None
+            )
+            sync_state.record(results)
+            sync_state.save()
+            if self.aborted:
+                if tracker is not None:
+                    tracker.save_state()
+                self._write_report(results, started_at)
+                await self._push_metrics(results, started_at)
+                return results
+            with self._span("finalize"):
+                await self._finalize(results, tracker, maint, user_repos)
+            self._write_report(results, started_at)
+            await self._push_metrics(results, started_at)
+            self.fanout_results = await self._fan_out()
             return results
 
+    async def _remote_unchanged(self, task: SyncTask) -> bool:
+        """Whether the remote's branch heads equal the mirror's; False when unsure."""
+        argv = _build_argv(task, self.config, ls_remote=True)
+        try:
+            code, output = await self.git_runner(argv, task.destination, self.timeout_seconds)
+        except Exception as exc:  # noqa: BLE001 - the full fetch reports real failures
+            logger.debug("ls-remote pre-check of %s failed: %s", task.name, exc)
+            return False
+        if code != 0:
+            return False
+        remote = parse_ls_remote_heads(output)
+        return bool(remote) and remote == read_heads(task.destination)
+
+    async def _git_output(self, argv: list[str], cwd: Path) -> str:
+        code, output = await self.git_runner(argv, cwd, self.timeout_seconds)
+        if code != 0:
+            raise RuntimeError(output or f"git exited with code {code}")
+        return output
+
+    async def _init_mirror(self, task: SyncTask) -> None:
+        """Create an empty bare repository whose ``origin`` fetches only ``task.refspecs``.
+
+        ``clone`` would fetch every branch first, which is what a refspec limit on a
+        monorepo with thousands of CI refs is there to avoid.
+        """
+        await self._git_output(local_git("init", "--quiet", "--bare"), task.destination)
+        await self._git_output(local_git("config", "remote.origin.url", task.url), task.destination)
+        for refspec in task.refspecs or ():
+            await self._git_output(
+                local_git("config", "--add", "remote.origin.fetch", refspec), task.destination
+            )
+        if task.clone_filter is not None:  # what clone --filter would have recorded
+            for key, value in (
+                ("remote.origin.promisor", "true"),
+                ("remote.origin.partialclonefilter", task.clone_filter),
+            ):
+                await self._git_output(local_git("config", key, value), task.destination)
+
+    async def _local_refs(self, task: SyncTask) -> dict[str, str]:
+        output = await self._git_output(
+            local_git("for-each-ref", "--format=%(objectname) %(refname)"), task.destination
+        )
+        return parse_refs(output)
+
+    async def _prune_refs(self, task: SyncTask, *, archive: bool) -> list[str]:
+        """Delete the refs deleted upstream; ``archive`` keeps them under ``refs/attic/<date>/``.
+
+        See gitout.ref_attic for why gitout prunes instead of ``fetch --prune``.
+        """
+        listing = _build_argv(task, self.config, ls_remote=True, ls_remote_heads=False)
+        remote = parse_refs(await self._git_output(listing, task.destination))
+        if not remote:
+            return []  # nothing to compare with; the fetch reports what is wrong
+        local = await self._local_refs(task)
+        day = datetime.now(UTC).date().isoformat()
+        moved = pruned_refs(local, remote, heads_only=task.single_branch_only)
+        for ref, sha in moved.items():
+            if archive:
+                await self._git_output(
+                    local_git("update-ref", attic_ref(ref, day), sha), task.destination
+                )
+            await self._git_output(local_git("update-ref", "-d", ref, sha), task.destination)
+        if moved and archive:
+            logger.info(
+                "Kept %d ref(s) deleted upstream from %s under refs/attic/%s/",
+                len(moved),
+                task.name,
+                day,
+            )
+        return list(moved)
+
+    async def _keep_rewritten_refs(self, task: SyncTask, before: dict[str, str]) -> list[str]:
+        """Keep the old tip of every ref the fetch moved non-fast-forward."""
+        after = await self._local_refs(task)
+        day = datetime.now(UTC).date().isoformat()
+        rewritten: list[str] = []
+        for ref, (old, new) in moved_refs(before, after).items():
+            ancestry = local_git("merge-base", "--is-ancestor", old, new)
+            code, _ = await self.git_runner(ancestry, task.destination, self.timeout_seconds)
+            if code == 0:
+                continue  # fast-forward
+            await self._git_output(
+                local_git("update-ref", forced_ref(ref, day), old), task.destination
+            )
+            rewritten.append(ref)
+        if rewritten:
+            logger.warning(
+                "Upstream rewrote %d ref(s) of %s (%s); kept the old tips under "
+                "refs/attic/%s/forced/",
+                len(rewritten),
+                task.name,
+                ", ".join(rewritten),
+                day,
+            )
+        return rewritten
+
+    async def _write_tarball(self, task: SyncTask) -> None:
+        """Export the default branch of a freshly synced mirror (tarball / overrides).
+
+        Never fatal: a failed or timed-out export is logged for the repository.
+        """
+        tarball = self.config.tarball
+
+        async def run(argv: list[str], cwd: Path) -> tuple[int, str]:
+            return await self.git_runner(argv, cwd, self.timeout_seconds)
+
+        root = self.destination / Path(tarball.directory).expanduser()
+        try:
+            await write_tarball(
+                local_git(),
+                task.destination,
+                tarball_dir(root, self.destination, task.destination),
+                run,
+                keep=tarball.keep,
+                zstd=shutil.which("zstd") is not None,
+            )
+        except (OSError, RuntimeError) as exc:
+            logger.warning("Exporting a tarball of %s failed: %s", task.name, exc)
+
+    async def _push_mirror(self, task: SyncTask, push: PushMirror) -> None:
+        """Push a freshly synced mirror to the [push_mirror] server (never fatal)."""
+        relative = task.destination.relative_to(self.destination).as_posix()
+        url = push_url(push.url, task.name, relative)
+        options = [arg for option in self._push_options for arg in ("-c", option)]
+        argv = push_argv(local_git(*options), url, push)
+        try:
+            code, output = await self.git_runner(argv, task.destination, self.timeout_seconds)
+        except (OSError, RuntimeError) as exc:
+            code, output = -1, str(exc)
+        if code != 0:
+            logger.warning("Pushing %s to %s failed: %s", task.name, url, output.strip())
+        else:
+            logger.debug("Pushed %s to %s", task.name, url)
+
+    async def _write_bundle(self, task: SyncTask) -> bool:
+        """Bundle a freshly synced mirror ([bundle]); drop the mirror unless it is kept.
+
+        False when the bundle could not be written (the mirror is then always kept).
+        """
+        bundle = self.config.bundle
+        root = bundle_root(self.destination, bundle.directory)
+
+        async def run(argv: list[str], cwd: Path) -> tuple[int, str]:
+            return await self.git_runner(argv, cwd, self.timeout_seconds)
+
+        try:
+            if bundle.incremental:
+                written = await write_incremental_bundle(
+                    local_git(),
+                    task.destination,
+                    chain_path(root, self.destination, task.destination),
+                    run,
+                    verify=bundle.verify,
+                    full_every=bundle.full_every,
+                    now=datetime.now(UTC),
+                )
+            else:
+                target = bundle_path(root, self.destination, task.destination)
+                written = await write_bundle(
+                    local_git(), task.destination, target, run, verify=bundle.verify
+                )
+        except (OSError, RuntimeError) as exc:
+            logger.warning("Bundling %s failed: %s", task.name, exc)
+            return False
+        if written and not bundle.keep_mirrors:
+            await asyncio.to_thread(shutil.rmtree, task.destination)
+        return written
+
+    def _quarantine_if_corrupt(self, task: SyncTask, message: str) -> bool:
+        """Set aside a mirror whose update failed on local corruption (fetch.reclone_corrupt)."""
+        if not self.config.fetch.reclone_corrupt or not is_corruption(message):
+            return False
+        moved = quarantine(task.destination, datetime.now(UTC).date())
+        logger.warning(
+            "The mirror of %s is corrupt; moved it to %s and cloning it again", task.name, moved
+        )
+        return True
+
     async def _sync_one(
         self,
         task: SyncTask,
@@ -540,8 +1880,14 @@ class Engine:
         lfs: LfsSupport | None,
         large_repo_semaphore: asyncio.Semaphore,
     ) -> SyncOutcome:
+        await self._refresh_app_token()
         is_clone = not task.destination.exists()
-        cwd = task.destination.parent if is_clone else task.destination
+        if task.ls_remote_check and not is_clone and await self._remote_unchanged(task):
+            logger.debug("Skipping %s: remote heads match the mirror", task.name)
+            return SyncOutcome(task=task, ok=True, skipped=True)
+        # Mirrors with their own refspecs start as an empty repository (_init_mirror).
+        init_mirror = is_clone and task.refspecs is not None
+        cwd = task.destination.parent if is_clone and not init_mirror else task.destination
         if is_clone:
             cwd.mkdir(parents=True, exist_ok=True)
         logger.debug("Syncing %s (%s)", task.name, "clone" if is_clone else "update")
@@ -551,7 +1897,7 @@ class Engine:
         # large repos).
         if tracker is not None:
             strategy = tracker.get_recommended_strategy(
-                task.name, task.size_kb, self.config.large_repos
+                task.identity, task.size_kb, self.config.large_repos
             )
             base_http1 = strategy.use_http1
             use_shallow = strategy.use_shallow_clone
@@ -563,20 +1909,43 @@ class Engine:
                 self.config.large_repos.timeout_multiplier if task.is_large_repo else 1.0
             )
         effective_timeout = self.timeout_seconds * timeout_multiplier
+        # Anything kept under refs/attic/ must survive the fetch: gitout prunes itself
+        # (into the attic with fetch.attic) and git fetches without --prune.
+        fetch = self.config.fetch
+        keep_rewritten = fetch.keep_rewritten and not is_clone
+        self_prune = (fetch.attic or keep_rewritten) and task.prune and not is_clone
+        fetch_task = replace(task, prune=False) if self_prune else task
+        before: dict[str, str] | None = None
+        rewritten: list[str] = []
 
         async def operation(context: RetryContext) -> str:
+            nonlocal before, rewritten
             force_http1 = base_http1 or context.should_use_http1_fallback
             show_progress = task.is_large_repo or context.is_retry
+            if init_mirror and not (task.destination / "HEAD").exists():
+                await self._init_mirror(task)
+            if keep_rewritten and before is None:
+                # Taken once: a failed attempt may already have moved some refs.
+                before = await self._local_refs(task)
+            if self_prune:
+                await self._prune_refs(task, archive=fetch.attic)
             argv = _build_argv(
-                task,
+                fetch_task,
                 self.config,
                 force_http1=force_http1,
                 use_shallow_clone=use_shallow,
                 show_progress=show_progress,
             )
-            code, output = await self.git_runner(argv, cwd, effective_timeout)
-            if code != 0:
-                raise RuntimeError(output or f"git exited with code {code}")
+            attributes: dict[str, AttributeValue] = {
+                "gitout.attempt": context.attempt,
+                "gitout.operation": "clone" if is_clone else "fetch",
+            }
+            with self._span("git", **attributes):
+                code, output = await self.git_runner(argv, cwd, effective_timeout)
+                if code != 0:
+                    raise RuntimeError(output or f"git exited with code {code}")
+            if before is not None:
+                rewritten = await self._keep_rewritten_refs(task, before)
             return output
 
         async def run_with_retry() -> None:
@@ -592,6 +1961,10 @@ class Engine:
         except SyncFailureException as exc:
             category = exc.error_categories[-1] if exc.error_categories else classify(str(exc))
             cause_message = str(exc.__cause__) if exc.__cause__ else str(exc)
+            if not is_clone and self._quarantine_if_corrupt(task, cause_message):
+                return await self._sync_one(
+                    task, breaker, tracker, maint, lfs, large_repo_semaphore
+                )
             logger.warning(
                 "Failed to sync %s after %d attempt(s) [%s]: %s",
                 task.name,
@@ -600,19 +1973,23 @@ class Engine:
                 cause_message,
             )
             if tracker is not None:
-                tracker.record_failure(task.name, cause_message, category)
+                tracker.record_failure(task.identity, cause_message, category)
             if breaker is not None:
                 breaker.record_failure(category)
             return SyncOutcome(
                 task=task, ok=False, error=str(exc), category=category, attempts=exc.attempt_count
             )
         except Exception as exc:  # noqa: BLE001 - surfaced as an outcome, not raised
+            if not is_clone and self._quarantine_if_corrupt(task, str(exc)):
+                return await self._sync_one(
+                    task, breaker, tracker, maint, lfs, large_repo_semaphore
+                )
             category = classify(str(exc))
             logger.warning(
                 "Failed to sync %s [%s]: %s", task.name, display_name(category), exc
             )
             if tracker is not None:
-                tracker.record_failure(task.name, str(exc), category)
+                tracker.record_failure(task.identity, str(exc), category)
             if breaker is not None:
                 breaker.record_failure(category)
             return SyncOutcome(task=task, ok=False, error=str(exc), category=category, attempts=1)
@@ -622,11 +1999,28 @@ class Engine:
         # thread pool so the event loop stays free for the other gather workers.
         logger.debug("Synced %s successfully", task.name)
         if tracker is not None:
-            tracker.record_success(task.name)
+            tracker.record_success(task.identity)
         if breaker is not None:
             breaker.record_success()
         if maint is not None:
             await asyncio.to_thread(maint.run_post_sync_maintenance, task.destination)
-        if lfs is not None:
-            await asyncio.to_thread(lfs.sync_lfs_if_needed, task.destination)
-        return SyncOutcome(task=task, ok=True)
+        if lfs is not None and not await asyncio.to_thread(
+            lfs.sync_lfs_if_needed, task.destination, _lfs_options(task, self.config)
+        ):
+            logger.warning(
+                "Fetching the LFS objects of %s failed; its large files are not backed up",
+                task.name,
+            )
+        if task.tarball:
+            await self._write_tarball(task)
+        if self.config.push_mirror is not None:
+            await self._push_mirror(task, self.config.push_mirror)
+        if self.config.bundle.enabled and not await self._write_bundle(task):
+            return SyncOutcome(
+                task=task,
+                ok=False,
+                error="Synced, but writing its bundle failed",
+                category=ErrorCategory.STORAGE_ERROR,
+                rewritten_refs=tuple(rewritten),
+            )
+        return SyncOutcome(task=task, ok=True, rewritten_refs=tuple(rewritten))
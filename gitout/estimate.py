@@ -0,0 +1,88 @@
+"""Download-size and API-cost estimate for ``sync --dry-run``.
+
+Discovery already ran by the time the plan is printed, so the estimate covers what
+the real run would add on top of it:
+
+* **download** — for a fresh clone, GitHub's ``diskUsage`` of the repository; for an
+  existing mirror, how far ``diskUsage`` has grown past the size on disk (a fetch only
+  transfers new objects). Repositories without ``diskUsage`` (plain ``[git.repos]``
+  entries, gists) are counted separately as unknown.
+* **API requests** — the minimum number of REST calls the enabled metadata exports and
+  migration archives make (extra branches, rulesets, stargazer pages and archive polls
+  only add to it).
+
+GitHub's ``diskUsage`` is the packed size on their side, so it is an approximation of
+the transfer, not a promise.
+"""
+
+from __future__ import annotations
+
+from collections.abc import Sequence
+from dataclasses import dataclass
+
+from gitout.config import Config
+from gitout.engine import SyncTask
+from gitout.github_archive import archive_targets
+from gitout.reports import directory_size
+from gitout.resources import format_size
+
+# Fewest REST requests per repository for each optional export.
+SETTINGS_REQUESTS = 3  # repository, branches, rulesets
+COMMUNITY_REQUESTS = 2  # stargazers, subscribers
+ARCHIVE_REQUESTS = 3  # start, poll, download
+
+
+@dataclass(frozen=True)
+class RunEstimate:
+    clones: int = 0
+    updates: int = 0
+    download_bytes: int = 0
+    unknown_size: int = 0  # repositories without diskUsage
+    api_requests: int = 0  # beyond discovery
+
+
+def estimate_run(tasks: Sequence[SyncTask], config: Config) -> RunEstimate:
+    clones = updates = download = unknown = 0
+    for task in tasks:
+        exists = task.destination.exists()
+        if exists:
+            updates += 1
+        else:
+            clones += 1
+        if task.size_kb is None:
+            unknown += 1
+            continue
+        remote = task.size_kb * 1024
+        local = (directory_size(task.destination) or 0) if exists else 0
+        download += max(remote - local, 0)
+
+    api_requests = 0
+    github = config.github
+    if github is not None:
+        owned = {t.name for t in tasks if t.reasons and "owned" in t.reasons}
+        if github.metadata.settings:
+            api_requests += SETTINGS_REQUESTS * len(owned)
+        if github.metadata.community:
+            api_requests += COMMUNITY_REQUESTS * len(owned)
+        archived = archive_targets(owned, github.archive.repos, include_owned=github.archive.owned)
+        api_requests += ARCHIVE_REQUESTS * len(archived)
+
+    return RunEstimate(
+        clones=clones,
+        updates=updates,
+        download_bytes=download,
+        unknown_size=unknown,
+        api_requests=api_requests,
+    )
+
+
+def format_estimate(estimate: RunEstimate) -> list[str]:
+    """The ``ESTIMATE`` lines printed after the dry-run plan."""
+    download = f"ESTIMATE download ~{format_size(estimate.download_bytes)}"
+    if estimate.unknown_size:
+        download += f" (+{estimate.unknown_size} repositories of unknown size)"
+    return [
+        f"ESTIMATE {estimate.clones} clones, {estimate.updates} updates",
+        download,
+        f"ESTIMATE at least {estimate.api_requests} GitHub API requests beyond discovery",
+    ]
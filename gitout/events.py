@@ -0,0 +1,50 @@
+"""Sync lifecycle events for live observers (the TUI, custom integrations).
+
+An :class:`~gitout.engine.Engine` built with ``on_event=`` calls the listener
+synchronously, from the event loop, as the run progresses:
+
+* :class:`RunStarted` once the task list is known;
+* :class:`RepoStarted` when a worker slot (numbered from 1) picks up a repository;
+* :class:`RepoFinished` with its outcome (``worker`` is None for tasks skipped before
+  they reached a worker, e.g. by the circuit breaker or failure tracker);
+* :class:`RunFinished` with every outcome, before metadata export and maintenance.
+
+Listeners must be quick and must not raise; an exception is logged and dropped so an
+observer can never break a backup.
+"""
+
+from __future__ import annotations
+
+from collections.abc import Callable
+from dataclasses import dataclass
+from typing import TYPE_CHECKING
+
+if TYPE_CHECKING:
+    from gitout.engine import SyncOutcome, SyncTask
+
+
+@dataclass(frozen=True)
+class RunStarted:
+    total: int
+    workers: int
+
+
+@dataclass(frozen=True)
+class RepoStarted:
+    task: SyncTask
+    worker: int
+
+
+@dataclass(frozen=True)
+class RepoFinished:
+    outcome: SyncOutcome
+    worker: int | None = None
+
+
+@dataclass(frozen=True)
+class RunFinished:
+    outcomes: list[SyncOutcome]
+
+
+SyncEvent = RunStarted | RepoStarted | RepoFinished | RunFinished
+EventListener = Callable[[SyncEvent], None]
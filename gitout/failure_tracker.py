@@ -2,7 +2,9 @@
 
 Persists per-repository failure history to a JSON state file (camelCase keys, so the
 file is interchangeable with the Kotlin implementation), drives auto-skip with a
-cooldown, and recommends a clone strategy from failure history + repo size.
+cooldown, and recommends a clone strategy from failure history + repo size. Records
+are keyed by the task identity (``host/name``); ``adopt_legacy_names`` moves history
+saved under bare names by earlier versions.
 
 The wall clock is injectable (``now_ms``) so tests are deterministic.
 """
@@ -12,7 +14,7 @@ from __future__ import annotations
 import contextlib
 import json
 import time
-from collections.abc import Callable
+from collections.abc import Callable, Mapping
 from dataclasses import dataclass, field, replace
 from pathlib import Path
 from typing import Any
@@ -108,6 +110,18 @@ class FailureTracker:
         with contextlib.suppress(OSError):
             self._state_file.write_text(json.dumps(payload, indent=2))
 
+    def adopt_legacy_names(self, aliases: Mapping[str, str]) -> None:
+        """Re-key records saved under a bare repo name to its host-qualified identity.
+
+        Records already stored under the new key win; unknown names are left alone
+        (``cleanup`` ages them out).
+        """
+        for old, new in aliases.items():
+            if old == new or old not in self._repositories or new in self._repositories:
+                continue
+            record = self._repositories.pop(old)
+            self._repositories[new] = replace(record, name=new)
+
     def record_success(self, repo_name: str) -> None:
         if not self._config.enabled:
             return
@@ -0,0 +1,101 @@
+"""Copies of the destination in further directories (``[fanout]``, ``sync --also``).
+
+The repositories are synced once, into the primary destination; afterwards every
+extra destination (a second disk, a mounted NAS) is brought up to date from it, so
+GitHub and the network are only asked once. A copy takes the files whose size or
+modification time differ from the primary's and, with ``delete``, removes files that
+are gone there (e.g. packs replaced by a repack). Within a pass objects go before
+refs — ``HEAD``, ``packed-refs`` and everything under ``refs/`` are copied last — so
+a mirror in an extra destination never names an object it does not have yet. Each
+file is written to a temporary name and renamed into place. gitout's ``*.tmp`` files
+are not copied.
+"""
+
+from __future__ import annotations
+
+import os
+import shutil
+from dataclasses import dataclass
+from pathlib import Path
+
+_REF_FILES = frozenset({"HEAD", "packed-refs"})
+
+
+@dataclass(frozen=True)
+class FanoutResult:
+    destination: Path
+    copied: int = 0
+    removed: int = 0
+    error: str | None = None
+
+    @property
+    def ok(self) -> bool:
+        return self.error is None
+
+
+def _is_ref_file(relative: Path) -> bool:
+    return relative.name in _REF_FILES or "refs" in relative.parts[:-1]
+
+
+def _walk(source: Path) -> tuple[list[Path], list[Path]]:
+    """Relative directories and files of ``source``; refs sorted after the rest."""
+    directories, files = [], []
+    for root, dirnames, filenames in os.walk(source):
+        dirnames.sort()
+        base = Path(root).relative_to(source)
+        directories += [base / name for name in dirnames]
+        files += [base / name for name in sorted(filenames) if not name.endswith(".tmp")]
+    return directories, sorted(files, key=_is_ref_file)
+
+
+def _unchanged(source: Path, target: Path) -> bool:
+    try:
+        a, b = source.lstat(), target.lstat()
+    except FileNotFoundError:
+        return False
+    return a.st_size == b.st_size and a.st_mtime_ns == b.st_mtime_ns
+
+
+def _copy(source: Path, target: Path) -> None:
+    target.parent.mkdir(parents=True, exist_ok=True)
+    partial = target.with_name(target.name + ".tmp")
+    partial.unlink(missing_ok=True)
+    shutil.copy2(source, partial, follow_symlinks=False)
+    os.replace(partial, target)
+
+
+def _remove_extras(target: Path, directories: set[Path], files: set[Path]) -> int:
+    removed = 0
+    for root, dirnames, filenames in os.walk(target, topdown=False):
+        base = Path(root).relative_to(target)
+        for name in filenames:
+            if base / name not in files:
+                (target / base / name).unlink()
+                removed += 1
+        for name in dirnames:
+            path = target / base / name
+            if base / name not in directories and not path.is_symlink():
+                shutil.rmtree(path)
+    return removed
+
+
+def fan_out(source: Path, target: Path, *, delete: bool = True) -> FanoutResult:
+    """Bring ``target`` up to date with ``source``; blocking (run it in a thread)."""
+    source, target = source.resolve(), target.expanduser().resolve()
+    if target == source or target.is_relative_to(source) or source.is_relative_to(target):
+        return FanoutResult(target, error=f"{target} overlaps the destination {source}")
+    copied = removed = 0
+    try:
+        directories, files = _walk(source)
+        for relative in directories:
+            # Bare repositories need their (often empty) refs/ and objects/ directories.
+            (target / relative).mkdir(parents=True, exist_ok=True)
+        for relative in files:
+            if not _unchanged(source / relative, target / relative):
+                _copy(source / relative, target / relative)
+                copied += 1
+        if delete:
+            removed = _remove_extras(target, set(directories), set(files))
+    except OSError as exc:
+        return FanoutResult(target, copied, removed, error=str(exc))
+    return FanoutResult(target, copied, removed)
@@ -0,0 +1,125 @@
+"""Configuration snippets that expose a backup destination to self-hosted git frontends.
+
+``gitout export FORMAT DESTINATION`` walks the destination for the bare repositories
+gitout maintains (``github/clone``, ``github/gists`` and ``git``) and renders:
+
+* ``cgit`` — one ``repo.url``/``repo.path`` section per repository, grouped with
+  ``repo.section`` by origin, ready to ``include=`` from ``cgitrc``;
+* ``gitolite`` — ``repo`` stanzas granting read access, for ``gitolite.conf``;
+* ``manifest`` — a JSON list of names and destination-relative paths, for an nginx
+  fancyindex (or any static) listing served next to the mirrors.
+
+Repository names are destination-relative POSIX paths such as
+``github/clone/octocat/hello``. Quarantined ``<repo>.corrupt-<date>`` copies are skipped.
+"""
+
+from __future__ import annotations
+
+import json
+from dataclasses import dataclass
+from pathlib import Path
+
+from gitout.quarantine import is_quarantined
+
+FRONTEND_FORMATS = ("cgit", "gitolite", "manifest")
+
+# Destination subtrees that hold mirrors, and the cgit section each one is listed under.
+_SECTIONS = {
+    "github/clone": "GitHub",
+    "github/gists": "Gists",
+    "git": "Git",
+}
+_DEFAULT_DESCRIPTION = "Unnamed repository"
+
+
+@dataclass(frozen=True)
+class MirroredRepo:
+    name: str  # destination-relative POSIX path
+    path: Path
+    section: str
+    description: str | None = None
+
+
+def _is_bare_repository(path: Path) -> bool:
+    return (path / "HEAD").is_file() and (path / "objects").is_dir()
+
+
+def _description(path: Path) -> str | None:
+    try:
+        text = (path / "description").read_text().strip()
+    except OSError:
+        return None
+    if not text or text.startswith(_DEFAULT_DESCRIPTION):
+        return None
+    return text
+
+
+def _walk(directory: Path) -> list[Path]:
+    if _is_bare_repository(directory):
+        return [directory]
+    found: list[Path] = []
+    for child in sorted(directory.iterdir()):
+        if (
+            child.is_dir()
+            and not child.is_symlink()
+            and not child.name.startswith(".")
+            and not is_quarantined(child)
+        ):
+            found += _walk(child)
+    return found
+
+
+def find_repositories(destination: Path) -> list[MirroredRepo]:
+    """Every bare repository under the destination's mirror subtrees, sorted by name."""
+    repos: list[MirroredRepo] = []
+    for subtree, section in _SECTIONS.items():
+        root = destination / subtree
+        if not root.is_dir():
+            continue
+        for path in _walk(root):
+            repos.append(
+                MirroredRepo(
+                    name=path.relative_to(destination).as_posix(),
+                    path=path.resolve(),
+                    section=section,
+                    description=_description(path),
+                )
+            )
+    return sorted(repos, key=lambda r: r.name)
+
+
+def render_cgit(repos: list[MirroredRepo]) -> str:
+    blocks = []
+    for repo in repos:
+        lines = [
+            f"repo.url={repo.name}",
+            f"repo.path={repo.path}",
+            f"repo.section={repo.section}",
+        ]
+        if repo.description:
+            lines.append(f"repo.desc={repo.description}")
+        blocks.append("\n".join(lines))
+    return "\n\n".join(blocks) + "\n" if blocks else ""
+
+
+def render_gitolite(repos: list[MirroredRepo]) -> str:
+    return "".join(f"repo {repo.name}\n    R = @all\n\n" for repo in repos)
+
+
+def render_manifest(repos: list[MirroredRepo]) -> str:
+    entries = [
+        {"name": r.name, "path": r.name, "section": r.section, "description": r.description}
+        for r in repos
+    ]
+    return json.dumps({"repositories": entries}, indent=2) + "\n"
+
+
+def render(fmt: str, destination: Path) -> str:
+    """Render the ``fmt`` snippet (one of :data:`FRONTEND_FORMATS`) for ``destination``."""
+    renderers = {"cgit": render_cgit, "gitolite": render_gitolite, "manifest": render_manifest}
+    renderer = renderers.get(fmt)
+    if renderer is None:
+        raise ValueError(f"Unknown export format {fmt!r} (expected one of {FRONTEND_FORMATS})")
+    if not destination.is_dir():
+        raise ValueError(f"Backup destination does not exist: {destination}")
+    return renderer(find_repositories(destination))
@@ -13,21 +13,41 @@ Argv shape (in order):
       -c http.postBuffer=<post_buffer_size>
       [-c http.lowSpeedLimit=<n> -c http.lowSpeedTime=<n>]   # when low_speed_limit > 0
       [-c credential.helper=store --file=<credentials_path>] # when credentials_path
+      [-c <key=value> ...]                      # extra_config, e.g. [transfer] pack tuning
       <operation...>
 
     operation when repo does NOT exist:
       shallow:        clone --depth=1 --single-branch [--progress] -- <url> <repo_name>
       single_branch:  clone --bare --single-branch [--branch <default_branch>]
+                            [--depth=<n>] [--shallow-since=<date>] [--filter=<spec>]
                             [--progress] -- <url> <repo_name>
-      mirror:         clone --mirror [--progress] -- <url> <repo_name>
-
-    operation when repo exists:
-      single_branch_only: fetch --prune origin
-      mirror (default):   remote update --prune
+      mirror:         clone --mirror [--depth=<n>] [--shallow-since=<date>]
+                            [--filter=<spec>] [--progress] -- <url> <repo_name>
+
+    ``--shallow-since`` comes from a per-repo ``since`` override. Later updates fetch
+    on top of the shallow boundary, so the cutoff stays where the clone put it.
+    ``--depth`` (``fetch.depth`` or a ``depth`` override) is passed to updates too, so
+    the kept history stays that many commits deep as the tips move. ``--filter`` makes
+    a partial clone (e.g. ``blob:none``); git records it on the ``origin`` remote, so
+    updates need no flag.
+
+    operation when repo exists (``--prune`` unless ``prune`` is false):
+      single_branch_only: fetch [--prune] [--depth=<n>] origin
+      mirror, refspecs:   fetch [--prune] [--depth=<n>] origin <refspec...>
+      mirror (default):   remote update [--prune]
+
+    The engine passes ``fetch.refspecs`` (``+refs/*:refs/*`` unless configured) so every
+    mirror fetches the same refs whatever refspec its ``origin`` remote was created with.
+
+    operation with ls_remote (either way):
+      ls-remote --heads -- <url>     # the ``git.skip_unchanged`` pre-check
+      ls-remote -- <url>             # every ref (ls_remote_heads=False; fetch.attic)
 """
 
 from __future__ import annotations
 
+from collections.abc import Sequence
+
 GIT_EXECUTABLE = "git"
 
 
@@ -48,6 +68,14 @@ def build_git_command(
     show_progress: bool = False,
     single_branch_only: bool = False,
     default_branch: str | None = None,
+    shallow_since: str | None = None,
+    extra_config: Sequence[str] = (),
+    ls_remote: bool = False,
+    prune: bool = True,
+    ls_remote_heads: bool = True,
+    refspecs: Sequence[str] = (),
+    depth: int | None = None,
+    clone_filter: str | None = None,
 ) -> list[str]:
     """Build the full ``git`` argv for a clone or update of a single repository."""
     command = [git_executable]
@@ -74,7 +102,17 @@ def build_git_command(
     if credentials_path is not None:
         command += ["-c", f"credential.helper=store --file={credentials_path}"]
 
-    if not repo_exists:
+    for option in extra_config:
+        command += ["-c", option]
+
+    if ls_remote:
+        if url is None:
+            raise ValueError("url is required to list remote heads")
+        command.append("ls-remote")
+        if ls_remote_heads:
+            command.append("--heads")
+        command += ["--", url]
+    elif not repo_exists:
         if url is None or repo_name is None:
             raise ValueError("url and repo_name are required to clone a repository")
         if use_shallow_clone:
@@ -85,16 +123,33 @@ def build_git_command(
             command += ["clone", "--bare", "--single-branch"]
             if default_branch is not None:
                 command += ["--branch", default_branch]
+            if depth is not None:
+                command.append(f"--depth={depth}")
+            if shallow_since is not None:
+                command.append(f"--shallow-since={shallow_since}")
+            if clone_filter is not None:
+                command.append(f"--filter={clone_filter}")
             if show_progress:
                 command.append("--progress")
         else:
             command += ["clone", "--mirror"]
+            if depth is not None:
+                command.append(f"--depth={depth}")
+            if shallow_since is not None:
+                command.append(f"--shallow-since={shallow_since}")
+            if clone_filter is not None:
+                command.append(f"--filter={clone_filter}")
             if show_progress:
                 command.append("--progress")
         command += ["--", url, repo_name]
-    elif single_branch_only:
-        command += ["fetch", "--prune", "origin"]
+    elif single_branch_only or refspecs:
+        command += ["fetch", "--prune"] if prune else ["fetch"]
+        if depth is not None:
+            command.append(f"--depth={depth}")
+        command.append("origin")
+        if not single_branch_only:
+            command += refspecs
     else:
-        command += ["remote", "update", "--prune"]
+        command += ["remote", "update", "--prune"] if prune else ["remote", "update"]
 
     return command
@@ -20,11 +20,12 @@ class RepositoryMetadata:
     visibility: str
     description: str | None
     updated_at: str
-    repo_type: str  # "owned" | "starred" | "watching" | "gist"
+    repo_type: str  # "owned" | "starred" | "watching" | "gist" | "starred_gist"
     disk_usage_kb: int | None = None
     default_branch: str | None = None
     topics: list[str] = field(default_factory=list)
     language: str | None = None
+    pushed_at: str | None = None  # last push to any branch (repositories only)
 
 
 @dataclass(frozen=True)
@@ -34,6 +35,7 @@ class UserRepositories:
     watching: set[str]
     gists: set[str]
     metadata: dict[str, RepositoryMetadata]
+    starred_gists: set[str] = field(default_factory=set)
 
 
 def _edges(connection: dict[str, Any] | None, key: str) -> list[dict[str, Any]]:
@@ -60,6 +62,7 @@ def _repo_metadata(node: dict[str, Any], repo_type: str) -> RepositoryMetadata:
         default_branch=default_branch_ref["name"] if default_branch_ref else None,
         topics=topics,
         language=primary_language["name"] if primary_language else None,
+        pushed_at=node.get("pushedAt"),
     )
 
 
@@ -77,12 +80,31 @@ def _gist_metadata(node: dict[str, Any]) -> RepositoryMetadata:
     )
 
 
-def parse_user_repositories(pages: list[dict[str, Any]]) -> UserRepositories:
+def _starred_gist_metadata(item: dict[str, Any]) -> RepositoryMetadata:
+    is_public = bool(item.get("public"))
+    return RepositoryMetadata(
+        name=item["id"],
+        is_archived=False,
+        is_private=not is_public,
+        is_fork=False,
+        visibility="PUBLIC" if is_public else "PRIVATE",
+        description=item.get("description"),
+        updated_at=item["updated_at"],
+        repo_type="starred_gist",
+    )
+
+
+def parse_user_repositories(
+    pages: list[dict[str, Any]], starred_gists: list[dict[str, Any]] | None = None
+) -> UserRepositories:
     """Fold successive GraphQL ``data`` payloads into a :class:`UserRepositories`.
 
     Each element of ``pages`` is one query response's ``data`` object (containing a
     ``user`` key). Metadata dedupes with owned > starred > watching priority; owned
     and gist metadata overwrite unconditionally. Mirrors ``GitHub.loadRepositories``.
+
+    ``starred_gists`` are items of the REST ``GET /gists/starred`` listing (GraphQL
+    has no starred-gists connection). Starred gists the user also owns stay gists.
     """
     owned: set[str] = set()
     starred: set[str] = set()
@@ -121,10 +143,50 @@ def parse_user_repositories(pages: list[dict[str, Any]]) -> UserRepositories:
                 gists.add(node["name"])
                 metadata[node["name"]] = _gist_metadata(node)
 
+    starred_gist_ids: set[str] = set()
+    for item in starred_gists or []:
+        gist_id = item["id"]
+        if gist_id in gists:
+            continue
+        starred_gist_ids.add(gist_id)
+        metadata.setdefault(gist_id, _starred_gist_metadata(item))
+
     return UserRepositories(
         owned=owned,
         starred=starred,
         watching=watching,
         gists=gists,
         metadata=metadata,
+        starred_gists=starred_gist_ids,
+    )
+
+
+def _rest_repo_metadata(item: dict[str, Any]) -> RepositoryMetadata:
+    visibility = item.get("visibility") or ("private" if item.get("private") else "public")
+    return RepositoryMetadata(
+        name=item["full_name"],
+        is_archived=bool(item.get("archived")),
+        is_private=bool(item.get("private")),
+        is_fork=bool(item.get("fork")),
+        visibility=visibility.upper(),
+        description=item.get("description"),
+        updated_at=item["updated_at"],
+        repo_type="owned",
+        disk_usage_kb=item.get("size"),
+        default_branch=item.get("default_branch"),
+        topics=list(item.get("topics") or []),
+        language=item.get("language"),
+        pushed_at=item.get("pushed_at"),
+    )
+
+
+def parse_installation_repositories(items: list[dict[str, Any]]) -> UserRepositories:
+    """Fold REST repository objects (``GET /installation/repositories``) as owned repos.
+
+    A GitHub App installation has no stars, watches or gists: everything it can access
+    is treated as owned.
+    """
+    metadata = {item["full_name"]: _rest_repo_metadata(item) for item in items}
+    return UserRepositories(
+        owned=set(metadata), starred=set(), watching=set(), gists=set(), metadata=metadata
     )
@@ -0,0 +1,181 @@
+"""GitHub App installation authentication (``[github.app]``).
+
+Organisations prefer backup bots that authenticate as a GitHub App over long-lived
+personal tokens. gitout signs a short-lived JWT with the App's private key, looks up the
+installation on ``github.user`` (unless ``installation_id`` is given), and mints an
+installation token, re-minting it shortly before its one-hour expiry. Discovery then
+lists the repositories the installation can access (``GET /installation/repositories``)
+instead of the user's GraphQL connections.
+
+JWT signing needs PyJWT with its crypto extra (``pip install 'gitout[app]'``), imported
+lazily. The httpx client, clock and signer are injectable for tests.
+"""
+
+from __future__ import annotations
+
+import asyncio
+import functools
+import time
+from collections.abc import Callable, Mapping
+from datetime import datetime
+from pathlib import Path
+from typing import Any
+
+import httpx
+
+from gitout.github import UserRepositories, parse_installation_repositories
+from gitout.github_client import (
+    GITHUB_API_URL,
+    RateLimiter,
+    TokenPool,
+    api_headers,
+    rest_url,
+)
+
+# Git expects this user name with an installation token.
+INSTALLATION_GIT_USER = "x-access-token"
+# Re-mint the installation token when it has less than this left.
+_REFRESH_MARGIN_SECONDS = 300.0
+# GitHub rejects App JWTs valid for more than ten minutes.
+_JWT_LIFETIME_SECONDS = 540
+
+# claims -> compact JWS signed with the App's private key
+Signer = Callable[[dict[str, Any]], str]
+
+
+def rs256_signer(private_key: str) -> Signer:
+    """Sign with PyJWT (``gitout[app]``); raises ValueError when it is missing."""
+    try:
+        import jwt
+    except ImportError as exc:
+        raise ValueError(
+            "GitHub App authentication needs PyJWT (pip install 'gitout[app]')"
+        ) from exc
+
+    def sign(claims: dict[str, Any]) -> str:
+        return str(jwt.encode(claims, private_key, algorithm="RS256"))
+
+    return sign
+
+
+class GitHubAppAuth:
+    def __init__(
+        self,
+        app_id: int,
+        sign: Signer,
+        *,
+        installation_id: int | None = None,
+        api_url: str = GITHUB_API_URL,
+        client: httpx.AsyncClient | None = None,
+        clock: Callable[[], float] = time.time,
+        user_agent: str | None = None,
+        extra_headers: Mapping[str, str] | None = None,
+    ) -> None:
+        self._app_id = app_id
+        self._sign = sign
+        self._installation_id = installation_id
+        self._api_url = rest_url(api_url)
+        self._client = client
+        self._clock = clock
+        self._user_agent = user_agent
+        self._extra_headers = extra_headers
+        self._token: str | None = None
+        self._expires_at = 0.0
+        self._lock = asyncio.Lock()
+
+    @classmethod
+    def from_key_file(cls, app_id: int, private_key_file: str, **kwargs: Any) -> GitHubAppAuth:
+        path = Path(private_key_file)
+        try:
+            private_key = path.read_text()
+        except OSError as exc:
+            raise ValueError(
+                f"Cannot read github.app.private_key_file {path}: {exc.strerror}"
+            ) from exc
+        return cls(app_id, rs256_signer(private_key), **kwargs)
+
+    def app_jwt(self) -> str:
+        now = int(self._clock())
+        # Backdated a minute to tolerate clock drift, as GitHub recommends.
+        claims = {"iat": now - 60, "exp": now + _JWT_LIFETIME_SECONDS, "iss": str(self._app_id)}
+        return self._sign(claims)
+
+    def _headers(self) -> dict[str, str]:
+        return {
+            "Accept": "application/vnd.github+json",
+            **api_headers(
+                self.app_jwt(), user_agent=self._user_agent, extra_headers=self._extra_headers
+            ),
+        }
+
+    async def _installation(self, http: httpx.AsyncClient, account: str) -> int:
+        if self._installation_id is None:
+            response = await http.get(
+                f"{self._api_url}/users/{account}/installation", headers=self._headers()
+            )
+            if response.status_code == 404:
+                raise ValueError(f"The GitHub App is not installed on {account}")
+            response.raise_for_status()
+            self._installation_id = int(response.json()["id"])
+        return self._installation_id
+
+    async def installation_token(self, account: str) -> str:
+        """A valid installation token for ``account``, minted when missing or expiring."""
+        async with self._lock:
+            fresh = self._clock() < self._expires_at - _REFRESH_MARGIN_SECONDS
+            if self._token is not None and fresh:
+                return self._token
+            http = self._client or httpx.AsyncClient(timeout=60.0)
+            try:
+                installation = await self._installation(http, account)
+                response = await http.post(
+                    f"{self._api_url}/app/installations/{installation}/access_tokens",
+                    headers=self._headers(),
+                )
+                response.raise_for_status()
+                body = response.json()
+            finally:
+                if self._client is None:
+                    await http.aclose()
+            self._token = str(body["token"])
+            self._expires_at = datetime.fromisoformat(body["expires_at"]).timestamp()
+            return self._token
+
+
+async def load_installation_repositories(
+    user: str,
+    token: str | TokenPool,
+    *,
+    client: httpx.AsyncClient | None = None,
+    api_url: str = GITHUB_API_URL,
+    user_agent: str | None = None,
+    extra_headers: Mapping[str, str] | None = None,
+    rate_limiter: RateLimiter | None = None,
+) -> UserRepositories:
+    """Every repository the installation ``token`` can access, as owned repositories.
+
+    ``user`` is unused (the installation decides); it keeps the :data:`RepoLoader` shape.
+    """
+    api_url = rest_url(api_url)
+    auth = token if isinstance(token, TokenPool) else None
+    limiter = rate_limiter or RateLimiter(tokens=auth)
+    headers = {
+        "Accept": "application/vnd.github+json",
+        **api_headers(token, user_agent=user_agent, extra_headers=extra_headers),
+    }
+    http = client or httpx.AsyncClient(timeout=60.0)
+    items: list[dict[str, Any]] = []
+    try:
+        url: str | None = f"{api_url}/installation/repositories?per_page=100"
+        while url is not None:
+            response = await limiter.send(
+                functools.partial(http.get, url, headers=headers, auth=auth),
+                what="GET /installation/repositories",
+            )
+            response.raise_for_status()
+            items.extend(response.json().get("repositories") or [])
+            url = response.links.get("next", {}).get("url")
+    finally:
+        if client is None:
+            await http.aclose()
+    return parse_installation_repositories(items)
@@ -0,0 +1,463 @@
+"""GitHub migration archives (``[github.archive]``).
+
+A git mirror carries the code but not the issues, pull requests, reviews, releases or
+wiki metadata of a repository. The user migrations API exports all of that: gitout
+starts one migration per repository, polls it until GitHub reports ``exported``, and
+downloads the archive (a ``.tar.gz``) to
+``destination/github/archive/<owner>/<repo>.tar.gz``. The download is written to a
+temporary file and only replaces the previous archive once its size matches the
+advertised ``Content-Length`` and the whole tarball reads back cleanly, so a truncated
+or corrupt download never clobbers a good backup.
+
+With ``retention = N`` each run writes a dated ``<repo>-<YYYY-MM-DD>.tar.gz`` instead
+of overwriting the single archive, and only the newest N dated archives of a repository
+are kept, giving point-in-time recovery of the issue and pull request history. The
+httpx client, the sleep function and the clock are injectable for tests.
+
+Before starting a migration the archiver lists the user's existing ones (once per run)
+and reuses a ``pending``/``exporting`` migration of the same single repository, or an
+``exported`` one created after the newest local archive, so re-running after an
+interrupted archive run picks up where it left off instead of queueing duplicates.
+GitHub deletes migration archives after seven days, so older migrations are never
+reused, and a reused archive that is already gone (404/410) starts a new migration.
+
+With ``lock_repositories`` the repository is unlocked as soon as its archive is safely
+on disk; ``gitout unlock`` releases every lock a migration still holds after a run that
+died mid-migration.
+
+Migrations belong to the user whose token created them, so with several tokens
+(a :class:`~gitout.github_client.TokenPool`) each migration is pinned to the token that
+was current when it was reused or started: its polls, download and unlock all use that
+token, and the listing of existing migrations is kept per token. ``gitout unlock``
+lists and unlocks the migrations of every token.
+
+Throttled responses (429, or a rate-limit 403) to any of these calls are retried
+after ``Retry-After`` or, without a hint, an exponential backoff; a migration that
+GitHub reports as ``failed`` is a per-repository failure, never a fatal error.
+"""
+
+from __future__ import annotations
+
+import asyncio
+import functools
+import logging
+import re
+import tarfile
+import time
+import zlib
+from collections.abc import Awaitable, Callable, Mapping
+from dataclasses import dataclass, field
+from datetime import UTC, datetime, timedelta
+from pathlib import Path
+from typing import Any
+
+import httpx
+
+from gitout.github_client import (
+    GITHUB_API_URL,
+    RateLimiter,
+    TokenPool,
+    api_headers,
+    rest_url,
+)
+from gitout.http_cache import HttpCache
+
+
+log = logging.getLogger(__name__)
+
+_ARCHIVE_SUFFIX = ".tar.gz"
+_LIST_PAGE_SIZE = 100
+_MAX_LIST_PAGES = 10
+_DATED_RE = re.compile(r"-\d{4}-\d{2}-\d{2}$")
+# GitHub deletes a migration's archive seven days after it was created.
+_REUSE_MAX_AGE = timedelta(days=7)
+
+
+class MigrationError(RuntimeError):
+    """A migration failed on GitHub's side or did not finish in time."""
+
+
+@dataclass(frozen=True)
+class MigrationRequest:
+    """Options of ``POST /user/migrations`` beyond the repository list."""
+
+    exclude_attachments: bool = False
+    exclude_releases: bool = False
+    exclude_metadata: bool = False
+    lock_repositories: bool = False  # locks the repositories on GitHub during export
+    exclude: tuple[str, ...] = field(default_factory=tuple)
+
+    def payload(self, name_with_owner: str) -> dict[str, Any]:
+        body: dict[str, Any] = {
+            "repositories": [name_with_owner],
+            "lock_repositories": self.lock_repositories,
+            "exclude_attachments": self.exclude_attachments,
+            "exclude_releases": self.exclude_releases,
+            "exclude_metadata": self.exclude_metadata,
+        }
+        if self.exclude:
+            body["exclude"] = list(self.exclude)
+        return body
+
+
+class MigrationArchiver:
+    def __init__(
+        self,
+        token: str | TokenPool,
+        *,
+        client: httpx.AsyncClient | None = None,
+        api_url: str = GITHUB_API_URL,
+        sleep: Callable[[float], Awaitable[None]] = asyncio.sleep,
+        poll_interval_seconds: float = 10.0,
+        max_wait_seconds: float = 3600.0,
+        user_agent: str | None = None,
+        extra_headers: Mapping[str, str] | None = None,
+        retention: int | None = None,
+        request: MigrationRequest | None = None,
+        clock: Callable[[], datetime] = lambda: datetime.now(UTC),
+        max_throttle_retries: int = 5,
+        backoff_seconds: float = 5.0,
+        cache: HttpCache | None = None,
+        rate_limiter: RateLimiter | None = None,
+    ) -> None:
+        self._client = client
+        self._cache = cache
+        self._api_url = rest_url(api_url)
+        self._sleep = sleep
+        self._poll_interval = poll_interval_seconds
+        self._max_wait = max_wait_seconds
+        self._retention = retention
+        self._request = request or MigrationRequest()
+        self._clock = clock
+        self._token = token
+        self._rate_limiter = rate_limiter or RateLimiter(
+            max_retries=max_throttle_retries,
+            backoff_seconds=backoff_seconds,
+            sleep=sleep,
+            tokens=token if isinstance(token, TokenPool) else None,
+        )
+        self._existing: dict[str, list[dict[str, Any]]] = {}
+        self._existing_lock = asyncio.Lock()
+        self._headers = {
+            "Accept": "application/vnd.github+json",
+            **api_headers(token, user_agent=user_agent, extra_headers=extra_headers),
+        }
+
+    def current_token(self) -> str:
+        """The token a new migration is pinned to (the pool's current one)."""
+        return self._token.token if isinstance(self._token, TokenPool) else self._token
+
+    def _tokens(self) -> list[str]:
+        return self._token.tokens if isinstance(self._token, TokenPool) else [self._token]
+
+    def _headers_for(self, token: str | None) -> dict[str, str]:
+        token = token or self.current_token()
+        return {**self._headers, "Authorization": f"Bearer {token}"}
+
+    async def _send(
+        self,
+        http: httpx.AsyncClient,
+        method: str,
+        url: str,
+        *,
+        token: str | None = None,
+        **kwargs: Any,
+    ) -> httpx.Response:
+        headers = kwargs.pop("headers", None) or self._headers_for(token)
+        return await self._rate_limiter.send(
+            functools.partial(http.request, method, url, headers=headers, **kwargs),
+            what=f"{method} {url}",
+        )
+
+    async def _get(self, http: httpx.AsyncClient, url: str, token: str) -> httpx.Response:
+        """GET through the conditional-request cache when one is configured."""
+        if self._cache is None:
+            return await self._send(http, "GET", url, token=token)
+        return await self._cache.get(
+            url, self._headers_for(token), lambda h: self._send(http, "GET", url, headers=h)
+        )
+
+    async def _list_migrations(
+        self, http: httpx.AsyncClient, token: str | None = None
+    ) -> list[dict[str, Any]]:
+        """The migrations of ``token``'s user, fetched once per token; [] when listing fails."""
+        token = token or self.current_token()
+        async with self._existing_lock:
+            if token not in self._existing:
+                migrations: list[dict[str, Any]] = []
+                try:
+                    for page in range(1, _MAX_LIST_PAGES + 1):
+                        url = f"{self._api_url}/user/migrations?per_page={_LIST_PAGE_SIZE}"
+                        response = await self._get(http, f"{url}&page={page}", token)
+                        response.raise_for_status()
+                        batch = response.json()
+                        if not isinstance(batch, list):
+                            break
+                        migrations += [m for m in batch if isinstance(m, dict)]
+                        if len(batch) < _LIST_PAGE_SIZE:
+                            break
+                except (httpx.HTTPError, ValueError) as exc:
+                    log.debug("Cannot list existing migrations (%s); starting new ones", exc)
+                self._existing[token] = migrations
+            return self._existing[token]
+
+    async def find_reusable(
+        self,
+        http: httpx.AsyncClient,
+        name_with_owner: str,
+        newer_than: datetime | None,
+        *,
+        token: str | None = None,
+    ) -> int | None:
+        """Id of an existing migration of just ``name_with_owner`` worth reusing."""
+        oldest = self._clock() - _REUSE_MAX_AGE
+        candidates: list[tuple[datetime, int]] = []
+        for migration in await self._list_migrations(http, token):
+            repos = [r.get("full_name", "") for r in migration.get("repositories") or []]
+            if [r.lower() for r in repos] != [name_with_owner.lower()]:
+                continue
+            created = _parse_time(str(migration.get("created_at") or ""))
+            if created is None or created <= oldest:
+                continue
+            state = migration.get("state")
+            if state in ("pending", "exporting") or (
+                state == "exported" and (newer_than is None or created > newer_than)
+            ):
+                candidates.append((created, int(migration["id"])))
+        return max(candidates)[1] if candidates else None
+
+    async def start(
+        self, http: httpx.AsyncClient, name_with_owner: str, *, token: str | None = None
+    ) -> int:
+        response = await self._send(
+            http,
+            "POST",
+            f"{self._api_url}/user/migrations",
+            token=token,
+            json=self._request.payload(name_with_owner),
+        )
+        response.raise_for_status()
+        migration_id: int = response.json()["id"]
+        return migration_id
+
+    async def wait(
+        self, http: httpx.AsyncClient, migration_id: int, *, token: str | None = None
+    ) -> None:
+        """Poll the migration until it is exported; raise on failure or timeout."""
+        deadline = time.monotonic() + self._max_wait
+        while True:
+            response = await self._send(
+                http, "GET", f"{self._api_url}/user/migrations/{migration_id}", token=token
+            )
+            response.raise_for_status()
+            state = response.json().get("state")
+            if state == "exported":
+                return
+            if state == "failed":
+                raise MigrationError(f"Migration {migration_id} failed on GitHub")
+            if time.monotonic() >= deadline:
+                raise MigrationError(
+                    f"Migration {migration_id} still {state!r} after {self._max_wait:.0f}s"
+                )
+            await self._sleep(self._poll_interval)
+
+    async def download(
+        self,
+        http: httpx.AsyncClient,
+        migration_id: int,
+        target: Path,
+        *,
+        token: str | None = None,
+    ) -> None:
+        """Stream the archive (GitHub redirects to short-lived storage) to ``target``."""
+        target.parent.mkdir(parents=True, exist_ok=True)
+        partial = target.with_name(target.name + ".partial")
+        url = f"{self._api_url}/user/migrations/{migration_id}/archive"
+        try:
+            attempt = 0
+            while not await self._stream_to(http, url, partial, attempt, token):
+                attempt += 1
+            await asyncio.to_thread(verify_archive, partial)
+            partial.replace(target)
+        finally:
+            partial.unlink(missing_ok=True)
+
+    async def _stream_to(
+        self, http: httpx.AsyncClient, url: str, partial: Path, attempt: int, token: str | None
+    ) -> bool:
+        """Stream ``url`` into ``partial``; False (after backing off) when throttled."""
+        async with http.stream(
+            "GET",
+            url,
+            headers=self._headers_for(token),
+            follow_redirects=True,  # httpx drops Authorization on the cross-host hop
+        ) as response:
+            if await self._rate_limiter.retry_after(response, attempt, f"GET {url}"):
+                return False
+            response.raise_for_status()
+            received = 0
+            with partial.open("wb") as handle:
+                async for chunk in response.aiter_bytes():
+                    handle.write(chunk)
+                    received += len(chunk)
+            expected = response.headers.get("Content-Length")
+            encoded = "Content-Encoding" in response.headers  # length is pre-decoding
+            if expected and expected.isdigit() and not encoded and int(expected) != received:
+                raise MigrationError(f"Archive download truncated: {received} of {expected} bytes")
+        return True
+
+    async def unlock(
+        self,
+        http: httpx.AsyncClient,
+        migration_id: int,
+        name_with_owner: str,
+        *,
+        token: str | None = None,
+    ) -> None:
+        """Release the lock ``migration_id`` holds on a repository (404: already unlocked)."""
+        repo = name_with_owner.rsplit("/", 1)[-1]
+        url = f"{self._api_url}/user/migrations/{migration_id}/repos/{repo}/lock"
+        response = await self._send(http, "DELETE", url, token=token)
+        if response.status_code != 404:
+            response.raise_for_status()
+
+    async def unlock_all(self) -> list[str]:
+        """Unlock the repositories of every migration that locked them; returns their names."""
+        owned = self._client is None
+        http = self._client or httpx.AsyncClient(timeout=60.0)
+        unlocked: list[str] = []
+        try:
+            for token in self._tokens():
+                for migration in await self._list_migrations(http, token):
+                    if not migration.get("lock_repositories"):
+                        continue
+                    for repository in migration.get("repositories") or []:
+                        name = repository.get("full_name", "")
+                        await self.unlock(http, int(migration["id"]), name, token=token)
+                        unlocked.append(name)
+        finally:
+            if owned:
+                await http.aclose()
+        return unlocked
+
+    def target_path(self, name_with_owner: str, archive_dir: Path) -> Path:
+        """``<repo>.tar.gz``, or ``<repo>-<date>.tar.gz`` when a retention is set."""
+        if self._retention is None:
+            return archive_dir / f"{name_with_owner}{_ARCHIVE_SUFFIX}"
+        stamp = self._clock().strftime("%Y-%m-%d")
+        return archive_dir / f"{name_with_owner}-{stamp}{_ARCHIVE_SUFFIX}"
+
+    async def archive(self, name_with_owner: str, archive_dir: Path) -> Path:
+        """Run one migration for ``name_with_owner`` and return the downloaded archive."""
+        owned = self._client is None
+        http = self._client or httpx.AsyncClient(timeout=300.0)
+        try:
+            target = self.target_path(name_with_owner, archive_dir)
+            newest = _newest_archive_time(target.parent, name_with_owner.rsplit("/", 1)[-1])
+            token = self.current_token()  # every call of this migration uses it
+            migration_id = await self.find_reusable(http, name_with_owner, newest, token=token)
+            reused = migration_id is not None
+            if migration_id is not None:
+                log.info("Reusing migration %d for %s", migration_id, name_with_owner)
+            else:
+                migration_id = await self.start(http, name_with_owner, token=token)
+                log.debug("Started migration %d for %s", migration_id, name_with_owner)
+            try:
+                try:
+                    await self.wait(http, migration_id, token=token)
+                    await self.download(http, migration_id, target, token=token)
+                except httpx.HTTPStatusError as exc:
+                    if not reused or exc.response.status_code not in (404, 410):
+                        raise
+                    log.info(
+                        "The archive of migration %d is gone; starting a new one for %s",
+                        migration_id,
+                        name_with_owner,
+                    )
+                    migration_id = await self.start(http, name_with_owner, token=token)
+                    await self.wait(http, migration_id, token=token)
+                    await self.download(http, migration_id, target, token=token)
+            except (httpx.HTTPError, MigrationError, OSError):
+                if self._request.lock_repositories:
+                    log.warning(
+                        "%s stays locked by migration %d; run `gitout unlock` to release it",
+                        name_with_owner,
+                        migration_id,
+                    )
+                raise
+            if self._request.lock_repositories:
+                await self.unlock(http, migration_id, name_with_owner, token=token)
+            if self._retention is not None:
+                repo = name_with_owner.rsplit("/", 1)[-1]
+                prune_archives(target.parent, repo, self._retention)
+            return target
+        finally:
+            if owned:
+                await http.aclose()
+
+    async def archive_to(self, name_with_owner: str, archive_dir: Path) -> bool:
+        """Archive one repository; failures are logged and reported as False."""
+        try:
+            path = await self.archive(name_with_owner, archive_dir)
+        except (httpx.HTTPError, MigrationError, OSError, KeyError) as exc:
+            log.warning("Failed to archive %s: %s", name_with_owner, exc)
+            return False
+        log.info("Archived %s to %s", name_with_owner, path)
+        return True
+
+
+def _parse_time(value: str) -> datetime | None:
+    try:
+        parsed = datetime.fromisoformat(value.replace("Z", "+00:00"))
+    except ValueError:
+        return None
+    return parsed if parsed.tzinfo is not None else parsed.replace(tzinfo=UTC)
+
+
+def _newest_archive_time(directory: Path, repo: str) -> datetime | None:
+    """Modification time of the newest local archive of ``repo`` (plain or dated)."""
+    paths = [directory / f"{repo}{_ARCHIVE_SUFFIX}"]
+    if directory.is_dir():
+        paths += [p for p in directory.glob(f"{repo}-*{_ARCHIVE_SUFFIX}") if _is_dated(p, repo)]
+    times = [p.stat().st_mtime for p in paths if p.is_file()]
+    return datetime.fromtimestamp(max(times), UTC) if times else None
+
+
+def verify_archive(path: Path) -> None:
+    """Read ``path`` through as a gzip tarball; raise MigrationError if it is not one."""
+    try:
+        with tarfile.open(path, mode="r:gz") as archive:
+            members = archive.getmembers()  # walks every header, hitting EOF if truncated
+            for member in members:
+                if member.isfile():
+                    extracted = archive.extractfile(member)
+                    if extracted is not None:
+                        while extracted.read(1024 * 1024):
+                            pass
+    except (tarfile.TarError, EOFError, OSError, zlib.error) as exc:
+        raise MigrationError(f"Downloaded archive {path.name} is unreadable: {exc}") from exc
+    if not members:
+        raise MigrationError(f"Downloaded archive {path.name} is empty")
+
+
+def archive_targets(owned: set[str], explicit: list[str], *, include_owned: bool) -> list[str]:
+    """Repositories to archive: the owned ones when enabled, plus the explicit list."""
+    candidates = [*sorted(owned), *explicit] if include_owned else explicit
+    return list(dict.fromkeys(candidates))
+
+
+def prune_archives(directory: Path, repo: str, retention: int) -> list[Path]:
+    """Delete all but the newest ``retention`` dated archives of ``repo``."""
+    dated = sorted(
+        path for path in directory.glob(f"{repo}-*{_ARCHIVE_SUFFIX}") if _is_dated(path, repo)
+    )
+    stale = dated[: max(len(dated) - retention, 0)]
+    for path in stale:
+        path.unlink(missing_ok=True)
+        log.debug("Pruned old archive %s", path)
+    return stale
+
+
+def _is_dated(path: Path, repo: str) -> bool:
+    suffix = path.name[len(repo) : -len(_ARCHIVE_SUFFIX)]
+    return bool(_DATED_RE.fullmatch(suffix))
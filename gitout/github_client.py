@@ -1,56 +1,66 @@
-"""Async GitHub GraphQL client: pages the UserRepos query and folds the result.
+"""Async GitHub GraphQL client: pages the UserRepos connections and folds the result.
 
 Port of the networking half of ``GitHub.kt`` (the pure fold lives in ``github.py``).
-The query is the same document as ``src/main/graphql/GitHub.graphql``.
+The selections match ``src/main/graphql/GitHub.graphql``, but each connection (owned,
+starred, watching, gists) is paged by its own query and cursor, concurrently, so every
+page is fetched exactly once. Starred gists are not exposed through GraphQL and are
+listed via the REST ``/gists/starred`` API.
+
+Every call goes through a :class:`RateLimiter`, which reads GitHub's budget headers
+(and the GraphQL ``rateLimit`` object) and sleeps until the window resets before the
+budget runs out, rather than failing with opaque 403s halfway through a large account.
+With several tokens (a :class:`TokenPool`) an exhausted or rejected token is swapped
+for the next one instead.
 """
 
 from __future__ import annotations
 
+import asyncio
+import functools
+import logging
+import math
+import time
+from collections.abc import Awaitable, Callable, Generator, Mapping, Sequence
+from datetime import datetime
+from email.utils import parsedate_to_datetime
 from typing import Any
+from urllib.parse import urlsplit
 
 import httpx
 
 from gitout import __version__
 from gitout.github import UserRepositories, parse_user_repositories
 
-GITHUB_GRAPHQL_ENDPOINT = "https://api.github.com/graphql"
-
-USER_REPOS_QUERY = """
-query UserRepos(
-  $login: String!,
-  $ownerAfter: String,
-  $starredAfter: String,
-  $watchingAfter: String,
-  $gistsAfter: String,
-) {
-  user(login: $login) {
-    ownedRepositories: repositories(first: 100, ownerAffiliations: OWNER, after: $ownerAfter) {
-      ownedEdges: edges {
-        cursor
-        node { ...RepoFields }
-      }
-    }
-    starredRepositories(first: 100, after: $starredAfter) {
-      starredEdges: edges {
-        cursor
-        node { ...RepoFields }
-      }
-    }
-    watchingRepositories: watching(first: 100, after: $watchingAfter) {
-      watchingEdges: edges {
-        cursor
-        node { ...RepoFields }
-      }
-    }
-    gistRepositories: gists(first: 100, privacy: ALL, after: $gistsAfter) {
-      gistEdges: edges {
-        cursor
-        node { name isPublic description updatedAt }
-      }
-    }
-  }
-}
+log = logging.getLogger(__name__)
+
+GITHUB_API_URL = "https://api.github.com"  # GraphQL at /graphql, REST everywhere else
+DEFAULT_USER_AGENT = f"gitout/{__version__}"
+
+
+def _enterprise_root(api_url: str) -> str | None:
+    """``https://<host>/api`` for a GitHub Enterprise Server ``api_url``, else None."""
+    api_url = api_url.rstrip("/").removesuffix("/v3")
+    return api_url if urlsplit(api_url).path == "/api" else None
+
+
+def rest_url(api_url: str) -> str:
+    """The REST root for ``api_url``: ``/api/v3`` on GitHub Enterprise Server."""
+    root = _enterprise_root(api_url)
+    return f"{root}/v3" if root is not None else api_url.rstrip("/")
+
+
+def graphql_url(api_url: str) -> str:
+    """The GraphQL endpoint for ``api_url``: ``/api/graphql`` on GitHub Enterprise Server."""
+    return f"{_enterprise_root(api_url) or api_url.rstrip('/')}/graphql"
+
+
+def web_url(api_url: str) -> str:
+    """The web origin clones and git credentials use: ``https://github.com`` or the server."""
+    parts = urlsplit(api_url)
+    host = parts.netloc if _enterprise_root(api_url) else parts.netloc.removeprefix("api.")
+    return f"{parts.scheme}://{host}"
 
+REPO_FIELDS_FRAGMENT = """
 fragment RepoFields on Repository {
   nameWithOwner
   isArchived
@@ -59,77 +69,380 @@ fragment RepoFields on Repository {
   visibility
   description
   updatedAt
+  pushedAt
   diskUsage
   defaultBranchRef { name }
-  repositoryTopics(first: 10) { nodes { topic { name } } }
+  repositoryTopics(first: 20) { nodes { topic { name } } }
   primaryLanguage { name }
 }
 """
 
+# Connection alias -> (user field, edges alias, node selection). Each connection is
+# paged on its own, so a long starred list never re-requests the exhausted others.
+CONNECTIONS = {
+    "ownedRepositories": (
+        "repositories(first: 100, ownerAffiliations: OWNER, after: $after)",
+        "ownedEdges",
+        "...RepoFields",
+    ),
+    "starredRepositories": (
+        "starredRepositories(first: 100, after: $after)",
+        "starredEdges",
+        "...RepoFields",
+    ),
+    "watchingRepositories": (
+        "watching(first: 100, after: $after)",
+        "watchingEdges",
+        "...RepoFields",
+    ),
+    "gistRepositories": (
+        "gists(first: 100, privacy: ALL, after: $after)",
+        "gistEdges",
+        "name isPublic description updatedAt",
+    ),
+}
+
+
+def connection_query(alias: str) -> str:
+    """The ``UserRepos`` query document paging the single connection ``alias``."""
+    user_field, edges_alias, node = CONNECTIONS[alias]
+    query = f"""
+query UserRepos($login: String!, $after: String) {{
+  user(login: $login) {{
+    {alias}: {user_field} {{
+      pageInfo {{ hasNextPage endCursor }}
+      {edges_alias}: edges {{
+        cursor
+        node {{ {node} }}
+      }}
+    }}
+  }}
+  rateLimit {{ cost remaining resetAt }}
+}}
+"""
+    return query + REPO_FIELDS_FRAGMENT if "...RepoFields" in node else query
+
+
+async def _page_connection(
+    http: httpx.AsyncClient,
+    api_url: str,
+    headers: Mapping[str, str],
+    user: str,
+    alias: str,
+    limiter: RateLimiter,
+    auth: TokenPool | None,
+) -> list[dict[str, Any]]:
+    """Every non-empty page of one connection, as ``data`` payloads for the fold."""
+    query = connection_query(alias)
+    edges_alias = CONNECTIONS[alias][1]
+    pages: list[dict[str, Any]] = []
+    after: str | None = None
+    while True:
+        response = await limiter.send(
+            functools.partial(
+                http.post,
+                graphql_url(api_url),
+                json={"query": query, "variables": {"login": user, "after": after}},
+                headers=headers,
+                auth=auth,
+            ),
+            resource="graphql",
+            what=f"GraphQL {alias}",
+        )
+        response.raise_for_status()
+        body = response.json()
+        limiter.observe_graphql((body.get("data") or {}).get("rateLimit"))
+        if body.get("errors"):
+            raise RuntimeError(f"GitHub GraphQL errors: {body['errors']}")
+        user_node = body["data"].get("user")
+        if user_node is None:
+            raise RuntimeError(f"GitHub user not found: {user}")
+
+        connection = user_node.get(alias) or {}
+        edges = connection.get(edges_alias) or []
+        if not edges:
+            break
+        pages.append({"user": {alias: connection}})
+        page_info = connection.get("pageInfo") or {}
+        if not page_info.get("hasNextPage", True):
+            break
+        after = page_info.get("endCursor") or edges[-1]["cursor"]
+    return pages
+
+
+def api_headers(
+    token: str | TokenPool,
+    *,
+    user_agent: str | None = None,
+    extra_headers: Mapping[str, str] | None = None,
+) -> dict[str, str]:
+    """Headers for a GitHub API request: custom tags, then auth and User-Agent.
+
+    ``extra_headers`` come from ``[http] headers``; validation keeps them from
+    replacing Authorization/User-Agent, and they are applied first regardless. A
+    :class:`TokenPool` contributes its current token (its auth flow sets the header on
+    every request anyway).
+    """
+    headers = dict(extra_headers or {})
+    headers["Authorization"] = f"Bearer {token.token if isinstance(token, TokenPool) else token}"
+    headers["User-Agent"] = user_agent or DEFAULT_USER_AGENT
+    return headers
 
-def _edges(connection: dict[str, Any] | None, key: str) -> list[dict[str, Any]]:
-    if not connection:
-        return []
-    return connection.get(key) or []
+
+def throttle_delay(response: httpx.Response, *, now: float | None = None) -> float | None:
+    """Seconds GitHub asks us to wait before retrying, or None when not throttled.
+
+    429s and rate-limit 403s are throttling. ``Retry-After`` (seconds or an HTTP date)
+    wins; an exhausted primary limit waits for ``X-RateLimit-Reset``. A throttled
+    response without either hint returns 0.0, leaving the caller's backoff to decide.
+    """
+    headers = response.headers
+    retry_after = headers.get("Retry-After")
+    exhausted = headers.get("X-RateLimit-Remaining") == "0"
+    if response.status_code != 429 and not (
+        response.status_code == 403 and (retry_after is not None or exhausted)
+    ):
+        return None
+    current = time.time() if now is None else now
+    if retry_after is not None:
+        if retry_after.strip().isdigit():
+            return float(retry_after.strip())
+        try:
+            return max(parsedate_to_datetime(retry_after).timestamp() - current, 0.0)
+        except (TypeError, ValueError):
+            return 0.0
+    reset = headers.get("X-RateLimit-Reset")
+    if exhausted and reset is not None and reset.isdigit():
+        return max(float(reset) - current, 0.0)
+    return 0.0
+
+
+class TokenPool(httpx.Auth):
+    """Several GitHub tokens used in turn, for budgets one token cannot cover.
+
+    Passed as the ``auth`` of each request: the current token is sent, and a 401 or a
+    throttled response (see :func:`throttle_delay`) retires it — until its reset, or for
+    good when rejected — and the request is replayed with the next usable token. When
+    no token is left the last response is returned for the caller to handle.
+    """
+
+    def __init__(self, tokens: Sequence[str], *, clock: Callable[[], float] = time.time) -> None:
+        if not tokens:
+            raise ValueError("TokenPool needs at least one token")
+        self._tokens = list(tokens)
+        self._clock = clock
+        self._index = 0
+        # token index -> epoch seconds until which it is not used (inf: rejected)
+        self._retired: dict[int, float] = {}
+
+    @property
+    def index(self) -> int:
+        return self._index
+
+    @property
+    def token(self) -> str:
+        return self._tokens[self._index]
+
+    @property
+    def tokens(self) -> list[str]:
+        return list(self._tokens)
+
+    def rotate(self, until: float) -> bool:
+        """Retire the current token until ``until``; False when no other token is usable."""
+        self._retired[self._index] = until
+        now = self._clock()
+        for step in range(1, len(self._tokens)):
+            candidate = (self._index + step) % len(self._tokens)
+            if self._retired.get(candidate, 0.0) <= now:
+                log.warning(
+                    "Switching to GitHub token #%d of %d", candidate + 1, len(self._tokens)
+                )
+                self._index = candidate
+                return True
+        return False
+
+    def auth_flow(self, request: httpx.Request) -> Generator[httpx.Request, httpx.Response, None]:
+        for _ in range(len(self._tokens)):
+            index = self._index
+            request.headers["Authorization"] = f"Bearer {self.token}"
+            response = yield request
+            if response.status_code == 401:
+                until = math.inf
+            else:
+                delay = throttle_delay(response, now=self._clock())
+                if delay is None:
+                    return
+                until = self._clock() + delay
+            # A concurrent request may have rotated already; replay with its pick.
+            if index == self._index and not self.rotate(until):
+                return
+
+
+# Calls kept in hand: with this few left, wait for the window to reset first.
+RATE_LIMIT_RESERVE = 50
+
+
+class RateLimiter:
+    """Paces GitHub calls against the per-resource (``core``, ``graphql``) budgets.
+
+    ``send`` waits for the reset when the resource's remaining budget is within the
+    reserve (or below the last GraphQL query cost), records the budget headers of the
+    response, and retries throttled responses (see :func:`throttle_delay`) with
+    exponential backoff. Budgets are tracked per token of ``tokens``; a low budget
+    rotates to the next token when there is one, rather than waiting. One limiter may
+    be shared by every collaborator of a run.
+    """
+
+    def __init__(
+        self,
+        *,
+        reserve: int = RATE_LIMIT_RESERVE,
+        max_retries: int = 5,
+        backoff_seconds: float = 5.0,
+        sleep: Callable[[float], Awaitable[None]] = asyncio.sleep,
+        clock: Callable[[], float] = time.time,
+        tokens: TokenPool | None = None,
+    ) -> None:
+        self._reserve = reserve
+        self._max_retries = max_retries
+        self._backoff = backoff_seconds
+        self._sleep = sleep
+        self._clock = clock
+        self._tokens = tokens
+        # (token index, resource) -> (remaining, reset as epoch seconds)
+        self._budgets: dict[tuple[int, str], tuple[int, float]] = {}
+        self._costs: dict[str, int] = {}
+
+    def _key(self, resource: str) -> tuple[int, str]:
+        return (self._tokens.index if self._tokens is not None else 0, resource)
+
+    def observe(self, response: httpx.Response, *, resource: str = "core") -> None:
+        """Record the ``X-RateLimit-*`` budget of ``response``, when it carries one."""
+        headers = response.headers
+        remaining = headers.get("X-RateLimit-Remaining", "")
+        reset = headers.get("X-RateLimit-Reset", "")
+        if remaining.isdigit() and reset.isdigit():
+            resource = headers.get("X-RateLimit-Resource", resource)
+            self._budgets[self._key(resource)] = (int(remaining), float(reset))
+
+    def observe_graphql(self, rate_limit: Mapping[str, Any] | None) -> None:
+        """Record a GraphQL ``rateLimit { cost remaining resetAt }`` selection."""
+        if not rate_limit:
+            return
+        try:
+            remaining = int(rate_limit["remaining"])
+            reset = datetime.fromisoformat(rate_limit["resetAt"]).timestamp()
+        except (KeyError, TypeError, ValueError):
+            return
+        self._budgets[self._key("graphql")] = (remaining, reset)
+        cost = rate_limit.get("cost")
+        if isinstance(cost, int):
+            self._costs["graphql"] = cost
+
+    async def wait(self, resource: str = "core") -> None:
+        """Sleep until ``resource`` resets when its remaining budget is too low."""
+        key = self._key(resource)
+        budget = self._budgets.get(key)
+        if budget is None:
+            return
+        remaining, reset = budget
+        if remaining > max(self._reserve, self._costs.get(resource, 1)):
+            return
+        if self._tokens is not None and self._tokens.rotate(reset):
+            return
+        delay = reset - self._clock()
+        if delay > 0:
+            log.warning(
+                "GitHub %s rate limit nearly exhausted (%d left); waiting %.0fs for the reset",
+                resource,
+                remaining,
+                delay,
+            )
+            await self._sleep(delay + 1.0)  # a second of slack for clock skew
+        self._budgets.pop(key, None)
+
+    async def send(
+        self,
+        send: Callable[[], Awaitable[httpx.Response]],
+        *,
+        resource: str = "core",
+        what: str = "request",
+    ) -> httpx.Response:
+        """Issue ``send()`` within the budget, retrying while it comes back throttled."""
+        attempt = 0
+        while True:
+            await self.wait(resource)
+            response = await send()
+            self.observe(response, resource=resource)
+            if not await self.retry_after(response, attempt, what):
+                return response
+            attempt += 1
+
+    async def retry_after(self, response: httpx.Response, attempt: int, what: str) -> bool:
+        """Sleep and return True when ``response`` is throttled and retries remain."""
+        delay = throttle_delay(response, now=self._clock())
+        if delay is None or attempt >= self._max_retries:
+            return False
+        wait = delay if delay > 0 else self._backoff * 2**attempt
+        log.info(
+            "GitHub throttled %s (HTTP %d); retrying in %.0fs", what, response.status_code, wait
+        )
+        await self._sleep(wait)
+        return True
 
 
 async def load_repositories(
     user: str,
-    token: str,
+    token: str | TokenPool,
     *,
     client: httpx.AsyncClient | None = None,
-    endpoint: str = GITHUB_GRAPHQL_ENDPOINT,
+    api_url: str = GITHUB_API_URL,
+    user_agent: str | None = None,
+    extra_headers: Mapping[str, str] | None = None,
+    starred_gists: bool = False,
+    rate_limiter: RateLimiter | None = None,
 ) -> UserRepositories:
-    """Page through every owned/starred/watching/gist connection and fold the result."""
+    """Page through every owned/starred/watching/gist connection and fold the result.
+
+    With ``starred_gists`` the token owner's starred gists are listed as well (the
+    REST endpoint only serves the authenticated user, not ``user``). ``api_url`` is the
+    API root, e.g. ``https://github.example.com/api`` for GitHub Enterprise Server, whose
+    REST calls go to ``/api/v3`` and GraphQL to ``/api/graphql``.
+    """
+    auth = token if isinstance(token, TokenPool) else None
+    limiter = rate_limiter or RateLimiter(tokens=auth)
     owned_managed = client is None
     http = client or httpx.AsyncClient(timeout=60.0)
-    headers = {
-        "Authorization": f"Bearer {token}",
-        "User-Agent": f"gitout/{__version__}",
-    }
-    cursors: dict[str, str | None] = {
-        "ownerAfter": None,
-        "starredAfter": None,
-        "watchingAfter": None,
-        "gistsAfter": None,
-    }
-    pages: list[dict[str, Any]] = []
+    headers = api_headers(token, user_agent=user_agent, extra_headers=extra_headers)
     try:
-        while True:
-            variables = {"login": user, **cursors}
-            response = await http.post(
-                endpoint,
-                json={"query": USER_REPOS_QUERY, "variables": variables},
-                headers=headers,
+        # A TaskGroup cancels the other pagers as soon as one fails, before the client closes.
+        try:
+            async with asyncio.TaskGroup() as group:
+                paged = [
+                    group.create_task(
+                        _page_connection(http, api_url, headers, user, alias, limiter, auth)
+                    )
+                    for alias in CONNECTIONS
+                ]
+        except ExceptionGroup as failed:
+            raise failed.exceptions[0] from None
+        # Concatenated in CONNECTIONS order: the fold gives owned > starred > watching.
+        pages = [page for task in paged for page in task.result()]
+
+        starred_gist_items: list[dict[str, Any]] = []
+        url: str | None = None
+        if starred_gists:
+            url = f"{rest_url(api_url)}/gists/starred?per_page=100"
+        while url is not None:
+            response = await limiter.send(
+                functools.partial(http.get, url, headers=headers, auth=auth),
+                what="GET /gists/starred",
             )
             response.raise_for_status()
-            body = response.json()
-            if body.get("errors"):
-                raise RuntimeError(f"GitHub GraphQL errors: {body['errors']}")
-
-            data = body["data"]
-            user_node = data.get("user")
-            if user_node is None:
-                raise RuntimeError(f"GitHub user not found: {user}")
-
-            owned = _edges(user_node.get("ownedRepositories"), "ownedEdges")
-            starred = _edges(user_node.get("starredRepositories"), "starredEdges")
-            watching = _edges(user_node.get("watchingRepositories"), "watchingEdges")
-            gists = _edges(user_node.get("gistRepositories"), "gistEdges")
-            if not owned and not starred and not watching and not gists:
-                break
-
-            pages.append(data)
-            if owned:
-                cursors["ownerAfter"] = owned[-1]["cursor"]
-            if starred:
-                cursors["starredAfter"] = starred[-1]["cursor"]
-            if watching:
-                cursors["watchingAfter"] = watching[-1]["cursor"]
-            if gists:
-                cursors["gistsAfter"] = gists[-1]["cursor"]
+            starred_gist_items.extend(response.json())
+            url = response.links.get("next", {}).get("url")
     finally:
         if owned_managed:
             await http.aclose()
 
-    return parse_user_repositories(pages)
+    return parse_user_repositories(pages, starred_gist_items)
@@ -0,0 +1,249 @@
+"""Export of GitHub repository data that the git data does not carry.
+
+For repositories where the token has admin rights, ``RepositorySettingsExporter``
+reads the merge/feature settings, the protection rules of every protected branch and
+the full ruleset definitions through the REST API, and writes them as one JSON
+document per repository under ``destination/github/settings/<owner>/<repo>.json``.
+Repositories without admin access are skipped (the protection endpoints would 403).
+
+``CommunitySnapshotExporter`` records who stars and watches a repository into a dated
+file, ``destination/github/community/<owner>/<repo>/<YYYY-MM-DD>.json``, so the
+history accumulates one snapshot per day. The httpx clients are injectable for tests.
+"""
+
+from __future__ import annotations
+
+import functools
+import json
+import logging
+from collections.abc import Awaitable, Callable, Mapping
+from datetime import UTC, datetime
+from pathlib import Path
+from typing import Any
+from urllib.parse import quote
+
+import httpx
+
+from gitout.github_client import (
+    GITHUB_API_URL,
+    RateLimiter,
+    TokenPool,
+    api_headers,
+    rest_url,
+)
+from gitout.http_cache import HttpCache
+
+
+# Repository fields that describe configuration rather than activity.
+SETTINGS_KEYS = (
+    "description",
+    "homepage",
+    "visibility",
+    "default_branch",
+    "topics",
+    "archived",
+    "is_template",
+    "has_issues",
+    "has_projects",
+    "has_wiki",
+    "has_discussions",
+    "allow_forking",
+    "web_commit_signoff_required",
+    "allow_merge_commit",
+    "allow_squash_merge",
+    "allow_rebase_merge",
+    "allow_auto_merge",
+    "allow_update_branch",
+    "delete_branch_on_merge",
+    "use_squash_pr_title_as_default",
+    "squash_merge_commit_title",
+    "squash_merge_commit_message",
+    "merge_commit_title",
+    "merge_commit_message",
+    "security_and_analysis",
+)
+
+log = logging.getLogger(__name__)
+
+
+def _now_iso() -> str:
+    return datetime.now(UTC).isoformat()
+
+
+class _RestExporter:
+    def __init__(
+        self,
+        token: str | TokenPool,
+        *,
+        client: httpx.AsyncClient | None = None,
+        api_url: str = GITHUB_API_URL,
+        now_iso: Callable[[], str] = _now_iso,
+        user_agent: str | None = None,
+        extra_headers: Mapping[str, str] | None = None,
+        cache: HttpCache | None = None,
+        rate_limiter: RateLimiter | None = None,
+    ) -> None:
+        self._client = client
+        self._cache = cache
+        self._auth = token if isinstance(token, TokenPool) else None
+        self._rate_limiter = rate_limiter or RateLimiter(tokens=self._auth)
+        self._api_url = rest_url(api_url)
+        self._now_iso = now_iso
+        self._headers = {
+            "Accept": "application/vnd.github+json",
+            **api_headers(token, user_agent=user_agent, extra_headers=extra_headers),
+        }
+
+    async def _fetch(
+        self, http: httpx.AsyncClient, url: str, headers: dict[str, str]
+    ) -> httpx.Response:
+        def send(request_headers: dict[str, str]) -> Awaitable[httpx.Response]:
+            return self._rate_limiter.send(
+                functools.partial(http.get, url, headers=request_headers, auth=self._auth),
+                what=f"GET {url}",
+            )
+
+        if self._cache is None:
+            return await send(headers)
+        return await self._cache.get(url, headers, send)
+
+    async def _get(self, http: httpx.AsyncClient, path: str) -> httpx.Response:
+        return await self._fetch(http, f"{self._api_url}{path}", self._headers)
+
+    async def _get_all(
+        self, http: httpx.AsyncClient, path: str, *, accept: str | None = None
+    ) -> list[Any]:
+        """GET a list endpoint, following ``Link: rel=next`` pagination.
+
+        403/404 (feature unavailable for the repo's plan) yield what was read so far.
+        """
+        headers = self._headers if accept is None else {**self._headers, "Accept": accept}
+        items: list[Any] = []
+        url: str | None = f"{self._api_url}{path}"
+        while url is not None:
+            response = await self._fetch(http, url, headers)
+            if response.status_code in (403, 404):
+                break
+            response.raise_for_status()
+            items.extend(response.json())
+            url = response.links.get("next", {}).get("url")
+        return items
+
+
+class RepositorySettingsExporter(_RestExporter):
+    async def export(self, name_with_owner: str) -> dict[str, Any] | None:
+        """Collect the settings document, or None when the token lacks admin rights."""
+        owned = self._client is None
+        http = self._client or httpx.AsyncClient(timeout=60.0)
+        try:
+            response = await self._get(http, f"/repos/{name_with_owner}")
+            response.raise_for_status()
+            repo = response.json()
+            if not (repo.get("permissions") or {}).get("admin"):
+                return None
+
+            protection: dict[str, Any] = {}
+            branches = await self._get_all(
+                http, f"/repos/{name_with_owner}/branches?protected=true&per_page=100"
+            )
+            for branch in branches:
+                name = branch["name"]
+                protection_path = (
+                    f"/repos/{name_with_owner}/branches/{quote(name, safe='')}/protection"
+                )
+                rules = await self._get(http, protection_path)
+                if rules.status_code == 200:
+                    protection[name] = rules.json()
+                elif rules.status_code != 404:  # 404: the protection went away meanwhile
+                    log.warning(
+                        "Cannot read the protection of %s branch %s: HTTP %d",
+                        name_with_owner,
+                        name,
+                        rules.status_code,
+                    )
+
+            rulesets: list[Any] = []
+            summaries = await self._get_all(
+                http, f"/repos/{name_with_owner}/rulesets?includes_parents=false&per_page=100"
+            )
+            for summary in summaries:
+                ruleset_path = f"/repos/{name_with_owner}/rulesets/{summary['id']}"
+                detail = await self._get(http, ruleset_path)
+                rulesets.append(detail.json() if detail.status_code == 200 else summary)
+        finally:
+            if owned:
+                await http.aclose()
+
+        return {
+            "repository": name_with_owner,
+            "exportedAt": self._now_iso(),
+            "settings": {key: repo[key] for key in SETTINGS_KEYS if key in repo},
+            "branchProtection": protection,
+            "rulesets": rulesets,
+        }
+
+    async def export_to(self, name_with_owner: str, settings_dir: Path) -> bool:
+        """Export and write ``<settings_dir>/<owner>/<repo>.json``; True when written.
+
+        API failures are logged and swallowed: a missing settings export must never
+        fail the backup itself.
+        """
+        try:
+            document = await self.export(name_with_owner)
+        except httpx.HTTPError as exc:
+            log.warning("Failed to export settings of %s: %s", name_with_owner, exc)
+            return False
+        if document is None:
+            log.debug("Skipping settings export of %s: no admin access", name_with_owner)
+            return False
+        target = settings_dir / f"{name_with_owner}.json"
+        target.parent.mkdir(parents=True, exist_ok=True)
+        target.write_text(json.dumps(document, indent=2))
+        return True
+
+
+class CommunitySnapshotExporter(_RestExporter):
+    async def export(self, name_with_owner: str) -> dict[str, Any]:
+        """Collect the current stargazers (with star dates) and watchers."""
+        owned = self._client is None
+        http = self._client or httpx.AsyncClient(timeout=60.0)
+        try:
+            stargazers = await self._get_all(
+                http,
+                f"/repos/{name_with_owner}/stargazers?per_page=100",
+                accept="application/vnd.github.star+json",  # adds starred_at
+            )
+            watchers = await self._get_all(
+                http, f"/repos/{name_with_owner}/subscribers?per_page=100"
+            )
+        finally:
+            if owned:
+                await http.aclose()
+
+        return {
+            "repository": name_with_owner,
+            "capturedAt": self._now_iso(),
+            "stargazers": [
+                {"login": entry["user"]["login"], "starredAt": entry.get("starred_at")}
+                for entry in stargazers
+                if entry.get("user")
+            ],
+            "watchers": [entry["login"] for entry in watchers],
+        }
+
+    async def export_to(self, name_with_owner: str, community_dir: Path) -> bool:
+        """Export and write ``<community_dir>/<owner>/<repo>/<date>.json``; True when written.
+
+        A second run on the same day replaces that day's snapshot. API failures are
+        logged and swallowed.
+        """
+        try:
+            document = await self.export(name_with_owner)
+        except httpx.HTTPError as exc:
+            log.warning("Failed to snapshot community of %s: %s", name_with_owner, exc)
+            return False
+        day = document["capturedAt"][:10]
+        target = community_dir / name_with_owner / f"{day}.json"
+        target.parent.mkdir(parents=True, exist_ok=True)
+        target.write_text(json.dumps(document, indent=2))
+        return True
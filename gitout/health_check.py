@@ -1,26 +1,50 @@
 """Healthchecks.io ping client (port of HealthCheckService.kt).
 
-``start()`` POSTs to ``{host}/{id}/start`` and returns a handle whose ``complete()``
-POSTs to ``{host}/{id}``. Network failures are logged and swallowed (a failed ping
-must never fail the backup). The httpx client is injectable for tests.
+``start()`` POSTs to ``{url}/start`` and returns a handle whose ``complete()`` POSTs
+to ``{url}`` and whose ``fail(log)`` POSTs to ``{url}/fail`` with the log (see
+:func:`failure_log`) as the body, cut to the 100 kB Healthchecks.io keeps. ``url`` is
+``{host}/{id}`` (``sync --hc-id``) or any ping URL (``[health_check] ping_url``), so
+other dead-man's-switch services with the same ``/start`` and ``/fail`` endpoints work
+too. Network failures are logged and swallowed (a failed ping must never fail the
+backup). The httpx client is injectable for tests.
 """
 
 from __future__ import annotations
 
 import logging
+from collections.abc import Sequence
+from typing import TYPE_CHECKING
 
 import httpx
 
+if TYPE_CHECKING:
+    from gitout.engine import SyncOutcome
+
 DEFAULT_HEALTHCHECK_HOST = "https://hc-ping.com"
+# Healthchecks.io stores the first 100 kB of a ping's body.
+PING_BODY_LIMIT = 100_000
 
 log = logging.getLogger(__name__)
 
 
-async def _post(client: httpx.AsyncClient | None, url: str, what: str) -> None:
+def failure_log(outcomes: Sequence[SyncOutcome]) -> str:
+    """The body of a failure ping: a count, then one line per failed repository."""
+    failed = [outcome for outcome in outcomes if not outcome.ok]
+    lines = [f"{len(failed)} of {len(outcomes)} repositories failed"]
+    lines += [f"FAILED {outcome.task.identity}: {outcome.error}" for outcome in failed]
+    return "\n".join(lines)
+
+
+async def _post(
+    client: httpx.AsyncClient | None, url: str, what: str, body: str | None = None
+) -> None:
     owned = client is None
     http = client or httpx.AsyncClient(timeout=30.0)
     try:
-        await http.post(url)
+        if body is None:
+            await http.post(url)
+        else:
+            await http.post(url, content=body.encode()[:PING_BODY_LIMIT])
     except httpx.HTTPError as exc:
         log.warning("Healthcheck %s request failed: %s", what, exc)
     finally:
@@ -36,6 +60,9 @@ class StartedHealthCheck:
     async def complete(self) -> None:
         await _post(self._client, self._url, "complete")
 
+    async def fail(self, body: str) -> None:
+        await _post(self._client, f"{self._url}/fail", "fail", body)
+
 
 class HealthCheck:
     def __init__(self, url: str, client: httpx.AsyncClient | None) -> None:
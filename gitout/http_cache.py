@@ -0,0 +1,87 @@
+"""Conditional GET cache for GitHub REST calls.
+
+GitHub does not count ``304 Not Modified`` answers against the rate limit, so the REST
+collaborators (metadata exporters, the migrations listing) route their GETs through an
+:class:`HttpCache`: the stored ``ETag``/``Last-Modified`` go out as ``If-None-Match``/
+``If-Modified-Since``, and a 304 is answered from the stored body as if it were a 200
+(``Link`` headers included, so pagination still works).
+
+The cache lives in ``<destination>/.gitout-http-cache.json``. It is only an
+optimisation: a missing or unreadable file starts empty, and write failures are
+ignored.
+"""
+
+from __future__ import annotations
+
+import contextlib
+import json
+from collections.abc import Awaitable, Callable, Mapping
+from pathlib import Path
+from typing import Any
+
+import httpx
+
+HTTP_CACHE_FILE = ".gitout-http-cache.json"
+
+# Response headers replayed on a cache hit.
+_REPLAYED_HEADERS = ("Content-Type", "Link", "ETag", "Last-Modified")
+
+Send = Callable[[dict[str, str]], Awaitable[httpx.Response]]
+
+
+class HttpCache:
+    def __init__(self, path: Path | None = None) -> None:
+        self._path = path
+        self._entries: dict[str, dict[str, Any]] = self._load()
+        self.hits = 0
+
+    def _load(self) -> dict[str, dict[str, Any]]:
+        if self._path is None:
+            return {}
+        try:
+            entries = json.loads(self._path.read_text()).get("entries", {})
+        except (OSError, ValueError, AttributeError):
+            return {}
+        return entries if isinstance(entries, dict) else {}
+
+    async def get(self, url: str, headers: Mapping[str, str], send: Send) -> httpx.Response:
+        """GET ``url`` through ``send`` with validators attached; 304s come back as 200s."""
+        entry = self._entries.get(url)
+        request_headers = dict(headers)
+        if entry is not None:
+            if entry.get("etag"):
+                request_headers["If-None-Match"] = entry["etag"]
+            if entry.get("lastModified"):
+                request_headers["If-Modified-Since"] = entry["lastModified"]
+        response = await send(request_headers)
+        if response.status_code == 304 and entry is not None:
+            self.hits += 1
+            return httpx.Response(
+                200,
+                content=entry["body"].encode(),
+                headers=entry.get("headers", {}),
+                request=response.request,
+            )
+        etag = response.headers.get("ETag")
+        last_modified = response.headers.get("Last-Modified")
+        if response.status_code == 200 and (etag or last_modified):
+            await response.aread()
+            self._entries[url] = {
+                "etag": etag,
+                "lastModified": last_modified,
+                "headers": {
+                    name: response.headers[name]
+                    for name in _REPLAYED_HEADERS
+                    if name in response.headers
+                },
+                "body": response.text,
+            }
+        return response
+
+    def save(self) -> None:
+        if self._path is None:
+            return
+        partial = self._path.with_name(self._path.name + ".tmp")
+        with contextlib.suppress(OSError):
+            partial.write_text(json.dumps({"version": 1, "entries": self._entries}))
+            partial.replace(self._path)
@@ -0,0 +1,68 @@
+"""Credentials kept in the OS keyring (Secret Service, macOS Keychain, Windows Credential
+Manager), so desktop installs need no token on disk.
+
+``gitout auth set github`` stores the token under service ``gitout``, account
+``github``; a sync falls back to it when neither the config nor the environment supplies
+a token (see :func:`gitout.engine.resolve_github_token`). The ``keyring`` package is
+optional (``pip install 'gitout[keyring]'``) and imported lazily: without it, or without
+a usable backend (a headless server), lookups simply find nothing.
+"""
+
+from __future__ import annotations
+
+import logging
+from typing import Any
+
+KEYRING_SERVICE = "gitout"
+PROVIDERS = ("github",)
+
+log = logging.getLogger(__name__)
+
+
+class KeyringUnavailableError(ValueError):
+    """The keyring package or a usable backend is missing."""
+
+
+def _keyring() -> Any:
+    try:
+        import keyring
+    except ImportError as exc:
+        raise KeyringUnavailableError(
+            "OS keyring support needs the keyring package (pip install 'gitout[keyring]')"
+        ) from exc
+    return keyring
+
+
+def get_secret(provider: str) -> str | None:
+    """The stored secret for ``provider``; None when absent or the keyring is unusable."""
+    try:
+        secret = _keyring().get_password(KEYRING_SERVICE, provider)
+    except KeyringUnavailableError:
+        return None
+    except Exception as exc:  # backends raise their own errors (D-Bus, locked keychain)
+        log.debug("Cannot read %s credentials from the OS keyring: %s", provider, exc)
+        return None
+    if not isinstance(secret, str) or not secret.strip():
+        return None
+    return secret.strip()
+
+
+def set_secret(provider: str, secret: str) -> None:
+    """Store ``secret`` for ``provider``; raises KeyringUnavailableError on failure."""
+    keyring = _keyring()
+    try:
+        keyring.set_password(KEYRING_SERVICE, provider, secret)
+    except Exception as exc:  # backends raise their own errors (D-Bus, locked keychain)
+        raise KeyringUnavailableError(f"Cannot store {provider} credentials: {exc}") from exc
+
+
+def delete_secret(provider: str) -> bool:
+    """Remove the stored secret for ``provider``; False when there was none."""
+    if get_secret(provider) is None:
+        return False
+    keyring = _keyring()
+    try:
+        keyring.delete_password(KEYRING_SERVICE, provider)
+    except Exception as exc:  # backends raise their own errors (D-Bus, locked keychain)
+        raise KeyringUnavailableError(f"Cannot delete {provider} credentials: {exc}") from exc
+    return True
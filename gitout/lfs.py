@@ -1,9 +1,15 @@
 """Git LFS detection and object fetching (port of LfsSupport.kt).
 
 ``git clone --mirror`` stores only LFS pointer files, so LFS-enabled repos need a
-follow-up ``git lfs fetch --all`` to back up the real content. Detection checks for a
-bare-repo ``lfs/`` directory or a ``filter=lfs`` entry in ``HEAD:.gitattributes``.
-Commands use the resolved git path; the runner is injectable for hermetic tests.
+follow-up ``git lfs fetch --all`` to back up the real content: it enumerates the
+pointers reachable from every ref and downloads their objects into the mirror's
+``lfs/objects/``. Detection checks for a bare-repo ``lfs/`` directory or a
+``filter=lfs`` entry in ``HEAD:.gitattributes``.
+
+The LFS server is reached with the repository's own credentials and transport: the
+engine passes the sync's ``-c`` options (credential helper, ``http.sslVerify``,
+``core.sshCommand``) as ``config_options``. Commands use the resolved git path; the
+runner is injectable for hermetic tests.
 """
 
 from __future__ import annotations
@@ -11,7 +17,7 @@ from __future__ import annotations
 import contextlib
 import os
 import subprocess
-from collections.abc import Callable, Mapping
+from collections.abc import Callable, Mapping, Sequence
 from pathlib import Path
 
 from gitout.git_exec import resolve_git_executable
@@ -70,15 +76,19 @@ class LfsSupport:
             return code == 0 and "filter=lfs" in stdout
         return False
 
-    def fetch_lfs_objects(self, repo_path: Path) -> bool:
+    def fetch_lfs_objects(self, repo_path: Path, config_options: Sequence[str] = ()) -> bool:
         if not repo_path.is_dir():
             return False
+        argv = [self._git]
+        for option in config_options:
+            argv += ["-c", option]
+        argv += ["-C", str(repo_path), "lfs", "fetch", "--all"]
         with contextlib.suppress(Exception):
-            code, _ = self._run_git([self._git, "-C", str(repo_path), "lfs", "fetch", "--all"])
+            code, _ = self._run_git(argv)
             return code == 0
         return False
 
-    def sync_lfs_if_needed(self, repo_path: Path) -> bool:
+    def sync_lfs_if_needed(self, repo_path: Path, config_options: Sequence[str] = ()) -> bool:
         if not self.is_lfs_repo(repo_path):
             return True  # not an LFS repo, nothing to do
-        return self.fetch_lfs_objects(repo_path)
+        return self.fetch_lfs_objects(repo_path, config_options)
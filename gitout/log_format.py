@@ -0,0 +1,184 @@
+"""Log levels and line formats for ``sync --log-level`` / ``-v`` and ``--log-format``.
+
+:func:`resolve_levels` maps the flags to a root level plus one for the trace loggers
+(:data:`TRACE_LOGGERS`): each git command with its exit code and output
+(``gitout.git``) and a line per HTTP request to the APIs (``httpx``). Those stay
+quiet unless ``trace`` (``-vv``) is asked for. :class:`HttpUrlFilter` cuts the URLs of
+the ``httpx`` lines to ``scheme://host``: Telegram bot and chat webhook URLs carry their
+secret in the path, which must not reach a ``--log-file``.
+
+:func:`file_handler` (``--log-file``) writes the same lines to a file as well, rotated
+when it reaches a size or, with ``--log-rotate-every``, after a fixed time; the newest
+``backups`` rotated files (``gitout.log.1``, ...) are kept.
+
+During ``sync``, :class:`RepositoryBufferingHandler` holds back the lines logged while
+a repository syncs (inside :func:`repository_logs`, set by the engine around each
+repository) and writes them in one piece when it finishes, so the lines of parallel
+workers do not interleave; each such record carries a ``repository`` field.
+
+``text`` is the human-readable ``time LEVEL logger: message`` line. ``json`` writes one
+object per line for log aggregators: ``time`` (UTC, ISO 8601), ``level``, ``logger``,
+``message``, ``exception`` when there is a traceback, and every field passed through
+``extra=`` (e.g. ``logger.info("Synced", extra={"repo": name})``).
+"""
+
+from __future__ import annotations
+
+import contextlib
+import json
+import logging
+from collections.abc import Iterator
+from contextvars import ContextVar
+from datetime import UTC, datetime
+from logging.handlers import RotatingFileHandler, TimedRotatingFileHandler
+from pathlib import Path
+
+import httpx
+
+LOG_FORMATS = ("text", "json")
+LOG_LEVELS = ("trace", "debug", "info", "warning", "error")
+TRACE_LOGGERS = ("gitout.git", "httpx")
+DEFAULT_LOG_MAX_BYTES = 10 * 1024 * 1024
+DEFAULT_LOG_BACKUPS = 5
+TEXT_FORMAT = "%(asctime)s %(levelname)s %(name)s: %(message)s"
+
+# (name, key) of the repository the current asyncio task syncs; see repository_logs.
+_repository: ContextVar[tuple[str, object] | None] = ContextVar("gitout_repository", default=None)
+
+# Attributes every LogRecord has; anything else on a record came from ``extra=``.
+_RECORD_ATTRIBUTES = frozenset(
+    vars(logging.LogRecord("", 0, "", 0, "", None, None)).keys() | {"message", "asctime"}
+)
+
+
+class JsonFormatter(logging.Formatter):
+    def format(self, record: logging.LogRecord) -> str:
+        entry: dict[str, object] = {
+            "time": datetime.fromtimestamp(record.created, UTC).isoformat(),
+            "level": record.levelname,
+            "logger": record.name,
+            "message": record.getMessage(),
+        }
+        if record.exc_info:
+            entry["exception"] = self.formatException(record.exc_info)
+        for key, value in vars(record).items():
+            if key not in _RECORD_ATTRIBUTES and not key.startswith("_"):
+                entry[key] = value
+        return json.dumps(entry, default=str)
+
+
+class HttpUrlFilter(logging.Filter):
+    """Reduce every URL argument of a record (httpx's request lines) to its origin."""
+
+    def filter(self, record: logging.LogRecord) -> bool:
+        if isinstance(record.args, tuple):
+            record.args = tuple(
+                f"{arg.scheme}://{arg.host}" if isinstance(arg, httpx.URL) else arg
+                for arg in record.args
+            )
+        return True
+
+
+HTTP_URL_FILTER = HttpUrlFilter()
+
+
+def formatter_for(log_format: str) -> logging.Formatter:
+    """The formatter of one of :data:`LOG_FORMATS`."""
+    if log_format == "json":
+        return JsonFormatter()
+    if log_format == "text":
+        return logging.Formatter(TEXT_FORMAT)
+    raise ValueError(f"Unknown log format {log_format!r}: expected one of {', '.join(LOG_FORMATS)}")
+
+
+def resolve_levels(verbose: int, quiet: bool, log_level: str | None = None) -> tuple[int, int]:
+    """``(root level, trace logger level)`` for the logging flags.
+
+    ``log_level`` (one of :data:`LOG_LEVELS`) wins; otherwise quiet -> WARNING,
+    default -> INFO, ``-v`` -> DEBUG and ``-vv`` -> DEBUG with the trace loggers on.
+    quiet wins over verbose when both are set.
+    """
+    if log_level is None:
+        if quiet:
+            log_level = "warning"
+        else:
+            log_level = ("info", "debug", "trace")[min(verbose, 2)]
+    if log_level not in LOG_LEVELS:
+        expected = ", ".join(LOG_LEVELS)
+        raise ValueError(f"Unknown log level {log_level!r}: expected one of {expected}")
+    if log_level == "trace":
+        return logging.DEBUG, logging.DEBUG
+    level = logging.getLevelNamesMapping()[log_level.upper()]
+    return level, max(level, logging.WARNING)
+
+
+def file_handler(
+    path: Path,
+    *,
+    max_bytes: int | None = None,
+    rotate_every_seconds: float | None = None,
+    backups: int = DEFAULT_LOG_BACKUPS,
+) -> logging.Handler:
+    """A handler appending to ``path``, rotated by time when given, else by size."""
+    path.parent.mkdir(parents=True, exist_ok=True)
+    if rotate_every_seconds is not None:
+        return TimedRotatingFileHandler(
+            path,
+            when="S",
+            interval=max(int(rotate_every_seconds), 1),
+            backupCount=backups,
+            encoding="utf-8",
+            utc=True,
+        )
+    return RotatingFileHandler(
+        path,
+        maxBytes=max_bytes or DEFAULT_LOG_MAX_BYTES,
+        backupCount=backups,
+        encoding="utf-8",
+    )
+
+
+class RepositoryBufferingHandler(logging.Handler):
+    """Pass records to ``target``, holding back those of a repository until it is done."""
+
+    def __init__(self, target: logging.Handler) -> None:
+        super().__init__()
+        self.target = target
+        self._buffers: dict[object, list[logging.LogRecord]] = {}
+
+    def emit(self, record: logging.LogRecord) -> None:
+        current = _repository.get()
+        if current is None:
+            self.target.handle(record)
+            return
+        name, key = current
+        record.repository = name
+        with self.lock:  # type: ignore[union-attr]
+            self._buffers.setdefault(key, []).append(record)
+
+    def flush_repository(self, key: object) -> None:
+        """Write the held-back records of one repository, in order and together."""
+        with self.lock:  # type: ignore[union-attr]
+            records = self._buffers.pop(key, [])
+            for record in records:
+                self.target.handle(record)
+
+    def close(self) -> None:
+        for key in list(self._buffers):
+            self.flush_repository(key)
+        self.target.close()
+        super().close()
+
+
+@contextlib.contextmanager
+def repository_logs(name: str) -> Iterator[None]:
+    """Attribute what is logged inside to repository ``name`` and flush it on exit."""
+    key = object()
+    token = _repository.set((name, key))
+    try:
+        yield
+    finally:
+        _repository.reset(token)
+        for handler in logging.getLogger().handlers:
+            if isinstance(handler, RepositoryBufferingHandler):
+                handler.flush_repository(key)
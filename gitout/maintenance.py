@@ -1,22 +1,35 @@
 """Post-sync repository maintenance (port of RepositoryMaintenance.kt).
 
 Strategies: ``gc-auto`` (``git gc --auto``), ``geometric`` (``git repack
---geometric=2 -d``), or ``none``. Optionally writes a commit-graph after every sync
-and runs a periodic full repack (``git repack -a -d``) on a weekly/monthly cadence
-(~1 sync/day → 7 / 30 syncs). Maintenance commands use the literal ``git`` (matching
-Kotlin). The command runner is injectable so tests assert argv without spawning git.
+--geometric=2 -d``), or ``none``. Optionally writes a multi-pack-index (one lookup
+across all packs, which a mirror updated by many fetches accumulates) and a
+commit-graph after every sync, and runs a periodic full repack (``git repack -a -d``)
+on a weekly/monthly cadence (~1 sync/day → 7 / 30 syncs). Maintenance commands use the
+literal ``git`` (matching Kotlin), with any ``git_config`` entries (the ``[transfer]``
+pack tuning) passed as ``-c`` options. The command runner is injectable so tests assert
+argv without spawning git.
+
+With ``gc_every = N`` each repository also gets a ``git gc --prune=<gc_prune>`` after
+every N-th successful sync of that repository: it packs loose objects, merges the small
+packs updates leave behind and drops unreachable objects older than ``gc_prune``. The
+per-repository counts survive restarts in ``state_path`` (``.gitout-maintenance.json``
+in the destination).
 """
 
 from __future__ import annotations
 
 import contextlib
+import json
 import os
 import subprocess
-from collections.abc import Callable
+import threading
+from collections.abc import Callable, Sequence
 from pathlib import Path
 
 from gitout.config import Maintenance
 
+MAINTENANCE_STATE_FILE = ".gitout-maintenance.json"
+
 # (argv, cwd) -> None
 GitCommandRunner = Callable[[list[str], Path], None]
 
@@ -38,10 +51,52 @@ class RepositoryMaintenance:
         *,
         timeout_seconds: float = 600.0,
         run_git: GitCommandRunner | None = None,
+        git_config: Sequence[str] = (),
+        state_path: Path | None = None,
     ) -> None:
         self._config = config
+        self._git = ["git"] + [arg for option in git_config for arg in ("-c", option)]
         self._run_git = run_git or _default_runner(timeout_seconds)
         self._sync_count = 0
+        self._state_path = state_path
+        self._lock = threading.Lock()  # workers finish repositories concurrently
+        self._syncs_since_gc = self._load_counts()
+
+    def _load_counts(self) -> dict[str, int]:
+        if self._state_path is None:
+            return {}
+        try:
+            data = json.loads(self._state_path.read_text())
+            return {str(k): int(v) for k, v in data.get("syncsSinceGc", {}).items()}
+        except (OSError, ValueError, AttributeError, TypeError):
+            return {}
+
+    def _key(self, repo_path: Path) -> str:
+        if self._state_path is None:
+            return str(repo_path)
+        return os.path.relpath(repo_path, self._state_path.parent)
+
+    def _gc_due(self, repo_path: Path) -> bool:
+        """Count one sync of ``repo_path``; True (and reset) when its gc is due."""
+        if self._config.gc_every < 1:
+            return False
+        key = self._key(repo_path)
+        with self._lock:
+            count = self._syncs_since_gc.get(key, 0) + 1
+            due = count >= self._config.gc_every
+            self._syncs_since_gc[key] = 0 if due else count
+        return due
+
+    def save_state(self) -> None:
+        """Persist the per-repository sync counts (no-op without ``state_path``)."""
+        if self._state_path is None or self._config.gc_every < 1:
+            return
+        with self._lock:
+            payload = {"syncsSinceGc": dict(sorted(self._syncs_since_gc.items()))}
+        partial = self._state_path.with_name(self._state_path.name + ".tmp")
+        with contextlib.suppress(OSError):
+            partial.write_text(json.dumps(payload, indent=2))
+            partial.replace(self._state_path)
 
     def run_post_sync_maintenance(self, repo_path: Path) -> None:
         if not self._config.enabled:
@@ -51,15 +106,25 @@ class RepositoryMaintenance:
 
         abs_path = str(repo_path)
         if self._config.strategy == "gc-auto":
-            self._run_git(["git", "-C", abs_path, "gc", "--auto"], repo_path)
+            self._run_git([*self._git, "-C", abs_path, "gc", "--auto"], repo_path)
         elif self._config.strategy == "geometric":
-            self._run_git(["git", "-C", abs_path, "repack", "--geometric=2", "-d"], repo_path)
+            self._run_git(
+                [*self._git, "-C", abs_path, "repack", "--geometric=2", "-d"], repo_path
+            )
         # "none" and unknown strategies run no repack command (unknown is a no-op here).
 
-        # The commit-graph is written after every strategy, including none/unknown.
+        if self._gc_due(repo_path):
+            prune = f"--prune={self._config.gc_prune}"
+            self._run_git([*self._git, "-C", abs_path, "gc", "--quiet", prune], repo_path)
+
+        # The indexes are written after every strategy, including none/unknown.
+        if self._config.write_multi_pack_index:
+            self._run_git(
+                [*self._git, "-C", abs_path, "multi-pack-index", "write"], repo_path
+            )
         if self._config.write_commit_graph:
             self._run_git(
-                ["git", "-C", abs_path, "commit-graph", "write", "--reachable"], repo_path
+                [*self._git, "-C", abs_path, "commit-graph", "write", "--reachable"], repo_path
             )
 
     def register_sync_and_check_repack(self) -> bool:
@@ -82,7 +147,7 @@ class RepositoryMaintenance:
         for repo in self.find_git_repos(destination_path):
             self._run_git(
                 [
-                    "git",
+                    *self._git,
                     "-C",
                     str(repo),
                     "repack",
@@ -0,0 +1,161 @@
+"""A ``gitout(1)`` man page generated from the CLI and the config model (``gitout man``).
+
+Packagers should not have to keep a hand-written page in sync: :func:`render_manpage`
+walks the Click command tree behind the Typer app (every subcommand with its
+arguments, options, defaults and environment variables) and the :class:`Config`
+dataclasses (every table and key with its type and default) and emits roff for
+``man -l``. The output carries no date, so it is reproducible.
+"""
+
+from __future__ import annotations
+
+import json
+from dataclasses import MISSING, Field, fields
+from typing import Any, get_args, get_origin, get_type_hints
+
+import click
+
+from gitout.config import Config, GitConfig, _dataclass_of
+
+_SUMMARY = "back up Git repositories from GitHub or any git host"
+# Filled from the table form of [git.repos] entries; not keys of their own.
+_DERIVED_FIELDS = frozenset({(GitConfig, "credentials"), (GitConfig, "refspecs")})
+
+
+def _escape(text: str) -> str:
+    """Roff-safe text: backslashes, dashes and leading control characters."""
+    escaped = text.replace("\\", "\\e").replace("-", "\\-")
+    return "\n".join(
+        f"\\&{line}" if line.startswith((".", "'")) else line for line in escaped.splitlines()
+    )
+
+
+def _paragraphs(text: str) -> list[str]:
+    return [" ".join(block.split()) for block in text.strip().split("\n\n") if block.strip()]
+
+
+def _option_lines(param: click.Option) -> list[str]:
+    """``.TP`` tag and body describing one option."""
+    tag = ", ".join(f"\\fB{_escape(opt)}\\fR" for opt in [*param.opts, *param.secondary_opts])
+    if not param.is_flag and not param.count:
+        tag += f" \\fI{_escape(param.metavar or param.type.name.upper())}\\fR"
+    notes = []
+    if param.envvar:
+        envvars = [param.envvar] if isinstance(param.envvar, str) else list(param.envvar)
+        notes.append("env: " + ", ".join(envvars))
+    if param.multiple and "repeatable" not in (param.help or ""):
+        notes.append("repeatable")
+    default = param.default
+    if isinstance(default, list | tuple):
+        default = " ".join(str(value) for value in default)
+    if default not in (None, False, "") and not callable(default) and not param.count:
+        notes.append(f"default: {default}")
+    body = _escape(param.help or "")
+    if notes:
+        body += f" ({_escape('; '.join(notes))})"
+    return [".TP", tag, body.strip()]
+
+
+def _command_section(name: str, command: click.Command) -> list[str]:
+    if isinstance(command, click.Group):
+        lines: list[str] = []
+        for sub in sorted(command.commands):
+            lines += _command_section(f"{name} {sub}", command.commands[sub])
+        return lines
+    arguments = [p for p in command.params if isinstance(p, click.Argument)]
+    usage = " ".join(
+        f"\\fI{p.human_readable_name}\\fR" if p.required else f"[\\fI{p.human_readable_name}\\fR]"
+        for p in arguments
+    )
+    lines = [f".SS {_escape(name)}", f"\\fB{_escape(name)}\\fR [\\fIOPTIONS\\fR] {usage}".rstrip()]
+    lines += [f".PP\n{_escape(text)}" for text in _paragraphs(command.help or "")]
+    for argument in arguments:
+        help_text = getattr(argument, "help", None)  # Typer's arguments carry one
+        if help_text:
+            lines += [".TP", f"\\fI{_escape(argument.human_readable_name)}\\fR", _escape(help_text)]
+    for param in command.params:
+        if isinstance(param, click.Option) and not param.hidden:
+            lines += _option_lines(param)
+    return lines
+
+
+def _toml_value(value: Any) -> str:
+    if value is None:
+        return "unset"
+    return json.dumps(value) if not isinstance(value, bool) else str(value).lower()
+
+
+def _default(item: Field[Any]) -> Any:
+    if item.default is not MISSING:
+        return item.default
+    if item.default_factory is not MISSING:
+        return item.default_factory()
+    return MISSING
+
+
+def _config_tables(cls: type, table: str) -> list[tuple[str, list[tuple[str, str, str]]]]:
+    """``(table header, [(key, type, default note)])`` for ``cls`` and its nested tables."""
+    hints = get_type_hints(cls)
+    keys: list[tuple[str, str, str]] = []
+    nested: list[tuple[str, list[tuple[str, str, str]]]] = []
+    for item in fields(cls):
+        if (cls, item.name) in _DERIVED_FIELDS:
+            continue
+        hint = hints[item.name]
+        child = _dataclass_of(hint)
+        where = f"{table}.{item.name}" if table else item.name
+        if child is not None:
+            nested += _config_tables(child, where)
+            continue
+        origin = get_origin(hint)
+        element = get_args(hint)[-1] if origin in (list, dict) else None
+        element_cls = _dataclass_of(element) if element is not None else None
+        if origin is list and element_cls is not None:
+            nested += _config_tables(element_cls, f"[{where}]")
+            continue
+        if origin is dict and element_cls is not None:
+            nested += _config_tables(element_cls, f"{where}.<name>")
+            continue
+        default = _default(item)
+        shown = "required" if default is MISSING else f"default: {_toml_value(default)}"
+        keys.append((item.name, str(item.type), shown))
+    return [(table, keys), *nested]
+
+
+def _config_section(config_cls: type) -> list[str]:
+    lines = [
+        ".SH CONFIGURATION",
+        "The configuration is a TOML, JSON or YAML document; each table below lists its "
+        "keys with their type and default.",
+    ]
+    for table, keys in _config_tables(config_cls, ""):
+        if not keys:
+            continue
+        lines.append(f".SS [{_escape(table)}]" if table else ".SS Top level")
+        for name, kind, default in keys:
+            lines += [".TP", f"\\fB{_escape(name)}\\fR", _escape(f"{kind} ({default})")]
+    return lines
+
+
+def render_manpage(command: click.Command, version: str, *, config_cls: type = Config) -> str:
+    """The roff source of ``gitout(1)``."""
+    lines = [
+        f'.TH GITOUT 1 "" "gitout {_escape(version)}" "User Commands"',
+        ".SH NAME",
+        f"gitout \\- {_SUMMARY}",
+        ".SH SYNOPSIS",
+        "\\fBgitout\\fR \\fICOMMAND\\fR [\\fIOPTIONS\\fR] [\\fIARGUMENTS\\fR]",
+        ".SH DESCRIPTION",
+    ]
+    lines += [_escape(text) for text in _paragraphs(command.help or _SUMMARY)]
+    lines.append(".SH OPTIONS")
+    for param in command.params:
+        if isinstance(param, click.Option) and not param.hidden:
+            lines += _option_lines(param)
+    lines.append(".SH COMMANDS")
+    if isinstance(command, click.Group):
+        for name in sorted(command.commands):
+            lines += _command_section(f"gitout {name}", command.commands[name])
+    lines += _config_section(config_cls)
+    lines += [".SH SEE ALSO", "\\fBgit\\fR(1), \\fBgit\\-clone\\fR(1)"]
+    return "\n".join(lines) + "\n"
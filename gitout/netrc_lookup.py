@@ -0,0 +1,44 @@
+"""Credentials from the user's ``.netrc``, the file curl and git already read.
+
+It is the last resort for a host without configured credentials: the GitHub token
+(after the environment and the OS keyring, see
+:func:`gitout.engine.resolve_github_token`) and plain ``[git.repos]`` HTTP(S) remotes
+without a login. ``$NETRC`` names the file; otherwise ``~/.netrc`` is used. A missing
+or malformed file simply finds nothing; ``default`` entries are ignored so a catch-all
+login is never sent to an unrelated host.
+"""
+
+from __future__ import annotations
+
+import logging
+import netrc
+from collections.abc import Iterable, Mapping
+from pathlib import Path
+
+log = logging.getLogger(__name__)
+
+
+def netrc_path(environ: Mapping[str, str]) -> Path:
+    configured = environ.get("NETRC")
+    return Path(configured).expanduser() if configured else Path.home() / ".netrc"
+
+
+def netrc_login(hosts: Iterable[str], environ: Mapping[str, str]) -> tuple[str, str] | None:
+    """``(login, password)`` of the first ``machine`` entry among ``hosts``, or None."""
+    path = netrc_path(environ)
+    if not path.is_file():
+        return None
+    try:
+        entries = {
+            machine.casefold(): entry
+            for machine, entry in netrc.netrc(str(path)).hosts.items()
+            if machine != "default"
+        }
+    except (OSError, netrc.NetrcParseError) as exc:
+        log.debug("Ignoring unreadable %s: %s", path, exc)
+        return None
+    for host in hosts:
+        entry = entries.get(host.casefold())
+        if entry is not None and entry[2]:
+            return entry[0] or "", entry[2]
+    return None
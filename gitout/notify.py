@@ -0,0 +1,200 @@
+"""Run summaries sent to people rather than machines (``[notify]``).
+
+``[notify.email]`` mails the summary of every real run (or, with ``only_on_failure``,
+only of the runs where a repository or the run itself failed) through an SMTP server:
+``starttls`` upgrades a plain connection (port 587), ``ssl`` connects with TLS from the
+start (port 465) and ``none`` sends in the clear. ``username`` logs in with the
+password from ``password_env`` or ``password_file``. The subject is the summary line
+of :func:`gitout.webhooks.summary_line` after ``subject_prefix``; the plain-text body
+adds the timings, the counts and one line per failed repository.
+
+``[notify.slack]`` and ``[notify.discord]`` post a concise message (the summary line,
+the counts and the first :data:`MAX_FAILED_IN_CHAT` failed repositories with their
+errors) to the service's incoming ``webhook_url``; each has its own
+``only_on_failure``. The URL is the credential, so a failed post logs only its host.
+Telegram keeps its bot-token ``[telegram]`` section, whose completion message lists
+the failed repositories the same way.
+
+Every message is built from the same payload as the webhooks
+(:func:`gitout.webhooks.build_payload`). Mail is sent in a thread; a failure is logged,
+never raised. The mail sender and the httpx client are injectable for tests.
+"""
+
+from __future__ import annotations
+
+import asyncio
+import logging
+import os
+import smtplib
+import ssl
+from collections.abc import Callable, Mapping
+from email.message import EmailMessage
+from pathlib import Path
+from typing import Any
+
+import httpx
+
+from gitout.config import ChatNotify, EmailNotify, Notify
+from gitout.webhooks import failure_reason, redacted_url, summary_line
+
+log = logging.getLogger(__name__)
+
+# Delivers a finished message: (message) -> None, raising on failure.
+EmailSender = Callable[[EmailMessage], None]
+
+# Failed repositories listed by name in a chat message.
+MAX_FAILED_IN_CHAT = 10
+# Discord rejects messages longer than this.
+_DISCORD_CONTENT_LIMIT = 2000
+
+
+def email_password(config: EmailNotify, environ: Mapping[str, str]) -> str:
+    """The SMTP password of ``[notify.email]``, from its variable or file (trimmed)."""
+    if config.password_env is not None:
+        password = environ.get(config.password_env, "").strip()
+        if not password:
+            raise ValueError(f"notify.email: ${config.password_env} is not set")
+        return password
+    if config.password_file is None:
+        raise ValueError("notify.email has a username but no password source")
+    path = Path(config.password_file).expanduser()
+    try:
+        password = path.read_text().strip()
+    except OSError as exc:
+        raise ValueError(f"notify.email: cannot read {path}: {exc.strerror}") from exc
+    if not password:
+        raise ValueError(f"notify.email: {path} is empty")
+    return password
+
+
+def summary_text(payload: dict[str, Any]) -> str:
+    """The plain-text body of a run summary."""
+    summary = payload["summary"]
+    lines = [
+        summary_line(payload),
+        "",
+        f"Started:  {payload['startedAt']}",
+        f"Finished: {payload['finishedAt']}",
+        f"Duration: {payload['durationSeconds']}s",
+        f"Attempted {summary['attempted']}, succeeded {summary['succeeded']}, "
+        f"failed {summary['failed']}, skipped {summary['skipped']}",
+    ]
+    if payload["failures"]:
+        lines += ["", "Failed repositories:"]
+        lines += [f"  {f['identity']}: {f['error']}" for f in payload["failures"]]
+    lines += ["", f"gitout {payload['gitoutVersion']}"]
+    return "\n".join(lines) + "\n"
+
+
+def email_message(config: EmailNotify, payload: dict[str, Any]) -> EmailMessage:
+    message = EmailMessage()
+    message["Subject"] = f"{config.subject_prefix} {summary_line(payload)}".strip()
+    message["From"] = config.sender
+    message["To"] = ", ".join(config.recipients)
+    message.set_content(summary_text(payload))
+    return message
+
+
+class EmailNotifier:
+    def __init__(
+        self,
+        config: EmailNotify,
+        *,
+        environ: Mapping[str, str] | None = None,
+        sender: EmailSender | None = None,
+    ) -> None:
+        self.config = config
+        self._environ = os.environ if environ is None else environ
+        self._sender = sender or self._send_smtp
+
+    async def notify(self, payload: dict[str, Any]) -> bool:
+        """Mail the run of ``payload`` unless it is filtered out; False when not sent."""
+        if self.config.only_on_failure and payload["event"] == "success":
+            return False
+        message = email_message(self.config, payload)
+        try:
+            await asyncio.to_thread(self._sender, message)
+        except (OSError, smtplib.SMTPException, ValueError) as exc:
+            log.warning("Mailing the run summary via %s failed: %s", self.config.server, exc)
+            return False
+        return True
+
+    def _send_smtp(self, message: EmailMessage) -> None:
+        config = self.config
+        context = ssl.create_default_context()
+        smtp: smtplib.SMTP
+        if config.security == "ssl":
+            smtp = smtplib.SMTP_SSL(
+                config.server, config.port, timeout=config.timeout_seconds, context=context
+            )
+        else:
+            smtp = smtplib.SMTP(config.server, config.port, timeout=config.timeout_seconds)
+        with smtp:
+            if config.security == "starttls":
+                smtp.starttls(context=context)
+            if config.username:
+                smtp.login(config.username, email_password(config, self._environ))
+            smtp.send_message(message)
+
+
+def chat_text(payload: dict[str, Any], *, bold: str) -> str:
+    """A short chat message; ``bold`` is the service's markup (``*`` or ``**``)."""
+    summary = payload["summary"]
+    lines = [
+        f"{bold}gitout: {summary_line(payload)}{bold}",
+        f"Succeeded {summary['succeeded']}, failed {summary['failed']}, "
+        f"skipped {summary['skipped']} in {payload['durationSeconds']}s",
+    ]
+    failures = payload["failures"]
+    lines += [f"\u2022 `{f['identity']}`: {f['error']}" for f in failures[:MAX_FAILED_IN_CHAT]]
+    if len(failures) > MAX_FAILED_IN_CHAT:
+        lines.append(f"... and {len(failures) - MAX_FAILED_IN_CHAT} more")
+    return "\n".join(lines)
+
+
+def chat_body(service: str, payload: dict[str, Any]) -> dict[str, str]:
+    """The JSON a ``slack`` or ``discord`` incoming webhook takes."""
+    if service == "slack":
+        text = chat_text(payload, bold="*")
+        return {"text": text.replace("&", "&amp;").replace("<", "&lt;").replace(">", "&gt;")}
+    if service == "discord":
+        return {"content": chat_text(payload, bold="**")[:_DISCORD_CONTENT_LIMIT]}
+    raise ValueError(f"Unknown chat service {service!r}")
+
+
+class ChatNotifier:
+    def __init__(
+        self, service: str, config: ChatNotify, *, client: httpx.AsyncClient | None = None
+    ) -> None:
+        self.service = service
+        self.config = config
+        self._client = client
+
+    async def notify(self, payload: dict[str, Any]) -> bool:
+        """Post the run of ``payload`` unless it is filtered out; False when not posted."""
+        if self.config.only_on_failure and payload["event"] == "success":
+            return False
+        http = self._client or httpx.AsyncClient(timeout=self.config.timeout_seconds)
+        try:
+            response = await http.post(
+                self.config.webhook_url, json=chat_body(self.service, payload)
+            )
+            response.raise_for_status()
+        except httpx.HTTPError as exc:
+            log.warning(
+                "Posting the run summary to %s (%s) failed: %s",
+                self.service,
+                redacted_url(self.config.webhook_url),
+                failure_reason(exc),
+            )
+            return False
+        finally:
+            if self._client is None:
+                await http.aclose()
+        return True
+
+
+def chat_notifiers(config: Notify) -> list[ChatNotifier]:
+    """A notifier per configured chat service."""
+    services = (("slack", config.slack), ("discord", config.discord))
+    return [ChatNotifier(service, chat) for service, chat in services if chat is not None]
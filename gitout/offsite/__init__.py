@@ -0,0 +1,2 @@
+"""Off-site copies of the destination: storage backends and the uploader (``[offsite]``),
+and tree replication through rclone (``[rclone]``)."""
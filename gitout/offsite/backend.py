@@ -0,0 +1,40 @@
+"""The storage backend interface and the factory for ``offsite.backend``.
+
+A backend stores a local file under a key (a ``/``-separated path relative to the
+backend's root or prefix). Uploads are blocking; the engine runs them in a worker
+thread. Backends raise :class:`OffsiteError` for failures worth reporting per file.
+"""
+
+from __future__ import annotations
+
+from collections.abc import Mapping
+from pathlib import Path
+from typing import Protocol
+
+from gitout.config import Offsite
+
+
+class OffsiteError(RuntimeError):
+    """An upload failed, or the backend cannot be used at all."""
+
+
+class StorageBackend(Protocol):
+    @property
+    def target(self) -> str:
+        """Where uploads go, e.g. ``s3://bucket/prefix/`` (logs and upload state)."""
+        ...
+
+    def upload(self, source: Path, key: str) -> None: ...
+
+
+def build_backend(offsite: Offsite, environ: Mapping[str, str]) -> StorageBackend:
+    """The backend ``offsite.backend`` names; ``environ`` resolves password variables."""
+    if offsite.backend == "s3":
+        from gitout.offsite.s3 import S3Backend
+
+        return S3Backend(offsite.s3)
+    if offsite.backend == "webdav":
+        from gitout.offsite.webdav import WebDavBackend, webdav_password
+
+        return WebDavBackend(offsite.webdav, webdav_password(offsite.webdav, environ))
+    raise OffsiteError(f"Unknown offsite backend {offsite.backend!r}")
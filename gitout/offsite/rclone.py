@@ -0,0 +1,111 @@
+"""Replicate the destination tree to an rclone remote after a sync (``[rclone]``).
+
+``rclone sync`` (or ``copy`` with ``mode = "copy"``) runs from the destination to
+``rclone.remote``, so any of rclone's backends can hold an off-site copy. ``exclude``
+and ``include`` become rclone ``--filter`` rules in that order (with includes, everything
+else is excluded), after a rule skipping gitout's ``*.tmp`` files. rclone logs as JSON
+at ERROR level; every line naming an object becomes a :class:`RcloneFailure`, and the
+outcome of the last run is written to ``.gitout-rclone.json`` in the destination.
+"""
+
+from __future__ import annotations
+
+import contextlib
+import json
+import logging
+from collections.abc import Awaitable, Callable
+from dataclasses import asdict, dataclass, field
+from datetime import datetime
+from pathlib import Path
+
+from gitout.config import Rclone
+
+log = logging.getLogger(__name__)
+
+RCLONE_REPORT_FILE = ".gitout-rclone.json"
+
+# (argv, cwd, timeout seconds) -> (exit code, combined output)
+RcloneRunner = Callable[[list[str], Path, float], Awaitable[tuple[int, str]]]
+
+
+@dataclass(frozen=True)
+class RcloneFailure:
+    path: str  # relative to the destination; "" for errors not about one file
+    message: str
+
+    def describe(self) -> str:
+        return f"{self.path}: {self.message}" if self.path else self.message
+
+
+@dataclass(frozen=True)
+class RcloneReport:
+    remote: str
+    exit_code: int
+    started_at: str
+    finished_at: str
+    failures: list[RcloneFailure] = field(default_factory=list)
+
+    @property
+    def ok(self) -> bool:
+        return self.exit_code == 0
+
+
+def rclone_argv(destination: Path, config: Rclone) -> list[str]:
+    argv = [config.binary, config.mode, str(destination), config.remote]
+    argv += ["--use-json-log", "--log-level", "ERROR", "--filter", "- *.tmp"]
+    for pattern in config.exclude:
+        argv += ["--filter", f"- {pattern}"]
+    for pattern in config.include:
+        argv += ["--filter", f"+ {pattern}"]
+    if config.include:
+        argv += ["--filter", "- **"]
+    return [*argv, *config.flags]
+
+
+def parse_failures(output: str) -> list[RcloneFailure]:
+    """The errors in rclone's JSON log; other lines count as one failure each."""
+    failures = []
+    for line in output.splitlines():
+        line = line.strip()
+        if not line:
+            continue
+        try:
+            entry = json.loads(line)
+        except ValueError:
+            failures.append(RcloneFailure("", line))
+            continue
+        if not isinstance(entry, dict) or entry.get("level") not in ("error", "critical"):
+            continue
+        path = str(entry.get("object") or "")
+        failures.append(RcloneFailure(path, str(entry.get("msg", "")).strip()))
+    return failures
+
+
+def _write_report(destination: Path, report: RcloneReport) -> None:
+    path = destination / RCLONE_REPORT_FILE
+    partial = path.with_name(path.name + ".tmp")
+    with contextlib.suppress(OSError):
+        partial.write_text(json.dumps({**asdict(report), "ok": report.ok}, indent=2))
+        partial.replace(path)
+
+
+async def replicate(
+    destination: Path,
+    config: Rclone,
+    run: RcloneRunner,
+    *,
+    now: Callable[[], datetime],
+) -> RcloneReport:
+    """Run rclone once and record the outcome; never raises for rclone failures."""
+    started = now().isoformat()
+    argv = rclone_argv(destination, config)
+    try:
+        code, output = await run(argv, destination, config.timeout_seconds)
+    except (OSError, RuntimeError) as exc:  # rclone missing, or timed out
+        code, output = -1, str(exc)
+    failures = parse_failures(output) if code != 0 else []
+    if code != 0 and not failures:
+        failures = [RcloneFailure("", f"rclone exited with {code}")]
+    report = RcloneReport(config.remote, code, started, now().isoformat(), failures)
+    _write_report(destination, report)
+    return report
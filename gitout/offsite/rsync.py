@@ -0,0 +1,61 @@
+"""rsync of the destination to SSH targets after a successful sync (``[replicate]``).
+
+Each target in ``replicate.targets`` gets ``rsync --archive --partial --delay-updates``
+of the destination's contents over ``ssh_command``, one target after another. Mirror
+packfiles are immutable and content-named, so rsync's size-and-time check only sends
+the packs (and refs) that changed since the last run; ``--delay-updates`` moves the
+updated files into place together at the end, so a reader of the target never sees a
+ref before the objects it needs. With ``delete`` packs removed by a repack disappear
+from the target as well. gitout's ``*.tmp`` files are never sent.
+"""
+
+from __future__ import annotations
+
+import logging
+from collections.abc import Awaitable, Callable
+from dataclasses import dataclass
+from pathlib import Path
+
+from gitout.config import Replicate
+
+log = logging.getLogger(__name__)
+
+# (argv, cwd, timeout seconds) -> (exit code, combined output)
+RsyncRunner = Callable[[list[str], Path, float], Awaitable[tuple[int, str]]]
+
+
+@dataclass(frozen=True)
+class ReplicationResult:
+    target: str
+    exit_code: int
+    output: str = ""
+
+    @property
+    def ok(self) -> bool:
+        return self.exit_code == 0
+
+
+def rsync_argv(destination: Path, target: str, config: Replicate) -> list[str]:
+    argv = [config.binary, "--archive", "--partial", "--delay-updates", "--exclude", "*.tmp"]
+    argv += ["-e", config.ssh_command]
+    if config.delete:
+        argv.append("--delete-delay")
+    if config.bandwidth_limit_kbps > 0:
+        argv.append(f"--bwlimit={config.bandwidth_limit_kbps}")
+    # The trailing slash copies the destination's contents, not the directory itself.
+    return [*argv, *config.flags, f"{destination}/", target]
+
+
+async def replicate_to_targets(
+    destination: Path, config: Replicate, run: RsyncRunner
+) -> list[ReplicationResult]:
+    """rsync to every target in order; a failing target does not stop the others."""
+    results = []
+    for target in config.targets:
+        argv = rsync_argv(destination, target, config)
+        try:
+            code, output = await run(argv, destination, config.timeout_seconds)
+        except (OSError, RuntimeError) as exc:  # rsync missing, or timed out
+            code, output = -1, str(exc)
+        results.append(ReplicationResult(target, code, output.strip()))
+    return results
@@ -0,0 +1,87 @@
+"""S3-compatible object storage (AWS S3, Backblaze B2, MinIO, ...) through boto3.
+
+boto3 is optional (``pip install 'gitout[s3]'``) and imported lazily. Credentials come
+from the SDK's default chain (``AWS_ACCESS_KEY_ID``/``AWS_SECRET_ACCESS_KEY``,
+``~/.aws``, instance roles) or the named ``profile``; ``endpoint_url`` points it at a
+non-AWS provider. Files above ``multipart_threshold_mb`` are sent as a multipart upload
+in ``multipart_chunk_mb`` parts, and every request is retried up to ``max_attempts``
+times with the SDK's standard backoff.
+"""
+
+from __future__ import annotations
+
+from pathlib import Path
+from typing import Any
+
+from gitout.config import S3Storage
+from gitout.offsite.backend import OffsiteError
+
+_MB = 1024 * 1024
+
+
+def _boto3() -> Any:
+    try:
+        import boto3
+    except ImportError as exc:
+        raise OffsiteError(
+            "The s3 offsite backend needs boto3 (pip install 'gitout[s3]')"
+        ) from exc
+    return boto3
+
+
+class S3Backend:
+    def __init__(self, config: S3Storage, *, client: Any = None, transfer: Any = None) -> None:
+        self._config = config
+        self._client = client
+        self._transfer = transfer
+
+    @property
+    def target(self) -> str:
+        return f"s3://{self._config.bucket}/{self._prefix}"
+
+    @property
+    def _prefix(self) -> str:
+        prefix = self._config.prefix.strip("/")
+        return f"{prefix}/" if prefix else ""
+
+    def _client_and_transfer(self) -> tuple[Any, Any]:
+        if self._client is None:
+            boto3 = _boto3()
+            from botocore.config import Config as BotoConfig
+
+            session = boto3.session.Session(profile_name=self._config.profile)
+            self._client = session.client(
+                "s3",
+                endpoint_url=self._config.endpoint_url,
+                region_name=self._config.region,
+                config=BotoConfig(
+                    retries={"max_attempts": self._config.max_attempts, "mode": "standard"}
+                ),
+            )
+        if self._transfer is None:
+            from boto3.s3.transfer import TransferConfig
+
+            self._transfer = TransferConfig(
+                multipart_threshold=self._config.multipart_threshold_mb * _MB,
+                multipart_chunksize=self._config.multipart_chunk_mb * _MB,
+            )
+        return self._client, self._transfer
+
+    def upload(self, source: Path, key: str) -> None:
+        try:
+            client, transfer = self._client_and_transfer()
+        except OffsiteError:
+            raise
+        except Exception as exc:  # e.g. botocore's ProfileNotFound for a missing profile
+            raise OffsiteError(f"Cannot create the S3 client for {self.target}: {exc}") from exc
+        extra = {"StorageClass": self._config.storage_class} if self._config.storage_class else {}
+        try:
+            client.upload_file(
+                str(source),
+                self._config.bucket,
+                self._prefix + key,
+                ExtraArgs=extra or None,
+                Config=transfer,
+            )
+        except Exception as exc:  # botocore raises its own hierarchy (ClientError, ...)
+            raise OffsiteError(f"Uploading {key} to {self.target} failed: {exc}") from exc
@@ -0,0 +1,125 @@
+"""Upload the destination's artifacts to the ``[offsite]`` backend after a sync.
+
+Everything under ``offsite.paths`` (by default the bundles, tarballs and migration
+archives) is uploaded under its destination-relative path; with ``mirrors = true`` the
+files of every bare mirror (see :func:`gitout.frontends.find_repositories`) are too, so
+a full repack is shipped as its new packs. ``.gitout-offsite.json`` in the destination
+remembers the size and modification time each file had when it was uploaded to each
+backend target, so unchanged files are not sent again. A failed upload is logged and
+retried on the next run; nothing is ever deleted remotely. Temporary ``*.tmp`` files
+are skipped.
+"""
+
+from __future__ import annotations
+
+import contextlib
+import json
+import logging
+import os
+from collections.abc import Iterator
+from dataclasses import dataclass
+from pathlib import Path
+
+from gitout.config import Offsite
+from gitout.frontends import find_repositories
+from gitout.offsite.backend import OffsiteError, StorageBackend
+
+log = logging.getLogger(__name__)
+
+OFFSITE_STATE_FILE = ".gitout-offsite.json"
+
+
+@dataclass(frozen=True)
+class UploadSummary:
+    target: str
+    uploaded: int = 0
+    unchanged: int = 0
+    failed: int = 0
+
+
+def _signature(path: Path) -> str:
+    stat = path.stat()
+    return f"{stat.st_size}:{stat.st_mtime_ns}"
+
+
+def _files_under(path: Path) -> Iterator[Path]:
+    if path.is_file():
+        yield path
+        return
+    for root, dirnames, filenames in os.walk(path):
+        dirnames.sort()
+        for name in sorted(filenames):
+            yield Path(root) / name
+
+
+def collect_files(destination: Path, offsite: Offsite) -> list[str]:
+    """Destination-relative POSIX paths of every file to upload, sorted."""
+    roots = [destination / path.strip() for path in offsite.paths]
+    if offsite.mirrors:
+        roots += [repo.path for repo in find_repositories(destination)]
+    found: set[str] = set()
+    for root in roots:
+        if not root.exists():
+            continue
+        for path in _files_under(root):
+            if path.name.endswith(".tmp") or not path.is_file():
+                continue
+            found.add(path.resolve().relative_to(destination.resolve()).as_posix())
+    return sorted(found)
+
+
+class OffsiteUploader:
+    def __init__(
+        self,
+        offsite: Offsite,
+        destination: Path,
+        backend: StorageBackend,
+        *,
+        state_path: Path | None = None,
+    ) -> None:
+        self._offsite = offsite
+        self._destination = destination
+        self._backend = backend
+        self._state_path = state_path or destination / OFFSITE_STATE_FILE
+
+    def _load_state(self) -> dict[str, dict[str, str]]:
+        try:
+            data = json.loads(self._state_path.read_text())
+            return {
+                str(target): {str(k): str(v) for k, v in files.items()}
+                for target, files in data.get("targets", {}).items()
+            }
+        except (OSError, ValueError, AttributeError):
+            return {}
+
+    def _save_state(self, state: dict[str, dict[str, str]]) -> None:
+        partial = self._state_path.with_name(self._state_path.name + ".tmp")
+        with contextlib.suppress(OSError):
+            partial.write_text(json.dumps({"targets": state}, indent=2, sort_keys=True))
+            partial.replace(self._state_path)
+
+    def upload(self) -> UploadSummary:
+        """Upload new and changed files; blocking (run it in a worker thread)."""
+        target = self._backend.target
+        state = self._load_state()
+        uploaded_files = state.setdefault(target, {})
+        uploaded = unchanged = failed = 0
+        for relative in collect_files(self._destination, self._offsite):
+            path = self._destination / relative
+            try:
+                signature = _signature(path)
+            except OSError:
+                continue  # removed since it was listed
+            if uploaded_files.get(relative) == signature:
+                unchanged += 1
+                continue
+            try:
+                self._backend.upload(path, relative)
+            except OffsiteError as exc:
+                log.warning("%s", exc)
+                failed += 1
+                continue
+            uploaded_files[relative] = signature
+            uploaded += 1
+        self._save_state(state)
+        return UploadSummary(target, uploaded=uploaded, unchanged=unchanged, failed=failed)
@@ -0,0 +1,111 @@
+"""WebDAV storage (Nextcloud, ownCloud, Hetzner Storage Box, Apache mod_dav, ...).
+
+Each file is sent with one ``PUT`` of its content (with a ``Content-Length``, as some
+servers reject chunked bodies) to ``<url>/<key>``; the collections on the way are
+created with ``MKCOL`` first, once per run. Network errors and ``429``/``5xx`` answers
+are retried up to ``max_attempts`` times with exponential backoff; any other error
+answer fails the file at once. The login is HTTP Basic with ``username`` and the
+password from ``password_env`` or ``password_file``.
+"""
+
+from __future__ import annotations
+
+import time
+from collections.abc import Callable, Mapping
+from pathlib import Path
+from urllib.parse import quote
+
+import httpx
+
+from gitout.config import WebDav
+from gitout.offsite.backend import OffsiteError
+
+_RETRY_STATUSES = frozenset({429, 500, 502, 503, 504})
+# MKCOL on an existing collection: 405 Method Not Allowed (or a 301 to its slash form).
+_EXISTING_COLLECTION = frozenset({301, 405})
+
+
+def webdav_password(config: WebDav, environ: Mapping[str, str]) -> str | None:
+    """The password of ``[offsite.webdav]``, or None without a password source."""
+    if config.password_env is not None:
+        password = environ.get(config.password_env, "").strip()
+        if not password:
+            raise OffsiteError(f"offsite.webdav: ${config.password_env} is not set")
+        return password
+    if config.password_file is None:
+        return None
+    path = Path(config.password_file).expanduser()
+    try:
+        return path.read_text().strip()
+    except OSError as exc:
+        raise OffsiteError(f"offsite.webdav: cannot read {path}: {exc.strerror}") from exc
+
+
+class WebDavBackend:
+    def __init__(
+        self,
+        config: WebDav,
+        password: str | None = None,
+        *,
+        client: httpx.Client | None = None,
+        sleep: Callable[[float], None] = time.sleep,
+    ) -> None:
+        self._config = config
+        self._base = config.url.rstrip("/")
+        auth = (config.username, password or "") if config.username else None
+        self._client = client or httpx.Client(auth=auth, timeout=config.timeout_seconds)
+        self._sleep = sleep
+        self._collections: set[str] = set()
+
+    @property
+    def target(self) -> str:
+        return self._base + "/"
+
+    def _url(self, path: str) -> str:
+        return f"{self._base}/{quote(path)}"
+
+    def _request(self, method: str, path: str, source: Path | None = None) -> httpx.Response:
+        attempts = max(self._config.max_attempts, 1)
+        for attempt in range(1, attempts + 1):
+            try:
+                if source is None:
+                    response = self._client.request(method, self._url(path))
+                else:
+                    with source.open("rb") as body:
+                        size = str(source.stat().st_size)
+                        response = self._client.request(
+                            method, self._url(path), content=body, headers={"Content-Length": size}
+                        )
+            except httpx.TransportError as exc:
+                if attempt == attempts:
+                    raise OffsiteError(f"{method} {self._url(path)} failed: {exc}") from exc
+            else:
+                if response.status_code not in _RETRY_STATUSES or attempt == attempts:
+                    return response
+            self._sleep(min(2.0 ** (attempt - 1), 60.0))
+        raise AssertionError("unreachable")
+
+    def _ensure_collections(self, key: str) -> None:
+        parts = key.split("/")[:-1]
+        for depth in range(1, len(parts) + 1):
+            collection = "/".join(parts[:depth])
+            if collection in self._collections:
+                continue
+            response = self._request("MKCOL", collection + "/")
+            status = response.status_code
+            if status >= 400 and status not in _EXISTING_COLLECTION:
+                reason = f"HTTP {status} {response.reason_phrase}"
+                raise OffsiteError(f"Creating {self._url(collection)} failed: {reason}")
+            self._collections.add(collection)
+
+    def upload(self, source: Path, key: str) -> None:
+        try:
+            self._ensure_collections(key)
+            response = self._request("PUT", key, source)
+        except OSError as exc:
+            raise OffsiteError(f"Uploading {key} failed: {exc}") from exc
+        if response.status_code >= 300:
+            raise OffsiteError(
+                f"Uploading {key} to {self.target} failed: "
+                f"HTTP {response.status_code} {response.reason_phrase}"
+            )
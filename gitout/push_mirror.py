@@ -0,0 +1,57 @@
+"""Pushing every mirror on to a secondary git server after it syncs (``[push_mirror]``).
+
+Each successfully synced mirror is pushed to ``push_mirror.url``, a template filled
+in per repository: ``{name}`` is the repository's name (``octocat/hello``, a
+``[git.repos]`` key, ...), ``{owner}`` and ``{repo}`` its parts around the last
+``/`` (``owner`` is empty for a name without one) and ``{path}`` the mirror's
+destination-relative path. The target repositories must exist (Gitea and Forgejo can
+create them on push with ``ENABLE_PUSH_CREATE_USER``/``_ORG``).
+
+Only ``refspecs`` are pushed (branches and tags by default; ``refs/pull/*`` and
+gitout's own ``refs/attic/*`` are left out, as forges reject or ignore them) with
+``--force``, so the secondary follows rewritten history; ``prune`` also deletes the
+branches and tags that are gone from the mirror. HTTP(S) logins come from
+``username`` with ``password_env`` or ``password_file`` through a temporary
+credential-store file; SSH URLs use ``[ssh]``.
+"""
+
+from __future__ import annotations
+
+from collections.abc import Mapping
+from pathlib import Path
+
+from gitout.config import PushMirror
+
+PUSH_URL_FIELDS = ("name", "owner", "repo", "path")
+
+
+def push_url(template: str, name: str, relative_path: str) -> str:
+    """``template`` for one repository (see the module docstring)."""
+    owner, _, repo = name.rpartition("/")
+    return template.format(name=name, owner=owner, repo=repo, path=relative_path)
+
+
+def push_argv(git: list[str], url: str, config: PushMirror) -> list[str]:
+    argv = [*git, "push", "--force", "--porcelain"]
+    if config.prune:
+        argv.append("--prune")
+    return [*argv, url, *config.refspecs]
+
+
+def push_password(config: PushMirror, environ: Mapping[str, str]) -> str:
+    """The HTTP(S) password of ``[push_mirror]``, from its variable or file (trimmed)."""
+    if config.password_env is not None:
+        password = environ.get(config.password_env, "").strip()
+        if not password:
+            raise ValueError(f"push_mirror: ${config.password_env} is not set")
+        return password
+    if config.password_file is None:
+        raise ValueError("push_mirror has a username but no password source")
+    path = Path(config.password_file).expanduser()
+    try:
+        password = path.read_text().strip()
+    except OSError as exc:
+        raise ValueError(f"push_mirror: cannot read {path}: {exc.strerror}") from exc
+    if not password:
+        raise ValueError(f"push_mirror: {path} is empty")
+    return password
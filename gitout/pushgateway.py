@@ -0,0 +1,97 @@
+"""Push a run's metrics to a Prometheus Pushgateway (``[metrics] pushgateway_url``).
+
+A one-shot ``gitout sync`` from cron exits before any scraper could see it, so after
+every real run :func:`render_metrics` turns the outcomes into the Prometheus text
+format and :class:`Pushgateway` PUTs them to ``{url}/metrics/job/{job}``, replacing the
+previous run's group:
+
+* ``gitout_sync_repositories{status="ok|failed|skipped"}`` — repositories per outcome;
+* ``gitout_sync_duration_seconds`` and ``gitout_sync_last_run_timestamp_seconds``;
+* ``gitout_sync_last_success_timestamp_seconds`` — only pushed when nothing failed, so
+  the gateway keeps the last good run's value (alert on its age);
+* ``gitout_repository_up{repository}`` (1 synced or skipped, 0 failed) and
+  ``gitout_repository_sync_duration_seconds{repository}`` per repository.
+
+A failed push is logged and swallowed; the httpx client is injectable for tests.
+"""
+
+from __future__ import annotations
+
+import logging
+from collections.abc import Sequence
+from datetime import datetime
+from typing import TYPE_CHECKING
+from urllib.parse import quote
+
+import httpx
+
+from gitout.reports import outcome_label
+
+if TYPE_CHECKING:
+    from gitout.engine import SyncOutcome
+
+log = logging.getLogger(__name__)
+
+
+def _label(value: str) -> str:
+    return value.replace("\\", "\\\\").replace('"', '\\"').replace("\n", "\\n")
+
+
+def render_metrics(
+    outcomes: Sequence[SyncOutcome], *, started_at: datetime, finished_at: datetime
+) -> str:
+    """The Prometheus text exposition of one run (timestamps must be UTC-aware)."""
+    counts = {"ok": 0, "failed": 0, "skipped": 0}
+    for outcome in outcomes:
+        counts[outcome_label(outcome)] += 1
+    lines = [
+        "# TYPE gitout_sync_repositories gauge",
+        *(f'gitout_sync_repositories{{status="{k}"}} {v}' for k, v in counts.items()),
+        "# TYPE gitout_sync_duration_seconds gauge",
+        f"gitout_sync_duration_seconds {(finished_at - started_at).total_seconds():.3f}",
+        "# TYPE gitout_sync_last_run_timestamp_seconds gauge",
+        f"gitout_sync_last_run_timestamp_seconds {finished_at.timestamp():.0f}",
+    ]
+    if all(outcome.ok for outcome in outcomes):
+        lines += [
+            "# TYPE gitout_sync_last_success_timestamp_seconds gauge",
+            f"gitout_sync_last_success_timestamp_seconds {finished_at.timestamp():.0f}",
+        ]
+    lines.append("# TYPE gitout_repository_up gauge")
+    lines += [
+        f'gitout_repository_up{{repository="{_label(o.task.identity)}"}} {int(o.ok)}'
+        for o in outcomes
+    ]
+    lines.append("# TYPE gitout_repository_sync_duration_seconds gauge")
+    lines += [
+        f'gitout_repository_sync_duration_seconds{{repository="{_label(o.task.identity)}"}} '
+        f"{o.duration_seconds:.3f}"
+        for o in outcomes
+    ]
+    return "\n".join(lines) + "\n"
+
+
+class Pushgateway:
+    def __init__(
+        self, url: str, job: str = "gitout", *, client: httpx.AsyncClient | None = None
+    ) -> None:
+        self.url = f"{url.rstrip('/')}/metrics/job/{quote(job, safe='')}"
+        self._client = client
+
+    async def push(self, metrics: str) -> bool:
+        """PUT ``metrics`` (replacing the job's group); False when the push failed."""
+        http = self._client or httpx.AsyncClient(timeout=30.0)
+        try:
+            response = await http.put(
+                self.url,
+                content=metrics.encode(),
+                headers={"Content-Type": "text/plain; version=0.0.4"},
+            )
+            response.raise_for_status()
+        except httpx.HTTPError as exc:
+            log.warning("Pushing metrics to %s failed: %s", self.url, exc)
+            return False
+        finally:
+            if self._client is None:
+                await http.aclose()
+        return True
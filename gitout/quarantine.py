@@ -0,0 +1,55 @@
+"""Setting corrupt mirrors aside so the next sync clones them afresh.
+
+A mirror whose object database is damaged fails every fetch the same way. With
+``fetch.reclone_corrupt`` the engine recognises such failures (:func:`is_corruption`),
+renames the mirror to ``<repo>.corrupt-<date>`` and clones it again in the same run;
+``gitout verify --quarantine`` does the rename for mirrors fsck rejects. The broken
+copy is kept, not deleted, in case it still holds objects worth recovering, and is
+skipped by the destination walkers (``gitout export``, ``gitout verify``).
+"""
+
+from __future__ import annotations
+
+import re
+from datetime import date
+from pathlib import Path
+
+QUARANTINE_MARKER = ".corrupt-"
+
+# Local object-database damage, as git reports it while fetching into a mirror.
+_CORRUPTION_PATTERNS = (
+    "is corrupt",
+    "bad object",
+    "object file",
+    "unable to read sha1 file",
+    "invalid sha1 pointer",
+    "does not match index",
+    "bad packed object",
+    "not a git repository",
+    "bad index file",
+    "missing blob",
+    "missing tree",
+    "missing commit",
+)
+_QUARANTINED_RE = re.compile(re.escape(QUARANTINE_MARKER) + r"\d{4}-\d{2}-\d{2}(?:-\d+)?$")
+
+
+def is_corruption(error_message: str) -> bool:
+    """Whether a failed git command blamed the local repository's objects or refs."""
+    lower = error_message.lower()
+    return any(pattern in lower for pattern in _CORRUPTION_PATTERNS)
+
+
+def is_quarantined(path: Path) -> bool:
+    return _QUARANTINED_RE.search(path.name) is not None
+
+
+def quarantine(repo: Path, today: date) -> Path:
+    """Rename ``repo`` to ``<repo>.corrupt-<date>`` (``-2``, ``-3``... on reuse)."""
+    target = repo.with_name(f"{repo.name}{QUARANTINE_MARKER}{today.isoformat()}")
+    suffix = 2
+    while target.exists():
+        target = repo.with_name(f"{repo.name}{QUARANTINE_MARKER}{today.isoformat()}-{suffix}")
+        suffix += 1
+    repo.rename(target)
+    return target
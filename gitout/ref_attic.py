@@ -0,0 +1,67 @@
+"""Refs deleted or rewritten upstream, kept under ``refs/attic/<date>/``.
+
+With ``fetch.attic`` an update first lists the remote's refs (``git ls-remote``) and
+the mirror's own (``git for-each-ref``). Every local ref the remote no longer has is
+copied to ``refs/attic/<YYYY-MM-DD>/<ref without refs/>`` and then deleted, which is
+what ``--prune`` would have done; the fetch itself then runs without ``--prune``, since
+a mirror's ``+refs/*:refs/*`` refspec would otherwise prune the attic too.
+
+With ``fetch.keep_rewritten`` the mirror's refs are listed before and after the fetch;
+a ref that moved to a commit not descending from its old tip (a force-push) keeps the
+old tip as ``refs/attic/<YYYY-MM-DD>/forced/<ref without refs/>``. gitout then prunes
+the same way, without the attic copies unless ``fetch.attic`` is also set.
+"""
+
+from __future__ import annotations
+
+ATTIC_NAMESPACE = "refs/attic/"
+
+
+def parse_refs(output: str) -> dict[str, str]:
+    """Ref -> SHA from ``ls-remote`` or ``for-each-ref`` output; HEAD and peeled tags skipped."""
+    refs: dict[str, str] = {}
+    for line in output.splitlines():
+        parts = line.split(maxsplit=1)
+        if len(parts) != 2:
+            continue
+        sha, ref = parts[0], parts[1].strip()
+        if ref.startswith("refs/") and not ref.endswith("^{}"):
+            refs[ref] = sha
+    return refs
+
+
+def pruned_refs(
+    local: dict[str, str], remote: dict[str, str], *, heads_only: bool = False
+) -> dict[str, str]:
+    """The local refs a prune would delete (the attic itself is never a candidate).
+
+    ``heads_only`` limits them to branches, as for single-branch mirrors whose fetch
+    only maps ``refs/heads/``.
+    """
+    namespace = "refs/heads/" if heads_only else "refs/"
+    return {
+        ref: sha
+        for ref, sha in sorted(local.items())
+        if ref.startswith(namespace)
+        and not ref.startswith(ATTIC_NAMESPACE)
+        and ref not in remote
+    }
+
+
+def moved_refs(before: dict[str, str], after: dict[str, str]) -> dict[str, tuple[str, str]]:
+    """Ref -> ``(old, new)`` SHA for the refs a fetch moved (outside the attic)."""
+    return {
+        ref: (old, after[ref])
+        for ref, old in sorted(before.items())
+        if ref in after and after[ref] != old and not ref.startswith(ATTIC_NAMESPACE)
+    }
+
+
+def attic_ref(ref: str, day: str) -> str:
+    """Where ``ref`` is kept when pruned on ``day`` (``YYYY-MM-DD``)."""
+    return f"{ATTIC_NAMESPACE}{day}/{ref.removeprefix('refs/')}"
+
+
+def forced_ref(ref: str, day: str) -> str:
+    """Where the old tip of ``ref`` is kept when it was force-pushed on ``day``."""
+    return f"{ATTIC_NAMESPACE}{day}/forced/{ref.removeprefix('refs/')}"
@@ -0,0 +1,214 @@
+"""Loading the configuration from a local file, an HTTPS URL, or a git repository.
+
+``sync`` accepts three kinds of CONFIG source:
+
+* a local path, read as-is;
+* ``https://host/gitout.toml``, fetched with ``If-None-Match`` revalidation;
+* ``git+<clone-url>#<path>[@<ref>]``, read from a bare clone (``ref`` defaults to the
+  remote's HEAD), e.g. ``git+https://git.example/fleet.git#boxes/nas.toml@main``.
+
+Remote sources are cached under ``$XDG_CACHE_HOME/gitout/config`` so a box keeps
+running on its last known configuration while the central server is unreachable.
+Content can be pinned with a SHA-256 digest, and git sources can require a valid
+signature on the commit (``git verify-commit``, using the local GnuPG/SSH trust).
+The HTTP client and the git runner are injectable for tests.
+"""
+
+from __future__ import annotations
+
+import hashlib
+import logging
+import subprocess
+from collections.abc import Callable, Mapping
+from pathlib import Path
+
+import httpx
+
+from gitout.git_exec import resolve_git_executable
+
+log = logging.getLogger(__name__)
+
+GIT_SOURCE_PREFIX = "git+"
+
+# argv -> stdout; raises RemoteConfigError when git fails
+GitRunner = Callable[[list[str]], str]
+
+
+class RemoteConfigError(ValueError):
+    """The configuration source could not be loaded or failed verification."""
+
+
+def is_remote(source: str) -> bool:
+    return source.startswith(("https://", GIT_SOURCE_PREFIX))
+
+
+def default_cache_dir(environ: Mapping[str, str]) -> Path:
+    base = environ.get("XDG_CACHE_HOME") or str(Path.home() / ".cache")
+    return Path(base) / "gitout" / "config"
+
+
+def parse_git_source(source: str) -> tuple[str, str, str]:
+    """Split ``git+<url>#<path>[@<ref>]`` into ``(url, path, ref)``."""
+    url, sep, location = source[len(GIT_SOURCE_PREFIX) :].partition("#")
+    path, _, ref = location.partition("@")
+    if not url or not sep or not path:
+        raise RemoteConfigError(
+            f"Invalid git config source {source!r} (expected git+<url>#<path>[@<ref>])"
+        )
+    return url, path.lstrip("/"), ref or "HEAD"
+
+
+def _default_git_runner(timeout_seconds: float) -> GitRunner:
+    def run(argv: list[str]) -> str:
+        try:
+            result = subprocess.run(  # noqa: S603
+                argv, capture_output=True, text=True, timeout=timeout_seconds, check=False
+            )
+        except (OSError, subprocess.TimeoutExpired) as exc:
+            raise RemoteConfigError(f"git failed: {exc}") from exc
+        if result.returncode != 0:
+            message = result.stderr.strip() or f"git exited with {result.returncode}"
+            raise RemoteConfigError(message)
+        return result.stdout
+
+    return run
+
+
+def verify_sha256(text: str, expected: str | None) -> None:
+    if expected is None:
+        return
+    actual = hashlib.sha256(text.encode()).hexdigest()
+    if actual != expected.strip().lower():
+        raise RemoteConfigError(
+            f"Configuration SHA-256 mismatch: expected {expected.strip().lower()}, got {actual}"
+        )
+
+
+class RemoteConfigLoader:
+    def __init__(
+        self,
+        cache_dir: Path,
+        *,
+        client: httpx.Client | None = None,
+        run_git: GitRunner | None = None,
+        timeout_seconds: float = 60.0,
+    ) -> None:
+        self._cache_dir = cache_dir
+        self._client = client
+        self._run_git = run_git or _default_git_runner(timeout_seconds)
+        self._timeout = timeout_seconds
+
+    def _cache_key(self, source: str) -> str:
+        return hashlib.sha256(source.encode()).hexdigest()[:16]
+
+    def load(
+        self, source: str, *, sha256: str | None = None, verify_signature: bool = False
+    ) -> str:
+        """Return the TOML text of a remote ``source`` after verification."""
+        self._cache_dir.mkdir(parents=True, exist_ok=True)
+        if source.startswith(GIT_SOURCE_PREFIX):
+            text = self._load_git(source, verify_signature)
+            verify_sha256(text, sha256)
+            return text
+        if verify_signature:
+            raise RemoteConfigError(
+                "Signature verification needs a git+ source; pin HTTPS configs with a SHA-256"
+            )
+        return self._load_https(source, sha256)
+
+    def _load_https(self, url: str, sha256: str | None) -> str:
+        key = self._cache_key(url)
+        cached = self._cache_dir / f"{key}.toml"
+        etag_file = self._cache_dir / f"{key}.etag"
+        headers: dict[str, str] = {}
+        if cached.is_file() and etag_file.is_file():
+            headers["If-None-Match"] = etag_file.read_text()
+
+        owned = self._client is None
+        http = self._client or httpx.Client(timeout=self._timeout)
+        try:
+            response = http.get(url, headers=headers, follow_redirects=True)
+            if response.status_code != 304:
+                response.raise_for_status()
+        except httpx.HTTPError as exc:
+            if not cached.is_file():
+                raise RemoteConfigError(f"Failed to fetch config from {url}: {exc}") from exc
+            log.warning("Failed to fetch config from %s (%s); using cached copy", url, exc)
+            text = cached.read_text()
+            verify_sha256(text, sha256)
+            return text
+        finally:
+            if owned:
+                http.close()
+
+        if response.status_code == 304:
+            text = cached.read_text()
+            verify_sha256(text, sha256)
+            return text
+        text = response.text
+        verify_sha256(text, sha256)  # never cache content that fails its pin
+        cached.write_text(text)
+        etag = response.headers.get("ETag")
+        if etag:
+            etag_file.write_text(etag)
+        else:
+            etag_file.unlink(missing_ok=True)
+        return text
+
+    def _load_git(self, source: str, verify_signature: bool) -> str:
+        url, path, ref = parse_git_source(source)
+        git = resolve_git_executable()
+        repo = self._cache_dir / f"{self._cache_key(url)}.git"
+        if not repo.is_dir():
+            self._run_git([git, "clone", "--bare", "--quiet", "--", url, str(repo)])
+        else:
+            try:
+                self._run_git(
+                    [
+                        git,
+                        "-C",
+                        str(repo),
+                        "fetch",
+                        "--quiet",
+                        "--prune",
+                        "origin",
+                        "+refs/heads/*:refs/heads/*",
+                        "+refs/tags/*:refs/tags/*",
+                    ]
+                )
+            except RemoteConfigError as exc:
+                log.warning("Failed to fetch config repo %s (%s); using cached clone", url, exc)
+
+        if verify_signature:
+            try:
+                self._run_git([git, "-C", str(repo), "verify-commit", f"{ref}^{{commit}}"])
+            except RemoteConfigError as exc:
+                raise RemoteConfigError(
+                    f"Config commit {ref} of {url} has no valid signature: {exc}"
+                ) from exc
+        try:
+            return self._run_git([git, "-C", str(repo), "show", f"{ref}:{path}"])
+        except RemoteConfigError as exc:
+            raise RemoteConfigError(f"Cannot read {path} at {ref} from {url}: {exc}") from exc
+
+
+def load_config_text(
+    source: str,
+    *,
+    environ: Mapping[str, str],
+    sha256: str | None = None,
+    verify_signature: bool = False,
+    loader: RemoteConfigLoader | None = None,
+) -> str:
+    """Read the configuration from a local path or a remote source."""
+    if not is_remote(source):
+        path = Path(source)
+        if not path.is_file():
+            raise RemoteConfigError(f"Config file not found: {source}")
+        if verify_signature:
+            raise RemoteConfigError("Signature verification needs a git+ config source")
+        text = path.read_text()
+        verify_sha256(text, sha256)
+        return text
+    loader = loader or RemoteConfigLoader(default_cache_dir(environ))
+    return loader.load(source, sha256=sha256, verify_signature=verify_signature)
@@ -0,0 +1,152 @@
+"""Per-cycle JSON run reports.
+
+When ``[reports] enabled = true`` every real sync writes one self-contained document to
+``<reports.directory>/report-<YYYYmmddTHHMMSSZ>.json``: the repositories attempted, each
+outcome with its duration, attempt count, error, on-disk size and the refs force-pushed
+upstream (``fetch.keep_rewritten``), and a summary with the totals. Only the
+newest ``reports.retention`` files are kept. ``sync --report PATH`` writes the same
+document to a fixed path as well, with or without ``[reports]``. The files are
+independent of gitout's own state so external jobs can collect and archive them as
+they please.
+"""
+
+from __future__ import annotations
+
+import json
+import os
+import re
+from collections.abc import Sequence
+from datetime import datetime
+from pathlib import Path
+from typing import TYPE_CHECKING, Any
+
+from gitout import __version__
+
+if TYPE_CHECKING:
+    from gitout.engine import SyncOutcome
+
+REPORT_FORMAT = "gitout-run-report"
+REPORT_VERSION = 1
+
+_FILENAME_FORMAT = "report-%Y%m%dT%H%M%SZ"
+# Names written with _FILENAME_FORMAT, and the unprefixed ones of earlier versions.
+_REPORT_NAME_RE = re.compile(r"^(report-)?\d{8}T\d{6}Z\.json$")
+
+
+def directory_size(path: Path) -> int | None:
+    """Total size in bytes of the files under ``path``; None when it does not exist."""
+    if not path.exists():
+        return None
+    total = 0
+    for root, _, files in os.walk(path):
+        for name in files:
+            try:
+                total += os.lstat(os.path.join(root, name)).st_size
+            except OSError:
+                continue
+    return total
+
+
+def outcome_label(outcome: SyncOutcome) -> str:
+    """``ok``, ``failed`` or ``skipped``."""
+    if outcome.skipped:
+        return "skipped"
+    return "ok" if outcome.ok else "failed"
+
+
+def build_report(
+    outcomes: Sequence[SyncOutcome], *, started_at: datetime, finished_at: datetime
+) -> dict[str, Any]:
+    """Assemble the report document for one cycle (timestamps must be UTC-aware)."""
+    repositories = [
+        {
+            "name": o.task.name,
+            "identity": o.task.identity,
+            "groups": list(o.task.groups),
+            "url": o.task.url,
+            "destination": str(o.task.destination),
+            "outcome": outcome_label(o),
+            "durationSeconds": round(o.duration_seconds, 3),
+            "attempts": o.attempts,
+            "error": o.error,
+            "category": o.category.value if o.category is not None else None,
+            "sizeBytes": directory_size(o.task.destination),
+            "rewrittenRefs": list(o.rewritten_refs),
+        }
+        for o in outcomes
+    ]
+    labels = [r["outcome"] for r in repositories]
+    groups: dict[str, dict[str, int]] = {}
+    for entry in repositories:
+        for group in entry["groups"]:
+            counts = groups.setdefault(group, {"attempted": 0, "succeeded": 0, "failed": 0})
+            counts["attempted"] += 1
+            if entry["outcome"] == "ok":
+                counts["succeeded"] += 1
+            elif entry["outcome"] == "failed":
+                counts["failed"] += 1
+    return {
+        "format": REPORT_FORMAT,
+        "version": REPORT_VERSION,
+        "gitoutVersion": __version__,
+        "startedAt": started_at.isoformat(),
+        "finishedAt": finished_at.isoformat(),
+        "durationSeconds": round((finished_at - started_at).total_seconds(), 3),
+        "summary": {
+            "attempted": len(repositories),
+            "succeeded": labels.count("ok"),
+            "failed": labels.count("failed"),
+            "skipped": labels.count("skipped"),
+            "sizeBytes": sum(r["sizeBytes"] or 0 for r in repositories),
+            "rewrittenRefs": sum(len(r["rewrittenRefs"]) for r in repositories),
+        },
+        "groups": dict(sorted(groups.items())),
+        "repositories": repositories,
+    }
+
+
+def write_report(
+    report: dict[str, Any], directory: Path, *, retention: int, keep: Path | None = None
+) -> Path:
+    """Write ``report`` named after its start time, then prune beyond ``retention``.
+
+    The file is written to a temporary name first so collectors never see a partial
+    report.
+    """
+    started_at = datetime.fromisoformat(report["startedAt"])
+    target = directory / f"{started_at.strftime(_FILENAME_FORMAT)}.json"
+    write_report_file(report, target)
+    prune_reports(directory, retention, keep=keep)
+    return target
+
+
+def write_report_file(report: dict[str, Any], path: Path) -> Path:
+    """Write ``report`` to ``path`` (replacing it) via a temporary sibling file."""
+    path.parent.mkdir(parents=True, exist_ok=True)
+    partial = path.with_name(path.name + ".tmp")
+    partial.write_text(json.dumps(report, indent=2))
+    partial.replace(path)
+    return path
+
+
+def prune_reports(directory: Path, retention: int, *, keep: Path | None = None) -> list[Path]:
+    """Delete all but the newest ``retention`` reports; returns the deleted paths.
+
+    ``keep`` (the ``sync --report`` path) is never counted or deleted, even when it is
+    inside ``directory`` under a report name.
+
+    Only report names count (``report-<timestamp>.json``); other files, such as a
+    state file when the directory is the destination, are left alone. Reports from
+    before the ``report-`` prefix (``<timestamp>.json``) sort first and so are pruned
+    before any current one.
+    """
+    kept = keep.resolve() if keep is not None else None
+    reports = sorted(
+        p
+        for p in directory.iterdir()
+        if _REPORT_NAME_RE.match(p.name) and p.is_file() and p.resolve() != kept
+    )
+    stale = reports[: max(len(reports) - retention, 0)]
+    for path in stale:
+        path.unlink(missing_ok=True)
+    return stale
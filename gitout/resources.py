@@ -0,0 +1,115 @@
+"""Process resource checks for long-running (``--cron``) mode.
+
+Each scheduled cycle builds its engine, clients and repository lists from scratch and
+drops them afterwards; ``end_cycle`` then forces a garbage collection and logs the
+resident set size and open file-descriptor count so that growth across cycles is
+visible. With a ``max_rss`` budget a warning is logged whenever the process exceeds
+it, and a warning is logged whenever the open descriptors pass ``FD_WARN_FRACTION`` of
+the ``RLIMIT_NOFILE`` soft limit, before a leak makes clones fail with "Too many open
+files". Readers are injectable for tests; each returns None where the platform offers
+no cheap way to measure.
+"""
+
+from __future__ import annotations
+
+import gc
+import logging
+import os
+import re
+from collections.abc import Callable
+
+log = logging.getLogger(__name__)
+
+_SIZE_RE = re.compile(r"^\s*(\d+(?:\.\d+)?)\s*([kmgt]?)i?b?\s*$", re.IGNORECASE)
+_SIZE_UNITS = {"": 1, "k": 1024, "m": 1024**2, "g": 1024**3, "t": 1024**4}
+# Share of the open-file limit in use after a cycle that is worth a warning.
+FD_WARN_FRACTION = 0.8
+
+
+def parse_size(text: str) -> int:
+    """Parse a byte size such as ``"512M"``, ``"1.5G"``, ``"800MiB"`` or ``"1048576"``."""
+    match = _SIZE_RE.match(text)
+    if match is None:
+        raise ValueError(f"Invalid size: {text!r} (expected e.g. 512M or 2G)")
+    number, unit = match.groups()
+    size = int(float(number) * _SIZE_UNITS[unit.lower()])
+    if size <= 0:
+        raise ValueError(f"Size must be positive: {text!r}")
+    return size
+
+
+def format_size(size: int) -> str:
+    """Render a byte count for humans: ``"512 B"``, ``"1.5 KiB"``, ``"2.0 GiB"``."""
+    value = float(size)
+    for unit in ("B", "KiB", "MiB", "GiB"):
+        if value < 1024:
+            return f"{value:.0f} {unit}" if unit == "B" else f"{value:.1f} {unit}"
+        value /= 1024
+    return f"{value:.1f} TiB"
+
+
+def current_rss_bytes() -> int | None:
+    """Current resident set size, from ``/proc/self/statm`` (Linux only)."""
+    try:
+        with open("/proc/self/statm") as handle:
+            resident_pages = int(handle.read().split()[1])
+    except (OSError, IndexError, ValueError):
+        return None
+    return resident_pages * os.sysconf("SC_PAGE_SIZE")
+
+
+def open_fd_count() -> int | None:
+    """Number of open file descriptors, from ``/proc/self/fd`` or ``/dev/fd``."""
+    for directory in ("/proc/self/fd", "/dev/fd"):
+        try:
+            return len(os.listdir(directory))
+        except OSError:
+            continue
+    return None
+
+
+def fd_limit() -> int | None:
+    """The soft ``RLIMIT_NOFILE`` limit; None when unlimited or not on Unix."""
+    try:
+        import resource
+    except ImportError:
+        return None
+    soft, _ = resource.getrlimit(resource.RLIMIT_NOFILE)
+    return None if soft == resource.RLIM_INFINITY else soft
+
+
+def end_cycle(
+    max_rss: int | None = None,
+    *,
+    read_rss: Callable[[], int | None] = current_rss_bytes,
+    count_fds: Callable[[], int | None] = open_fd_count,
+    read_fd_limit: Callable[[], int | None] = fd_limit,
+) -> bool:
+    """Release the finished cycle's garbage and check the RSS and descriptor budgets.
+
+    Returns False when ``max_rss`` is set and the measured RSS exceeds it, or when the
+    open descriptors pass ``FD_WARN_FRACTION`` of the open-file limit.
+    """
+    gc.collect()
+    rss = read_rss()
+    fds = count_fds()
+    log.debug("End of cycle: rss=%s bytes, open fds=%s", rss, fds)
+    limit = read_fd_limit() if fds is not None else None
+    within = True
+    if fds is not None and limit is not None and fds > limit * FD_WARN_FRACTION:
+        log.warning(
+            "%d of %d allowed file descriptors are open after the cycle; "
+            "they are leaking or the limit (ulimit -n) is too low",
+            fds,
+            limit,
+        )
+        within = False
+    if max_rss is not None and rss is not None and rss > max_rss:
+        log.warning(
+            "Resident memory %.1f MiB exceeds --max-rss %.1f MiB (open fds: %s)",
+            rss / 1024**2,
+            max_rss / 1024**2,
+            fds if fds is not None else "unknown",
+        )
+        return False
+    return within
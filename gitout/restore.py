@@ -0,0 +1,183 @@
+"""Pushing a backed-up repository to a new remote (``gitout restore SOURCE URL``).
+
+``SOURCE`` may be any of the forms gitout keeps a repository in:
+
+* a bare mirror (a directory with ``HEAD`` and ``objects/``), pushed as it is;
+* a bundle (``*.bundle``, from ``[bundle]`` or a snapshot), fetched into a scratch
+  bare repository first;
+* an incremental bundle chain (a ``*.bundles`` directory with ``manifest.json``): its
+  bundles are fetched in order and the refs set to those of the last manifest entry;
+* a GitHub migration archive (``.tar.gz`` or ``.zip``): the repository under
+  ``repositories/<owner>/<repo>.git`` is extracted (``repository`` picks one when the
+  archive holds several; wikis are skipped unless named).
+
+Branches and tags are pushed (``all_refs`` pushes every ref with ``--mirror``
+instead); nothing is forced unless ``force``. Scratch repositories live in a
+temporary directory that is removed afterwards. The git runner is injectable.
+"""
+
+from __future__ import annotations
+
+import tarfile
+import tempfile
+import zipfile
+from dataclasses import dataclass
+from pathlib import Path, PurePosixPath
+
+from gitout.bundle import BUNDLE_MANIFEST, load_chain
+from gitout.engine import GitRunner, default_git_runner, local_git
+from gitout.ref_attic import parse_refs
+
+RESTORE_REFSPECS = ("refs/heads/*:refs/heads/*", "refs/tags/*:refs/tags/*")
+
+
+class RestoreError(RuntimeError):
+    """The source cannot be read or the push failed."""
+
+
+@dataclass(frozen=True)
+class RestoreResult:
+    kind: str  # "mirror", "bundle", "chain" or "archive"
+    branches: int
+    tags: int
+
+
+def source_kind(source: Path) -> str:
+    if source.is_dir() and (source / "HEAD").is_file() and (source / "objects").is_dir():
+        return "mirror"
+    if source.is_dir() and (source / BUNDLE_MANIFEST).is_file():
+        return "chain"
+    if source.is_file() and source.name.endswith((".tar.gz", ".tgz", ".zip")):
+        return "archive"
+    if source.is_file():
+        return "bundle"
+    raise RestoreError(f"{source} is not a mirror, bundle, bundle chain or migration archive")
+
+
+def _archive_members(archive: Path) -> list[str]:
+    if archive.name.endswith(".zip"):
+        with zipfile.ZipFile(archive) as zipped:
+            return zipped.namelist()
+    with tarfile.open(archive) as tarred:
+        return tarred.getnames()
+
+
+def _archive_repositories(members: list[str]) -> list[str]:
+    """``owner/repo.git`` of each repository in a migration archive, sorted."""
+    found = set()
+    for member in members:
+        parts = PurePosixPath(member).parts
+        if len(parts) >= 3 and parts[0] == "repositories" and parts[2].endswith(".git"):
+            found.add(f"{parts[1]}/{parts[2]}")
+    return sorted(found)
+
+
+def _extract_repository(archive: Path, scratch: Path, repository: str | None) -> Path:
+    try:
+        return _extract(archive, scratch, repository)
+    except (tarfile.TarError, zipfile.BadZipFile, OSError) as exc:
+        raise RestoreError(f"Cannot read {archive.name}: {exc}") from exc
+
+
+def _extract(archive: Path, scratch: Path, repository: str | None) -> Path:
+    available = _archive_repositories(_archive_members(archive))
+    if repository is not None:
+        wanted = repository.removesuffix(".git") + ".git"
+        chosen = [name for name in available if name == wanted]
+    else:
+        chosen = [name for name in available if not name.endswith(".wiki.git")]
+    if len(chosen) != 1:
+        listing = ", ".join(name.removesuffix(".git") for name in available) or "none"
+        raise RestoreError(f"Choose one repository of {archive.name} with --repository: {listing}")
+    prefix = f"repositories/{chosen[0]}/"
+    if archive.name.endswith(".zip"):
+        with zipfile.ZipFile(archive) as zipped:
+            zipped.extractall(scratch, [m for m in zipped.namelist() if m.startswith(prefix)])
+    else:
+        with tarfile.open(archive) as tarred:
+            members = [m for m in tarred.getmembers() if m.name.startswith(prefix)]
+            tarred.extractall(scratch, members, filter="data")
+    return scratch / "repositories" / chosen[0]
+
+
+class Restorer:
+    def __init__(
+        self, *, git_runner: GitRunner = default_git_runner, timeout_seconds: float = 3600.0
+    ) -> None:
+        self._run_git = git_runner
+        self._timeout = timeout_seconds
+
+    async def _git(self, cwd: Path, *args: str) -> str:
+        try:
+            code, output = await self._run_git(local_git(*args), cwd, self._timeout)
+        except (OSError, RuntimeError) as exc:
+            raise RestoreError(str(exc)) from exc
+        if code != 0:
+            raise RestoreError(f"git {args[0]} failed: {output.strip()}")
+        return output
+
+    async def _refs(self, repo: Path) -> dict[str, str]:
+        output = await self._git(repo, "for-each-ref", "--format=%(objectname) %(refname)")
+        return parse_refs(output)
+
+    async def _scratch_repository(self, scratch: Path) -> Path:
+        repo = scratch / "restore.git"
+        await self._git(scratch, "init", "--quiet", "--bare", str(repo))
+        return repo
+
+    async def _from_bundles(self, repo: Path, bundles: list[Path]) -> None:
+        for bundle in bundles:
+            await self._git(repo, "fetch", "--quiet", str(bundle), "+refs/*:refs/*")
+
+    async def _from_chain(self, repo: Path, chain_dir: Path) -> None:
+        chain = load_chain(chain_dir)
+        if not chain:
+            raise RestoreError(f"{chain_dir / BUNDLE_MANIFEST} lists no bundles")
+        await self._from_bundles(repo, [chain_dir / e.file for e in chain if e.file is not None])
+        final = chain[-1].refs
+        for ref in (await self._refs(repo)).keys() - final.keys():
+            await self._git(repo, "update-ref", "-d", ref)
+        for ref, sha in final.items():
+            await self._git(repo, "update-ref", ref, sha)
+
+    async def _prepare(
+        self, source: Path, kind: str, scratch: Path, repository: str | None
+    ) -> Path:
+        if kind == "mirror":
+            return source
+        if kind == "archive":
+            return _extract_repository(source, scratch, repository)
+        repo = await self._scratch_repository(scratch)
+        if kind == "chain":
+            await self._from_chain(repo, source)
+        else:
+            await self._from_bundles(repo, [source])
+        return repo
+
+    async def restore(
+        self,
+        source: Path,
+        url: str,
+        *,
+        repository: str | None = None,
+        all_refs: bool = False,
+        force: bool = False,
+        dry_run: bool = False,
+    ) -> RestoreResult:
+        """Push ``source`` to ``url``; with ``dry_run`` only count what would be pushed."""
+        kind = source_kind(source)
+        with tempfile.TemporaryDirectory(prefix="gitout-restore-") as tmp:
+            repo = await self._prepare(source.resolve(), kind, Path(tmp), repository)
+            refs = await self._refs(repo)
+            if not dry_run:
+                push = ["push", "--porcelain"]
+                if force:
+                    push.append("--force")
+                if all_refs:
+                    push += ["--mirror", url]
+                else:
+                    push += [url, *RESTORE_REFSPECS]
+                await self._git(repo, *push)
+        branches = sum(1 for ref in refs if ref.startswith("refs/heads/"))
+        tags = sum(1 for ref in refs if ref.startswith("refs/tags/"))
+        return RestoreResult(kind, branches, tags)
@@ -0,0 +1,259 @@
+"""Read-only smart-HTTP server over a backup destination (``gitout serve``).
+
+Every bare repository under the destination can be cloned and fetched over HTTP, e.g.
+``git clone http://backup.lan:8080/github/clone/octocat/hello``, so machines on the
+LAN keep working from the backup while the upstream host is down. Requests are handed
+to ``git http-backend`` (as CGI) with the destination as ``GIT_PROJECT_ROOT``; pushes
+(``git-receive-pack``) are refused before git sees them and receive-pack is disabled
+in the backend as well.
+
+``token`` requires ``Authorization: Bearer <token>`` or HTTP basic auth with the token
+as password (``http://gitout:<token>@host/...``; the user name is ignored). ``prefix``
+serves the tree below a path such as ``/git`` for a reverse proxy. Each request runs
+in its own thread; the response is streamed from git, not buffered.
+"""
+
+from __future__ import annotations
+
+import base64
+import binascii
+import contextlib
+import hmac
+import logging
+import os
+import shutil
+import socket
+import subprocess
+import tempfile
+import threading
+from collections.abc import Mapping
+from http.server import BaseHTTPRequestHandler, ThreadingHTTPServer
+from pathlib import Path
+from typing import IO
+from urllib.parse import parse_qs, unquote, urlsplit
+
+from gitout.git_exec import resolve_git_executable
+
+log = logging.getLogger(__name__)
+
+DEFAULT_BIND = "127.0.0.1:8080"
+RECEIVE_PACK = "git-receive-pack"
+_REALM = 'Basic realm="gitout"'
+
+
+def parse_bind(bind: str) -> tuple[str, int]:
+    """``HOST:PORT`` (``[::]:8080`` for IPv6) as a ``(host, port)`` pair."""
+    host, sep, port = bind.rpartition(":")
+    if not sep or not port.isdigit() or not 0 <= int(port) <= 65535:
+        raise ValueError(f"Invalid bind address {bind!r}: expected HOST:PORT")
+    host = host.removeprefix("[").removesuffix("]")
+    return host or "0.0.0.0", int(port)
+
+
+def normalize_prefix(prefix: str) -> str:
+    """``"git/"`` -> ``"/git"``; an empty prefix serves the tree at ``/``."""
+    stripped = prefix.strip("/")
+    return f"/{stripped}" if stripped else ""
+
+
+def authorized(header: str | None, token: str | None) -> bool:
+    """Whether an ``Authorization`` header carries ``token`` (always true without one)."""
+    if token is None:
+        return True
+    if header is None:
+        return False
+    scheme, _, value = header.partition(" ")
+    if scheme.lower() == "bearer":
+        offered = value.strip()
+    elif scheme.lower() == "basic":
+        try:
+            offered = base64.b64decode(value.strip()).decode().partition(":")[2]
+        except (binascii.Error, UnicodeDecodeError):
+            return False
+    else:
+        return False
+    return hmac.compare_digest(offered.encode(), token.encode())
+
+
+def backend_environment(
+    destination: Path,
+    *,
+    method: str,
+    path_info: str,
+    query: str,
+    headers: Mapping[str, str],
+    content_length: int | None,
+    remote_addr: str,
+    environ: Mapping[str, str],
+) -> dict[str, str]:
+    """The CGI environment ``git http-backend`` runs a request in."""
+    env = {
+        **environ,
+        "GIT_PROJECT_ROOT": str(destination),
+        "GIT_HTTP_EXPORT_ALL": "1",
+        "REQUEST_METHOD": method,
+        "PATH_INFO": path_info,
+        "QUERY_STRING": query,
+        "REMOTE_ADDR": remote_addr,
+        "CONTENT_TYPE": headers.get("Content-Type", ""),
+    }
+    if content_length is not None:
+        env["CONTENT_LENGTH"] = str(content_length)
+    if headers.get("Git-Protocol"):
+        env["GIT_PROTOCOL"] = headers["Git-Protocol"]
+    if headers.get("Content-Encoding"):
+        env["HTTP_CONTENT_ENCODING"] = headers["Content-Encoding"]
+    return env
+
+
+def _read_chunked(stream: IO[bytes]) -> bytes:
+    body = bytearray()
+    while True:
+        size = int(stream.readline().split(b";", 1)[0].strip() or b"0", 16)
+        if size == 0:
+            while stream.readline().strip():  # trailers
+                pass
+            return bytes(body)
+        body += stream.read(size)
+        stream.readline()
+
+
+class GitHttpServer(ThreadingHTTPServer):
+    daemon_threads = True
+
+    def __init__(
+        self,
+        address: tuple[str, int],
+        destination: Path,
+        *,
+        token: str | None = None,
+        prefix: str = "",
+        environ: Mapping[str, str] | None = None,
+    ) -> None:
+        if ":" in address[0]:
+            self.address_family = socket.AF_INET6
+        self.destination = destination.resolve()
+        self.token = token
+        self.prefix = normalize_prefix(prefix)
+        self.environ = dict(os.environ if environ is None else environ)
+        super().__init__(address, GitHttpHandler)
+
+    @property
+    def url(self) -> str:
+        host, port = self.server_address[:2]
+        host = f"[{host}]" if ":" in str(host) else str(host)
+        return f"http://{host}:{port}{self.prefix}/"
+
+
+class GitHttpHandler(BaseHTTPRequestHandler):
+    server: GitHttpServer
+    server_version = "gitout"
+
+    def log_message(self, format: str, *args: object) -> None:
+        log.info("%s %s", self.address_string(), format % args)
+
+    def do_GET(self) -> None:
+        self._handle()
+
+    def do_POST(self) -> None:
+        self._handle()
+
+    def _refuse(self, status: int, message: str, *, challenge: bool = False) -> None:
+        body = f"{message}\n".encode()
+        self.send_response(status)
+        if challenge:
+            self.send_header("WWW-Authenticate", _REALM)
+        self.send_header("Content-Type", "text/plain; charset=utf-8")
+        self.send_header("Content-Length", str(len(body)))
+        self.end_headers()
+        self.wfile.write(body)
+
+    def _handle(self) -> None:
+        if not authorized(self.headers.get("Authorization"), self.server.token):
+            self._refuse(401, "Authentication required", challenge=True)
+            return
+        url = urlsplit(self.path)
+        path = unquote(url.path)
+        prefix = self.server.prefix
+        if prefix and path != prefix and not path.startswith(prefix + "/"):
+            self._refuse(404, "Not found")
+            return
+        path_info = path[len(prefix) :] or "/"
+        services = parse_qs(url.query).get("service", [])
+        if path_info.endswith("/" + RECEIVE_PACK) or RECEIVE_PACK in services:
+            self._refuse(403, "This server is read-only")
+            return
+        if ".." in path_info.split("/"):
+            self._refuse(404, "Not found")
+            return
+        body = b""
+        length = None
+        if self.command == "POST":
+            if "chunked" in self.headers.get("Transfer-Encoding", "").lower():
+                body = _read_chunked(self.rfile)
+            else:
+                body = self.rfile.read(int(self.headers.get("Content-Length") or 0))
+            length = len(body)
+        env = backend_environment(
+            self.server.destination,
+            method=self.command,
+            path_info=path_info,
+            query=url.query,
+            headers={name.title(): value for name, value in self.headers.items()},
+            content_length=length,
+            remote_addr=self.client_address[0],
+            environ=self.server.environ,
+        )
+        self._run_backend(env, body)
+
+    def _run_backend(self, env: dict[str, str], body: bytes) -> None:
+        argv = [
+            resolve_git_executable(),
+            "-c",
+            "safe.directory=*",
+            "-c",
+            "http.receivepack=false",
+            "http-backend",
+        ]
+        with tempfile.TemporaryFile() as errors:
+            process = subprocess.Popen(  # noqa: S603
+                argv, stdin=subprocess.PIPE, stdout=subprocess.PIPE, stderr=errors, env=env
+            )
+            assert process.stdin is not None and process.stdout is not None
+            feeder = threading.Thread(target=_feed, args=(process.stdin, body), daemon=True)
+            feeder.start()
+            try:
+                self._relay(process.stdout)
+            except OSError as exc:  # the client went away
+                log.debug("Aborted response to %s: %s", self.address_string(), exc)
+                process.kill()
+            finally:
+                process.stdout.close()
+                feeder.join()
+                process.wait()
+            if process.returncode != 0:
+                errors.seek(0)
+                log.warning("git http-backend failed: %s", errors.read().decode().strip())
+
+    def _relay(self, stdout: IO[bytes]) -> None:
+        status = 200
+        headers: list[tuple[str, str]] = []
+        while line := stdout.readline():
+            line = line.rstrip(b"\r\n")
+            if not line:
+                break
+            name, _, value = line.decode("latin-1").partition(":")
+            if name.lower() == "status":
+                status = int(value.split()[0])
+            else:
+                headers.append((name, value.strip()))
+        self.send_response(status)
+        for name, value in headers:
+            self.send_header(name, value)
+        self.end_headers()
+        shutil.copyfileobj(stdout, self.wfile)
+
+
+def _feed(stdin: IO[bytes], body: bytes) -> None:
+    with contextlib.suppress(OSError), stdin:
+        stdin.write(body)
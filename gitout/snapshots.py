@@ -0,0 +1,198 @@
+"""Point-in-time snapshots of the destination with grandfather-father-son retention.
+
+A mirror follows upstream, so a branch deleted or force-pushed there (by mistake or
+by an attacker) is soon gone from the backup as well. With ``[snapshots]`` enabled
+the engine, after a sync, takes a snapshot when the newest one is at least
+``interval_hours`` old, into ``<snapshots.directory>/<UTC time>/``:
+
+* ``mode = "bundle"``: ``<mirror path>.bundle`` for every mirror (``git bundle create
+  --all``, see :mod:`gitout.bundle`);
+* ``mode = "hardlink"`` (rsnapshot-style): the whole destination tree, hard-linked
+  rather than copied where that is safe. git never changes an object file once
+  written, so packs, their indexes and loose objects are linked to the live ones;
+  any other file is linked to the previous snapshot's copy when its size and
+  modification time are unchanged, and copied otherwise. Unchanged packfiles thus
+  cost no space however many snapshots keep them, and a pack removed by a repack
+  lives on in the snapshots until they expire. Files that cannot be linked (another
+  file system) are copied.
+
+A snapshot is written under a ``.partial`` name and renamed once complete, so an
+interrupted one is never mistaken for a finished one (and is removed by the next).
+
+Retention then keeps the newest snapshot of each of the last ``keep_daily`` days,
+``keep_weekly`` ISO weeks, ``keep_monthly`` months and ``keep_yearly`` years (UTC),
+plus the newest snapshot overall, and deletes the rest.
+"""
+
+from __future__ import annotations
+
+import asyncio
+import logging
+import os
+import re
+import shutil
+from collections.abc import Callable
+from datetime import datetime, timedelta
+from pathlib import Path
+
+from gitout.bundle import BundleGitRunner, bundle_path, write_bundle
+from gitout.config import Snapshots
+from gitout.frontends import find_repositories
+
+log = logging.getLogger(__name__)
+
+SNAPSHOT_NAME_FORMAT = "%Y-%m-%dT%H%M%SZ"
+PARTIAL_SUFFIX = ".partial"
+# Files git writes once and never modifies: packs with their companions, loose objects.
+_IMMUTABLE_RE = re.compile(
+    r"(?:^|/)objects/(?:pack/pack-[0-9a-f]+\.(?:pack|idx|rev|bitmap)|[0-9a-f]{2}/[0-9a-f]{38,62})$"
+)
+
+
+def list_snapshots(root: Path) -> list[tuple[datetime, Path]]:
+    """Complete snapshots under ``root``, oldest first."""
+    if not root.is_dir():
+        return []
+    found = []
+    for path in root.iterdir():
+        try:
+            stamp = datetime.strptime(path.name, SNAPSHOT_NAME_FORMAT)
+        except ValueError:
+            continue
+        if path.is_dir():
+            found.append((stamp, path))
+    return sorted(found)
+
+
+def snapshot_due(root: Path, now: datetime, interval_hours: int) -> bool:
+    snapshots = list_snapshots(root)
+    if not snapshots:
+        return True
+    newest = snapshots[-1][0]
+    return now.replace(tzinfo=None) - newest >= timedelta(hours=interval_hours)
+
+
+def _buckets(
+    period: Callable[[datetime], object], stamps: list[datetime], count: int
+) -> set[datetime]:
+    """The newest stamp of each of the ``count`` newest periods among ``stamps``."""
+    kept: dict[object, datetime] = {}
+    for stamp in sorted(stamps, reverse=True):
+        key = period(stamp)
+        if key not in kept:
+            if len(kept) == count:
+                break
+            kept[key] = stamp
+    return set(kept.values())
+
+
+def select_kept(stamps: list[datetime], policy: Snapshots) -> set[datetime]:
+    """The snapshot times ``policy`` keeps; the newest one always stays."""
+    if not stamps:
+        return set()
+    kept = {max(stamps)}
+    kept |= _buckets(lambda s: s.date(), stamps, policy.keep_daily)
+    kept |= _buckets(lambda s: s.isocalendar()[:2], stamps, policy.keep_weekly)
+    kept |= _buckets(lambda s: (s.year, s.month), stamps, policy.keep_monthly)
+    kept |= _buckets(lambda s: s.year, stamps, policy.keep_yearly)
+    return kept
+
+
+def prune_snapshots(root: Path, policy: Snapshots) -> list[Path]:
+    """Delete the snapshots (and leftover partial ones) ``policy`` does not keep."""
+    snapshots = list_snapshots(root)
+    kept = select_kept([stamp for stamp, _ in snapshots], policy)
+    removed = [path for stamp, path in snapshots if stamp not in kept]
+    if root.is_dir():
+        removed += [path for path in root.iterdir() if path.name.endswith(PARTIAL_SUFFIX)]
+    for path in removed:
+        shutil.rmtree(path, ignore_errors=True)
+    return removed
+
+
+def _link_or_copy(source: Path, target: Path) -> None:
+    try:
+        os.link(source, target)
+    except OSError:
+        shutil.copy2(source, target)
+
+
+def link_tree(destination: Path, target: Path, previous: Path | None, skip: Path) -> int:
+    """Snapshot ``destination`` (without ``skip``) into ``target``; the files copied."""
+    copied = 0
+    for root, dirnames, filenames in os.walk(destination):
+        base = Path(root)
+        dirnames[:] = sorted(d for d in dirnames if base / d != skip)
+        relative = base.relative_to(destination)
+        (target / relative).mkdir(parents=True, exist_ok=True)
+        for name in sorted(filenames):
+            if name.endswith(".tmp"):
+                continue
+            source = base / name
+            if source.is_symlink():
+                os.symlink(os.readlink(source), target / relative / name)
+                continue
+            if _IMMUTABLE_RE.search((relative / name).as_posix()):
+                _link_or_copy(source, target / relative / name)
+                continue
+            old = previous / relative / name if previous is not None else None
+            stat = source.stat()
+            if old is not None and old.is_file():
+                old_stat = old.stat()
+                if (old_stat.st_size, old_stat.st_mtime_ns) == (stat.st_size, stat.st_mtime_ns):
+                    _link_or_copy(old, target / relative / name)
+                    continue
+            shutil.copy2(source, target / relative / name)
+            copied += 1
+    return copied
+
+
+async def take_snapshot(
+    git: list[str],
+    destination: Path,
+    root: Path,
+    run_git: BundleGitRunner,
+    *,
+    now: datetime,
+    verify: bool,
+    workers: int,
+    mode: str = "bundle",
+) -> Path | None:
+    """Snapshot ``destination`` into a new directory of ``root``; None if that fails."""
+    target = root / now.strftime(SNAPSHOT_NAME_FORMAT)
+    if target.exists():  # taken within the same second
+        return target
+    partial = target.with_name(target.name + PARTIAL_SUFFIX)
+    shutil.rmtree(partial, ignore_errors=True)
+    partial.mkdir(parents=True)
+    if mode == "hardlink":
+        snapshots = list_snapshots(root)
+        previous = snapshots[-1][1] if snapshots else None
+        try:
+            copied = await asyncio.to_thread(link_tree, destination, partial, previous, root)
+        except OSError as exc:
+            log.warning("Snapshot %s failed and is discarded: %s", target.name, exc)
+            shutil.rmtree(partial, ignore_errors=True)
+            return None
+        log.debug("Snapshot %s copied %d changed files", target.name, copied)
+        partial.rename(target)
+        return target
+    semaphore = asyncio.Semaphore(max(workers, 1))
+
+    async def snapshot(mirror: Path) -> bool:
+        async with semaphore:
+            bundle = bundle_path(partial, destination, mirror)
+            try:
+                return await write_bundle(git, mirror, bundle, run_git, verify=verify)
+            except (OSError, RuntimeError) as exc:  # e.g. the git timeout
+                log.warning("Bundling %s for snapshot %s failed: %s", mirror, target.name, exc)
+                return False
+
+    repos = find_repositories(destination)
+    written = await asyncio.gather(*(snapshot(repo.path) for repo in repos))
+    if not all(written):
+        log.warning("Snapshot %s is incomplete; it is discarded", target.name)
+        shutil.rmtree(partial, ignore_errors=True)
+        return None
+    partial.rename(target)
+    return target
@@ -0,0 +1,115 @@
+"""SSH transport for ``git@host:owner/repo.git`` and ``ssh://`` remotes.
+
+git runs ``ssh`` itself, so ``[ssh]`` is applied through ``core.sshCommand`` on the
+tasks whose URL is an SSH one. Keys held by the running ssh-agent are offered first
+(``agent_socket`` points ssh at an agent a daemon did not inherit), then ``-i
+<key_file>``; with ``agent = false`` only the key file is used. ``BatchMode=yes``
+makes a missing credential fail instead of waiting on a prompt.
+
+A passphrase-protected key is unlocked through ``SSH_ASKPASS`` (OpenSSH 8.4+): for the
+duration of a sync gitout writes a small owner-only helper script that prints the
+passphrase from ``passphrase_file`` or ``passphrase_env``; the passphrase itself never
+appears in the script or in git's argv.
+
+The login comes from the URL. A user-less URL on a well-known forge logs in as
+``git`` (ssh would otherwise use the local account name, which those hosts reject).
+"""
+
+from __future__ import annotations
+
+import os
+import re
+import shlex
+import tempfile
+from pathlib import Path
+from urllib.parse import urlsplit
+
+from gitout.config import Ssh
+
+FORGE_SSH_USER = "git"
+# Hosts that only accept the ``git`` login over SSH.
+FORGE_HOSTS = frozenset(
+    {"github.com", "gist.github.com", "gitlab.com", "bitbucket.org", "codeberg.org"}
+)
+
+_SCP_URL_RE = re.compile(r"^(?:([\w.-]+)@)?([\w.-]+):(?!//)")
+_SSH_SCHEMES = ("ssh", "git+ssh", "ssh+git")
+
+
+def is_ssh_url(url: str) -> bool:
+    """Whether git reaches ``url`` over SSH (``ssh://`` or scp-like ``user@host:path``)."""
+    url = url.strip()
+    scheme = urlsplit(url).scheme
+    if scheme in _SSH_SCHEMES:
+        return True
+    match = _SCP_URL_RE.match(url)
+    return not scheme.startswith("http") and match is not None and match.group(1) is not None
+
+
+def ssh_login(url: str) -> tuple[str | None, str | None]:
+    """``(user, host)`` of an SSH URL; either is None when the URL does not say."""
+    url = url.strip()
+    parts = urlsplit(url)
+    if parts.scheme in _SSH_SCHEMES:
+        return parts.username, parts.hostname
+    match = _SCP_URL_RE.match(url)
+    return (match.group(1), match.group(2).casefold()) if match else (None, None)
+
+
+def default_login(url: str) -> str | None:
+    """The login to force for ``url``: ``git`` on a user-less forge URL, else None."""
+    user, host = ssh_login(url)
+    return FORGE_SSH_USER if user is None and host in FORGE_HOSTS else None
+
+
+def has_passphrase(ssh: Ssh) -> bool:
+    return ssh.passphrase_file is not None or ssh.passphrase_env is not None
+
+
+def ssh_command(ssh: Ssh, askpass: str | None = None, *, login: str | None = None) -> str | None:
+    """The ``core.sshCommand`` for ``[ssh]``, or None when it leaves ssh alone.
+
+    ``askpass`` is the helper from :func:`write_askpass`; it is omitted from dry runs.
+    ``login`` (see :func:`default_login`) is passed as ``-l``.
+    """
+    customised = (
+        ssh.key_file is not None
+        or has_passphrase(ssh)
+        or not ssh.agent
+        or ssh.agent_socket is not None
+    )
+    if not customised and login is None:
+        return None
+    parts: list[str] = []
+    if askpass is not None:
+        parts += [f"SSH_ASKPASS={shlex.quote(askpass)}", "SSH_ASKPASS_REQUIRE=force"]
+    parts.append("ssh")
+    if login is not None:
+        parts += ["-l", shlex.quote(login)]
+    if not ssh.agent:
+        parts += ["-o", "IdentityAgent=none"]
+    elif ssh.agent_socket is not None:
+        socket = os.path.expanduser(ssh.agent_socket)
+        parts += ["-o", shlex.quote(f"IdentityAgent={socket}")]
+    if ssh.key_file is not None:
+        parts += ["-i", shlex.quote(os.path.expanduser(ssh.key_file))]
+        if not ssh.agent:
+            parts += ["-o", "IdentitiesOnly=yes"]
+    if customised and not has_passphrase(ssh):
+        parts += ["-o", "BatchMode=yes"]
+    return " ".join(parts)
+
+
+def write_askpass(ssh: Ssh) -> Path | None:
+    """Write the ``SSH_ASKPASS`` helper for the configured passphrase (delete after use)."""
+    if ssh.passphrase_file is not None:
+        body = f"exec cat -- {shlex.quote(os.path.expanduser(ssh.passphrase_file))}"
+    elif ssh.passphrase_env is not None:
+        body = f'printf \'%s\\n\' "${ssh.passphrase_env}"'
+    else:
+        return None
+    fd, path = tempfile.mkstemp(prefix="gitout-askpass-")
+    with open(fd, "w") as handle:
+        handle.write(f"#!/bin/sh\n{body}\n")
+    os.chmod(path, 0o700)
+    return Path(path)
@@ -0,0 +1,109 @@
+"""Portable export/import of the per-destination state files.
+
+A backup destination carries JSON state next to the mirrors: the repository state
+snapshot (including the exclusion tombstones for deleted repos), the per-repository
+sync state, the failure history and the maintenance schedule. ``export_state`` bundles
+every state file that exists into one JSON document keyed by its path relative to the
+destination; ``import_state`` writes them back under another destination, so moving a
+backup to new hardware keeps its history.
+"""
+
+from __future__ import annotations
+
+import json
+import time
+from collections.abc import Callable
+from pathlib import Path, PurePosixPath
+from typing import Any
+
+from gitout import __version__
+from gitout.config import Config
+from gitout.maintenance import MAINTENANCE_STATE_FILE
+from gitout.sync_state import SYNC_STATE_FILE
+
+EXPORT_FORMAT = "gitout-state-export"
+EXPORT_VERSION = 1
+
+REPOSITORY_STATE_FILE = "github/.gitout-state.json"
+
+
+def _now_ms() -> int:
+    return int(time.time() * 1000)
+
+
+def state_files(config: Config) -> list[str]:
+    """Destination-relative (POSIX) paths of every state file gitout maintains."""
+    return [
+        REPOSITORY_STATE_FILE,
+        SYNC_STATE_FILE,
+        PurePosixPath(config.failure_tracking.state_file).as_posix(),
+        MAINTENANCE_STATE_FILE,
+    ]
+
+
+def _is_safe_relative(path: str) -> bool:
+    pure = PurePosixPath(path)
+    return bool(path) and not pure.is_absolute() and ".." not in pure.parts
+
+
+def export_state(
+    config: Config, destination: Path, *, now_ms: Callable[[], int] = _now_ms
+) -> dict[str, Any]:
+    """Collect the destination's state files into a portable document.
+
+    Missing files are skipped; a file that is not valid JSON raises ``ValueError``
+    rather than being silently dropped from the export.
+    """
+    files: dict[str, Any] = {}
+    for relative in state_files(config):
+        path = destination / relative
+        if not path.is_file():
+            continue
+        try:
+            files[relative] = json.loads(path.read_text())
+        except ValueError as exc:
+            raise ValueError(f"State file {path} is not valid JSON: {exc}") from exc
+    return {
+        "format": EXPORT_FORMAT,
+        "version": EXPORT_VERSION,
+        "gitoutVersion": __version__,
+        "exportedAt": now_ms(),
+        "files": files,
+    }
+
+
+def import_state(
+    payload: dict[str, Any], destination: Path, *, force: bool = False
+) -> list[str]:
+    """Write the state files from an export document under ``destination``.
+
+    Returns the relative paths written. Refuses to overwrite existing state unless
+    ``force`` is set, and rejects paths that would escape the destination.
+    """
+    if payload.get("format") != EXPORT_FORMAT:
+        raise ValueError("Not a gitout state export (missing or unknown 'format')")
+    version = payload.get("version")
+    if not isinstance(version, int) or version > EXPORT_VERSION:
+        raise ValueError(f"Unsupported state export version: {version}")
+    files = payload.get("files")
+    if not isinstance(files, dict):
+        raise ValueError("State export has no 'files' object")
+
+    for relative in files:
+        if not _is_safe_relative(relative):
+            raise ValueError(f"Refusing to import state file outside the destination: {relative}")
+    if not force:
+        existing = [r for r in files if (destination / r).exists()]
+        if existing:
+            raise ValueError(
+                "State already exists in the destination (use --force to overwrite): "
+                + ", ".join(sorted(existing))
+            )
+
+    written: list[str] = []
+    for relative, content in files.items():
+        path = destination / relative
+        path.parent.mkdir(parents=True, exist_ok=True)
+        path.write_text(json.dumps(content, indent=2))
+        written.append(relative)
+    return written
@@ -35,6 +35,7 @@ def metadata_to_dict(meta: RepositoryMetadata) -> dict[str, Any]:
         "defaultBranch": meta.default_branch,
         "topics": list(meta.topics),
         "language": meta.language,
+        "pushedAt": meta.pushed_at,
     }
 
 
@@ -52,6 +53,7 @@ def metadata_from_dict(data: dict[str, Any]) -> RepositoryMetadata:
         default_branch=data.get("defaultBranch"),
         topics=list(data.get("topics", [])),
         language=data.get("language"),
+        pushed_at=data.get("pushedAt"),
     )
 
 
@@ -0,0 +1,197 @@
+"""Per-repository sync state kept in the destination between runs.
+
+``<destination>/.gitout-sync-state.json`` maps each task identity (see
+:attr:`gitout.engine.SyncTask.identity`) to:
+
+* ``lastSuccess``/``lastAttempt`` — UTC ISO timestamps, and ``lastError`` (None after a
+  success);
+* ``pushedAt`` — GitHub's push time as seen by the last successful sync;
+* ``heads`` — branch name to commit SHA, read from the mirror after that sync;
+* ``sizeBytes`` — the mirror's on-disk size after that sync.
+
+Scheduled verification passes (``sync --cron ... --verify-cron ...``) record, per
+destination-relative mirror path, under ``verification``: ``lastVerified``, ``ok`` and
+the fsck ``error`` output of a failure.
+
+A failed sync only updates the attempt fields, so everything else still describes the
+last good mirror. Skipped repositories are left untouched. With
+``github.clone.skip_unchanged`` a repository whose ``pushedAt`` still matches is
+skipped without contacting the remote.
+
+A missing or unreadable file is treated as empty, so the worst case is one full run.
+"""
+
+from __future__ import annotations
+
+import contextlib
+import json
+from collections.abc import Callable, Iterable
+from dataclasses import dataclass, field, replace
+from datetime import UTC, datetime
+from pathlib import Path
+from typing import TYPE_CHECKING, Any
+
+from gitout.reports import directory_size
+
+if TYPE_CHECKING:
+    from gitout.engine import SyncOutcome, SyncTask
+    from gitout.verify import VerifyResult
+
+SYNC_STATE_FILE = ".gitout-sync-state.json"
+SYNC_STATE_VERSION = 1
+
+
+@dataclass(frozen=True)
+class RepoSyncState:
+    last_success: str | None = None
+    last_attempt: str | None = None
+    last_error: str | None = None
+    pushed_at: str | None = None
+    heads: dict[str, str] = field(default_factory=dict)
+    size_bytes: int | None = None
+
+    def to_dict(self) -> dict[str, Any]:
+        return {
+            "lastSuccess": self.last_success,
+            "lastAttempt": self.last_attempt,
+            "lastError": self.last_error,
+            "pushedAt": self.pushed_at,
+            "heads": dict(self.heads),
+            "sizeBytes": self.size_bytes,
+        }
+
+    @staticmethod
+    def from_dict(data: dict[str, Any]) -> RepoSyncState:
+        return RepoSyncState(
+            last_success=data.get("lastSuccess"),
+            last_attempt=data.get("lastAttempt"),
+            last_error=data.get("lastError"),
+            pushed_at=data.get("pushedAt"),
+            heads=dict(data.get("heads") or {}),
+            size_bytes=data.get("sizeBytes"),
+        )
+
+
+@dataclass(frozen=True)
+class RepoVerifyState:
+    last_verified: str
+    ok: bool
+    error: str | None = None
+
+    def to_dict(self) -> dict[str, Any]:
+        return {"lastVerified": self.last_verified, "ok": self.ok, "error": self.error}
+
+    @staticmethod
+    def from_dict(data: dict[str, Any]) -> RepoVerifyState:
+        return RepoVerifyState(
+            last_verified=str(data.get("lastVerified", "")),
+            ok=bool(data.get("ok")),
+            error=data.get("error"),
+        )
+
+
+def read_heads(repository: Path) -> dict[str, str]:
+    """Branch name -> SHA of a (bare) repository, from loose refs and ``packed-refs``.
+
+    Loose refs win over packed ones, as in git. Symbolic refs are ignored.
+    """
+    heads: dict[str, str] = {}
+    with contextlib.suppress(OSError):
+        for line in (repository / "packed-refs").read_text().splitlines():
+            sha, _, ref = line.partition(" ")
+            if ref.startswith("refs/heads/") and not line.startswith(("#", "^")):
+                heads[ref.removeprefix("refs/heads/")] = sha
+    loose = repository / "refs" / "heads"
+    if loose.is_dir():
+        for path in loose.rglob("*"):
+            if not path.is_file():
+                continue
+            with contextlib.suppress(OSError):
+                sha = path.read_text().strip()
+                if sha and not sha.startswith("ref:"):
+                    heads[path.relative_to(loose).as_posix()] = sha
+    return dict(sorted(heads.items()))
+
+
+def _now_iso() -> str:
+    return datetime.now(UTC).isoformat()
+
+
+class SyncStateStore:
+    def __init__(self, path: Path, *, now_iso: Callable[[], str] = _now_iso) -> None:
+        self._path = path
+        self._now_iso = now_iso
+        self.repositories: dict[str, RepoSyncState] = {}
+        self.verification: dict[str, RepoVerifyState] = {}
+        self._load()
+
+    def _load(self) -> None:
+        try:
+            data = json.loads(self._path.read_text())
+            repositories = {
+                identity: RepoSyncState.from_dict(entry)
+                for identity, entry in data.get("repositories", {}).items()
+            }
+            verification = {
+                name: RepoVerifyState.from_dict(entry)
+                for name, entry in data.get("verification", {}).items()
+            }
+        except (OSError, ValueError, AttributeError):
+            return
+        self.repositories = repositories
+        self.verification = verification
+
+    def is_unchanged(self, task: SyncTask) -> bool:
+        """True when ``task`` is mirrored and was not pushed since its last sync."""
+        previous = self.repositories.get(task.identity)
+        return (
+            task.pushed_at is not None
+            and previous is not None
+            and previous.pushed_at == task.pushed_at
+            and task.destination.exists()
+        )
+
+    def record(self, outcomes: Iterable[SyncOutcome]) -> None:
+        """Fold one run's outcomes in; skipped repositories are left untouched."""
+        now = self._now_iso()
+        for outcome in outcomes:
+            if outcome.skipped:
+                continue
+            task = outcome.task
+            if outcome.ok:
+                self.repositories[task.identity] = RepoSyncState(
+                    last_success=now,
+                    last_attempt=now,
+                    pushed_at=task.pushed_at,
+                    heads=read_heads(task.destination),
+                    size_bytes=directory_size(task.destination),
+                )
+            else:
+                previous = self.repositories.get(task.identity, RepoSyncState())
+                self.repositories[task.identity] = replace(
+                    previous, last_attempt=now, last_error=outcome.error
+                )
+
+    def record_verification(self, results: Iterable[VerifyResult]) -> None:
+        now = self._now_iso()
+        for result in results:
+            self.verification[result.name] = RepoVerifyState(
+                last_verified=now, ok=result.ok, error=None if result.ok else result.output
+            )
+
+    def save(self) -> None:
+        payload: dict[str, Any] = {
+            "version": SYNC_STATE_VERSION,
+            "repositories": {
+                identity: state.to_dict()
+                for identity, state in sorted(self.repositories.items())
+            },
+        }
+        if self.verification:
+            payload["verification"] = {
+                name: state.to_dict() for name, state in sorted(self.verification.items())
+            }
+        partial = self._path.with_name(self._path.name + ".tmp")
+        with contextlib.suppress(OSError):
+            partial.write_text(json.dumps(payload, indent=2))
+            partial.replace(self._path)
@@ -0,0 +1,85 @@
+"""Plain-file exports of each repository's default branch (``[tarball]``).
+
+For repositories with ``tarball.enabled`` (or a matching ``[[overrides]] tarball =
+true``) the engine writes ``git archive`` of the mirror's ``HEAD`` after every
+successful sync to ``<tarball.directory>/<mirror parent>/<repo>-<sha>.tar.zst``, e.g.
+``tarballs/github/clone/octocat/hello-<sha>.tar.zst``: the latest code as ordinary
+files, for recovery without git or for WORM storage. Compression uses ``zstd`` through
+git's ``tar.<format>.command``; without a ``zstd`` binary the export falls back to
+``.tar.gz``, which git produces itself. An existing tarball of the same commit is left
+alone; once a new one is written only the newest ``keep`` per repository remain (``0``
+keeps all).
+"""
+
+from __future__ import annotations
+
+import logging
+import re
+from collections.abc import Awaitable, Callable
+from pathlib import Path
+
+log = logging.getLogger(__name__)
+
+ZSTD_FORMAT = "tar.zst"
+GZIP_FORMAT = "tar.gz"
+
+# (argv, cwd) -> (exit code, combined output)
+TarballGitRunner = Callable[[list[str], Path], Awaitable[tuple[int, str]]]
+
+
+def tarball_dir(root: Path, destination: Path, mirror: Path) -> Path:
+    """The directory the tarballs of ``mirror`` (a path under ``destination``) go to."""
+    return root / mirror.relative_to(destination).parent
+
+
+def _tarball_re(name: str) -> re.Pattern[str]:
+    return re.compile(rf"^{re.escape(name)}-[0-9a-f]{{40,64}}\.tar\.(?:zst|gz)$")
+
+
+def _prune(directory: Path, name: str, keep: int) -> None:
+    pattern = _tarball_re(name)
+    tarballs = sorted(
+        (path for path in directory.iterdir() if pattern.match(path.name)),
+        key=lambda path: path.stat().st_mtime,
+        reverse=True,
+    )
+    for old in tarballs[keep:]:
+        old.unlink(missing_ok=True)
+
+
+async def write_tarball(
+    git: list[str],
+    mirror: Path,
+    directory: Path,
+    run_git: TarballGitRunner,
+    *,
+    keep: int,
+    zstd: bool,
+) -> Path | None:
+    """Export ``HEAD`` of ``mirror`` into ``directory``; the tarball, or None when there
+    is nothing to export or git fails. Errors of ``run_git`` itself (a timeout) propagate."""
+    head = [*git, "rev-parse", "--verify", "--quiet", "HEAD^{commit}"]
+    code, output = await run_git(head, mirror)
+    sha = output.strip()
+    if code != 0 or not sha:
+        log.debug("Not exporting a tarball of %s: its default branch has no commit", mirror)
+        return None
+    fmt = ZSTD_FORMAT if zstd else GZIP_FORMAT
+    target = directory / f"{mirror.name}-{sha}.{fmt}"
+    if target.exists():
+        return target
+    directory.mkdir(parents=True, exist_ok=True)
+    partial = target.with_name(target.name + ".tmp")
+    options = ["-c", f"tar.{ZSTD_FORMAT}.command=zstd -q -c"] if zstd else []
+    archive = [*git, *options, "archive", f"--format={fmt}", "-o", str(partial), sha]
+    try:
+        code, output = await run_git(archive, mirror)
+        if code != 0:
+            log.warning("Exporting a tarball of %s failed: %s", mirror, output.strip())
+            return None
+        partial.replace(target)
+    finally:
+        partial.unlink(missing_ok=True)  # a failed or timed-out export leaves no partial
+    if keep > 0:
+        _prune(directory, mirror.name, keep)
+    return target
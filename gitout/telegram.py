@@ -11,7 +11,7 @@ from __future__ import annotations
 import html
 import logging
 import os
-from collections.abc import Callable, Mapping
+from collections.abc import Callable, Mapping, Sequence
 from dataclasses import dataclass, replace
 from datetime import datetime
 from pathlib import Path
@@ -28,6 +28,9 @@ log = logging.getLogger(__name__)
 # A message sender: (html_text) -> None.
 MessageSender = Callable[[str], None]
 
+# Failed items listed by name in a verification, replication or completion notification.
+MAX_FAILED_LISTED = 10
+
 
 @dataclass(frozen=True)
 class SyncStats:
@@ -81,6 +84,14 @@ def format_duration(seconds: int) -> str:
     return " ".join(parts)
 
 
+def _listed(items: Sequence[str]) -> list[str]:
+    """Indented lines naming the first :data:`MAX_FAILED_LISTED` items, then a count."""
+    lines = [f"  {html.escape(item)}" for item in items[:MAX_FAILED_LISTED]]
+    if len(items) > MAX_FAILED_LISTED:
+        lines.append(f"  ... and {len(items) - MAX_FAILED_LISTED} more")
+    return lines
+
+
 class TelegramNotificationService:
     def __init__(
         self,
@@ -183,7 +194,10 @@ class TelegramNotificationService:
         repo_line = f"\nCurrent: {current_repo}" if current_repo else ""
         self._send(f"<b>Sync Progress</b>\n\n{completed}/{total} ({percentage}%){repo_line}")
 
-    def notify_sync_completion(self, successful: int, failed: int, duration_seconds: int) -> None:
+    def notify_sync_completion(
+        self, successful: int, failed: int, duration_seconds: int, failures: Sequence[str] = ()
+    ) -> None:
+        """Report a finished sync; ``failures`` describes each failed repository."""
         self._stats = replace(
             self._stats,
             is_syncing=False,
@@ -194,12 +208,39 @@ class TelegramNotificationService:
         )
         if not self.is_enabled() or self._config is None or not self._config.notify_completion:
             return
-        self._send(
-            "<b>GitOut Sync Completed</b>\n\n"
-            f"Succeeded: {successful}\n"
-            f"Failed: {failed}\n"
-            f"Duration: {format_duration(duration_seconds)}"
-        )
+        lines = [
+            "<b>GitOut Sync Completed</b>\n",
+            f"Succeeded: {successful}",
+            f"Failed: {failed}",
+            f"Duration: {format_duration(duration_seconds)}",
+        ]
+        lines += _listed(failures)
+        self._send("\n".join(lines))
+
+    def notify_verification(self, passed: int, failed: list[str]) -> None:
+        """Report a scheduled integrity pass; ``failed`` names the mirrors fsck rejected."""
+        if not self.is_enabled():
+            return
+        lines = [
+            "<b>GitOut Verification " + ("Failed" if failed else "Passed") + "</b>\n",
+            f"Passed: {passed}",
+            f"Failed: {len(failed)}",
+        ]
+        lines += _listed(failed)
+        self._send("\n".join(lines))
+
+    def notify_replication_failure(self, remote: str, exit_code: int, failed: list[str]) -> None:
+        """Report an rclone run that did not complete; ``failed`` describes each error."""
+        if not self.is_enabled():
+            return
+        lines = [
+            "<b>GitOut Replication Failed</b>\n",
+            f"Remote: {html.escape(remote)}",
+            f"Exit code: {exit_code}",
+            f"Errors: {len(failed)}",
+        ]
+        lines += _listed(failed)
+        self._send("\n".join(lines))
 
     # --- interactive command handlers ---
 
@@ -0,0 +1,125 @@
+"""Startup check of the GitHub token, before any repository is listed.
+
+A revoked or mistyped token used to surface as an HTTP error deep inside discovery;
+:func:`check_token` asks ``GET /user`` first and turns a 401 into an actionable
+message. Classic tokens report their scopes in ``X-OAuth-Scopes``, which is compared
+with what the enabled features need (:func:`required_scopes`: migration archives and
+settings exports cannot work without ``repo``). Scopes that only widen what is backed
+up (:func:`recommended_scopes`: private repositories, secret gists, repositories of
+organizations) are warned about when missing. Fine-grained tokens and App installation
+tokens send no such header, so only their validity is checked.
+"""
+
+from __future__ import annotations
+
+import logging
+from collections.abc import Awaitable, Callable, Iterable, Mapping
+
+import httpx
+
+from gitout.config import GitHubConfig
+from gitout.github_client import GITHUB_API_URL, api_headers, rest_url, web_url
+
+log = logging.getLogger(__name__)
+
+# Scope -> broader scopes that include it.
+IMPLIED_BY = {
+    "public_repo": ("repo",),
+    "read:org": ("write:org", "admin:org"),
+    "read:user": ("user",),
+}
+
+TokenChecker = Callable[[str, Mapping[str, str]], Awaitable[str]]
+
+
+class TokenCheckError(RuntimeError):
+    """The token was rejected or lacks a scope the configuration needs."""
+
+
+def required_scopes(github: GitHubConfig) -> dict[str, str]:
+    """Scope -> the enabled ``[github]`` feature that cannot work without it."""
+    needs = []
+    if github.archive.owned or github.archive.repos:
+        needs.append("migration archives")
+    if github.metadata.settings:
+        needs.append("settings exports")
+    return {"repo": " and ".join(needs)} if needs else {}
+
+
+def recommended_scopes(github: GitHubConfig) -> dict[str, str]:
+    """Scope -> what is left out of the backup without it (a warning, not an error)."""
+    scopes = {} if required_scopes(github) else {"repo": "private repositories"}
+    if github.clone.gists or github.clone.gists_starred:
+        scopes["gist"] = "secret gists"
+    user = github.user.lower()
+    named = [*github.clone.repos, *github.archive.repos]
+    if any(name.partition("/")[0].lower() != user for name in named if "/" in name):
+        scopes["read:org"] = "private repositories of other owners in clone.repos/archive.repos"
+    return scopes
+
+
+def missing_scopes(granted: Iterable[str], required: Iterable[str]) -> list[str]:
+    """The ``required`` scopes neither granted nor implied by a granted scope."""
+    have = {scope.strip() for scope in granted if scope.strip()}
+    return [
+        scope
+        for scope in required
+        if scope not in have and not have.intersection(IMPLIED_BY.get(scope, ()))
+    ]
+
+
+def tokens_page(api_url: str) -> str:
+    """Where the token owner manages their tokens (GitHub or an Enterprise Server)."""
+    return f"{web_url(api_url)}/settings/tokens"
+
+
+async def check_token(
+    token: str,
+    required: Mapping[str, str],
+    *,
+    recommended: Mapping[str, str] | None = None,
+    client: httpx.AsyncClient | None = None,
+    api_url: str = GITHUB_API_URL,
+    user_agent: str | None = None,
+    extra_headers: Mapping[str, str] | None = None,
+) -> str:
+    """The login ``token`` belongs to; raises TokenCheckError when it is unusable.
+
+    A missing ``required`` scope is an error; a missing ``recommended`` one is logged.
+    """
+    http = client or httpx.AsyncClient(timeout=30.0)
+    try:
+        response = await http.get(
+            f"{rest_url(api_url)}/user",
+            headers=api_headers(token, user_agent=user_agent, extra_headers=extra_headers),
+        )
+    finally:
+        if client is None:
+            await http.aclose()
+    if response.status_code == 401:
+        raise TokenCheckError(
+            "GitHub rejected the token (401 Bad credentials). Check github.token, "
+            "github.token_file, github.token_cmd or GITHUB_TOKEN, or create a new token at "
+            f"{tokens_page(api_url)}"
+        )
+    response.raise_for_status()
+    login = str(response.json().get("login", ""))
+    scopes_header = response.headers.get("X-OAuth-Scopes")
+    if scopes_header is None:
+        return login  # fine-grained or installation token: permissions are not listed
+    missing = missing_scopes(scopes_header.split(","), required)
+    if missing:
+        needs = "; ".join(f"{scope} ({required[scope]})" for scope in missing)
+        raise TokenCheckError(
+            f"The GitHub token for {login or 'this account'} lacks scope(s): {needs}. "
+            f"Regenerate it with those scopes at {tokens_page(api_url)}"
+        )
+    lacking = missing_scopes(scopes_header.split(","), recommended or {})
+    if lacking and recommended:
+        log.warning(
+            "The GitHub token for %s lacks scope(s) %s, so the backup leaves out: %s",
+            login or "this account",
+            ", ".join(lacking),
+            "; ".join(recommended[scope] for scope in lacking),
+        )
+    return login
@@ -0,0 +1,158 @@
+"""OpenTelemetry spans of each run, exported over OTLP/HTTP (``[tracing]``).
+
+With ``[tracing] endpoint`` set, the engine records a span tree per real sync and
+POSTs it as OTLP JSON to ``{endpoint}/v1/traces`` when the run ends:
+
+* ``sync`` — the whole run, with ``discover`` (GitHub enumeration), one ``repository``
+  span per repository (``gitout.repository``, ``gitout.outcome``, ``gitout.attempts``)
+  and ``finalize`` (metadata export, migration ``archive`` downloads, maintenance);
+* ``git`` — each attempt of a repository's clone or fetch (``gitout.attempt``), so
+  retries show up as siblings.
+
+Spans nest through a :class:`~contextvars.ContextVar`, so the parallel repositories of
+one run each get their own branch of the tree. No OpenTelemetry SDK is needed; the
+httpx client is injectable and a failed export is logged, never raised.
+"""
+
+from __future__ import annotations
+
+import contextlib
+import logging
+import secrets
+import time
+from collections.abc import Iterator, Mapping
+from contextvars import ContextVar
+from dataclasses import dataclass, field
+
+import httpx
+
+from gitout import __version__
+
+log = logging.getLogger(__name__)
+
+# OTLP span kind and status codes.
+_SPAN_KIND_INTERNAL = 1
+_STATUS_OK = 1
+_STATUS_ERROR = 2
+
+AttributeValue = str | int | float | bool
+
+
+@dataclass
+class Span:
+    name: str
+    trace_id: str
+    span_id: str
+    parent_span_id: str | None
+    start_ns: int
+    end_ns: int = 0
+    attributes: dict[str, AttributeValue] = field(default_factory=dict)
+    error: str | None = None
+
+
+_current: ContextVar[Span | None] = ContextVar("gitout_span", default=None)
+
+
+def _value(value: AttributeValue) -> dict[str, object]:
+    if isinstance(value, bool):
+        return {"boolValue": value}
+    if isinstance(value, int):
+        return {"intValue": str(value)}
+    if isinstance(value, float):
+        return {"doubleValue": value}
+    return {"stringValue": value}
+
+
+def _attributes(attributes: Mapping[str, AttributeValue]) -> list[dict[str, object]]:
+    return [{"key": key, "value": _value(value)} for key, value in attributes.items()]
+
+
+class Tracer:
+    """Records spans and exports them to an OTLP/HTTP collector."""
+
+    def __init__(
+        self,
+        endpoint: str,
+        *,
+        service_name: str = "gitout",
+        headers: Mapping[str, str] | None = None,
+        timeout_seconds: float = 10.0,
+        client: httpx.AsyncClient | None = None,
+    ) -> None:
+        self.url = f"{endpoint.rstrip('/')}/v1/traces"
+        self.service_name = service_name
+        self._headers = dict(headers or {})
+        self._timeout = timeout_seconds
+        self._client = client
+        self.finished: list[Span] = []
+
+    @contextlib.contextmanager
+    def span(self, name: str, **attributes: AttributeValue) -> Iterator[Span]:
+        """A child of the current span (or a new trace); set attributes on the yielded span."""
+        parent = _current.get()
+        span = Span(
+            name=name,
+            trace_id=parent.trace_id if parent is not None else secrets.token_hex(16),
+            span_id=secrets.token_hex(8),
+            parent_span_id=parent.span_id if parent is not None else None,
+            start_ns=time.time_ns(),
+            attributes=dict(attributes),
+        )
+        token = _current.set(span)
+        try:
+            yield span
+        except BaseException as exc:
+            span.error = str(exc) or type(exc).__name__
+            raise
+        finally:
+            _current.reset(token)
+            span.end_ns = time.time_ns()
+            self.finished.append(span)
+
+    def payload(self, spans: list[Span]) -> dict[str, object]:
+        """The OTLP JSON ``ExportTraceServiceRequest`` of ``spans``."""
+        encoded = []
+        for span in spans:
+            entry: dict[str, object] = {
+                "traceId": span.trace_id,
+                "spanId": span.span_id,
+                "name": span.name,
+                "kind": _SPAN_KIND_INTERNAL,
+                "startTimeUnixNano": str(span.start_ns),
+                "endTimeUnixNano": str(span.end_ns),
+                "attributes": _attributes(span.attributes),
+                "status": {"code": _STATUS_OK}
+                if span.error is None
+                else {"code": _STATUS_ERROR, "message": span.error},
+            }
+            if span.parent_span_id is not None:
+                entry["parentSpanId"] = span.parent_span_id
+            encoded.append(entry)
+        resource = {"service.name": self.service_name, "service.version": __version__}
+        return {
+            "resourceSpans": [
+                {
+                    "resource": {"attributes": _attributes(resource)},
+                    "scopeSpans": [
+                        {"scope": {"name": "gitout", "version": __version__}, "spans": encoded}
+                    ],
+                }
+            ]
+        }
+
+    async def export(self) -> bool:
+        """Send and forget the finished spans; False when the collector did not take them."""
+        spans, self.finished = self.finished, []
+        if not spans:
+            return True
+        http = self._client or httpx.AsyncClient(timeout=self._timeout)
+        try:
+            response = await http.post(self.url, json=self.payload(spans), headers=self._headers)
+            response.raise_for_status()
+        except httpx.HTTPError as exc:
+            log.warning("Exporting %d spans to %s failed: %s", len(spans), self.url, exc)
+            return False
+        finally:
+            if self._client is None:
+                await http.aclose()
+        return True
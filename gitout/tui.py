@@ -0,0 +1,153 @@
+"""Live terminal dashboard for ``gitout tui``.
+
+A :class:`Dashboard` consumes the engine's sync events (see :mod:`gitout.events`) and
+log records, and renders with rich: overall progress, what each worker is doing and
+for how long, a scrolling event log, and the failures so far. ``run_dashboard`` drives
+a sync under ``rich.live.Live``; the clock is injectable for tests.
+"""
+
+from __future__ import annotations
+
+import asyncio
+import logging
+import time
+from collections import deque
+from collections.abc import Callable, Coroutine
+from dataclasses import dataclass
+from typing import Any
+
+from rich.console import Group, RenderableType
+from rich.live import Live
+from rich.panel import Panel
+from rich.table import Table
+from rich.text import Text
+
+from gitout.engine import SyncOutcome
+from gitout.events import RepoFinished, RepoStarted, RunFinished, RunStarted, SyncEvent
+
+DEFAULT_LOG_LINES = 12
+DEFAULT_FAILURE_ROWS = 8
+
+
+@dataclass
+class _Activity:
+    name: str
+    started: float
+
+
+class Dashboard:
+    def __init__(
+        self,
+        *,
+        clock: Callable[[], float] = time.monotonic,
+        log_lines: int = DEFAULT_LOG_LINES,
+        failure_rows: int = DEFAULT_FAILURE_ROWS,
+    ) -> None:
+        self._clock = clock
+        self._started = clock()
+        self._failure_rows = failure_rows
+        self.total = 0
+        self.workers = 0
+        self.done = 0
+        self.succeeded = 0
+        self.skipped = 0
+        self.finished = False
+        self.activity: dict[int, _Activity] = {}
+        self.failures: list[SyncOutcome] = []
+        self.log: deque[str] = deque(maxlen=log_lines)
+
+    def handle(self, event: SyncEvent) -> None:
+        """Engine ``on_event`` listener."""
+        if isinstance(event, RunStarted):
+            self.total, self.workers = event.total, event.workers
+            self._started = self._clock()
+            self.note(f"Syncing {event.total} repositories with {event.workers} workers")
+        elif isinstance(event, RepoStarted):
+            self.activity[event.worker] = _Activity(event.task.name, self._clock())
+        elif isinstance(event, RepoFinished):
+            self._finish(event.outcome, event.worker)
+        elif isinstance(event, RunFinished):
+            self.finished = True
+            self.note(f"Finished: {self.succeeded}/{self.total} succeeded")
+
+    def _finish(self, outcome: SyncOutcome, worker: int | None) -> None:
+        if worker is not None:
+            self.activity.pop(worker, None)
+        self.done += 1
+        name = outcome.task.name
+        if outcome.skipped:
+            self.skipped += 1
+            self.note(f"skipped {name}" + (f": {outcome.error}" if outcome.error else ""))
+        elif outcome.ok:
+            self.succeeded += 1
+            self.note(f"ok      {name} ({outcome.duration_seconds:.1f}s)")
+        else:
+            self.failures.append(outcome)
+            self.note(f"FAILED  {name}: {outcome.error}")
+
+    def note(self, message: str) -> None:
+        """Append a line to the scrolling event log."""
+        self.log.append(message)
+
+    def render(self) -> RenderableType:
+        elapsed = self._clock() - self._started
+        state = "done" if self.finished else "running"
+        header = Text(
+            f"gitout {state} — {self.done}/{self.total} repositories, "
+            f"{self.succeeded} ok, {len(self.failures)} failed, {self.skipped} skipped, "
+            f"{elapsed:.0f}s elapsed"
+        )
+
+        workers = Table(expand=True, show_edge=False)
+        workers.add_column("Worker", width=6, justify="right")
+        workers.add_column("Repository")
+        workers.add_column("Time", width=8, justify="right")
+        now = self._clock()
+        for slot in range(1, self.workers + 1):
+            current = self.activity.get(slot)
+            if current is None:
+                workers.add_row(str(slot), Text("idle", style="dim"), "")
+            else:
+                workers.add_row(str(slot), current.name, f"{now - current.started:.0f}s")
+
+        failures = Table(expand=True, show_edge=False)
+        failures.add_column("Repository")
+        failures.add_column("Error")
+        for outcome in self.failures[-self._failure_rows :]:
+            failures.add_row(outcome.task.name, outcome.error or "")
+
+        return Group(
+            header,
+            Panel(workers, title="Workers"),
+            Panel(Text("\n".join(self.log)), title="Events"),
+            Panel(failures, title=f"Failures ({len(self.failures)})"),
+        )
+
+    def __rich__(self) -> RenderableType:
+        return self.render()
+
+
+class DashboardLogHandler(logging.Handler):
+    """Route log records into the dashboard's event log instead of stderr."""
+
+    def __init__(self, dashboard: Dashboard) -> None:
+        super().__init__()
+        self._dashboard = dashboard
+        self.setFormatter(logging.Formatter("%(levelname)-7s %(message)s"))
+
+    def emit(self, record: logging.LogRecord) -> None:
+        self._dashboard.note(self.format(record))
+
+
+def run_dashboard(
+    dashboard: Dashboard, sync: Callable[[], Coroutine[Any, Any, list[SyncOutcome]]]
+) -> list[SyncOutcome]:
+    """Run ``sync`` while redrawing ``dashboard``; log records go to the event log."""
+    root = logging.getLogger()
+    saved_handlers = root.handlers[:]
+    root.handlers = [DashboardLogHandler(dashboard)]
+    try:
+        with Live(dashboard, refresh_per_second=4, redirect_stderr=True):
+            return asyncio.run(sync())
+    finally:
+        root.handlers = saved_handlers
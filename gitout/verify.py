@@ -0,0 +1,107 @@
+"""Integrity check of the mirrors in a backup destination (``gitout verify``).
+
+Every bare repository :func:`gitout.frontends.find_repositories` finds is checked with
+``git fsck --no-progress --no-dangling``: objects are hashed and parsed, and every ref
+must point at a reachable, complete history. ``connectivity_only`` runs ``fsck
+--connectivity-only`` instead, which only walks the graph and is much faster on large
+mirrors. Repositories are checked concurrently, at most ``workers`` at a time; the git
+runner is injectable so tests need not spawn git. With ``--quarantine`` the CLI moves
+mirrors fsck rejected aside (:func:`gitout.quarantine.quarantine`) so the next sync
+clones them again; one whose check timed out or could not start is only reported.
+
+:func:`run_verification_pass` is the scheduled variant behind ``sync --cron ...
+--verify-cron ...``: it records each result in the sync state store, quarantines the
+mirrors fsck rejected when ``fetch.reclone_corrupt`` is on, and reports through Telegram.
+"""
+
+from __future__ import annotations
+
+import asyncio
+import logging
+from dataclasses import dataclass
+from datetime import UTC, datetime
+from pathlib import Path
+
+from gitout.config import Config
+from gitout.engine import GitRunner, auto_worker_count, default_git_runner
+from gitout.frontends import find_repositories
+from gitout.quarantine import quarantine
+from gitout.sync_state import SYNC_STATE_FILE, SyncStateStore
+from gitout.telegram import TelegramNotificationService
+
+log = logging.getLogger(__name__)
+
+
+@dataclass(frozen=True)
+class VerifyResult:
+    name: str  # destination-relative POSIX path
+    path: Path
+    ok: bool
+    output: str = ""
+    corrupt: bool = False  # fsck ran and rejected it (not a timeout or spawn error)
+
+
+def fsck_argv(*, connectivity_only: bool = False) -> list[str]:
+    argv = ["git", "fsck", "--no-progress", "--no-dangling"]
+    if connectivity_only:
+        argv.append("--connectivity-only")
+    return argv
+
+
+async def verify_destination(
+    destination: Path,
+    *,
+    workers: int = 4,
+    timeout_seconds: float = 600.0,
+    connectivity_only: bool = False,
+    git_runner: GitRunner = default_git_runner,
+) -> list[VerifyResult]:
+    """One result per mirror under ``destination``, sorted by name."""
+    if not destination.is_dir():
+        raise ValueError(f"Backup destination does not exist: {destination}")
+    argv = fsck_argv(connectivity_only=connectivity_only)
+    semaphore = asyncio.Semaphore(max(workers, 1))
+
+    async def check(name: str, path: Path) -> VerifyResult:
+        async with semaphore:
+            try:
+                code, output = await git_runner(argv, path, timeout_seconds)
+            except (OSError, RuntimeError) as exc:
+                return VerifyResult(name, path, False, str(exc))
+        return VerifyResult(name, path, code == 0, output.strip(), corrupt=code != 0)
+
+    repos = find_repositories(destination)
+    return list(await asyncio.gather(*(check(repo.name, repo.path) for repo in repos)))
+
+
+async def run_verification_pass(
+    config: Config,
+    destination: Path,
+    *,
+    workers: int | None = None,
+    timeout_seconds: float = 600.0,
+    telegram: TelegramNotificationService | None = None,
+    git_runner: GitRunner = default_git_runner,
+) -> list[VerifyResult]:
+    """Full fsck of ``destination``, recorded in its sync state and notified."""
+    if workers == 0:  # --workers auto
+        workers = auto_worker_count(())
+    results = await verify_destination(
+        destination,
+        workers=workers or config.parallelism.workers,
+        timeout_seconds=timeout_seconds,
+        git_runner=git_runner,
+    )
+    store = SyncStateStore(destination / SYNC_STATE_FILE)
+    store.record_verification(results)
+    store.save()
+    failed = [result for result in results if not result.ok]
+    for result in failed:
+        log.warning("Verification of %s failed: %s", result.name, result.output)
+        if config.fetch.reclone_corrupt and result.corrupt:
+            moved = quarantine(result.path, datetime.now(UTC).date())
+            log.warning("Moved %s to %s; the next sync clones it again", result.name, moved)
+    log.info("Verified %d/%d repositories", len(results) - len(failed), len(results))
+    if telegram is not None:
+        telegram.notify_verification(len(results) - len(failed), [r.name for r in failed])
+    return results
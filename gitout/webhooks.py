@@ -0,0 +1,170 @@
+"""Run notifications POSTed to generic webhooks (``[[webhooks]]``).
+
+After every real run each configured webhook whose ``events`` include the run's event
+(``success`` when every repository synced or was skipped, ``failure`` otherwise or when
+the run itself raised) gets one POST. Without a ``template`` the body is the JSON
+summary of :func:`build_payload`:
+
+* ``event``, ``gitoutVersion``, ``startedAt``, ``finishedAt`` and ``durationSeconds``;
+* ``summary`` — ``attempted``, ``succeeded``, ``failed`` and ``skipped`` counts;
+* ``error`` — why the run itself failed, or null;
+* ``failures`` — ``identity``, ``error`` and ``category`` of each failed repository.
+
+A ``template`` is a :class:`string.Template` filled with :func:`template_values`
+(``$event``, ``$summary``, ``$failed``, ``$failures`` — one ``identity: error`` line
+per failure — and so on). When ``content_type`` is JSON the values are escaped as JSON
+string contents, so ``{"text": "$summary\\n$failures"}`` stays valid whatever the
+errors say. Failed deliveries are logged and swallowed; the httpx client is injectable
+for tests. Hook URLs often embed a secret token, so logs name only their host
+(:func:`redacted_url`) and the status code or error type (:func:`failure_reason`),
+never the httpx message, which repeats the URL.
+"""
+
+from __future__ import annotations
+
+import json
+import logging
+import string
+from collections.abc import Sequence
+from datetime import datetime
+from typing import TYPE_CHECKING, Any
+
+import httpx
+
+from gitout import __version__
+from gitout.config import Webhook
+from gitout.reports import outcome_label
+
+if TYPE_CHECKING:
+    from gitout.engine import SyncOutcome
+
+log = logging.getLogger(__name__)
+
+
+def redacted_url(url: str) -> str:
+    """``scheme://host`` of ``url``, without the path and query that may carry a token."""
+    try:
+        parsed = httpx.URL(url)
+    except httpx.InvalidURL:
+        return "<invalid URL>"
+    return f"{parsed.scheme}://{parsed.host}"
+
+
+def failure_reason(exc: httpx.HTTPError) -> str:
+    """Why a POST failed, without the request URL that httpx puts in its messages."""
+    if isinstance(exc, httpx.HTTPStatusError):
+        return f"HTTP {exc.response.status_code}"
+    return type(exc).__name__
+
+
+def run_event(outcomes: Sequence[SyncOutcome], error: str | None = None) -> str:
+    """``success`` or ``failure``."""
+    if error is not None or not all(outcome.ok for outcome in outcomes):
+        return "failure"
+    return "success"
+
+
+def build_payload(
+    outcomes: Sequence[SyncOutcome],
+    *,
+    started_at: datetime,
+    finished_at: datetime,
+    error: str | None = None,
+) -> dict[str, Any]:
+    """The JSON summary of one run (timestamps must be UTC-aware)."""
+    labels = [outcome_label(outcome) for outcome in outcomes]
+    return {
+        "event": run_event(outcomes, error),
+        "gitoutVersion": __version__,
+        "startedAt": started_at.isoformat(),
+        "finishedAt": finished_at.isoformat(),
+        "durationSeconds": round((finished_at - started_at).total_seconds(), 3),
+        "summary": {
+            "attempted": len(outcomes),
+            "succeeded": labels.count("ok"),
+            "failed": labels.count("failed"),
+            "skipped": labels.count("skipped"),
+        },
+        "error": error,
+        "failures": [
+            {
+                "identity": o.task.identity,
+                "error": o.error,
+                "category": o.category.value if o.category is not None else None,
+            }
+            for o in outcomes
+            if not o.ok
+        ],
+    }
+
+
+def summary_line(payload: dict[str, Any]) -> str:
+    """One line saying how the run of :func:`build_payload` went."""
+    summary = payload["summary"]
+    if payload["error"] is not None:
+        return f"gitout sync failed: {payload['error']}"
+    if summary["failed"]:
+        return f"{summary['failed']} of {summary['attempted']} repositories failed"
+    return f"{summary['succeeded']} of {summary['attempted']} repositories synced"
+
+
+def template_values(payload: dict[str, Any]) -> dict[str, str]:
+    """The ``$placeholders`` of a webhook template, from :func:`build_payload`."""
+    summary = payload["summary"]
+    return {
+        "event": payload["event"],
+        "summary": summary_line(payload),
+        "attempted": str(summary["attempted"]),
+        "succeeded": str(summary["succeeded"]),
+        "failed": str(summary["failed"]),
+        "skipped": str(summary["skipped"]),
+        "duration_seconds": str(payload["durationSeconds"]),
+        "started_at": payload["startedAt"],
+        "finished_at": payload["finishedAt"],
+        "error": payload["error"] or "",
+        "failures": "\n".join(f"{f['identity']}: {f['error']}" for f in payload["failures"]),
+        "version": payload["gitoutVersion"],
+    }
+
+
+def render_body(hook: Webhook, payload: dict[str, Any]) -> bytes:
+    """The request body for ``hook``: its template filled in, or the JSON payload."""
+    if hook.template is None:
+        return json.dumps(payload).encode()
+    values = template_values(payload)
+    if "json" in hook.content_type.lower():
+        values = {key: json.dumps(value)[1:-1] for key, value in values.items()}
+    return string.Template(hook.template).safe_substitute(values).encode()
+
+
+class WebhookNotifier:
+    def __init__(
+        self, webhooks: Sequence[Webhook], *, client: httpx.AsyncClient | None = None
+    ) -> None:
+        self.webhooks = list(webhooks)
+        self._client = client
+
+    async def notify(self, payload: dict[str, Any]) -> int:
+        """POST ``payload`` to every webhook subscribed to its event; the number delivered."""
+        delivered = 0
+        for hook in self.webhooks:
+            if payload["event"] in hook.events and await self._post(hook, payload):
+                delivered += 1
+        return delivered
+
+    async def _post(self, hook: Webhook, payload: dict[str, Any]) -> bool:
+        http = self._client or httpx.AsyncClient(timeout=hook.timeout_seconds)
+        try:
+            response = await http.post(
+                hook.url,
+                content=render_body(hook, payload),
+                headers={"Content-Type": hook.content_type, **hook.headers},
+            )
+            response.raise_for_status()
+        except httpx.HTTPError as exc:
+            log.warning("Webhook %s failed: %s", redacted_url(hook.url), failure_reason(exc))
+            return False
+        finally:
+            if self._client is None:
+                await http.aclose()
+        return True
@@ -3,12 +3,46 @@
 from __future__ import annotations
 
 import json
+import sys
+import types
 from pathlib import Path
 from typing import Any
 
+import pytest
+
 FIXTURES = Path(__file__).parent / "fixtures"
 
 
 def load_json(*parts: str) -> Any:
     """Load a JSON fixture relative to ``tests/fixtures``."""
     return json.loads(FIXTURES.joinpath(*parts).read_text())
+
+
+def fake_keyring(
+    monkeypatch: pytest.MonkeyPatch, *, broken: bool = False
+) -> dict[tuple[str, str], str]:
+    """Install a ``keyring`` module backed by a dict; ``broken`` makes every call fail."""
+    store: dict[tuple[str, str], str] = {}
+    module = types.ModuleType("keyring")
+
+    def check() -> None:
+        if broken:
+            raise RuntimeError("no backend available")
+
+    def get_password(service: str, user: str) -> str | None:
+        check()
+        return store.get((service, user))
+
+    def set_password(service: str, user: str, secret: str) -> None:
+        check()
+        store[(service, user)] = secret
+
+    def delete_password(service: str, user: str) -> None:
+        check()
+        del store[(service, user)]
+
+    module.get_password = get_password  # type: ignore[attr-defined]
+    module.set_password = set_password  # type: ignore[attr-defined]
+    module.delete_password = delete_password  # type: ignore[attr-defined]
+    monkeypatch.setitem(sys.modules, "keyring", module)
+    return store
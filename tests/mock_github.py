@@ -0,0 +1,225 @@
+"""In-memory GitHub API double for integration tests.
+
+:class:`MockGitHub` answers the subset of the API gitout talks to:
+
+* ``POST /graphql`` — the per-connection ``UserRepos`` queries, paged by the
+  ``after`` cursor (``page_size`` edges per page, with ``pageInfo``);
+* ``GET /gists/starred`` — REST pagination with ``Link: rel="next"``;
+* ``/user/migrations`` — start, list, poll (``exporting`` for ``export_polls`` GETs,
+  then ``exported``), archive download and repository unlock.
+
+Every request is recorded in ``requests``. :meth:`throttle` queues rate-limit
+responses for a path prefix, so retry handling can be exercised deterministically.
+
+Use :meth:`client` for an ``httpx.AsyncClient`` over a MockTransport, or :meth:`serve`
+for a real HTTP server on localhost (for code that builds its own client from an
+``api_url``).
+"""
+
+from __future__ import annotations
+
+import json
+import threading
+from collections.abc import Iterator
+from contextlib import contextmanager
+from dataclasses import dataclass, field
+from http.server import BaseHTTPRequestHandler, ThreadingHTTPServer
+from typing import Any
+
+import httpx
+
+API_URL = "https://api.test"
+
+
+def repo_node(name_with_owner: str, **fields: Any) -> dict[str, Any]:
+    """A ``RepoFields`` GraphQL node with plain defaults; ``fields`` override them."""
+    node: dict[str, Any] = {
+        "nameWithOwner": name_with_owner,
+        "isArchived": False,
+        "isPrivate": False,
+        "isFork": False,
+        "visibility": "PUBLIC",
+        "description": None,
+        "updatedAt": "2024-01-01T00:00:00Z",
+        "diskUsage": 100,
+        "defaultBranchRef": {"name": "main"},
+        "repositoryTopics": {"nodes": []},
+        "primaryLanguage": None,
+    }
+    node.update(fields)
+    return node
+
+
+def gist_node(name: str, **fields: Any) -> dict[str, Any]:
+    node: dict[str, Any] = {
+        "name": name,
+        "isPublic": True,
+        "description": None,
+        "updatedAt": "2024-01-01T00:00:00Z",
+    }
+    node.update(fields)
+    return node
+
+
+# GraphQL connection alias -> (edges alias, MockGitHub attribute)
+_CONNECTIONS = {
+    "ownedRepositories": ("ownedEdges", "owned"),
+    "starredRepositories": ("starredEdges", "starred"),
+    "watchingRepositories": ("watchingEdges", "watching"),
+    "gistRepositories": ("gistEdges", "gists"),
+}
+
+
+@dataclass
+class MockGitHub:
+    user: str = "octocat"
+    owned: list[dict[str, Any]] = field(default_factory=list)
+    starred: list[dict[str, Any]] = field(default_factory=list)
+    watching: list[dict[str, Any]] = field(default_factory=list)
+    gists: list[dict[str, Any]] = field(default_factory=list)
+    starred_gists: list[dict[str, Any]] = field(default_factory=list)
+    page_size: int = 100
+    archive: bytes = b""
+    export_polls: int = 1
+    token: str = "tok"
+    requests: list[httpx.Request] = field(default_factory=list)
+    migrations: dict[int, dict[str, Any]] = field(default_factory=dict)
+    unlocked: list[tuple[int, str]] = field(default_factory=list)
+    _throttles: list[tuple[str, httpx.Response]] = field(default_factory=list)
+    _polls: dict[int, int] = field(default_factory=dict)
+
+    def throttle(self, prefix: str, *, times: int = 1, retry_after: str = "0") -> None:
+        """Answer the next ``times`` requests under ``prefix`` with a 429."""
+        for _ in range(times):
+            response = httpx.Response(429, headers={"Retry-After": retry_after})
+            self._throttles.append((prefix, response))
+
+    def paths(self, method: str | None = None) -> list[str]:
+        return [r.url.path for r in self.requests if method is None or r.method == method]
+
+    def client(self) -> httpx.AsyncClient:
+        return httpx.AsyncClient(transport=httpx.MockTransport(self.handle))
+
+    def handle(self, request: httpx.Request) -> httpx.Response:
+        self.requests.append(request)
+        path = request.url.path
+        for index, (prefix, response) in enumerate(self._throttles):
+            if path.startswith(prefix):
+                del self._throttles[index]
+                return response
+        if path.endswith("/archive-blob"):
+            return httpx.Response(200, content=self.archive)
+        if request.headers.get("Authorization") != f"Bearer {self.token}":
+            return httpx.Response(401, json={"message": "Bad credentials"})
+        if path == "/graphql" and request.method == "POST":
+            return self._graphql(json.loads(request.content))
+        if path == "/gists/starred":
+            return self._starred_gists(request)
+        if path.startswith("/user/migrations"):
+            return self._migrations(request, path.removeprefix("/user/migrations"))
+        return httpx.Response(404, json={"message": "Not Found"})
+
+    def _graphql(self, body: dict[str, Any]) -> httpx.Response:
+        variables = body.get("variables") or {}
+        if variables.get("login") != self.user:
+            return httpx.Response(200, json={"data": {"user": None}})
+        user: dict[str, Any] = {}
+        for alias, (edges_alias, attribute) in _CONNECTIONS.items():
+            if f"{alias}:" not in body["query"]:
+                continue
+            nodes: list[dict[str, Any]] = getattr(self, attribute)
+            start = int(variables.get("after") or 0)
+            page = nodes[start : start + self.page_size]
+            edges = [
+                {"cursor": str(start + offset + 1), "node": node}
+                for offset, node in enumerate(page)
+            ]
+            end = start + len(page)
+            user[alias] = {
+                edges_alias: edges,
+                "pageInfo": {
+                    "hasNextPage": end < len(nodes),
+                    "endCursor": str(end) if page else None,
+                },
+            }
+        return httpx.Response(200, json={"data": {"user": user}})
+
+    def _starred_gists(self, request: httpx.Request) -> httpx.Response:
+        page = int(request.url.params.get("page", "1"))
+        start = (page - 1) * self.page_size
+        items = self.starred_gists[start : start + self.page_size]
+        headers = {}
+        if start + self.page_size < len(self.starred_gists):
+            next_url = request.url.copy_set_param("page", str(page + 1))
+            headers["Link"] = f'<{next_url}>; rel="next"'
+        return httpx.Response(200, json=items, headers=headers)
+
+    def _migrations(self, request: httpx.Request, rest: str) -> httpx.Response:
+        if rest == "" and request.method == "POST":
+            payload = json.loads(request.content)
+            migration_id = len(self.migrations) + 1
+            self.migrations[migration_id] = {
+                "id": migration_id,
+                "state": "pending",
+                "lock_repositories": bool(payload.get("lock_repositories")),
+                "created_at": "2024-01-01T00:00:00Z",
+                "repositories": [{"full_name": name} for name in payload["repositories"]],
+            }
+            return httpx.Response(201, json=self.migrations[migration_id])
+        if rest == "" and request.method == "GET":
+            return httpx.Response(200, json=list(self.migrations.values()))
+        parts = rest.strip("/").split("/")
+        migration = self.migrations.get(int(parts[0])) if parts[0].isdigit() else None
+        if migration is None:
+            return httpx.Response(404, json={"message": "Not Found"})
+        if len(parts) == 1:
+            polls = self._polls[migration["id"]] = self._polls.get(migration["id"], 0) + 1
+            migration["state"] = "exported" if polls >= self.export_polls else "exporting"
+            return httpx.Response(200, json=migration)
+        if parts[1:] == ["archive"]:
+            blob = request.url.copy_with(path=f"/migrations/{migration['id']}/archive-blob")
+            return httpx.Response(302, headers={"Location": str(blob)})
+        if len(parts) == 4 and parts[1] == "repos" and parts[3] == "lock":
+            self.unlocked.append((migration["id"], parts[2]))
+            return httpx.Response(204)
+        return httpx.Response(404, json={"message": "Not Found"})
+
+    @contextmanager
+    def serve(self) -> Iterator[str]:
+        """Serve the mock on ``127.0.0.1``; yields its ``api_url``."""
+        mock = self
+
+        class Handler(BaseHTTPRequestHandler):
+            def _dispatch(self) -> None:
+                length = int(self.headers.get("Content-Length") or 0)
+                host, port = self.server.server_address[:2]
+                request = httpx.Request(
+                    self.command,
+                    f"http://{host!s}:{port}{self.path}",
+                    headers=dict(self.headers),
+                    content=self.rfile.read(length),
+                )
+                response = mock.handle(request)
+                body = response.content
+                self.send_response(response.status_code)
+                for name, value in response.headers.items():
+                    if name.lower() not in ("content-length", "transfer-encoding"):
+                        self.send_header(name, value)
+                self.send_header("Content-Length", str(len(body)))
+                self.end_headers()
+                self.wfile.write(body)
+
+            do_GET = do_POST = do_DELETE = _dispatch
+
+            def log_message(self, format: str, *args: Any) -> None:
+                return None
+
+        server = ThreadingHTTPServer(("127.0.0.1", 0), Handler)
+        thread = threading.Thread(target=server.serve_forever, daemon=True)
+        thread.start()
+        try:
+            host, port = server.server_address[:2]
+            yield f"http://{host!s}:{port}"
+        finally:
+            server.shutdown()
+            server.server_close()
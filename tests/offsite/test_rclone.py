@@ -0,0 +1,88 @@
+"""Replicating the destination with rclone, against a fake runner."""
+
+from __future__ import annotations
+
+import json
+from datetime import UTC, datetime
+from pathlib import Path
+
+from gitout.config import Rclone
+from gitout.offsite.rclone import (
+    RCLONE_REPORT_FILE,
+    RcloneFailure,
+    parse_failures,
+    rclone_argv,
+    replicate,
+)
+
+
+def _now() -> datetime:
+    return datetime(2026, 1, 2, 3, 4, 5, tzinfo=UTC)
+
+
+def test_argv_turns_excludes_then_includes_into_filters(tmp_path: Path) -> None:
+    config = Rclone(
+        remote="b2:bucket/gitout",
+        mode="copy",
+        include=["bundles/**"],
+        exclude=["bundles/tmp/**"],
+        flags=["--transfers", "8"],
+    )
+    argv = rclone_argv(tmp_path, config)
+    assert argv[:4] == ["rclone", "copy", str(tmp_path), "b2:bucket/gitout"]
+    filters = [argv[i + 1] for i, arg in enumerate(argv) if arg == "--filter"]
+    assert filters == ["- *.tmp", "- bundles/tmp/**", "+ bundles/**", "- **"]
+    assert argv[-2:] == ["--transfers", "8"]
+
+
+def test_parse_failures_keeps_errors_and_unparsed_lines() -> None:
+    output = "\n".join(
+        [
+            json.dumps({"level": "error", "msg": "Failed to copy: 403", "object": "git/a/HEAD"}),
+            json.dumps({"level": "notice", "msg": "ignored"}),
+            json.dumps({"level": "error", "msg": "Attempt 3/3 failed with 1 errors"}),
+            "panic: something odd",
+        ]
+    )
+    assert parse_failures(output) == [
+        RcloneFailure("git/a/HEAD", "Failed to copy: 403"),
+        RcloneFailure("", "Attempt 3/3 failed with 1 errors"),
+        RcloneFailure("", "panic: something odd"),
+    ]
+
+
+async def test_failed_run_is_reported_and_recorded(tmp_path: Path) -> None:
+    calls: list[tuple[list[str], Path, float]] = []
+
+    async def runner(argv: list[str], cwd: Path, timeout: float) -> tuple[int, str]:
+        calls.append((argv, cwd, timeout))
+        return 1, json.dumps({"level": "error", "msg": "denied", "object": "bundles/x.bundle"})
+
+    report = await replicate(tmp_path, Rclone(remote="r:x"), runner, now=_now)
+
+    assert not report.ok
+    assert report.failures == [RcloneFailure("bundles/x.bundle", "denied")]
+    assert calls[0][1:] == (tmp_path, 21600.0)
+    saved = json.loads((tmp_path / RCLONE_REPORT_FILE).read_text())
+    assert saved["ok"] is False and saved["exit_code"] == 1
+    assert saved["failures"] == [{"path": "bundles/x.bundle", "message": "denied"}]
+
+
+async def test_missing_binary_counts_as_a_failure(tmp_path: Path) -> None:
+    async def runner(argv: list[str], cwd: Path, timeout: float) -> tuple[int, str]:
+        raise FileNotFoundError("No such file or directory: 'rclone'")
+
+    report = await replicate(tmp_path, Rclone(remote="r:x"), runner, now=_now)
+
+    assert report.exit_code == -1
+    assert [f.describe() for f in report.failures] == ["No such file or directory: 'rclone'"]
+
+
+async def test_successful_run_has_no_failures(tmp_path: Path) -> None:
+    async def runner(argv: list[str], cwd: Path, timeout: float) -> tuple[int, str]:
+        return 0, ""
+
+    report = await replicate(tmp_path, Rclone(remote="r:x"), runner, now=_now)
+
+    assert report.ok and report.failures == []
+    assert report.started_at == "2026-01-02T03:04:05+00:00"
@@ -0,0 +1,55 @@
+"""rsync replication to SSH targets, against a fake runner."""
+
+from __future__ import annotations
+
+from pathlib import Path
+
+from gitout.config import Replicate
+from gitout.offsite.rsync import ReplicationResult, replicate_to_targets, rsync_argv
+
+
+def test_argv_copies_the_destination_contents_over_ssh(tmp_path: Path) -> None:
+    config = Replicate(
+        targets=["backup@nas2:/srv/gitout"],
+        ssh_command="ssh -p 2222 -o BatchMode=yes",
+        bandwidth_limit_kbps=5000,
+        flags=["--checksum"],
+    )
+    assert rsync_argv(tmp_path, "backup@nas2:/srv/gitout", config) == [
+        "rsync",
+        "--archive",
+        "--partial",
+        "--delay-updates",
+        "--exclude",
+        "*.tmp",
+        "-e",
+        "ssh -p 2222 -o BatchMode=yes",
+        "--delete-delay",
+        "--bwlimit=5000",
+        "--checksum",
+        f"{tmp_path}/",
+        "backup@nas2:/srv/gitout",
+    ]
+    assert "--delete-delay" not in rsync_argv(tmp_path, "h:/x", Replicate(delete=False))
+
+
+async def test_a_failing_target_does_not_stop_the_others(tmp_path: Path) -> None:
+    seen: list[str] = []
+
+    async def runner(argv: list[str], cwd: Path, timeout: float) -> tuple[int, str]:
+        seen.append(argv[-1])
+        if argv[-1].startswith("down:"):
+            raise RuntimeError("git operation timed out after 60.0s")
+        if argv[-1].startswith("full:"):
+            return 11, "rsync: write failed: No space left on device (28)\n"
+        return 0, ""
+
+    config = Replicate(targets=["down:/a", "full:/b", "ok:/c"], timeout_seconds=60)
+    results = await replicate_to_targets(tmp_path, config, runner)
+
+    assert seen == ["down:/a", "full:/b", "ok:/c"]
+    assert results == [
+        ReplicationResult("down:/a", -1, "git operation timed out after 60.0s"),
+        ReplicationResult("full:/b", 11, "rsync: write failed: No space left on device (28)"),
+        ReplicationResult("ok:/c", 0, ""),
+    ]
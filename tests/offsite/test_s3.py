@@ -0,0 +1,76 @@
+"""The S3 backend against a recording client (boto3 is not needed)."""
+
+from __future__ import annotations
+
+import sys
+import types
+from pathlib import Path
+from typing import Any
+
+import pytest
+
+from gitout.config import Offsite, S3Storage
+from gitout.offsite.backend import OffsiteError, build_backend
+from gitout.offsite.s3 import S3Backend
+
+
+class RecordingClient:
+    def __init__(self, error: Exception | None = None) -> None:
+        self.error = error
+        self.calls: list[tuple[Any, ...]] = []
+
+    def upload_file(self, filename: str, bucket: str, key: str, **kwargs: Any) -> None:
+        if self.error is not None:
+            raise self.error
+        self.calls.append((filename, bucket, key, kwargs))
+
+
+def test_uploads_go_under_the_prefix_with_the_storage_class(tmp_path: Path) -> None:
+    client = RecordingClient()
+    transfer = object()
+    config = S3Storage(bucket="backups", prefix="/gitout/", storage_class="STANDARD_IA")
+    backend = S3Backend(config, client=client, transfer=transfer)
+    source = tmp_path / "a.bundle"
+    source.write_text("x")
+
+    backend.upload(source, "bundles/git/a.bundle")
+
+    assert backend.target == "s3://backups/gitout/"
+    assert client.calls == [
+        (
+            str(source),
+            "backups",
+            "gitout/bundles/git/a.bundle",
+            {"ExtraArgs": {"StorageClass": "STANDARD_IA"}, "Config": transfer},
+        )
+    ]
+
+
+def test_client_errors_become_offsite_errors(tmp_path: Path) -> None:
+    client = RecordingClient(error=RuntimeError("AccessDenied"))
+    backend = S3Backend(S3Storage(bucket="b"), client=client, transfer=object())
+    with pytest.raises(OffsiteError, match="AccessDenied"):
+        backend.upload(tmp_path / "x", "x")
+
+
+def test_missing_boto3_is_reported(tmp_path: Path, monkeypatch: pytest.MonkeyPatch) -> None:
+    monkeypatch.setitem(sys.modules, "boto3", None)
+    backend = build_backend(Offsite(s3=S3Storage(bucket="b")), {})
+    with pytest.raises(OffsiteError, match=r"gitout\[s3\]"):
+        backend.upload(tmp_path / "x", "x")
+
+
+def test_client_setup_errors_become_offsite_errors(
+    tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    class Session:
+        def __init__(self, profile_name: str | None = None) -> None:
+            raise RuntimeError(f"The config profile ({profile_name}) could not be found")
+
+    boto3 = types.SimpleNamespace(session=types.SimpleNamespace(Session=Session))
+    monkeypatch.setitem(sys.modules, "boto3", boto3)
+    monkeypatch.setitem(sys.modules, "botocore", types.ModuleType("botocore"))
+    monkeypatch.setitem(sys.modules, "botocore.config", types.SimpleNamespace(Config=dict))
+    backend = S3Backend(S3Storage(bucket="b", profile="gone"))
+    with pytest.raises(OffsiteError, match=r"Cannot create the S3 client.*\(gone\)"):
+        backend.upload(tmp_path / "x", "x")
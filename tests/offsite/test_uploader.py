@@ -0,0 +1,85 @@
+"""Uploading the destination's artifacts to an off-site backend."""
+
+from __future__ import annotations
+
+import json
+import os
+from pathlib import Path
+
+from gitout.config import Offsite
+from gitout.offsite.backend import OffsiteError
+from gitout.offsite.uploader import OFFSITE_STATE_FILE, OffsiteUploader, collect_files
+
+
+class FakeBackend:
+    def __init__(self, target: str = "s3://bucket/", fail: frozenset[str] = frozenset()) -> None:
+        self.target = target
+        self.fail = set(fail)
+        self.uploads: list[tuple[str, bytes]] = []
+
+    def upload(self, source: Path, key: str) -> None:
+        if key in self.fail:
+            raise OffsiteError(f"Uploading {key} failed")
+        self.uploads.append((key, source.read_bytes()))
+
+
+def _write(path: Path, text: str = "x") -> None:
+    path.parent.mkdir(parents=True, exist_ok=True)
+    path.write_text(text)
+
+
+def _destination(tmp_path: Path) -> Path:
+    _write(tmp_path / "bundles" / "git" / "a.bundle")
+    _write(tmp_path / "bundles" / "git" / "b.bundle.tmp")  # a bundle being written
+    _write(tmp_path / "github" / "archive" / "me" / "repo.tar.gz")
+    mirror = tmp_path / "git" / "a"
+    _write(mirror / "HEAD", "ref: refs/heads/main\n")
+    _write(mirror / "objects" / "pack" / "pack-1.pack")
+    _write(tmp_path / ".gitout-sync-state.json", "{}")
+    return tmp_path
+
+
+def test_collect_files_follows_paths_and_mirrors(tmp_path: Path) -> None:
+    dest = _destination(tmp_path)
+    assert collect_files(dest, Offsite()) == [
+        "bundles/git/a.bundle",
+        "github/archive/me/repo.tar.gz",
+    ]
+    assert collect_files(dest, Offsite(paths=[], mirrors=True)) == [
+        "git/a/HEAD",
+        "git/a/objects/pack/pack-1.pack",
+    ]
+
+
+def test_only_new_or_changed_files_are_uploaded_again(tmp_path: Path) -> None:
+    dest = _destination(tmp_path)
+    backend = FakeBackend()
+
+    first = OffsiteUploader(Offsite(), dest, backend).upload()
+    assert (first.uploaded, first.unchanged, first.failed) == (2, 0, 0)
+
+    bundle = dest / "bundles" / "git" / "a.bundle"
+    bundle.write_text("newer")
+    os.utime(bundle, ns=(1, 10**18))
+    second = OffsiteUploader(Offsite(), dest, backend).upload()
+
+    assert (second.uploaded, second.unchanged) == (1, 1)
+    assert backend.uploads[-1] == ("bundles/git/a.bundle", b"newer")
+    state = json.loads((dest / OFFSITE_STATE_FILE).read_text())
+    assert sorted(state["targets"]["s3://bucket/"]) == [
+        "bundles/git/a.bundle",
+        "github/archive/me/repo.tar.gz",
+    ]
+
+
+def test_failed_uploads_are_retried_and_targets_tracked_apart(tmp_path: Path) -> None:
+    dest = _destination(tmp_path)
+    flaky = FakeBackend(fail=frozenset({"bundles/git/a.bundle"}))
+
+    summary = OffsiteUploader(Offsite(), dest, flaky).upload()
+    assert (summary.uploaded, summary.failed) == (1, 1)
+
+    flaky.fail.clear()
+    assert OffsiteUploader(Offsite(), dest, flaky).upload().uploaded == 1
+    other = FakeBackend(target="s3://elsewhere/")
+    assert OffsiteUploader(Offsite(), dest, other).upload().uploaded == 2
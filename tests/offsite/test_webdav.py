@@ -0,0 +1,94 @@
+"""The WebDAV backend against httpx's mock transport."""
+
+from __future__ import annotations
+
+from pathlib import Path
+
+import httpx
+import pytest
+
+from gitout.config import Offsite, WebDav
+from gitout.offsite.backend import OffsiteError, build_backend
+from gitout.offsite.webdav import WebDavBackend, webdav_password
+
+_URL = "https://box.example/dav/gitout"
+
+
+def _backend(
+    handler: httpx.MockTransport, *, max_attempts: int = 3, sleeps: list[float] | None = None
+) -> WebDavBackend:
+    client = httpx.Client(transport=handler, auth=("u", "p"))
+    sleep_log = sleeps if sleeps is not None else []
+    return WebDavBackend(
+        WebDav(url=_URL + "/", max_attempts=max_attempts), client=client, sleep=sleep_log.append
+    )
+
+
+def _file(tmp_path: Path, text: str = "bundle") -> Path:
+    path = tmp_path / "x.bundle"
+    path.write_text(text)
+    return path
+
+
+def test_collections_are_created_once_then_files_put(tmp_path: Path) -> None:
+    requests: list[tuple[str, str, bytes]] = []
+
+    def handle(request: httpx.Request) -> httpx.Response:
+        requests.append((request.method, request.url.raw_path.decode(), request.read()))
+        exists = request.method == "MKCOL" and request.url.path.endswith("/bundles/")
+        return httpx.Response(405 if exists else 201)
+
+    backend = _backend(httpx.MockTransport(handle))
+    backend.upload(_file(tmp_path), "bundles/git/a b.bundle")
+    backend.upload(_file(tmp_path, "other"), "bundles/git/c.bundle")
+
+    assert backend.target == _URL + "/"
+    assert [(method, path) for method, path, _ in requests] == [
+        ("MKCOL", "/dav/gitout/bundles/"),
+        ("MKCOL", "/dav/gitout/bundles/git/"),
+        ("PUT", "/dav/gitout/bundles/git/a%20b.bundle"),
+        ("PUT", "/dav/gitout/bundles/git/c.bundle"),
+    ]
+    assert requests[2][2] == b"bundle"
+
+
+def test_server_errors_are_retried_with_backoff(tmp_path: Path) -> None:
+    answers = iter([503, 502, 201])
+    sleeps: list[float] = []
+    backend = _backend(httpx.MockTransport(lambda r: httpx.Response(next(answers))), sleeps=sleeps)
+
+    backend.upload(_file(tmp_path), "x.bundle")
+
+    assert sleeps == [1.0, 2.0]
+
+
+def test_client_errors_fail_the_file_at_once(tmp_path: Path) -> None:
+    calls: list[str] = []
+
+    def handle(request: httpx.Request) -> httpx.Response:
+        calls.append(request.method)
+        return httpx.Response(507 if len(calls) > 3 else 403)
+
+    backend = _backend(httpx.MockTransport(handle))
+    with pytest.raises(OffsiteError, match="HTTP 403"):
+        backend.upload(_file(tmp_path), "x.bundle")
+    assert calls == ["PUT"]
+
+
+def test_network_errors_give_up_after_max_attempts(tmp_path: Path) -> None:
+    def handle(request: httpx.Request) -> httpx.Response:
+        raise httpx.ConnectError("connection refused", request=request)
+
+    backend = _backend(httpx.MockTransport(handle), max_attempts=2)
+    with pytest.raises(OffsiteError, match="connection refused"):
+        backend.upload(_file(tmp_path), "x.bundle")
+
+
+def test_password_comes_from_the_environment_or_a_file(tmp_path: Path) -> None:
+    secret = tmp_path / "secret"
+    secret.write_text("s3cret\n")
+    assert webdav_password(WebDav(password_file=str(secret)), {}) == "s3cret"
+    assert webdav_password(WebDav(password_env="DAV_PW"), {"DAV_PW": " pw "}) == "pw"
+    assert webdav_password(WebDav(), {}) is None
+    with pytest.raises(OffsiteError, match=r"\$DAV_PW is not set"):
+        build_backend(Offsite(backend="webdav", webdav=WebDav(url=_URL, password_env="DAV_PW")), {})
@@ -0,0 +1,196 @@
+"""``git bundle`` exports of the mirrors."""
+
+from __future__ import annotations
+
+import shutil
+import subprocess
+from datetime import UTC, datetime, timedelta
+from pathlib import Path
+
+import pytest
+
+from gitout.bundle import (
+    BUNDLE_MANIFEST,
+    bundle_path,
+    bundle_root,
+    chain_path,
+    load_chain,
+    write_bundle,
+    write_incremental_bundle,
+)
+
+
+def _git(cwd: Path, *args: str) -> str:
+    return subprocess.run(  # noqa: S603
+        ["git", "-c", "user.name=t", "-c", "user.email=t@e", *args],
+        cwd=cwd,
+        check=True,
+        capture_output=True,
+        text=True,
+    ).stdout.strip()
+
+
+async def _run(argv: list[str], cwd: Path) -> tuple[int, str]:
+    done = subprocess.run(argv, cwd=cwd, capture_output=True, text=True, check=False)  # noqa: S603
+    return done.returncode, done.stdout + done.stderr
+
+
+def test_bundle_paths_mirror_the_destination_layout(tmp_path: Path) -> None:
+    root = bundle_root(tmp_path, "bundles")
+    mirror = tmp_path / "github" / "clone" / "octocat" / "hello"
+    assert bundle_path(root, tmp_path, mirror) == (
+        tmp_path / "bundles" / "github" / "clone" / "octocat" / "hello.bundle"
+    )
+    assert bundle_root(tmp_path, "/srv/bundles") == Path("/srv/bundles")
+    assert chain_path(root, tmp_path, mirror).name == "hello.bundles"
+
+
+@pytest.mark.skipif(shutil.which("git") is None, reason="git not installed")
+async def test_written_bundle_clones_back_to_the_same_refs(tmp_path: Path) -> None:
+    origin = tmp_path / "origin"
+    origin.mkdir()
+    _git(origin, "init", "-q", "-b", "main")
+    _git(origin, "commit", "-q", "--allow-empty", "-m", "one")
+    _git(origin, "tag", "v1")
+    target = tmp_path / "bundles" / "x.bundle"
+
+    assert await write_bundle(["git"], origin, target, _run, verify=True)
+
+    assert sorted(p.name for p in target.parent.iterdir()) == ["x.bundle"]
+    _git(tmp_path, "clone", "-q", "--mirror", str(target), "restored")
+    restored = tmp_path / "restored"
+    assert _git(restored, "rev-parse", "main") == _git(origin, "rev-parse", "main")
+    assert _git(restored, "tag") == "v1"
+
+
+@pytest.mark.skipif(shutil.which("git") is None, reason="git not installed")
+async def test_empty_repository_has_nothing_to_bundle(tmp_path: Path) -> None:
+    _git(tmp_path, "init", "-q", "--bare", "empty")
+    target = tmp_path / "empty.bundle"
+    assert await write_bundle(["git"], tmp_path / "empty", target, _run, verify=True)
+    assert not target.exists()
+    assert not (tmp_path / "empty.bundle.tmp").exists()
+
+
+async def test_a_bundle_that_does_not_verify_keeps_the_old_one(tmp_path: Path) -> None:
+    target = tmp_path / "x.bundle"
+    target.write_text("previous")
+    calls: list[list[str]] = []
+
+    async def run(argv: list[str], cwd: Path) -> tuple[int, str]:
+        calls.append(argv)
+        if argv[1] == "bundle" and argv[2] == "create":
+            Path(argv[4]).write_text("new")
+            return 0, ""
+        return 1, "error: unrecognized header"
+
+    assert not await write_bundle(["git"], tmp_path, target, run, verify=True)
+    assert target.read_text() == "previous"
+    assert [argv[2] for argv in calls] == ["create", "verify"]
+    assert not (tmp_path / "x.bundle.tmp").exists()
+
+
+class _Chain:
+    """A work repository whose incremental bundle chain is written one sync at a time."""
+
+    def __init__(self, tmp_path: Path, full_every: int = 0) -> None:
+        self.repo = tmp_path / "repo"
+        self.repo.mkdir()
+        _git(self.repo, "init", "-q", "-b", "main")
+        self.directory = tmp_path / "x.bundles"
+        self.full_every = full_every
+        self.now = datetime(2026, 1, 1, tzinfo=UTC)
+
+    def commit(self, message: str) -> str:
+        _git(self.repo, "commit", "-q", "--allow-empty", "-m", message)
+        return _git(self.repo, "rev-parse", "HEAD")
+
+    async def bundle(self) -> bool:
+        self.now += timedelta(hours=1)
+        return await write_incremental_bundle(
+            ["git"],
+            self.repo,
+            self.directory,
+            _run,
+            verify=True,
+            full_every=self.full_every,
+            now=self.now,
+        )
+
+    def restore(self, into: Path) -> str:
+        _git(into.parent, "init", "-q", "--bare", into.name)
+        for entry in load_chain(self.directory):
+            if entry.file is not None:
+                _git(into, "fetch", "-q", str(self.directory / entry.file), "refs/*:refs/*")
+        return _git(into, "rev-parse", "main")
+
+
+@pytest.mark.skipif(shutil.which("git") is None, reason="git not installed")
+async def test_incremental_chain_ships_only_new_objects(tmp_path: Path) -> None:
+    chain = _Chain(tmp_path)
+    assert await chain.bundle()  # nothing to bundle yet
+    assert not chain.directory.exists()
+
+    first = chain.commit("one")
+    assert await chain.bundle()
+    assert await chain.bundle()  # unchanged refs add nothing
+    second = chain.commit("two")
+    assert await chain.bundle()
+
+    entries = load_chain(chain.directory)
+    assert [e.kind for e in entries] == ["full", "incremental"]
+    assert entries[1].prerequisites == [first]
+    assert entries[1].refs == {"refs/heads/main": second}
+    assert sorted(p.name for p in chain.directory.iterdir()) == sorted(
+        [BUNDLE_MANIFEST, *(e.file for e in entries if e.file)]
+    )
+    assert chain.restore(tmp_path / "restored") == second
+
+
+@pytest.mark.skipif(shutil.which("git") is None, reason="git not installed")
+async def test_deleted_refs_are_recorded_without_a_bundle(tmp_path: Path) -> None:
+    chain = _Chain(tmp_path)
+    chain.commit("one")
+    _git(chain.repo, "branch", "topic")
+    assert await chain.bundle()
+    _git(chain.repo, "branch", "-D", "topic")
+    assert await chain.bundle()
+
+    last = load_chain(chain.directory)[-1]
+    assert last.file is None
+    assert list(last.refs) == ["refs/heads/main"]
+
+
+@pytest.mark.skipif(shutil.which("git") is None, reason="git not installed")
+async def test_a_new_chain_starts_after_full_every_and_drops_the_old_one(tmp_path: Path) -> None:
+    chain = _Chain(tmp_path, full_every=1)
+    chain.commit("one")
+    assert await chain.bundle()
+    chain.commit("two")
+    assert await chain.bundle()
+    old_files = {e.file for e in load_chain(chain.directory)}
+    third = chain.commit("three")
+    assert await chain.bundle()
+
+    entries = load_chain(chain.directory)
+    assert [e.kind for e in entries] == ["full"]
+    assert not old_files & {p.name for p in chain.directory.iterdir()}
+    assert chain.restore(tmp_path / "restored") == third
+
+
+@pytest.mark.skipif(shutil.which("git") is None, reason="git not installed")
+async def test_a_vanished_tip_starts_a_new_chain(tmp_path: Path) -> None:
+    chain = _Chain(tmp_path)
+    chain.commit("one")
+    assert await chain.bundle()
+    _git(chain.repo, "checkout", "-q", "--orphan", "fresh")
+    _git(chain.repo, "branch", "-D", "main")
+    _git(chain.repo, "branch", "-m", "main")
+    rewritten = chain.commit("rewritten")
+    _git(chain.repo, "reflog", "expire", "--expire=now", "--all")
+    _git(chain.repo, "gc", "-q", "--prune=now")
+
+    assert await chain.bundle()
+
+    assert [e.kind for e in load_chain(chain.directory)] == ["full"]
+    assert chain.restore(tmp_path / "restored") == rewritten
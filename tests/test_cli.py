@@ -2,15 +2,25 @@
 
 from __future__ import annotations
 
+import functools
 import logging
 from pathlib import Path
+from typing import Any
 
+import httpx
 import pytest
 from typer.testing import CliRunner
 
-from gitout import cli
+from gitout import cli, verify
 from gitout.cli import _configure_logging
+from gitout.device_flow import DeviceCode
+from gitout.doctor import Diagnosis
+from gitout.engine import SyncOutcome, SyncTask
 from gitout.github import RepositoryMetadata, UserRepositories
+from gitout.log_format import JsonFormatter
+from gitout.restore import RestoreError, RestoreResult
+from gitout.token_check import TokenCheckError
+from tests.helpers import fake_keyring
 
 runner = CliRunner()
 
@@ -30,12 +40,13 @@ def test_dry_run_git_only(tmp_path: Path) -> None:
     assert result.exit_code == 0, result.output
     assert "DRY RUN" in result.output
     assert "clone --mirror -- https://example.com/x.git mirror" in result.output
+    assert "ESTIMATE 1 clones, 0 updates" in result.output
 
 
 def test_invalid_config_exits_nonzero(tmp_path: Path) -> None:
     config = _write_config(tmp_path, "version = 1\n[search]\nenabled = true\ntop_k = 0\n")
     result = runner.invoke(cli.app, ["sync", str(config), str(tmp_path / "dest"), "--dry-run"])
-    assert result.exit_code == 1
+    assert result.exit_code == cli.EXIT_CONFIG_ERROR
     assert "search.top_k must be between 1 and 100, got 0" in result.output
 
 
@@ -60,7 +71,11 @@ def test_dry_run_github(tmp_path: Path, monkeypatch: pytest.MonkeyPatch) -> None
             },
         )
 
+    async def fake_check(token: str, required: object, **options: object) -> str:
+        return "me"
+
     monkeypatch.setattr(cli, "load_repositories", fake_loader)
+    monkeypatch.setattr(cli, "check_token", fake_check)
     config = _write_config(
         tmp_path,
         'version = 0\n[github]\nuser = "me"\ntoken = "tok"\n[github.clone]\ngists = true\n',
@@ -71,6 +86,23 @@ def test_dry_run_github(tmp_path: Path, monkeypatch: pytest.MonkeyPatch) -> None
     assert "https://gist.github.com/g1.git" in result.output
 
 
+def test_rejected_token_exits_before_discovery(
+    tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    async def fake_loader(user: str, token: str) -> UserRepositories:
+        raise AssertionError("discovery must not run with a rejected token")
+
+    async def fake_check(token: str, required: object, **options: object) -> str:
+        raise TokenCheckError("GitHub rejected the token (401 Bad credentials).")
+
+    monkeypatch.setattr(cli, "load_repositories", fake_loader)
+    monkeypatch.setattr(cli, "check_token", fake_check)
+    config = _write_config(tmp_path, 'version = 0\n[github]\nuser = "me"\ntoken = "bad"\n')
+    result = runner.invoke(cli.app, ["sync", str(config), str(tmp_path), "--dry-run"])
+    assert result.exit_code == cli.EXIT_TOTAL_FAILURE
+    assert "401 Bad credentials" in result.output
+
+
 def test_search_not_enabled(tmp_path: Path) -> None:
     config = _write_config(tmp_path, "version = 0\n")
     result = runner.invoke(cli.app, ["search", "kotlin", str(config), str(tmp_path)])
@@ -107,6 +139,749 @@ def test_configure_logging_levels() -> None:
         # quiet wins over verbose
         _configure_logging(verbose=1, quiet=True)
         assert root.level == logging.WARNING
+
+        root.handlers = []
+        _configure_logging(verbose=0, quiet=False, log_format="json")
+        (handler,) = root.handlers
+        assert isinstance(handler.formatter, JsonFormatter)
     finally:
         root.level = original_level
         root.handlers = original_handlers
+
+
+def test_state_export_then_import(tmp_path: Path) -> None:
+    config = _write_config(tmp_path, "version = 0\n")
+    source = tmp_path / "old"
+    (source / "github").mkdir(parents=True)
+    (source / "github" / ".gitout-state.json").write_text('{"version": 1}')
+    export_file = tmp_path / "state.json"
+
+    result = runner.invoke(cli.app, ["state", "export", str(config), str(source), str(export_file)])
+    assert result.exit_code == 0, result.output
+    assert "Exported 1 state file(s)" in result.output
+
+    target = tmp_path / "new"
+    result = runner.invoke(cli.app, ["state", "import", str(target), str(export_file)])
+    assert result.exit_code == 0, result.output
+    assert (target / "github" / ".gitout-state.json").exists()
+
+    # A second import without --force refuses to clobber the now-present state.
+    result = runner.invoke(cli.app, ["state", "import", str(target), str(export_file)])
+    assert result.exit_code == 1
+
+
+def test_auth_set_and_delete_use_the_keyring(monkeypatch: pytest.MonkeyPatch) -> None:
+    store = fake_keyring(monkeypatch)
+
+    result = runner.invoke(cli.app, ["auth", "set", "github", "--stdin"], input=" s3cret\n")
+    assert result.exit_code == 0, result.output
+    assert store == {("gitout", "github"): "s3cret"}
+    assert "s3cret" not in result.output
+
+    result = runner.invoke(cli.app, ["auth", "delete", "github"])
+    assert result.exit_code == 0, result.output
+    assert store == {}
+
+    result = runner.invoke(cli.app, ["auth", "set", "gitlab", "--stdin"], input="tok\n")
+    assert result.exit_code == 1
+
+
+def test_login_writes_an_owner_only_token_file(
+    tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    async def fake_login(client_id: str, on_code: Any, **kwargs: Any) -> str:
+        assert client_id == "client-1"
+        assert kwargs["scopes"] == ("repo",)
+        on_code(DeviceCode("dev", "ABCD-1234", "https://github.com/login/device", 5.0, 900.0))
+        return "gho_token"
+
+    monkeypatch.setattr(cli, "device_login", fake_login)
+    token_file = tmp_path / "secrets" / "github_token"
+    args = ["login", "--store", "file", "--token-file", str(token_file), "--scope", "repo"]
+    result = runner.invoke(cli.app, args, env={"GITOUT_OAUTH_CLIENT_ID": "client-1"})
+
+    assert result.exit_code == 0, result.output
+    assert "enter the code ABCD-1234" in result.output
+    assert token_file.read_text() == "gho_token\n"
+    assert token_file.stat().st_mode & 0o777 == 0o600
+
+    result = runner.invoke(cli.app, ["login", "--client-id", "x", "--store", "file"])
+    assert result.exit_code == 1
+    assert "--token-file" in result.output
+
+
+def test_destination_conflict_exits_with_message(tmp_path: Path) -> None:
+    config = _write_config(
+        tmp_path,
+        'version = 0\n[git.repos]\nmirror = "https://a.example/x.git"\n'
+        'Mirror = "https://b.example/y.git"\n',
+    )
+    result = runner.invoke(cli.app, ["sync", str(config), str(tmp_path / "dest"), "--dry-run"])
+    assert result.exit_code == cli.EXIT_CONFIG_ERROR
+    assert "same destination" in result.output
+
+
+def test_cron_builds_fresh_engine_per_cycle(
+    tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    config = _write_config(tmp_path, 'version = 0\n[git.repos]\nx = "https://e.example/x.git"\n')
+    engines: list[object] = []
+    budgets: list[int | None] = []
+
+    class FakeEngine:
+        def __init__(self, **kwargs: object) -> None:
+            engines.append(self)
+
+        async def perform_sync(self, dry_run: bool = False) -> list[object]:
+            return []
+
+    async def fake_run_cron(expression: str, action: Any, **kwargs: Any) -> None:
+        for _ in range(3):
+            await action()
+
+    monkeypatch.setattr(cli, "Engine", FakeEngine)
+    monkeypatch.setattr(cli, "run_cron", fake_run_cron)
+    monkeypatch.setattr(cli, "end_cycle", lambda max_rss: budgets.append(max_rss))
+    result = runner.invoke(
+        cli.app,
+        ["sync", str(config), str(tmp_path), "--cron", "* * * * *", "--max-rss", "1G"],
+    )
+    assert result.exit_code == 0, result.output
+    assert len(engines) == 3 and len({id(e) for e in engines}) == 3
+    assert budgets == [1024**3] * 3
+
+
+def test_interval_accepts_a_duration(tmp_path: Path, monkeypatch: pytest.MonkeyPatch) -> None:
+    config = _write_config(tmp_path, 'version = 0\n[git.repos]\nx = "https://e.example/x.git"\n')
+    intervals: list[float] = []
+    timeouts: list[object] = []
+
+    class FakeEngine:
+        def __init__(self, **kwargs: object) -> None:
+            timeouts.append(kwargs["timeout_seconds"])
+
+        async def perform_sync(self, dry_run: bool = False) -> list[object]:
+            return []
+
+    async def fake_run_interval(seconds: float, action: Any, **kwargs: Any) -> None:
+        intervals.append(seconds)
+        await action()
+
+    monkeypatch.setattr(cli, "Engine", FakeEngine)
+    monkeypatch.setattr(cli, "run_interval", fake_run_interval)
+    monkeypatch.setattr(cli, "end_cycle", lambda max_rss: None)
+    args = ["sync", str(config), str(tmp_path), "--interval", "1h30m", "--timeout", "10m"]
+    result = runner.invoke(cli.app, args)
+    assert result.exit_code == 0, result.output
+    assert "Running every 1h30m" in result.output
+    assert intervals == [5400.0]
+    assert timeouts == [600.0]
+
+    result = runner.invoke(cli.app, [*args, "--cron", "0 * * * *"])
+    assert result.exit_code == cli.EXIT_USAGE_ERROR
+    assert "mutually exclusive" in result.output
+
+    result = runner.invoke(cli.app, ["sync", str(config), str(tmp_path), "--interval", "soon"])
+    assert result.exit_code == 2
+    assert "soon" in result.output
+
+
+def test_verify_cron_runs_a_second_schedule(
+    tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    config = _write_config(tmp_path, "version = 0\n")
+    schedules: list[str] = []
+    passes: list[Path] = []
+
+    async def fake_run_cron(expression: str, action: Any, **kwargs: Any) -> None:
+        schedules.append(expression)
+        await action()
+
+    async def fake_pass(cfg: object, destination: Path, **kwargs: Any) -> list[object]:
+        passes.append(destination)
+        return []
+
+    monkeypatch.setattr(cli, "run_cron", fake_run_cron)
+    monkeypatch.setattr(cli, "run_verification_pass", fake_pass)
+    monkeypatch.setattr(cli, "end_cycle", lambda max_rss: None)
+    args = ["sync", str(config), str(tmp_path), "--verify-cron", "0 3 * * 0"]
+
+    result = runner.invoke(cli.app, args)
+    assert result.exit_code == cli.EXIT_USAGE_ERROR
+    assert "--verify-cron needs --cron" in result.output
+
+    result = runner.invoke(cli.app, [*args, "--cron", "0 * * * *"])
+    assert result.exit_code == 0, result.output
+    assert schedules == ["0 * * * *", "0 3 * * 0"]
+    assert passes == [tmp_path]
+
+
+def test_invalid_max_rss_exits_nonzero(tmp_path: Path) -> None:
+    config = _write_config(tmp_path, "version = 0\n")
+    result = runner.invoke(cli.app, ["sync", str(config), str(tmp_path), "--max-rss", "lots"])
+    assert result.exit_code == cli.EXIT_USAGE_ERROR
+    assert "Invalid size" in result.output
+
+
+def test_config_sha256_pin_mismatch_exits_nonzero(tmp_path: Path) -> None:
+    config = _write_config(tmp_path, "version = 0\n")
+    result = runner.invoke(
+        cli.app,
+        ["sync", str(config), str(tmp_path), "--dry-run", "--config-sha256", "ab" * 32],
+    )
+    assert result.exit_code == cli.EXIT_CONFIG_ERROR
+    assert "SHA-256 mismatch" in result.output
+
+
+def test_missing_push_mirror_password_is_a_config_error(
+    tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    monkeypatch.delenv("GITEA_TOKEN", raising=False)
+    monkeypatch.setenv("GITOUT_FAKE_SECRET", "do-not-print")
+    config = _write_config(
+        tmp_path,
+        'version = 0\n[push_mirror]\nurl = "https://gitea.example/b/{repo}.git"\n'
+        'username = "gitout"\npassword_env = "GITEA_TOKEN"\n',
+    )
+    result = runner.invoke(cli.app, ["sync", str(config), str(tmp_path)])
+    assert result.exit_code == cli.EXIT_CONFIG_ERROR
+    assert "push_mirror: $GITEA_TOKEN is not set" in result.output
+    assert "do-not-print" not in result.output
+
+
+def test_unreadable_git_repo_password_file_is_a_config_error(tmp_path: Path) -> None:
+    config = _write_config(
+        tmp_path,
+        'version = 0\n[git.repos.mirror]\nurl = "https://example.com/x.git"\n'
+        f'username = "me"\npassword_file = "{tmp_path / "missing"}"\n',
+    )
+    result = runner.invoke(cli.app, ["sync", str(config), str(tmp_path)])
+    assert result.exit_code == cli.EXIT_CONFIG_ERROR
+    assert 'git.repos "mirror": cannot read' in result.output
+    assert "Traceback" not in result.output
+
+
+def test_missing_destination_is_a_config_error(tmp_path: Path) -> None:
+    config = _write_config(tmp_path, 'version = 0\n[git.repos]\nx = "https://e.example/x.git"\n')
+    result = runner.invoke(cli.app, ["sync", str(config), str(tmp_path / "missing")])
+    assert result.exit_code == cli.EXIT_CONFIG_ERROR
+    assert "Destination must exist and must be a directory" in result.output
+
+
+def test_dry_run_with_json_config(tmp_path: Path) -> None:
+    config = tmp_path / "config.json"
+    config.write_text('{"version": 0, "git": {"repos": {"mirror": "https://example.com/x.git"}}}')
+    result = runner.invoke(cli.app, ["sync", str(config), str(tmp_path / "dest"), "--dry-run"])
+    assert result.exit_code == 0, result.output
+    assert "clone --mirror -- https://example.com/x.git mirror" in result.output
+
+
+def test_unparseable_config_exits_nonzero(tmp_path: Path) -> None:
+    config = tmp_path / "config.json"
+    config.write_text("[1, 2]")
+    result = runner.invoke(cli.app, ["sync", str(config), str(tmp_path), "--dry-run"])
+    assert result.exit_code == cli.EXIT_CONFIG_ERROR
+    assert "Invalid configuration" in result.output
+
+
+def test_export_frontend_config(tmp_path: Path) -> None:
+    repo = tmp_path / "git" / "mirror"
+    (repo / "objects").mkdir(parents=True)
+    (repo / "HEAD").write_text("ref: refs/heads/main\n")
+
+    result = runner.invoke(cli.app, ["export", "gitolite", str(tmp_path)])
+    assert result.exit_code == 0, result.output
+    assert result.output == "repo git/mirror\n    R = @all\n\n"
+
+    result = runner.invoke(cli.app, ["export", "gitweb", str(tmp_path)])
+    assert result.exit_code == 1
+
+
+def test_tui_runs_a_sync_with_the_dashboard(tmp_path: Path) -> None:
+    config = _write_config(tmp_path, "version = 0\n")
+    dest = tmp_path / "dest"
+    dest.mkdir()
+    result = runner.invoke(cli.app, ["tui", str(config), str(dest)])
+    assert result.exit_code == 0, result.output
+    assert "Synced 0/0 repositories." in result.output
+
+
+def test_sync_reports_each_fanout_destination(tmp_path: Path) -> None:
+    config = _write_config(tmp_path, "version = 0\n")
+    dest = tmp_path / "dest"
+    dest.mkdir()
+    (dest / "notes.txt").write_text("kept in every copy")
+    copy = tmp_path / "nas"
+    result = runner.invoke(
+        cli.app,
+        ["sync", str(config), str(dest), "--also", str(copy), "--also", str(dest / "inside")],
+    )
+    assert result.exit_code == 1, result.output
+    assert "Synced 0/0 repositories." in result.output
+    assert f"Copied to {copy}: " in result.output
+    assert f"FAILED copy to {dest / 'inside'}: " in result.output
+    assert (copy / "notes.txt").read_text() == "kept in every copy"
+
+def test_unknown_group_exits_nonzero(tmp_path: Path) -> None:
+    config = _write_config(tmp_path, 'version = 0\n[groups.work]\nrepos = ["a"]\n')
+    result = runner.invoke(
+        cli.app, ["sync", str(config), str(tmp_path), "--dry-run", "--group", "play"]
+    )
+    assert result.exit_code == cli.EXIT_CONFIG_ERROR
+    assert "Unknown group(s): play" in result.output
+
+
+def test_unlock_requires_github_section(tmp_path: Path) -> None:
+    config = _write_config(tmp_path, "version = 0\n")
+    result = runner.invoke(cli.app, ["unlock", str(config)])
+    assert result.exit_code == 1
+    assert "nothing to unlock" in result.output
+
+
+def test_unlock_releases_the_migrations_of_every_token(
+    tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    config = _write_config(tmp_path, 'version = 0\n[github]\nuser = "me"\ntoken = ["a", "b"]\n')
+
+    def handle(request: httpx.Request) -> httpx.Response:
+        token = request.headers["Authorization"].removeprefix("Bearer ")
+        if request.method == "DELETE":
+            return httpx.Response(204)
+        migration = {
+            "id": 1 if token == "a" else 2,
+            "lock_repositories": True,
+            "repositories": [{"full_name": f"me/{token}"}],
+        }
+        return httpx.Response(200, json=[migration])
+
+    client = httpx.AsyncClient(transport=httpx.MockTransport(handle))
+    monkeypatch.setattr(
+        cli, "MigrationArchiver", functools.partial(cli.MigrationArchiver, client=client)
+    )
+    result = runner.invoke(cli.app, ["unlock", str(config)])
+    assert result.exit_code == 0, result.output
+    assert "Unlocked me/a" in result.output
+    assert "Unlocked me/b" in result.output
+
+
+def test_verify_reports_each_mirror_and_fails_on_corruption(
+    tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    repo = tmp_path / "git" / "mirror"
+    (repo / "objects").mkdir(parents=True)
+    (repo / "HEAD").write_text("ref: refs/heads/main\n")
+
+    async def fsck(argv: list[str], cwd: Path, timeout: float) -> tuple[int, str]:
+        return (1, "missing blob 1234\n") if "--connectivity-only" in argv else (0, "")
+
+    monkeypatch.setattr(
+        cli,
+        "verify_destination",
+        functools.partial(verify.verify_destination, git_runner=fsck),
+    )
+    result = runner.invoke(cli.app, ["verify", str(tmp_path)])
+    assert result.exit_code == 0, result.output
+    assert "OK git/mirror" in result.output
+    assert "Verified 1/1 repositories." in result.output
+
+    result = runner.invoke(cli.app, ["verify", str(tmp_path), "--connectivity-only"])
+    assert result.exit_code == 1
+    assert "missing blob 1234" in result.output
+    assert "Verified 0/1 repositories." in result.output
+
+    result = runner.invoke(
+        cli.app, ["verify", str(tmp_path), "--connectivity-only", "--quarantine"]
+    )
+    assert result.exit_code == 1
+    assert "the next sync clones it again" in result.output
+    assert not repo.exists()
+    assert [p.name.startswith("mirror.corrupt-") for p in repo.parent.iterdir()] == [True]
+
+
+def test_verify_never_quarantines_a_mirror_whose_check_timed_out(
+    tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    repo = tmp_path / "git" / "mirror"
+    (repo / "objects").mkdir(parents=True)
+    (repo / "HEAD").write_text("ref: refs/heads/main\n")
+
+    async def fsck(argv: list[str], cwd: Path, timeout: float) -> tuple[int, str]:
+        raise RuntimeError(f"git operation timed out after {timeout}s")
+
+    monkeypatch.setattr(
+        cli,
+        "verify_destination",
+        functools.partial(verify.verify_destination, git_runner=fsck),
+    )
+    result = runner.invoke(cli.app, ["verify", str(tmp_path), "--quarantine"])
+    assert result.exit_code == 1
+    assert "Kept git/mirror" in result.output
+    assert repo.is_dir()
+
+
+def test_restore_prints_what_was_pushed_and_fails_on_errors(
+    tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    calls: list[tuple[Path, str, dict[str, Any]]] = []
+
+    class FakeRestorer:
+        def __init__(self, **_: Any) -> None:
+            pass
+
+        async def restore(self, source: Path, url: str, **options: Any) -> RestoreResult:
+            calls.append((source, url, options))
+            if url == "bad":
+                raise RestoreError("git push failed: denied")
+            return RestoreResult("bundle", 3, 2)
+
+    monkeypatch.setattr(cli, "Restorer", FakeRestorer)
+    bundle = tmp_path / "hello.bundle"
+    bundle.write_bytes(b"")
+
+    result = runner.invoke(cli.app, ["restore", str(bundle), "ssh://new/hello", "--force"])
+    assert result.exit_code == 0, result.output
+    assert "Restored 3 branches and 2 tags to ssh://new/hello." in result.output
+    assert calls[0][2]["force"] is True
+
+    result = runner.invoke(cli.app, ["restore", str(bundle), "bad"])
+    assert result.exit_code == 1
+    assert "git push failed: denied" in result.output
+
+
+def test_serve_rejects_an_invalid_bind_address(tmp_path: Path) -> None:
+    result = runner.invoke(cli.app, ["serve", str(tmp_path), "--bind", "localhost"])
+    assert result.exit_code == 1
+    assert "expected HOST:PORT" in result.output
+
+
+def test_check_config_reports_unknown_keys_and_errors(tmp_path: Path) -> None:
+    config = _write_config(tmp_path, "version = 0\n[fetch]\npruen = true\n")
+    result = runner.invoke(cli.app, ["check-config", str(config)])
+    assert result.exit_code == 0, result.output
+    assert "WARNING Unknown key fetch.pruen (ignored)" in result.output
+    assert "is valid (1 warning(s))." in result.output
+
+    result = runner.invoke(cli.app, ["check-config", str(config), "--strict"])
+    assert result.exit_code == 1
+
+    config.write_text('version = 0\n[git.repos]\nmirror = "nope"\n')
+    result = runner.invoke(cli.app, ["check-config", str(config)])
+    assert result.exit_code == 1
+    assert "1 error(s), 0 warning(s)." in result.output
+
+
+def test_doctor_prints_a_report_and_fails_on_failed_checks(
+    monkeypatch: pytest.MonkeyPatch,
+) -> None:
+    results = [Diagnosis("git", "ok", "git version 2.45.0")]
+
+    async def fake_diagnose(config: Any, destination: Any, **_: Any) -> list[Diagnosis]:
+        return results
+
+    monkeypatch.setattr(cli, "diagnose", fake_diagnose)
+    result = runner.invoke(cli.app, ["doctor"])
+    assert result.exit_code == 0, result.output
+    assert "OK      git         git version 2.45.0" in result.output
+    assert "All checks passed." in result.output
+
+    results.append(Diagnosis("network", "fail", "https://api.github.com is unreachable"))
+    result = runner.invoke(cli.app, ["doctor"])
+    assert result.exit_code == 1
+    assert "FAIL    network     https://api.github.com is unreachable" in result.output
+    assert "1 check(s) failed." in result.output
+
+
+def test_man_writes_the_page_to_a_file(tmp_path: Path) -> None:
+    target = tmp_path / "gitout.1"
+    result = runner.invoke(cli.app, ["man", "--output", str(target)])
+    assert result.exit_code == 0, result.output
+    assert target.read_text().startswith(".TH GITOUT 1 ")
+    assert ".SS gitout man" in runner.invoke(cli.app, ["man"]).output
+
+
+def test_dry_run_only_one_repository(tmp_path: Path) -> None:
+    config = _write_config(
+        tmp_path,
+        'version = 0\n[git.repos]\na = "https://example.com/a.git"\n'
+        'b = "https://example.com/b.git"\n',
+    )
+    dest = str(tmp_path / "dest")
+    result = runner.invoke(cli.app, ["sync", str(config), dest, "--dry-run", "--only", "b"])
+    assert result.exit_code == 0, result.output
+    assert "https://example.com/b.git b" in result.output
+    assert "a.git" not in result.output
+
+    result = runner.invoke(cli.app, ["sync", str(config), dest, "--dry-run", "--only", "c"])
+    assert result.exit_code == cli.EXIT_CONFIG_ERROR
+    assert "No repository named c to sync" in result.output
+
+
+def test_dry_run_filter_and_exclude(tmp_path: Path) -> None:
+    config = _write_config(
+        tmp_path,
+        'version = 0\n[git.repos]\nwork-a = "https://example.com/a.git"\n'
+        'work-b = "https://example.com/b.git"\nhome = "https://example.com/h.git"\n',
+    )
+    dest = str(tmp_path / "dest")
+    args = ["sync", str(config), dest, "--dry-run", "--filter", "work-*", "--exclude", "*-b"]
+    result = runner.invoke(cli.app, args)
+    assert result.exit_code == 0, result.output
+    assert "a.git work-a" in result.output
+    assert "b.git" not in result.output
+    assert "h.git" not in result.output
+
+    result = runner.invoke(cli.app, ["sync", str(config), dest, "--dry-run", "--filter", "re:("])
+    assert result.exit_code == cli.EXIT_USAGE_ERROR
+    assert "Invalid pattern(s): re:(" in result.output
+
+
+def test_dry_run_clone_overrides(tmp_path: Path, monkeypatch: pytest.MonkeyPatch) -> None:
+    async def fake_loader(user: str, token: str) -> UserRepositories:
+        return UserRepositories(
+            owned={"me/repo"},
+            starred={"other/star"},
+            watching=set(),
+            gists={"g1"},
+            metadata={},
+        )
+
+    async def fake_check(token: str, required: object, **options: object) -> str:
+        return "me"
+
+    monkeypatch.setattr(cli, "load_repositories", fake_loader)
+    monkeypatch.setattr(cli, "check_token", fake_check)
+    config = _write_config(
+        tmp_path,
+        'version = 0\n[github]\nuser = "me"\ntoken = "tok"\n[github.clone]\ngists = true\n',
+    )
+    dest = str(tmp_path / "dest")
+    args = ["sync", str(config), dest, "--dry-run", "--no-gists", "--no-owned", "--starred"]
+    result = runner.invoke(cli.app, args)
+    assert result.exit_code == 0, result.output
+    assert "https://github.com/other/star.git" in result.output
+    assert "me/repo" not in result.output
+    assert "g1" not in result.output
+
+    git_only = _write_config(tmp_path, 'version = 0\n[git.repos]\na = "https://example.com/a"\n')
+    result = runner.invoke(cli.app, ["sync", str(git_only), dest, "--dry-run", "--no-starred"])
+    assert result.exit_code == cli.EXIT_CONFIG_ERROR
+    assert "--no-starred: the config has no [github] section" in result.output
+
+
+def test_sync_is_configurable_through_the_environment(tmp_path: Path) -> None:
+    config = _write_config(
+        tmp_path,
+        'version = 0\n[git.repos]\nwork-a = "https://example.com/a.git"\n'
+        'work-b = "https://example.com/b.git"\n',
+    )
+    env = {
+        "GITOUT_CONFIG": str(config),
+        "GITOUT_DEST": str(tmp_path / "dest"),
+        "GITOUT_DRY_RUN": "1",
+        "GITOUT_EXCLUDE": "*-b",
+        "GITOUT_TIMEOUT": "10m",
+    }
+    result = runner.invoke(cli.app, ["sync"], env=env)
+    assert result.exit_code == 0, result.output
+    assert "a.git work-a" in result.output
+    assert "b.git" not in result.output
+
+
+def test_runtime_table_supplies_defaults_that_flags_override(
+    tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    config = _write_config(
+        tmp_path,
+        'version = 0\n[git.repos]\nx = "https://e.example/x.git"\n'
+        '[runtime]\nworkers = 8\ninterval = "6h"\ntimeout = "15m"\n'
+        'retry_attempts = 2\nretry_delay = "1s"\nretry_backoff = "constant"\n',
+    )
+    engines: list[dict[str, Any]] = []
+    intervals: list[float] = []
+
+    class FakeEngine:
+        fanout_results: list[object] = []
+        aborted = False
+
+        def __init__(self, **kwargs: Any) -> None:
+            engines.append(kwargs)
+
+        async def perform_sync(self, dry_run: bool = False) -> list[object]:
+            return []
+
+    async def fake_run_interval(seconds: float, action: Any, **kwargs: Any) -> None:
+        intervals.append(seconds)
+        await action()
+
+    monkeypatch.setattr(cli, "Engine", FakeEngine)
+    monkeypatch.setattr(cli, "run_interval", fake_run_interval)
+    monkeypatch.setattr(cli, "end_cycle", lambda max_rss: None)
+    result = runner.invoke(cli.app, ["sync", str(config), str(tmp_path)])
+    assert result.exit_code == 0, result.output
+    assert intervals == [21600.0]
+    assert engines[0]["workers"] == 8
+    assert engines[0]["timeout_seconds"] == 900.0
+    policy = engines[0]["retry_policy"]
+    assert (policy.max_attempts, policy.base_delay_ms) == (2, 1000)
+
+    args = ["sync", str(config), str(tmp_path), "--once", "--workers", "2", "--timeout", "30"]
+    result = runner.invoke(cli.app, args)
+    assert result.exit_code == 0, result.output
+    assert intervals == [21600.0]  # --once ignored the schedule
+    assert (engines[1]["workers"], engines[1]["timeout_seconds"]) == (2, 30.0)
+
+
+def test_workers_auto(tmp_path: Path, monkeypatch: pytest.MonkeyPatch) -> None:
+    config = _write_config(tmp_path, 'version = 0\n[git.repos]\nx = "https://e.example/x.git"\n')
+    workers: list[object] = []
+
+    class FakeEngine:
+        fanout_results: list[object] = []
+        aborted = False
+
+        def __init__(self, **kwargs: Any) -> None:
+            workers.append(kwargs["workers"])
+
+        async def perform_sync(self, dry_run: bool = False) -> list[object]:
+            return []
+
+    monkeypatch.setattr(cli, "Engine", FakeEngine)
+    for value in ("auto", "AUTO", "0", "3"):
+        result = runner.invoke(cli.app, ["sync", str(config), str(tmp_path), "--workers", value])
+        assert result.exit_code == 0, result.output
+    assert workers == [0, 0, 0, 3]
+
+    result = runner.invoke(cli.app, ["sync", str(config), str(tmp_path), "--workers", "many"])
+    assert result.exit_code == 2
+
+
+def test_fail_fast_exits_1_even_without_exit_on_failure(
+    tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    config = _write_config(
+        tmp_path,
+        'version = 0\nexit_on_failure = false\n[git.repos]\nx = "https://e.example/x.git"\n',
+    )
+    flags: list[object] = []
+
+    class FakeEngine:
+        fanout_results: list[object] = []
+
+        def __init__(self, **kwargs: Any) -> None:
+            flags.append(kwargs["fail_fast"])
+            self.aborted = bool(kwargs["fail_fast"])
+
+        async def perform_sync(self, dry_run: bool = False) -> list[object]:
+            return []
+
+    monkeypatch.setattr(cli, "Engine", FakeEngine)
+    result = runner.invoke(cli.app, ["sync", str(config), str(tmp_path)])
+    assert result.exit_code == 0, result.output
+    result = runner.invoke(cli.app, ["sync", str(config), str(tmp_path), "--fail-fast"])
+    assert result.exit_code == cli.EXIT_PARTIAL_FAILURE
+    assert "Stopped after the first failure" in result.output
+    assert flags == [False, True]
+
+
+def test_sync_exit_code_tells_partial_from_total_failure() -> None:
+    ok = SyncOutcome(SyncTask("a", "https://e.example/a.git", Path("a")), ok=True)
+    failed = SyncOutcome(SyncTask("b", "https://e.example/b.git", Path("b")), ok=False, error="x")
+    assert cli._sync_exit_code([]) == 0
+    assert cli._sync_exit_code([ok]) == 0
+    assert cli._sync_exit_code([ok], copies_failed=True) == cli.EXIT_PARTIAL_FAILURE
+    assert cli._sync_exit_code([ok, failed]) == cli.EXIT_PARTIAL_FAILURE
+    assert cli._sync_exit_code([failed]) == cli.EXIT_TOTAL_FAILURE
+
+
+def test_unknown_log_format_is_a_usage_error(tmp_path: Path) -> None:
+    config = _write_config(tmp_path, 'version = 0\n[git.repos]\nx = "https://e.example/x.git"\n')
+    result = runner.invoke(
+        cli.app, ["sync", str(config), str(tmp_path), "--dry-run"], env={"GITOUT_LOG_FORMAT": "xml"}
+    )
+    assert result.exit_code == cli.EXIT_USAGE_ERROR
+    assert "--log-format must be one of text, json" in result.output
+
+
+def test_unknown_log_level_is_a_usage_error(tmp_path: Path) -> None:
+    config = _write_config(tmp_path, 'version = 0\n[git.repos]\nx = "https://e.example/x.git"\n')
+    args = ["sync", str(config), str(tmp_path), "--dry-run", "--log-level", "loud"]
+    result = runner.invoke(cli.app, args)
+    assert result.exit_code == cli.EXIT_USAGE_ERROR
+    assert "--log-level must be one of trace, debug" in result.output
+    result = runner.invoke(cli.app, [*args[:-1], "WARNING"])
+    assert result.exit_code == 0, result.output
+
+
+def test_log_file_receives_the_log_lines(tmp_path: Path) -> None:
+    config = _write_config(tmp_path, 'version = 0\n[git.repos]\nx = "https://e.example/x.git"\n')
+    log_file = tmp_path / "logs" / "gitout.log"
+    root = logging.getLogger()
+    original_handlers = root.handlers[:]
+    try:
+        result = runner.invoke(
+            cli.app,
+            ["sync", str(config), str(tmp_path), "--dry-run", "--log-file", str(log_file)],
+            env={"GITOUT_LOG_FORMAT": "json", "GITOUT_LOG_MAX_SIZE": "1M"},
+        )
+        assert result.exit_code == 0, result.output
+        logging.getLogger("gitout.test").warning("hello")
+    finally:
+        for handler in root.handlers[len(original_handlers) :]:
+            handler.close()
+        root.handlers = original_handlers
+    assert '"message": "hello"' in log_file.read_text()
+
+    result = runner.invoke(
+        cli.app, ["sync", str(config), str(tmp_path), "--log-file", "x", "--log-max-size", "big"]
+    )
+    assert result.exit_code == 2
+
+
+def test_per_repository_status_lines_color_and_quiet(
+    tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    config = _write_config(
+        tmp_path,
+        'version = 0\nexit_on_failure = false\n[git.repos]\nx = "https://e.example/x.git"\n',
+    )
+
+    class FakeEngine:
+        fanout_results: list[object] = []
+        aborted = False
+
+        def __init__(self, **kwargs: Any) -> None:
+            pass
+
+        async def perform_sync(self, dry_run: bool = False) -> list[SyncOutcome]:
+            return [
+                SyncOutcome(SyncTask("a", "https://e.example/a.git", Path("a")), ok=True),
+                SyncOutcome(
+                    SyncTask("b", "https://e.example/b.git", Path("b")), ok=True, skipped=True
+                ),
+                SyncOutcome(
+                    SyncTask("c", "https://e.example/c.git", Path("c")), ok=False, error="boom"
+                ),
+            ]
+
+    monkeypatch.setattr(cli, "Engine", FakeEngine)
+    args = ["sync", str(config), str(tmp_path)]
+    result = runner.invoke(cli.app, args)  # not a terminal: auto means no color
+    assert result.exit_code == 0, result.output
+    assert "OK a\n" in result.output
+    assert "SKIPPED b\n" in result.output
+    assert "FAILED https://e.example/c.git: boom" in result.output
+    assert "\x1b[" not in result.output
+
+    result = runner.invoke(cli.app, [*args, "--color", "always"])
+    assert "\x1b[32mOK\x1b[0m a" in result.output
+    assert "\x1b[31m\x1b[1mFAILED\x1b[0m" in result.output
+
+    result = runner.invoke(cli.app, [*args, "--color", "sometimes"])
+    assert result.exit_code == cli.EXIT_USAGE_ERROR
+
+
+    result = runner.invoke(cli.app, [*args, "--quiet"])
+    assert "OK a" not in result.output
+    assert "SKIPPED b" not in result.output
+    assert "FAILED https://e.example/c.git: boom" in result.output
+    assert "Synced 2/3 repositories." in result.output
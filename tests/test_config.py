@@ -5,21 +5,51 @@ Red until ``gitout.config`` is implemented in Phase 1.
 
 from __future__ import annotations
 
+import importlib.util
+import json
+import tomllib
+
 import pytest
 
 from gitout import config as cfg
 from gitout.config import (
+    Bundle,
+    ChatNotify,
     Config,
+    EmailNotify,
+    Fanout,
+    Fetch,
     GitConfig,
+    GitHubApp,
+    GitHubArchive,
     GitHubClone,
     GitHubConfig,
+    GitRepoCredentials,
+    HealthCheckConfig,
     Http,
     Maintenance,
     Metrics,
+    Notify,
+    Offsite,
     Parallelism,
+    PushMirror,
+    Rclone,
+    Replicate,
+    Reports,
+    RepoGroup,
+    RepoOverride,
+    Runtime,
+    S3Storage,
     Search,
+    Snapshots,
+    Ssh,
+    Tarball,
     Telegram,
+    Tracing,
+    Transfer,
     ValidationError,
+    WebDav,
+    Webhook,
 )
 from tests.helpers import load_json
 
@@ -43,6 +73,93 @@ def test_parse_coerces_allowed_users_int_or_string() -> None:
     assert parsed.telegram.allowed_users == [42, 99]
 
 
+
+def test_parse_offsite_with_nested_s3_table() -> None:
+    toml_text = (
+        'version = 0\n[offsite]\npaths = ["bundles"]\n'
+        '[offsite.s3]\nbucket = "backups"\nendpoint_url = "https://s3.example"\n'
+    )
+    parsed = cfg.parse(toml_text)
+    assert parsed.offsite == Offsite(
+        paths=["bundles"], s3=S3Storage(bucket="backups", endpoint_url="https://s3.example")
+    )
+
+
+def test_parse_runtime_table() -> None:
+    toml_text = (
+        'version = 0\n[runtime]\nworkers = 8\ninterval = "6h"\ntimeout = "15m"\n'
+        'retry_attempts = 3\nretry_backoff = "exponential"\n'
+    )
+    parsed = cfg.parse(toml_text)
+    assert parsed.runtime == Runtime(
+        workers=8, interval="6h", timeout="15m", retry_attempts=3, retry_backoff="exponential"
+    )
+    assert cfg.validate(parsed) == []
+
+
+def test_parse_webhooks_array() -> None:
+    toml_text = (
+        'version = 0\n[[webhooks]]\nurl = "https://hooks.example/a"\n'
+        '[[webhooks]]\nurl = "https://hooks.example/b"\nevents = ["failure"]\n'
+        'template = "$summary"\ncontent_type = "text/plain"\n'
+    )
+    parsed = cfg.parse(toml_text)
+    assert parsed.webhooks == [
+        Webhook(url="https://hooks.example/a"),
+        Webhook(
+            url="https://hooks.example/b",
+            events=["failure"],
+            template="$summary",
+            content_type="text/plain",
+        ),
+    ]
+    assert cfg.validate(parsed) == []
+
+
+def test_parse_notify_email_table() -> None:
+    toml_text = (
+        'version = 0\n[notify.email]\nserver = "smtp.example.com"\nusername = "gitout"\n'
+        'password_env = "SMTP_PASSWORD"\nsender = "gitout@example.com"\n'
+        'recipients = ["ops@example.com"]\nonly_on_failure = true\n'
+    )
+    parsed = cfg.parse(toml_text)
+    assert parsed.notify.email == EmailNotify(
+        server="smtp.example.com",
+        username="gitout",
+        password_env="SMTP_PASSWORD",
+        sender="gitout@example.com",
+        recipients=["ops@example.com"],
+        only_on_failure=True,
+    )
+    assert cfg.validate(parsed) == []
+    assert cfg.parse("version = 0\n").notify.email is None
+
+
+def test_parse_notify_chat_tables() -> None:
+    toml_text = (
+        'version = 0\n[notify.slack]\nwebhook_url = "https://hooks.slack.com/services/T/B/x"\n'
+        '[notify.discord]\nwebhook_url = "https://discord.com/api/webhooks/1/t"\n'
+        "only_on_failure = true\n"
+    )
+    parsed = cfg.parse(toml_text)
+    assert parsed.notify.slack == ChatNotify(webhook_url="https://hooks.slack.com/services/T/B/x")
+    assert parsed.notify.discord == ChatNotify(
+        webhook_url="https://discord.com/api/webhooks/1/t", only_on_failure=True
+    )
+    assert cfg.validate(parsed) == []
+
+
+def test_parse_tracing_table() -> None:
+    toml_text = (
+        'version = 0\n[tracing]\nendpoint = "http://otel:4318"\n'
+        '[tracing.headers]\nx-api-key = "k"\n'
+    )
+    parsed = cfg.parse(toml_text)
+    assert parsed.tracing == Tracing(endpoint="http://otel:4318", headers={"x-api-key": "k"})
+    assert cfg.validate(parsed) == []
+    assert cfg.parse("version = 0\n").tracing is None
+
+
 # --- validate(): (id, config, codes that MUST appear, codes that MUST NOT appear) ---
 _VALIDATE_CASES: list[tuple[str, Config, set[str], set[str]]] = [
     ("valid_minimal", Config(version=1), set(), {"InvalidVersion"}),
@@ -68,6 +185,124 @@ _VALIDATE_CASES: list[tuple[str, Config, set[str], set[str]]] = [
         {"NoGitHubCloneOptionsEnabled"},
         set(),
     ),
+    (
+        "github_archive_zero_retention",
+        Config(
+            version=1,
+            github=GitHubConfig(
+                user="x", archive=GitHubArchive(retention=0, exclude=["attachments"])
+            ),
+        ),
+        {"InvalidArchiveRetention", "InvalidArchiveExclude"},
+        set(),
+    ),
+    (
+        "github_token_list_with_blank_entry",
+        Config(version=1, github=GitHubConfig(user="x", token=["tok1", ""])),
+        {"EmptyGitHubToken"},
+        set(),
+    ),
+    (
+        "github_blank_token_file",
+        Config(version=1, github=GitHubConfig(user="x", token_file="")),
+        {"EmptyGitHubTokenFile"},
+        set(),
+    ),
+    (
+        "github_app_without_id_or_key",
+        Config(version=1, github=GitHubConfig(user="acme", app=GitHubApp(installation_id=0))),
+        {"InvalidGitHubAppId", "EmptyGitHubAppKey", "InvalidGitHubInstallationId"},
+        set(),
+    ),
+    (
+        "github_blank_token_cmd",
+        Config(version=1, github=GitHubConfig(user="x", token_cmd=" ")),
+        {"EmptyGitHubTokenCmd"},
+        set(),
+    ),
+    (
+        "github_api_url_without_scheme",
+        Config(version=1, github=GitHubConfig(user="x", api_url="ghe.example.com/api")),
+        {"InvalidGitHubApiUrl"},
+        set(),
+    ),
+    (
+        "github_clone_blank_topic",
+        Config(
+            version=1,
+            github=GitHubConfig(user="x", clone=GitHubClone(ignored_topics=["ok", " "])),
+        ),
+        {"EmptyTopic"},
+        set(),
+    ),
+    (
+        "github_clone_blank_language",
+        Config(version=1, github=GitHubConfig(user="x", clone=GitHubClone(languages=[""]))),
+        {"EmptyLanguage"},
+        set(),
+    ),
+    (
+        "github_clone_unknown_fork_mode",
+        Config(version=1, github=GitHubConfig(user="x", clone=GitHubClone(forks="never"))),
+        {"InvalidForkMode"},
+        set(),
+    ),
+    (
+        "git_repo_login_without_password_on_ssh",
+        Config(
+            version=1,
+            git=GitConfig(
+                repos={"nas": "git@nas.lan:x.git"},
+                credentials={"nas": GitRepoCredentials(username=" ")},
+            ),
+        ),
+        {"EmptyGitRepoUsername", "MissingGitRepoPassword", "GitRepoCredentialsNeedHttp"},
+        set(),
+    ),
+    (
+        "git_repo_login_with_two_password_sources",
+        Config(
+            version=1,
+            git=GitConfig(
+                repos={"x": "https://git.example.com/x.git"},
+                credentials={
+                    "x": GitRepoCredentials("me", password_env="X-TOKEN", password_file="/p")
+                },
+            ),
+        ),
+        {"ConflictingGitRepoPassword", "InvalidGitRepoPasswordEnv"},
+        set(),
+    ),
+    (
+        "git_repo_empty_and_invalid_refspecs",
+        Config(
+            version=1,
+            git=GitConfig(
+                repos={"a": "https://x/a.git", "b": "https://x/b.git"},
+                refspecs={"a": [], "b": ["+refs/heads/main:refs/heads/main", "main main"]},
+            ),
+        ),
+        {"EmptyGitRepoRefspecs", "InvalidGitRepoRefspec"},
+        set(),
+    ),
+    (
+        "github_clone_unknown_protocol",
+        Config(version=1, github=GitHubConfig(user="x", clone=GitHubClone(protocol="git"))),
+        {"InvalidCloneProtocol"},
+        set(),
+    ),
+    (
+        "ssh_blank_key_and_two_passphrase_sources",
+        Config(version=1, ssh=Ssh(key_file=" ", passphrase_file="p", passphrase_env="PASS")),
+        {"EmptySshKeyFile", "ConflictingSshPassphrase"},
+        set(),
+    ),
+    (
+        "ssh_passphrase_env_not_a_variable_name",
+        Config(version=1, ssh=Ssh(passphrase_env="MY-PASS", agent_socket=" ")),
+        {"InvalidSshPassphraseEnv", "EmptySshAgentSocket"},
+        set(),
+    ),
     (
         "git_repo_path_traversal_name",
         Config(version=1, git=GitConfig(repos={"../evil": "https://example.com/x.git"})),
@@ -98,6 +333,18 @@ _VALIDATE_CASES: list[tuple[str, Config, set[str], set[str]]] = [
         {"InvalidMetricsFormat"},
         set(),
     ),
+    (
+        "health_check_bad_ping_url",
+        Config(version=1, health_check=HealthCheckConfig(ping_url="hc-ping.com/uuid")),
+        {"InvalidHealthCheckPingUrl"},
+        set(),
+    ),
+    (
+        "metrics_bad_pushgateway",
+        Config(version=1, metrics=Metrics(pushgateway_url="pushgw:9091", pushgateway_job=" ")),
+        {"InvalidPushgatewayUrl", "EmptyPushgatewayJob"},
+        set(),
+    ),
     (
         "telegram_empty_chat_id",
         Config(version=1, telegram=Telegram(chat_id="")),
@@ -116,6 +363,24 @@ _VALIDATE_CASES: list[tuple[str, Config, set[str], set[str]]] = [
         {"InvalidHttpVersion"},
         set(),
     ),
+    (
+        "http_custom_header_ok",
+        Config(version=1, http=Http(user_agent="bot/1", headers={"X-Backup-Host": "nas"})),
+        set(),
+        {"InvalidHttpHeader", "EmptyUserAgent"},
+    ),
+    (
+        "http_header_reserved_or_malformed",
+        Config(version=1, http=Http(headers={"Authorization": "x", "Bad Name": "y"})),
+        {"InvalidHttpHeader"},
+        set(),
+    ),
+    (
+        "http_blank_user_agent",
+        Config(version=1, http=Http(user_agent=" ")),
+        {"EmptyUserAgent"},
+        set(),
+    ),
     ("search_topk_zero", Config(version=1, search=Search(top_k=0)), {"InvalidTopK"}, set()),
     ("search_topk_too_high", Config(version=1, search=Search(top_k=101)), {"InvalidTopK"}, set()),
     ("search_topk_min_ok", Config(version=1, search=Search(top_k=1)), set(), {"InvalidTopK"}),
@@ -138,12 +403,361 @@ _VALIDATE_CASES: list[tuple[str, Config, set[str], set[str]]] = [
         set(),
         {"EmptyQdrantUrl", "EmptyCollectionName"},
     ),
+    (
+        "reports_blank_directory_zero_retention",
+        Config(version=1, reports=Reports(directory="", retention=0)),
+        {"EmptyReportsDirectory", "InvalidReportsRetention"},
+        set(),
+    ),
+    (
+        "bundle_blank_directory",
+        Config(version=1, bundle=Bundle(enabled=True, directory="  ", full_every=-7)),
+        {"EmptyBundleDirectory", "InvalidBundleFullEvery"},
+        set(),
+    ),
+    (
+        "tarball_blank_directory_negative_keep",
+        Config(version=1, tarball=Tarball(directory="", keep=-2)),
+        {"EmptyTarballDirectory", "InvalidTarballKeep"},
+        set(),
+    ),
+    (
+        "offsite_unknown_backend_and_escaping_paths",
+        Config(
+            version=1,
+            offsite=Offsite(backend="ftp", paths=["bundles", "../elsewhere", "/abs"]),
+        ),
+        {"InvalidOffsiteBackend", "InvalidOffsitePath"},
+        {"EmptyS3Bucket"},
+    ),
+    (
+        "offsite_s3_blank_bucket_small_parts",
+        Config(
+            version=1,
+            offsite=Offsite(s3=S3Storage(bucket=" ", multipart_chunk_mb=1, max_attempts=0)),
+        ),
+        {"EmptyS3Bucket", "InvalidS3PartSize", "InvalidS3Attempts"},
+        {"InvalidOffsiteBackend", "InvalidOffsitePath"},
+    ),
+    (
+        "offsite_webdav_bad_url_and_password_sources",
+        Config(
+            version=1,
+            offsite=Offsite(
+                backend="webdav",
+                webdav=WebDav(
+                    url="ftp://box", password_env="1PW", password_file="pw", max_attempts=0
+                ),
+            ),
+        ),
+        {
+            "InvalidWebDavUrl",
+            "ConflictingWebDavPassword",
+            "InvalidWebDavPasswordEnv",
+            "InvalidWebDavAttempts",
+        },
+        {"EmptyS3Bucket"},
+    ),
+    (
+        "rclone_blank_remote_bad_mode_and_filters",
+        Config(
+            version=1,
+            rclone=Rclone(remote=" ", mode="move", include=[""], timeout_seconds=0),
+        ),
+        {"EmptyRcloneRemote", "InvalidRcloneMode", "EmptyRcloneFilter", "InvalidRcloneTimeout"},
+        set(),
+    ),
+    (
+        "snapshots_nothing_kept",
+        Config(
+            version=1,
+            snapshots=Snapshots(
+                enabled=True, keep_daily=0, keep_weekly=0, keep_monthly=0, keep_yearly=0
+            ),
+        ),
+        {"NoSnapshotsKept"},
+        {"InvalidSnapshotsKeep"},
+    ),
+    (
+        "snapshots_blank_directory_negative_values",
+        Config(
+            version=1,
+            snapshots=Snapshots(directory=" ", mode="zfs", interval_hours=-1, keep_weekly=-2),
+        ),
+        {
+            "EmptySnapshotsDirectory",
+            "InvalidSnapshotsMode",
+            "InvalidSnapshotsInterval",
+            "InvalidSnapshotsKeep",
+        },
+        {"NoSnapshotsKept"},
+    ),
+    (
+        "push_mirror_valid_template",
+        Config(
+            version=1,
+            push_mirror=PushMirror(
+                url="https://gitea.example/{owner}/{repo}.git", username="u", password_env="PW"
+            ),
+        ),
+        set(),
+        {"InvalidPushMirrorUrl", "PushMirrorPasswordWithoutUsername"},
+    ),
+    (
+        "push_mirror_bad_template_password_and_refspec",
+        Config(
+            version=1,
+            push_mirror=PushMirror(
+                url="https://gitea.example/{org}/{repo}.git",
+                password_env="PW",
+                password_file="pw",
+                refspecs=["refs/heads/*:refs/heads/* extra"],
+            ),
+        ),
+        {
+            "InvalidPushMirrorUrl",
+            "PushMirrorPasswordWithoutUsername",
+            "ConflictingPushMirrorPassword",
+            "InvalidPushMirrorRefspec",
+        },
+        set(),
+    ),
+    (
+        "tracing_bad_endpoint_name_and_timeout",
+        Config(
+            version=1,
+            tracing=Tracing(endpoint="grpc://collector:4317", service_name="", timeout_seconds=-1),
+        ),
+        {"InvalidTracingEndpoint", "EmptyTracingServiceName", "InvalidTracingTimeout"},
+        set(),
+    ),
+    (
+        "notify_email_bad_server_addresses_and_credentials",
+        Config(
+            version=0,
+            notify=Notify(
+                email=EmailNotify(
+                    server="",
+                    port=70000,
+                    security="tls",
+                    sender="backups",
+                    password_env="SMTP_PASSWORD",
+                    password_file="/run/secrets/smtp",
+                    timeout_seconds=-1,
+                )
+            ),
+        ),
+        {
+            "EmptyEmailServer",
+            "InvalidEmailPort",
+            "InvalidEmailSecurity",
+            "InvalidEmailAddress",
+            "EmptyEmailRecipients",
+            "EmailPasswordWithoutUsername",
+            "ConflictingEmailPassword",
+            "InvalidEmailTimeout",
+        },
+        set(),
+    ),
+    (
+        "notify_email_bad_recipient",
+        Config(
+            version=0,
+            notify=Notify(
+                email=EmailNotify(
+                    server="smtp.example.com", sender="gitout@example.com", recipients=["ops"]
+                )
+            ),
+        ),
+        {"InvalidEmailAddress"},
+        set(),
+    ),
+    (
+        "notify_chat_bad_webhook_url_and_timeout",
+        Config(
+            version=0,
+            notify=Notify(
+                slack=ChatNotify(webhook_url="hooks.slack.com/services/x"),
+                discord=ChatNotify(
+                    webhook_url="https://discord.com/api/webhooks/1/t", timeout_seconds=0
+                ),
+            ),
+        ),
+        {"InvalidChatWebhookUrl", "InvalidChatTimeout"},
+        set(),
+    ),
+    (
+        "webhooks_bad_url_event_template_and_timeout",
+        Config(
+            version=0,
+            webhooks=[
+                Webhook(url="hooks.example/run", events=["done"], timeout_seconds=0),
+                Webhook(url="https://hooks.example/a", template="$event $nope"),
+                Webhook(url="https://hooks.example/b", template='{"cost": "5$"}'),
+            ],
+        ),
+        {
+            "InvalidWebhookUrl",
+            "InvalidWebhookEvent",
+            "InvalidWebhookTemplate",
+            "InvalidWebhookTimeout",
+        },
+        set(),
+    ),
+    (
+        "fanout_relative_blank_and_duplicate_destinations",
+        Config(
+            version=1,
+            fanout=Fanout(destinations=["/mnt/nas/", "backup", " ", "~/copy", "/mnt/nas"]),
+        ),
+        {"InvalidFanoutDestination", "DuplicateFanoutDestination"},
+        set(),
+    ),
+    (
+        "replicate_without_targets",
+        Config(version=1, replicate=Replicate(ssh_command=" ", bandwidth_limit_kbps=-1)),
+        {"EmptyReplicateTargets", "EmptyReplicateSshCommand", "InvalidReplicateBandwidth"},
+        {"InvalidReplicateTarget"},
+    ),
+    (
+        "replicate_local_and_daemon_targets",
+        Config(
+            version=1,
+            replicate=Replicate(
+                targets=["backup@nas2:/srv/gitout", "/mnt/usb", "nas::module"],
+                timeout_seconds=0,
+            ),
+        ),
+        {"InvalidReplicateTarget", "InvalidReplicateTimeout"},
+        {"EmptyReplicateTargets"},
+    ),
+    (
+        "overrides_blank_pattern_bad_since",
+        Config(
+            version=1,
+            overrides=[RepoOverride(pattern=" "), RepoOverride(pattern="x", since="2020-13-01")],
+        ),
+        {"EmptyOverridePattern", "InvalidSinceDate"},
+        set(),
+    ),
+    (
+        "transfer_negative_threads_bad_window",
+        Config(version=1, transfer=Transfer(pack_threads=-1, window_memory="lots")),
+        {"InvalidPackThreads", "InvalidWindowMemory"},
+        set(),
+    ),
+    (
+        "transfer_valid",
+        Config(version=1, transfer=Transfer(pack_threads=0, window_memory="256m")),
+        set(),
+        {"InvalidPackThreads", "InvalidWindowMemory"},
+    ),
+    (
+        "fetch_bad_refspecs",
+        Config(
+            version=1,
+            fetch=Fetch(
+                refspecs=["refs/heads/*:refs/heads/*", " ", "a b:c"],
+                tags="some",
+                depth=-1,
+                filter="blobs",
+            ),
+            overrides=[
+                RepoOverride(pattern="x", depth=-5, filter="tree", branches=[]),
+                RepoOverride(pattern="y", branches=["main", "refs/heads/dev", "a b"]),
+            ],
+        ),
+        {
+            "InvalidFetchRefspec",
+            "InvalidFetchTags",
+            "InvalidDepth",
+            "InvalidCloneFilter",
+            "EmptyOverrideBranches",
+            "InvalidOverrideBranch",
+        },
+        set(),
+    ),
+    (
+        "fetch_valid_refspecs",
+        Config(
+            version=1,
+            fetch=Fetch(
+                refspecs=["+refs/*:refs/*", "^refs/pull/*", "HEAD"], tags="none", filter="blob:none"
+            ),
+            overrides=[
+                RepoOverride(pattern="a", filter=""),
+                RepoOverride(pattern="b", filter="blob:limit=10m"),
+                RepoOverride(pattern="c", branches=["main", "release/*"]),
+            ],
+        ),
+        set(),
+        {
+            "InvalidFetchRefspec",
+            "InvalidFetchTags",
+            "InvalidCloneFilter",
+            "EmptyOverrideBranches",
+            "InvalidOverrideBranch",
+        },
+    ),
+    (
+        "groups_bad_name_and_empty",
+        Config(version=1, groups={"my work": RepoGroup(repos=["a"]), "empty": RepoGroup()}),
+        {"InvalidGroupName", "EmptyGroup"},
+        set(),
+    ),
+    (
+        "overrides_depth_with_since",
+        Config(
+            version=1,
+            overrides=[
+                RepoOverride(pattern="x", since="2020-01-01", depth=1),
+                RepoOverride(pattern="y", since="2020-01-01", depth=0),
+            ],
+        ),
+        {"DepthWithSince"},
+        set(),
+    ),
+    (
+        "overrides_valid_since",
+        Config(version=1, overrides=[RepoOverride(pattern="legacy/*", since="2020-01-01")]),
+        set(),
+        {"InvalidSinceDate"},
+    ),
+    (
+        "runtime_valid_schedule",
+        Config(version=1, runtime=Runtime(workers=32, cron="0 3 * * *", retry_delay="250ms")),
+        set(),
+        {
+            "InvalidRuntimeWorkers",
+            "InvalidRuntimeDuration",
+            "InvalidRuntimeCron",
+            "ConflictingRuntimeSchedule",
+        },
+    ),
+    (
+        "runtime_interval_and_cron",
+        Config(version=1, runtime=Runtime(interval="1d", cron="0 3 * * *", timeout="ten")),
+        {"ConflictingRuntimeSchedule", "InvalidRuntimeDuration"},
+        {"InvalidRuntimeCron"},
+    ),
+    (
+        "runtime_non_finite_durations",
+        Config(version=1, runtime=Runtime(interval="nan", timeout="inf", retry_delay="1e400")),
+        {"InvalidRuntimeDuration"},
+        set(),
+    ),
     (
         "maintenance_bad_strategy",
         Config(version=1, maintenance=Maintenance(strategy="turbo")),
         {"InvalidMaintenanceStrategy"},
         set(),
     ),
+    (
+        "maintenance_negative_gc_interval_bad_prune",
+        Config(version=1, maintenance=Maintenance(gc_every=-1, gc_prune="2 weeks ago")),
+        {"InvalidGcInterval", "InvalidGcPrune"},
+        set(),
+    ),
 ]
 
 
@@ -195,7 +809,8 @@ _MESSAGE_CASES: list[tuple[str, ValidationError, str]] = [
     (
         "NoGitHubCloneOptionsEnabled",
         ValidationError(code="NoGitHubCloneOptionsEnabled"),
-        "github.clone has no clone options enabled (set starred, watched, gists, or repos)",
+        "github.clone has no clone options enabled "
+        "(set starred, watched, gists, gists_starred, or repos)",
     ),
     (
         "InvalidHttpVersion",
@@ -246,14 +861,39 @@ def test_all_known_codes_produce_non_fallback_messages() -> None:
         Maintenance,
         Parallelism,
         PriorityPattern,
+        Reports,
         Search,
         Telegram,
     )
 
     bad = Config(
         version=-1,
-        github=GitHubConfig(user="", clone=GitHubClone(gists=False)),
-        git=GitConfig(repos={"../evil": "not-a-url", "ok": ""}),
+        github=GitHubConfig(
+            user="",
+            token=["tok", " "],
+            token_file=" ",
+            token_cmd="",
+            app=GitHubApp(id=0, private_key_file=" ", installation_id=-1),
+            api_url="api.github.com",
+            archive=GitHubArchive(retention=0, exclude=["wiki"]),
+            clone=GitHubClone(
+                gists=False,
+                forks="sometimes",
+                protocol="ftp",
+                topics=[""],
+                ignore=["re:("],
+                languages=[""],
+            ),
+        ),
+        git=GitConfig(
+            repos={"../evil": "not-a-url", "ok": "", "ssh": "git@h:x.git"},
+            credentials={
+                "ssh": GitRepoCredentials(username=""),
+                "ok": GitRepoCredentials("u", password_env="1X", password_file="/p"),
+            },
+            refspecs={"ok": [], "ssh": [" "]},
+        ),
+        ssh=Ssh(key_file="", passphrase_file=" ", passphrase_env="1PASS", agent_socket=""),
         parallelism=Parallelism(
             workers=0,
             progress_interval_ms=50,
@@ -263,10 +903,24 @@ def test_all_known_codes_produce_non_fallback_messages() -> None:
                 PriorityPattern(pattern="p", priority=1, timeout=0),
             ],
         ),
-        metrics=Metrics(format="xml", export_path="   "),
+        runtime=Runtime(
+            workers=-1,
+            interval="soon",
+            cron="* *",
+            retry_attempts=0,
+            retry_backoff="random",
+        ),
+        metrics=Metrics(
+            format="xml", export_path="   ", pushgateway_url="ftp://x", pushgateway_job=""
+        ),
         telegram=Telegram(chat_id="", notify_progress_step_percent=0),
         http=Http(
-            version="HTTP/3", post_buffer_size=0, low_speed_limit=-1, low_speed_time=0
+            version="HTTP/3",
+            post_buffer_size=0,
+            low_speed_limit=-1,
+            low_speed_time=0,
+            user_agent="",
+            headers={"User-Agent": "x"},
         ),
         large_repos=LargeRepoConfig(
             size_threshold_kb=100,
@@ -282,16 +936,196 @@ def test_all_known_codes_produce_non_fallback_messages() -> None:
             full_repack_interval="daily",
             repack_window=0,
             repack_depth=0,
+            gc_every=-30,
+            gc_prune="",
         ),
         search=Search(enabled=True, top_k=0, qdrant_url="   ", collection_name=""),
         lfs=Lfs(),
         exit_on_failure=True,
+        reports=Reports(directory=" ", retention=0),
+        bundle=Bundle(directory="", full_every=-1),
+        tarball=Tarball(directory=" ", keep=-1),
+        offsite=Offsite(
+            paths=[".."],
+            s3=S3Storage(bucket="", multipart_threshold_mb=0, max_attempts=0),
+        ),
+        rclone=Rclone(mode="mirror", exclude=[" "], timeout_seconds=-1),
+        fanout=Fanout(destinations=["relative", "/a", "/a"]),
+        push_mirror=PushMirror(
+            url="{path", password_env="A", password_file="b", refspecs=["a b"]
+        ),
+        tracing=Tracing(endpoint="collector:4318", service_name=" ", timeout_seconds=0),
+        webhooks=[Webhook(url="x", events=["always"], template="$x", timeout_seconds=0)],
+        notify=Notify(
+            email=EmailNotify(
+                server=" ",
+                port=0,
+                security="tls",
+                sender="gitout",
+                password_env="A",
+                password_file="b",
+                timeout_seconds=0,
+            ),
+            slack=ChatNotify(webhook_url="", timeout_seconds=-1),
+        ),
+        health_check=HealthCheckConfig(ping_url="hc-ping.com/x"),
+        snapshots=Snapshots(
+            enabled=True,
+            mode="copy",
+            directory="",
+            interval_hours=-1,
+            keep_daily=-1,
+            keep_weekly=0,
+            keep_monthly=0,
+        ),
+        replicate=Replicate(
+            targets=["local"], ssh_command="", bandwidth_limit_kbps=-5, timeout_seconds=0
+        ),
+        overrides=[
+            RepoOverride(pattern="", since="yesterday", branches=[]),
+            RepoOverride(pattern="x", branches=[" "]),
+        ],
+        groups={"a b": RepoGroup(repos=[" "])},
+        transfer=Transfer(pack_threads=-2, window_memory="1 GB"),
+        fetch=Fetch(refspecs=[""], tags="", depth=-1, filter="all"),
+    )
+
+    # Backend-specific sections are only checked for the selected backend.
+    bad_webdav = Config(
+        version=-1,
+        offsite=Offsite(
+            backend="webdav",
+            webdav=WebDav(url="", password_env="-", password_file="f", max_attempts=0),
+        ),
     )
 
-    errors = cfg.validate(bad)
+    errors = cfg.validate(bad) + cfg.validate(bad_webdav)
     assert errors, "Expected validation errors for the crafted bad config"
     for error in errors:
         fallback = f"{error.code} {error.detail}" if error.detail else error.code
         assert error.message != fallback, (
             f"Code {error.code!r} produced the raw fallback message; add it to _MESSAGE_MAP"
         )
+
+
+@pytest.mark.parametrize(
+    ("source", "expected"),
+    [
+        ("config.toml", "toml"),
+        ("config", "toml"),
+        ("/etc/gitout/config.JSON", "json"),
+        ("config.yml", "yaml"),
+        ("https://cfg.example/gitout.yaml?token=x", "yaml"),
+        ("git+https://g.example/fleet.git#boxes/nas.json@main", "json"),
+    ],
+)
+def test_format_for_path(source: str, expected: str) -> None:
+    assert cfg.format_for_path(source) == expected
+
+
+def test_json_config_matches_toml() -> None:
+    toml_text = "\n".join(_PARSE_CASES[0]["toml_lines"])
+    as_json = json.dumps(tomllib.loads(toml_text))
+    assert cfg.parse(as_json, "json") == cfg.parse(toml_text)
+
+
+def test_json_config_must_be_a_mapping() -> None:
+    with pytest.raises(ValueError, match="mapping"):
+        cfg.parse("[1, 2]", "json")
+
+
+@pytest.mark.skipif(importlib.util.find_spec("yaml") is None, reason="PyYAML not installed")
+def test_yaml_config_matches_toml() -> None:
+    text = "version: 0\ngithub:\n  user: me\n  clone:\n    starred: true\n"
+    expected = cfg.parse('version = 0\n[github]\nuser = "me"\n[github.clone]\nstarred = true\n')
+    assert cfg.parse(text, "yaml") == expected
+
+
+def test_overrides_parse_and_match_in_order() -> None:
+    config = cfg.parse(
+        "version = 0\n"
+        '[[overrides]]\npattern = "legacy/*"\nsince = "2015-01-01"\n'
+        '[[overrides]]\npattern = "legacy/huge"\nsince = "2020-01-01"\n'
+    )
+    assert [o.since for o in cfg.overrides_for(config, "legacy/huge")] == [
+        "2015-01-01",
+        "2020-01-01",
+    ]
+    assert cfg.overrides_for(config, "other/repo") == []
+
+
+def test_github_app_parses() -> None:
+    config = cfg.parse(
+        'version = 0\n[github]\nuser = "acme"\n'
+        '[github.app]\nid = 42\nprivate_key_file = "/run/secrets/app.pem"\n'
+    )
+    assert config.github is not None
+    assert config.github.app == GitHubApp(id=42, private_key_file="/run/secrets/app.pem")
+    plain = cfg.parse('version = 0\n[github]\nuser = "acme"\n')
+    assert plain.github is not None
+    assert plain.github.app is None
+
+
+def test_groups_parse_and_match_globs() -> None:
+    config = cfg.parse(
+        "version = 0\n"
+        '[groups.work]\nrepos = ["acme/*", "mirror"]\n'
+        '[groups.oss]\nrepos = ["acme/public-*"]\n'
+    )
+    assert config.groups["work"].repos == ["acme/*", "mirror"]
+    assert cfg.groups_for(config, "acme/public-site") == ["work", "oss"]
+    assert cfg.groups_for(config, "mirror") == ["work"]
+    assert cfg.groups_for(config, "other/repo") == []
+
+
+def test_unknown_keys_are_reported_with_dotted_paths() -> None:
+    text = (
+        "version = 0\nexit_on_faliure = false\n"
+        '[github]\nuser = "me"\n[github.clone]\nstartred = true\n'
+        '[git.repos]\nplain = "https://example.com/a.git"\n'
+        'table = { url = "https://example.com/b.git", usrname = "u", refspecs = [] }\n'
+        '[[overrides]]\npattern = "a/*"\ndepht = 1\n'
+        '[groups.work]\nrepo = ["a/*"]\n'
+        '[offsite.s3]\nbukket = "b"\n'
+    )
+    assert cfg.unknown_keys(text) == [
+        "exit_on_faliure",
+        "github.clone.startred",
+        "git.repos.table.usrname",
+        "overrides[0].depht",
+        "groups.work.repo",
+        "offsite.s3.bukket",
+    ]
+    assert cfg.unknown_keys('{"version": 0, "fetch": {"prune": true}}', "json") == []
+
+
+@pytest.mark.parametrize(
+    ("pattern", "name", "expected"),
+    [
+        ("myorg/tool", "myorg/tool", True),
+        ("myorg/experiment-*", "myorg/experiment-2", True),
+        ("myorg/experiment-*", "other/experiment-2", False),
+        ("re:^fork-", "fork-of-thing", True),
+        ("re:^fork-", "me/fork-of-thing", False),
+        ("re:/fork-", "me/fork-of-thing", True),
+    ],
+)
+def test_matches_pattern(pattern: str, name: str, expected: bool) -> None:
+    assert cfg.matches_pattern(pattern, name) is expected
+
+
+def test_invalid_regex_patterns_rejected() -> None:
+    config = Config(
+        version=1,
+        github=GitHubConfig(user="x", clone=GitHubClone(ignore=["re:(", "ok/*"])),
+        overrides=[RepoOverride(pattern="re:[")],
+        groups={"work": RepoGroup(repos=["re:*"])},
+    )
+    fields = [e.detail["field"] for e in cfg.validate(config) if e.code == "InvalidPattern"]
+    assert fields == ["github.clone.ignore", "overrides.pattern", "groups.work.repos"]
+
+
+def test_transfer_options() -> None:
+    config = cfg.parse("version = 0\n[transfer]\npack_threads = 8\nwindow_memory = \"1g\"\n")
+    assert cfg.transfer_options(config) == ["pack.threads=8", "pack.windowMemory=1g"]
+    assert cfg.transfer_options(Config(version=0)) == []
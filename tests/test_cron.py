@@ -6,7 +6,13 @@ from datetime import datetime
 
 import pytest
 
-from gitout.cron import CronExpression, run_cron
+from gitout.cron import (
+    CronExpression,
+    format_duration,
+    parse_duration,
+    run_cron,
+    run_interval,
+)
 
 
 def test_parse_requires_five_fields() -> None:
@@ -74,3 +80,49 @@ async def test_run_cron_drives_action_for_n_iterations() -> None:
     await run_cron("30 2 * * *", action, sleep=sleep, now=lambda: clock["t"], iterations=1)
     assert fired == [1]
     assert slept == [1800.0]  # 30 minutes
+
+
+@pytest.mark.parametrize(
+    ("text", "seconds"),
+    [
+        ("600", 600.0),
+        ("1.5", 1.5),
+        ("90s", 90.0),
+        ("10m", 600.0),
+        ("1h30m", 5400.0),
+        ("1d", 86400.0),
+        ("1w2d", 777600.0),
+        ("1H 30M", 5400.0),
+        ("250ms", 0.25),
+    ],
+)
+def test_parse_duration(text: str, seconds: float) -> None:
+    assert parse_duration(text) == seconds
+
+
+@pytest.mark.parametrize(
+    "text", ["", "h", "1x", "1h30", "10m-5s", "0", "0s", "-5", "inf", "nan", "-inf", "1e400"]
+)
+def test_parse_duration_rejects(text: str) -> None:
+    with pytest.raises(ValueError, match="[Dd]uration"):
+        parse_duration(text)
+
+
+def test_format_duration() -> None:
+    assert format_duration(5400.0) == "1h30m"
+    assert format_duration(86400.0) == "1d"
+    assert format_duration(90.0) == "1m30s"
+    assert format_duration(0.25) == "0.25s"
+
+
+async def test_run_interval_runs_first_then_sleeps_between() -> None:
+    events: list[str] = []
+
+    async def sleep(seconds: float) -> None:
+        events.append(f"sleep {seconds:g}")
+
+    async def action() -> None:
+        events.append("run")
+
+    await run_interval(5400.0, action, sleep=sleep, iterations=3)
+    assert events == ["run", "sleep 5400", "run", "sleep 5400", "run"]
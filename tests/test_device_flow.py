@@ -0,0 +1,111 @@
+"""OAuth device flow against an in-memory GitHub login endpoint (no network)."""
+
+from __future__ import annotations
+
+from urllib.parse import parse_qs
+
+import httpx
+import pytest
+
+from gitout.device_flow import (
+    DEVICE_GRANT_TYPE,
+    DeviceCode,
+    DeviceFlowError,
+    device_login,
+    poll_for_token,
+)
+
+
+def _login_server(
+    answers: list[dict[str, object]], seen: list[dict[str, list[str]]]
+) -> httpx.MockTransport:
+    def handle(request: httpx.Request) -> httpx.Response:
+        assert request.headers["Accept"] == "application/json"
+        form = parse_qs(request.content.decode())
+        seen.append(form)
+        if request.url.path == "/login/device/code":
+            return httpx.Response(
+                200,
+                json={
+                    "device_code": "dev-123",
+                    "user_code": "ABCD-1234",
+                    "verification_uri": "https://github.test/login/device",
+                    "interval": 5,
+                    "expires_in": 900,
+                },
+            )
+        assert request.url.path == "/login/oauth/access_token"
+        return httpx.Response(200, json=answers.pop(0))
+
+    return httpx.MockTransport(handle)
+
+
+async def test_device_login_polls_until_authorised() -> None:
+    seen: list[dict[str, list[str]]] = []
+    sleeps: list[float] = []
+    codes: list[DeviceCode] = []
+    answers: list[dict[str, object]] = [
+        {"error": "authorization_pending"},
+        {"error": "slow_down", "interval": 10},
+        {"access_token": "gho_token", "token_type": "bearer", "scope": "repo,gist"},
+    ]
+
+    async def sleep(seconds: float) -> None:
+        sleeps.append(seconds)
+
+    async with httpx.AsyncClient(transport=_login_server(answers, seen)) as client:
+        token = await device_login(
+            "client-1",
+            codes.append,
+            scopes=("repo", "gist"),
+            web_url="https://github.test/",
+            client=client,
+            sleep=sleep,
+        )
+
+    assert token == "gho_token"
+    assert codes[0].user_code == "ABCD-1234"
+    assert seen[0] == {"client_id": ["client-1"], "scope": ["repo gist"]}
+    assert seen[1]["grant_type"] == [DEVICE_GRANT_TYPE]
+    assert seen[1]["device_code"] == ["dev-123"]
+    assert sleeps == [5.0, 5.0, 10.0]  # slow_down raises the interval
+
+
+@pytest.mark.parametrize(
+    ("answer", "message"),
+    [
+        ({"error": "access_denied", "error_description": "denied by user"}, "denied by user"),
+        ({"error": "expired_token"}, "expired_token"),
+    ],
+)
+async def test_poll_fails_on_terminal_errors(answer: dict[str, object], message: str) -> None:
+    code = DeviceCode("dev-123", "ABCD-1234", "https://github.test/login/device", 1.0, 60.0)
+
+    async def sleep(seconds: float) -> None:
+        return None
+
+    async with httpx.AsyncClient(transport=_login_server([answer], [])) as client:
+        with pytest.raises(DeviceFlowError, match=message):
+            await poll_for_token(
+                client, "client-1", code, web_url="https://github.test", sleep=sleep
+            )
+
+
+async def test_poll_gives_up_when_the_code_expires() -> None:
+    code = DeviceCode("dev-123", "ABCD-1234", "https://github.test/login/device", 5.0, 12.0)
+    now = [0.0]
+
+    async def sleep(seconds: float) -> None:
+        now[0] += seconds
+
+    pending: list[dict[str, object]] = [{"error": "authorization_pending"}] * 3
+    async with httpx.AsyncClient(transport=_login_server(pending, [])) as client:
+        with pytest.raises(DeviceFlowError, match="expired"):
+            await poll_for_token(
+                client,
+                "client-1",
+                code,
+                web_url="https://github.test",
+                sleep=sleep,
+                clock=lambda: now[0],
+            )
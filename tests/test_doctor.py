@@ -0,0 +1,106 @@
+"""``gitout doctor`` diagnostics with fake git, HTTP and token checks."""
+
+from __future__ import annotations
+
+from collections.abc import Mapping
+from pathlib import Path
+
+import httpx
+
+from gitout.config import parse
+from gitout.doctor import Diagnosis, diagnose
+from gitout.engine import GitRunner
+from gitout.token_check import TokenCheckError
+
+
+def _git(responses: dict[str, tuple[int, str]]) -> GitRunner:
+    async def run(argv: list[str], cwd: Path, timeout: float) -> tuple[int, str]:
+        key = " ".join(argv[3:])  # after "<git> -c safe.directory=*"
+        return responses.get(key, (1, ""))
+
+    return run
+
+
+def _client(status: int = 200) -> httpx.AsyncClient:
+    return httpx.AsyncClient(transport=httpx.MockTransport(lambda _: httpx.Response(status)))
+
+
+async def _checker(token: str, required: Mapping[str, str]) -> str:
+    if token != "good":
+        raise TokenCheckError("GitHub rejected the token (401 Bad credentials).")
+    return "octocat"
+
+
+async def test_healthy_environment_reports_every_check(tmp_path: Path) -> None:
+    config = parse('version = 0\n[github]\nuser = "octocat"\ntoken = "good"\n')
+    git = _git({"--version": (0, "git version 2.45.0\n")})
+
+    async with _client() as client:
+        found = await diagnose(
+            config, tmp_path, environ={}, client=client, token_checker=_checker, git_runner=git
+        )
+
+    assert [d.check for d in found] == ["git", "tls", "tls", "network", "token", "destination"]
+    assert all(d.status == "ok" for d in found), found
+    assert found[0].detail == "git version 2.45.0"
+    assert found[1].detail == "git TLS backend: git's build default"
+    assert found[3].detail.startswith("https://api.github.com answered HTTP 200 in ")
+    assert found[4] == Diagnosis("token", "ok", "Token of octocat has the needed scopes")
+    assert found[5].detail.startswith(f"{tmp_path} is writable, ")
+
+
+async def test_broken_environment_fails_each_check(tmp_path: Path) -> None:
+    config = parse(
+        'version = 0\n[github]\nuser = "octocat"\ntoken = "bad"\n'
+        '[ssl]\ncert_file = "/nonexistent/ca.pem"\nverify_certificates = false\n'
+        "[lfs]\nfetch_lfs = true\n"
+    )
+    git = _git(
+        {
+            "--version": (0, "git version 2.45.0\n"),
+            "config --get http.sslBackend": (0, "openssl\n"),
+            "config --get http.sslCAInfo": (0, f"{tmp_path}\n"),
+        }
+    )
+
+    def unreachable(request: httpx.Request) -> httpx.Response:
+        raise httpx.ConnectError("connection refused", request=request)
+
+    async with httpx.AsyncClient(transport=httpx.MockTransport(unreachable)) as client:
+        found = await diagnose(
+            config,
+            tmp_path / "missing",
+            environ={},
+            client=client,
+            token_checker=_checker,
+            git_runner=git,
+        )
+
+    by_status = {(d.check, d.status) for d in found}
+    assert ("git", "fail") in by_status  # git lfs is missing
+    assert Diagnosis("tls", "ok", "git TLS backend: openssl") in found
+    assert [d.detail for d in found if d.status == "fail" and d.check == "tls"] == [
+        "CA bundle from ssl.cert_file does not exist: /nonexistent/ca.pem",
+        f"CA bundle from http.sslCAInfo does not exist: {tmp_path}",
+    ]
+    assert Diagnosis("tls", "warning", "ssl.verify_certificates is off") in found
+    assert ("network", "fail") in by_status
+    assert Diagnosis("token", "fail", "GitHub rejected the token (401 Bad credentials).") in found
+    assert found[-1] == Diagnosis(
+        "destination", "fail", f"Backup destination does not exist: {tmp_path / 'missing'}"
+    )
+
+
+async def test_missing_git_stops_at_the_git_check() -> None:
+    async def no_git(argv: list[str], cwd: Path, timeout: float) -> tuple[int, str]:
+        raise FileNotFoundError(2, "No such file or directory", "git")
+
+    async with _client(404) as client:
+        found = await diagnose(
+            None, None, environ={"GITHUB_TOKEN": "t"}, client=client, git_runner=no_git
+        )
+
+    assert found[0].check == "git"
+    assert found[0].status == "fail"
+    assert found[0].detail.startswith("git does not run: ")
+    assert found[-1] == Diagnosis("token", "ok", "A GitHub token is available (no config to check)")
@@ -0,0 +1,64 @@
+"""Dry-run download/API estimate from planned tasks and config."""
+
+from __future__ import annotations
+
+from pathlib import Path
+
+from gitout.config import Config, GitHubArchive, GitHubConfig, GitHubMetadata
+from gitout.engine import SyncTask
+from gitout.estimate import RunEstimate, estimate_run, format_estimate
+
+
+def _task(tmp_path: Path, name: str, size_kb: int | None, **kwargs: object) -> SyncTask:
+    return SyncTask(
+        name=name,
+        url=f"https://github.com/{name}.git",
+        destination=tmp_path / name,
+        size_kb=size_kb,
+        **kwargs,  # type: ignore[arg-type]
+    )
+
+
+def test_clones_count_full_size_updates_count_growth(tmp_path: Path) -> None:
+    existing = tmp_path / "me" / "old"
+    existing.mkdir(parents=True)
+    (existing / "pack").write_bytes(b"x" * 1024)  # 1 KiB already on disk
+    tasks = [
+        _task(tmp_path, "me/new", 2048),
+        _task(tmp_path, "me/old", 3),
+        _task(tmp_path, "gist:abc", None),
+    ]
+    estimate = estimate_run(tasks, Config(version=0))
+    assert estimate == RunEstimate(
+        clones=2, updates=1, download_bytes=2048 * 1024 + 2 * 1024, unknown_size=1
+    )
+
+
+def test_api_requests_for_enabled_exports(tmp_path: Path) -> None:
+    owned = frozenset({"owned"})
+    tasks = [
+        _task(tmp_path, "me/a", 1, reasons=owned),
+        _task(tmp_path, "me/b", 1, reasons=owned),
+        _task(tmp_path, "them/c", 1, reasons=frozenset({"starred"})),
+    ]
+    config = Config(
+        version=0,
+        github=GitHubConfig(
+            user="me",
+            metadata=GitHubMetadata(settings=True, community=True),
+            archive=GitHubArchive(owned=True, repos=["org/x"]),
+        ),
+    )
+    # 2 owned x (3 settings + 2 community) + 3 archives x 3
+    assert estimate_run(tasks, config).api_requests == 19
+
+
+def test_format_estimate() -> None:
+    lines = format_estimate(
+        RunEstimate(clones=1, updates=2, download_bytes=3 * 1024**3, unknown_size=4)
+    )
+    assert lines == [
+        "ESTIMATE 1 clones, 2 updates",
+        "ESTIMATE download ~3.0 GiB (+4 repositories of unknown size)",
+        "ESTIMATE at least 0 GitHub API requests beyond discovery",
+    ]
@@ -0,0 +1,78 @@
+"""Copying the synced destination to further directories."""
+
+from __future__ import annotations
+
+import os
+from pathlib import Path
+
+from gitout.fanout import _walk, fan_out
+
+
+def _write(path: Path, text: str = "x") -> None:
+    path.parent.mkdir(parents=True, exist_ok=True)
+    path.write_text(text)
+
+
+def _mirror(root: Path) -> Path:
+    mirror = root / "git" / "x"
+    _write(mirror / "HEAD", "ref: refs/heads/main\n")
+    _write(mirror / "packed-refs", "")
+    _write(mirror / "refs" / "heads" / "main", "0" * 40 + "\n")
+    _write(mirror / "objects" / "pack" / "pack-1.pack", "pack")
+    (mirror / "refs" / "tags").mkdir()
+    _write(mirror / "objects" / "pack" / "pack-2.pack.tmp", "half written")
+    return mirror
+
+
+def test_refs_are_copied_after_the_objects(tmp_path: Path) -> None:
+    _mirror(tmp_path)
+    directories, files = _walk(tmp_path)
+    assert Path("git/x/refs/tags") in directories
+    assert [f.as_posix() for f in files] == [
+        "git/x/objects/pack/pack-1.pack",
+        "git/x/HEAD",
+        "git/x/packed-refs",
+        "git/x/refs/heads/main",
+    ]
+
+
+def test_copies_changes_and_removes_what_is_gone(tmp_path: Path) -> None:
+    source, target = tmp_path / "src", tmp_path / "nas"
+    mirror = _mirror(source)
+
+    first = fan_out(source, target)
+    assert (first.copied, first.removed, first.ok) == (4, 0, True)
+    assert (target / "git" / "x" / "refs" / "tags").is_dir()
+    assert not (target / "git" / "x" / "objects" / "pack" / "pack-2.pack.tmp").exists()
+
+    (mirror / "objects" / "pack" / "pack-1.pack").unlink()
+    _write(mirror / "objects" / "pack" / "pack-3.pack", "repacked")
+    _write(target / "stale" / "file")
+    second = fan_out(source, target)
+
+    assert (second.copied, second.removed) == (1, 2)
+    assert sorted(p.name for p in (target / "git" / "x" / "objects" / "pack").iterdir()) == [
+        "pack-3.pack"
+    ]
+    assert not (target / "stale").exists()
+    assert fan_out(source, target).copied == 0
+
+
+def test_modified_files_are_copied_again_and_delete_can_be_off(tmp_path: Path) -> None:
+    source, target = tmp_path / "src", tmp_path / "nas"
+    _write(source / "state.json", "{}")
+    _write(target / "extra.json", "{}")
+    fan_out(source, target, delete=False)
+
+    _write(source / "state.json", "{ }")
+    os.utime(source / "state.json", ns=(1, 10**18))
+    result = fan_out(source, target, delete=False)
+
+    assert (result.copied, result.removed) == (1, 0)
+    assert (target / "state.json").read_text() == "{ }"
+    assert (target / "extra.json").exists()
+
+
+def test_overlapping_destinations_are_refused(tmp_path: Path) -> None:
+    assert fan_out(tmp_path, tmp_path / "copy").error is not None
+    assert fan_out(tmp_path / "src", tmp_path).error is not None
@@ -0,0 +1,68 @@
+"""Frontend configuration export (cgit, gitolite, manifest) over a fake destination."""
+
+from __future__ import annotations
+
+import json
+from pathlib import Path
+
+import pytest
+
+from gitout.frontends import find_repositories, render
+
+
+def _bare(path: Path, description: str | None = None) -> None:
+    (path / "objects").mkdir(parents=True)
+    (path / "refs").mkdir()
+    (path / "HEAD").write_text("ref: refs/heads/main\n")
+    (path / "description").write_text(
+        description or "Unnamed repository; edit this file 'description' to name it.\n"
+    )
+
+
+@pytest.fixture
+def destination(tmp_path: Path) -> Path:
+    _bare(tmp_path / "github" / "clone" / "octocat" / "hello", "Hello world")
+    _bare(tmp_path / "github" / "gists" / "abc123")
+    _bare(tmp_path / "git" / "self" / "dotfiles")
+    (tmp_path / "github" / "archive" / "octocat").mkdir(parents=True)  # not a mirror tree
+    (tmp_path / "git" / "not-a-repo").mkdir()
+    _bare(tmp_path / "git" / "self" / "dotfiles.corrupt-2026-01-02")  # quarantined
+    return tmp_path
+
+
+def test_find_repositories(destination: Path) -> None:
+    repos = find_repositories(destination)
+    assert [(r.name, r.section, r.description) for r in repos] == [
+        ("git/self/dotfiles", "Git", None),
+        ("github/clone/octocat/hello", "GitHub", "Hello world"),
+        ("github/gists/abc123", "Gists", None),
+    ]
+
+
+def test_render_cgit(destination: Path) -> None:
+    text = render("cgit", destination)
+    hello = destination.resolve() / "github" / "clone" / "octocat" / "hello"
+    assert (
+        "repo.url=github/clone/octocat/hello\n"
+        f"repo.path={hello}\n"
+        "repo.section=GitHub\n"
+        "repo.desc=Hello world\n"
+    ) in text
+    assert text.count("repo.url=") == 3
+
+
+def test_render_gitolite_and_manifest(destination: Path) -> None:
+    assert render("gitolite", destination).startswith("repo git/self/dotfiles\n    R = @all\n")
+    manifest = json.loads(render("manifest", destination))
+    assert [e["path"] for e in manifest["repositories"]] == [
+        "git/self/dotfiles",
+        "github/clone/octocat/hello",
+        "github/gists/abc123",
+    ]
+
+
+def test_render_rejects_unknown_format_and_missing_destination(tmp_path: Path) -> None:
+    with pytest.raises(ValueError, match="Unknown export format"):
+        render("gitweb", tmp_path)
+    with pytest.raises(ValueError, match="does not exist"):
+        render("cgit", tmp_path / "missing")
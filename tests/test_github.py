@@ -38,3 +38,22 @@ def test_owned_priority_over_starred(parsed: github.UserRepositories) -> None:
     # repo-a appears in both owned and starred; metadata must remain "owned".
     assert "octocat/repo-a" in parsed.starred
     assert parsed.metadata["octocat/repo-a"].repo_type == "owned"
+
+
+def test_starred_gists_fold_with_own_gists_taking_priority() -> None:
+    starred = [
+        {"id": "gist-public-1", "public": True, "updated_at": "2024-01-01T00:00:00Z"},
+        {
+            "id": "f00d",
+            "public": False,
+            "description": "someone's snippet",
+            "updated_at": "2024-02-02T00:00:00Z",
+        },
+    ]
+    parsed = github.parse_user_repositories(_FIXTURE["pages"], starred)
+    assert parsed.starred_gists == {"f00d"}  # own gist-public-1 stays a plain gist
+    assert parsed.metadata["gist-public-1"].repo_type == "gist"
+    meta = parsed.metadata["f00d"]
+    assert meta.repo_type == "starred_gist"
+    assert meta.is_private is True
+    assert meta.description == "someone's snippet"
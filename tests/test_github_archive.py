@@ -0,0 +1,439 @@
+"""Migration archive export against an in-memory REST API (httpx MockTransport)."""
+
+from __future__ import annotations
+
+import io
+import json
+import tarfile
+from collections.abc import Callable
+from datetime import UTC, datetime
+from pathlib import Path
+
+import httpx
+import pytest
+
+from gitout.github_archive import (
+    MigrationArchiver,
+    MigrationError,
+    MigrationRequest,
+    archive_targets,
+    prune_archives,
+    verify_archive,
+)
+from gitout.github_client import TokenPool
+
+
+def _tarball(text: str = "issues") -> bytes:
+    """A small but real migration-style .tar.gz."""
+    buffer = io.BytesIO()
+    with tarfile.open(fileobj=buffer, mode="w:gz") as archive:
+        data = text.encode()
+        info = tarfile.TarInfo("issues_000001.json")
+        info.size = len(data)
+        archive.addfile(info, io.BytesIO(data))
+    return buffer.getvalue()
+
+
+TARBALL = _tarball()
+
+
+async def _no_sleep(seconds: float) -> None:
+    return None
+
+
+def _archiver(handler: object, **kwargs: object) -> MigrationArchiver:
+    transport = httpx.MockTransport(handler)  # type: ignore[arg-type]
+    return MigrationArchiver(
+        "tok",
+        client=httpx.AsyncClient(transport=transport),
+        api_url="https://api.test",
+        sleep=_no_sleep,
+        **kwargs,  # type: ignore[arg-type]
+    )
+
+
+async def test_archive_starts_polls_and_downloads(tmp_path: Path) -> None:
+    polls = {"n": 0}
+    started: list[object] = []
+
+    def handle(request: httpx.Request) -> httpx.Response:
+        if request.url.host == "storage.test":
+            assert "Authorization" not in request.headers  # not leaked to storage
+            return httpx.Response(200, content=TARBALL)
+        assert request.headers["Authorization"] == "Bearer tok"
+        path = request.url.path
+        if request.method == "POST" and path == "/user/migrations":
+            started.append(request.content)
+            return httpx.Response(201, json={"id": 42, "state": "pending"})
+        if path == "/user/migrations/42":
+            polls["n"] += 1
+            state = "exported" if polls["n"] >= 3 else "exporting"
+            return httpx.Response(200, json={"id": 42, "state": state})
+        if path == "/user/migrations/42/archive":
+            return httpx.Response(302, headers={"Location": "https://storage.test/blob"})
+        return httpx.Response(404)
+
+    assert await _archiver(handle).archive_to("me/repo", tmp_path) is True
+    assert polls["n"] == 3
+    assert b'"repositories":["me/repo"]' in started[0]  # type: ignore[operator]
+    assert (tmp_path / "me" / "repo.tar.gz").read_bytes() == TARBALL
+    assert not list((tmp_path / "me").glob("*.partial"))
+
+
+async def test_migration_request_options_are_sent(tmp_path: Path) -> None:
+    bodies: list[dict[str, object]] = []
+
+    def handle(request: httpx.Request) -> httpx.Response:
+        if request.method == "POST":
+            bodies.append(json.loads(request.content))
+            return httpx.Response(201, json={"id": 3, "state": "pending"})
+        if request.url.path.endswith("/archive"):
+            return httpx.Response(200, content=TARBALL)
+        return httpx.Response(200, json={"id": 3, "state": "exported"})
+
+    request = MigrationRequest(exclude_attachments=True, lock_repositories=True)
+    assert await _archiver(handle, request=request).archive_to("me/big", tmp_path) is True
+    assert bodies == [
+        {
+            "repositories": ["me/big"],
+            "lock_repositories": True,
+            "exclude_attachments": True,
+            "exclude_releases": False,
+            "exclude_metadata": False,
+        }
+    ]
+    assert MigrationRequest(exclude=("repositories",)).payload("me/big")["exclude"] == [
+        "repositories"
+    ]
+
+
+async def test_throttling_is_retried_after_retry_after(tmp_path: Path) -> None:
+    sleeps: list[float] = []
+    throttled = {
+        "POST /user/migrations",
+        "GET /user/migrations/5",
+        "GET /user/migrations/5/archive",
+    }
+
+    async def sleep(seconds: float) -> None:
+        sleeps.append(seconds)
+
+    def handle(request: httpx.Request) -> httpx.Response:
+        key = f"{request.method} {request.url.path}"
+        if key in throttled:
+            throttled.discard(key)
+            headers = {"Retry-After": "12"} if request.method == "POST" else {}
+            return httpx.Response(429, headers=headers)
+        if request.method == "POST":
+            return httpx.Response(201, json={"id": 5, "state": "pending"})
+        if request.url.path.endswith("/archive"):
+            return httpx.Response(200, content=TARBALL)
+        return httpx.Response(200, json={"id": 5, "state": "exported"})
+
+    transport = httpx.MockTransport(handle)
+    archiver = MigrationArchiver(
+        "tok",
+        client=httpx.AsyncClient(transport=transport),
+        api_url="https://api.test",
+        sleep=sleep,
+        backoff_seconds=1.0,
+    )
+    assert await archiver.archive_to("me/repo", tmp_path) is True
+    assert sleeps == [12.0, 1.0, 1.0]  # Retry-After wins, else backoff from attempt 0
+    assert (tmp_path / "me" / "repo.tar.gz").read_bytes() == TARBALL
+
+
+async def test_persistent_throttling_fails_the_repository_only(tmp_path: Path) -> None:
+    def handle(request: httpx.Request) -> httpx.Response:
+        return httpx.Response(429)
+
+    archiver = _archiver(handle, max_throttle_retries=2)
+    assert await archiver.archive_to("me/repo", tmp_path) is False
+
+
+def _listing_handler(
+    migrations: list[dict[str, object]], posts: list[str], lists: list[int]
+) -> Callable[[httpx.Request], httpx.Response]:
+    def handle(request: httpx.Request) -> httpx.Response:
+        path = request.url.path
+        if request.method == "POST":
+            posts.append(json.loads(request.content)["repositories"][0])
+            return httpx.Response(201, json={"id": 100 + len(posts), "state": "pending"})
+        if path == "/user/migrations":
+            lists.append(int(request.url.params["page"]))
+            return httpx.Response(200, json=migrations)
+        if path.endswith("/archive"):
+            return httpx.Response(200, content=TARBALL)
+        return httpx.Response(200, json={"state": "exported"})
+
+    return handle
+
+
+def _migration(mid: int, repo: str, state: str, created: str) -> dict[str, object]:
+    return {
+        "id": mid,
+        "state": state,
+        "created_at": created,
+        "repositories": [{"full_name": repo}],
+    }
+
+
+async def test_in_flight_migrations_are_reused(tmp_path: Path) -> None:
+    posts: list[str] = []
+    lists: list[int] = []
+    migrations = [
+        _migration(1, "me/a", "exporting", "2024-06-01T00:00:00Z"),
+        _migration(2, "me/a", "failed", "2024-06-02T00:00:00Z"),
+        _migration(3, "me/b", "exported", "2020-01-01T00:00:00Z"),  # older than local copy
+        _migration(4, "me/c", "pending", "2024-06-01T00:00:00Z"),
+    ]
+    (tmp_path / "me").mkdir()
+    (tmp_path / "me" / "b.tar.gz").write_bytes(TARBALL)  # mtime: now
+    now = datetime(2024, 6, 3, tzinfo=UTC)
+    archiver = _archiver(_listing_handler(migrations, posts, lists), clock=lambda: now)
+
+    for repo in ("me/a", "me/b", "me/C", "me/d"):
+        assert await archiver.archive_to(repo, tmp_path) is True
+
+    assert posts == ["me/b", "me/d"]  # a and c (case-insensitive) were in flight
+    assert lists == [1]  # listed once per run
+
+
+async def test_exported_migration_newer_than_local_archive_is_reused(tmp_path: Path) -> None:
+    posts: list[str] = []
+    migrations = [_migration(7, "me/a", "exported", "2999-01-01T00:00:00Z")]
+    (tmp_path / "me").mkdir()
+    (tmp_path / "me" / "a.tar.gz").write_bytes(b"old")
+    archiver = _archiver(_listing_handler(migrations, posts, []))
+    assert await archiver.archive_to("me/a", tmp_path) is True
+    assert posts == []
+    assert (tmp_path / "me" / "a.tar.gz").read_bytes() == TARBALL
+
+
+async def test_migrations_older_than_a_week_are_not_reused(tmp_path: Path) -> None:
+    posts: list[str] = []
+    migrations = [
+        _migration(1, "me/a", "exported", "2024-06-01T00:00:00Z"),
+        _migration(2, "me/b", "exporting", "2024-06-01T00:00:00Z"),
+    ]
+    now = datetime(2024, 6, 8, 1, tzinfo=UTC)
+    archiver = _archiver(_listing_handler(migrations, posts, []), clock=lambda: now)
+    assert await archiver.archive_to("me/a", tmp_path) is True
+    assert await archiver.archive_to("me/b", tmp_path) is True
+    assert posts == ["me/a", "me/b"]
+
+
+async def test_expired_archive_of_a_reused_migration_starts_a_new_one(tmp_path: Path) -> None:
+    posts: list[str] = []
+    migrations = [_migration(7, "me/a", "exported", "2999-01-01T00:00:00Z")]
+    listing = _listing_handler(migrations, posts, [])
+
+    def handle(request: httpx.Request) -> httpx.Response:
+        if request.url.path == "/user/migrations/7/archive":
+            return httpx.Response(410)
+        return listing(request)
+
+    assert await _archiver(handle).archive_to("me/a", tmp_path) is True
+    assert posts == ["me/a"]
+    assert (tmp_path / "me" / "a.tar.gz").read_bytes() == TARBALL
+
+
+async def test_locked_repository_is_unlocked_after_download(tmp_path: Path) -> None:
+    calls: list[str] = []
+
+    def handle(request: httpx.Request) -> httpx.Response:
+        calls.append(f"{request.method} {request.url.path}")
+        if request.method == "POST":
+            return httpx.Response(201, json={"id": 8, "state": "pending"})
+        if request.method == "DELETE":
+            return httpx.Response(204)
+        if request.url.path.endswith("/archive"):
+            return httpx.Response(200, content=TARBALL)
+        return httpx.Response(200, json={"id": 8, "state": "exported"})
+
+    archiver = _archiver(handle, request=MigrationRequest(lock_repositories=True))
+    assert await archiver.archive_to("me/repo", tmp_path) is True
+    assert calls[-2:] == [
+        "GET /user/migrations/8/archive",
+        "DELETE /user/migrations/8/repos/repo/lock",
+    ]
+
+
+async def test_unlock_all_releases_locks_of_locking_migrations() -> None:
+    deleted: list[str] = []
+    migrations = [
+        {**_migration(1, "me/a", "failed", ""), "lock_repositories": True},
+        {**_migration(2, "me/b", "exported", ""), "lock_repositories": False},
+        {**_migration(3, "me/c", "exporting", ""), "lock_repositories": True},
+    ]
+
+    def handle(request: httpx.Request) -> httpx.Response:
+        if request.method == "DELETE":
+            deleted.append(request.url.path)
+            return httpx.Response(404 if "/3/" in request.url.path else 204)
+        return httpx.Response(200, json=migrations)
+
+    assert await _archiver(handle).unlock_all() == ["me/a", "me/c"]
+    assert deleted == ["/user/migrations/1/repos/a/lock", "/user/migrations/3/repos/c/lock"]
+
+
+async def test_a_migration_keeps_the_token_that_started_it(tmp_path: Path) -> None:
+    pool = TokenPool(["a", "b"])
+    seen: list[str] = []
+
+    def handle(request: httpx.Request) -> httpx.Response:
+        seen.append(f"{request.method} {request.url.path} {request.headers['Authorization']}")
+        if request.method == "POST":
+            # The budget of token "a" is spent: the pool moves on to "b" for new work.
+            exhausted = {"X-RateLimit-Remaining": "0", "X-RateLimit-Reset": "9999999999"}
+            return httpx.Response(201, json={"id": 8, "state": "pending"}, headers=exhausted)
+        if request.method == "DELETE":
+            return httpx.Response(204)
+        if request.url.path.endswith("/archive"):
+            return httpx.Response(200, content=TARBALL)
+        if request.url.path == "/user/migrations":
+            return httpx.Response(200, json=[])
+        return httpx.Response(200, json={"id": 8, "state": "exported"})
+
+    archiver = MigrationArchiver(
+        pool,
+        client=httpx.AsyncClient(transport=httpx.MockTransport(handle)),
+        api_url="https://api.test",
+        sleep=_no_sleep,
+        request=MigrationRequest(lock_repositories=True),
+    )
+    assert await archiver.archive_to("me/repo", tmp_path) is True
+    assert pool.token == "b"
+    assert [call for call in seen if not call.startswith("GET /user/migrations ")] == [
+        "POST /user/migrations Bearer a",
+        "GET /user/migrations/8 Bearer a",
+        "GET /user/migrations/8/archive Bearer a",
+        "DELETE /user/migrations/8/repos/repo/lock Bearer a",
+    ]
+
+
+async def test_unlock_all_lists_the_migrations_of_every_token() -> None:
+    deleted: list[str] = []
+
+    def handle(request: httpx.Request) -> httpx.Response:
+        token = request.headers["Authorization"].removeprefix("Bearer ")
+        if request.method == "DELETE":
+            deleted.append(f"{request.url.path} {token}")
+            return httpx.Response(204)
+        migration = _migration(1 if token == "a" else 2, f"me/{token}", "failed", "")
+        return httpx.Response(200, json=[{**migration, "lock_repositories": True}])
+
+    archiver = MigrationArchiver(
+        TokenPool(["a", "b"]),
+        client=httpx.AsyncClient(transport=httpx.MockTransport(handle)),
+        api_url="https://api.test",
+    )
+    assert await archiver.unlock_all() == ["me/a", "me/b"]
+    assert deleted == ["/user/migrations/1/repos/a/lock a", "/user/migrations/2/repos/b/lock b"]
+
+
+async def test_failed_migration_is_reported_not_raised(tmp_path: Path) -> None:
+    def handle(request: httpx.Request) -> httpx.Response:
+        if request.method == "POST":
+            return httpx.Response(201, json={"id": 7, "state": "pending"})
+        return httpx.Response(200, json={"id": 7, "state": "failed"})
+
+    assert await _archiver(handle).archive_to("me/repo", tmp_path) is False
+    assert not (tmp_path / "me" / "repo.tar.gz").exists()
+
+
+async def test_migration_times_out(tmp_path: Path) -> None:
+    def handle(request: httpx.Request) -> httpx.Response:
+        if request.method == "POST":
+            return httpx.Response(201, json={"id": 7, "state": "pending"})
+        return httpx.Response(200, json={"id": 7, "state": "exporting"})
+
+    archiver = _archiver(handle, max_wait_seconds=0.0)
+    assert await archiver.archive_to("me/repo", tmp_path) is False
+
+
+async def test_retention_keeps_newest_dated_archives(tmp_path: Path) -> None:
+    def handle(request: httpx.Request) -> httpx.Response:
+        if request.method == "POST":
+            return httpx.Response(201, json={"id": 1, "state": "pending"})
+        if request.url.path.endswith("/archive"):
+            return httpx.Response(200, content=_tarball("new"))
+        return httpx.Response(200, json={"id": 1, "state": "exported"})
+
+    owner = tmp_path / "me"
+    owner.mkdir()
+    for day in ("2024-05-01", "2024-05-15"):
+        (owner / f"repo-{day}.tar.gz").write_bytes(b"old")
+    (owner / "repo-tools-2024-01-01.tar.gz").write_bytes(b"other repo")
+    archiver = _archiver(
+        handle, retention=2, clock=lambda: datetime(2024, 6, 1, 3, 0, tzinfo=UTC)
+    )
+
+    assert await archiver.archive_to("me/repo", tmp_path) is True
+    assert sorted(p.name for p in owner.iterdir()) == [
+        "repo-2024-05-15.tar.gz",
+        "repo-2024-06-01.tar.gz",
+        "repo-tools-2024-01-01.tar.gz",
+    ]
+    assert (owner / "repo-2024-06-01.tar.gz").read_bytes() == _tarball("new")
+
+
+def test_prune_archives_ignores_undated_files(tmp_path: Path) -> None:
+    (tmp_path / "repo.tar.gz").write_bytes(b"")
+    (tmp_path / "repo-2024-01-01.tar.gz").write_bytes(b"")
+    assert prune_archives(tmp_path, "repo", 1) == []
+    assert prune_archives(tmp_path, "repo", 0) == [tmp_path / "repo-2024-01-01.tar.gz"]
+    assert (tmp_path / "repo.tar.gz").exists()
+
+
+async def test_corrupt_or_truncated_download_keeps_previous_archive(tmp_path: Path) -> None:
+    bodies = [TARBALL[: len(TARBALL) // 2], b"<html>not an archive</html>"]
+
+    def handle(request: httpx.Request) -> httpx.Response:
+        if request.method == "POST":
+            return httpx.Response(201, json={"id": 9, "state": "pending"})
+        if request.url.path.endswith("/archive"):
+            return httpx.Response(200, content=bodies.pop(0))
+        return httpx.Response(200, json={"id": 9, "state": "exported"})
+
+    previous = tmp_path / "me" / "repo.tar.gz"
+    previous.parent.mkdir()
+    previous.write_bytes(b"good backup")
+    archiver = _archiver(handle)
+    assert await archiver.archive_to("me/repo", tmp_path) is False
+    assert await archiver.archive_to("me/repo", tmp_path) is False
+    assert previous.read_bytes() == b"good backup"
+    assert list(previous.parent.iterdir()) == [previous]
+
+
+async def test_short_read_against_content_length_is_rejected(tmp_path: Path) -> None:
+    def handle(request: httpx.Request) -> httpx.Response:
+        if request.method == "POST":
+            return httpx.Response(201, json={"id": 9, "state": "pending"})
+        if request.url.path.endswith("/archive"):
+            headers = {"Content-Length": str(len(TARBALL) + 10)}
+            return httpx.Response(200, content=TARBALL, headers=headers)
+        return httpx.Response(200, json={"id": 9, "state": "exported"})
+
+    assert await _archiver(handle).archive_to("me/repo", tmp_path) is False
+    assert not (tmp_path / "me" / "repo.tar.gz").exists()
+
+
+def test_verify_archive_rejects_empty_tarball(tmp_path: Path) -> None:
+    buffer = io.BytesIO()
+    with tarfile.open(fileobj=buffer, mode="w:gz"):
+        pass
+    empty = tmp_path / "empty.tar.gz"
+    empty.write_bytes(buffer.getvalue())
+    with pytest.raises(MigrationError, match="empty"):
+        verify_archive(empty)
+
+
+def test_archive_targets() -> None:
+    owned = {"me/b", "me/a"}
+    assert archive_targets(owned, ["org/x", "me/a"], include_owned=True) == [
+        "me/a",
+        "me/b",
+        "org/x",
+    ]
+    assert archive_targets(owned, ["org/x"], include_owned=False) == ["org/x"]
@@ -2,12 +2,24 @@
 
 from __future__ import annotations
 
+import asyncio
 import json
 from typing import Any
 
 import httpx
+import pytest
 
-from gitout.github_client import load_repositories
+from gitout.github_client import (
+    DEFAULT_USER_AGENT,
+    RateLimiter,
+    TokenPool,
+    api_headers,
+    graphql_url,
+    load_repositories,
+    rest_url,
+    throttle_delay,
+    web_url,
+)
 
 _REPO_NODE = {
     "nameWithOwner": "me/r1",
@@ -33,24 +45,358 @@ def _empty_user() -> dict[str, Any]:
     }
 
 
+def _alias(payload: dict[str, Any]) -> str:
+    return next(alias for alias in _empty_user() if f"{alias}:" in payload["query"])
+
+
 async def test_load_repositories_pages_until_empty() -> None:
-    calls = {"n": 0}
+    calls: dict[str, int] = {}
 
     def handler(request: httpx.Request) -> httpx.Response:
         payload = json.loads(request.content)
         assert request.headers["Authorization"] == "Bearer tok"
         assert payload["variables"]["login"] == "me"
-        calls["n"] += 1
-        if calls["n"] == 1:
-            user = _empty_user()
-            user["ownedRepositories"] = {"ownedEdges": [{"cursor": "c1", "node": _REPO_NODE}]}
-            return httpx.Response(200, json={"data": {"user": user}})
-        return httpx.Response(200, json={"data": {"user": _empty_user()}})
+        alias = _alias(payload)
+        calls[alias] = calls.get(alias, 0) + 1
+        user = {alias: _empty_user()[alias]}
+        if alias == "ownedRepositories" and calls[alias] == 1:
+            user[alias] = {"ownedEdges": [{"cursor": "c1", "node": _REPO_NODE}]}
+        return httpx.Response(200, json={"data": {"user": user}})
 
     transport = httpx.MockTransport(handler)
     async with httpx.AsyncClient(transport=transport) as client:
         repos = await load_repositories("me", "tok", client=client)
 
-    assert calls["n"] == 2  # first page has data, second is empty -> stop
+    # Without pageInfo, the owned connection stops on its first empty page.
+    assert calls == {
+        "ownedRepositories": 2,
+        "starredRepositories": 1,
+        "watchingRepositories": 1,
+        "gistRepositories": 1,
+    }
     assert repos.owned == {"me/r1"}
     assert repos.metadata["me/r1"].default_branch == "main"
+
+
+async def test_load_repositories_follows_each_connection_cursor() -> None:
+    afters: dict[str, list[str | None]] = {}
+
+    def handler(request: httpx.Request) -> httpx.Response:
+        payload = json.loads(request.content)
+        alias = _alias(payload)
+        after = payload["variables"]["after"]
+        afters.setdefault(alias, []).append(after)
+        edges_alias = next(iter(_empty_user()[alias]))
+        user: dict[str, Any] = {alias: _empty_user()[alias]}
+        if alias == "starredRepositories":
+            name = "other/first" if after is None else "other/second"
+            user[alias] = {
+                edges_alias: [{"cursor": "ignored", "node": {**_REPO_NODE, "nameWithOwner": name}}],
+                "pageInfo": {"hasNextPage": after is None, "endCursor": "s1"},
+            }
+        return httpx.Response(200, json={"data": {"user": user}})
+
+    async with httpx.AsyncClient(transport=httpx.MockTransport(handler)) as client:
+        repos = await load_repositories("me", "tok", client=client)
+
+    assert afters["starredRepositories"] == [None, "s1"]
+    assert afters["ownedRepositories"] == [None]
+    assert repos.starred == {"other/first", "other/second"}
+
+
+async def test_load_repositories_sends_custom_user_agent_and_headers() -> None:
+    seen: list[httpx.Headers] = []
+
+    def handler(request: httpx.Request) -> httpx.Response:
+        seen.append(request.headers)
+        return httpx.Response(200, json={"data": {"user": _empty_user()}})
+
+    async with httpx.AsyncClient(transport=httpx.MockTransport(handler)) as client:
+        await load_repositories(
+            "me",
+            "tok",
+            client=client,
+            user_agent="backup-bot/1.0",
+            extra_headers={"X-Backup-Host": "nas01"},
+        )
+
+    assert seen[0]["User-Agent"] == "backup-bot/1.0"
+    assert seen[0]["X-Backup-Host"] == "nas01"
+    assert seen[0]["Authorization"] == "Bearer tok"
+
+
+async def test_a_failing_connection_cancels_the_other_pagers() -> None:
+    finished: list[str] = []
+
+    async def handler(request: httpx.Request) -> httpx.Response:
+        alias = _alias(json.loads(request.content))
+        if alias == "ownedRepositories":
+            return httpx.Response(502)
+        await asyncio.sleep(0.05)
+        finished.append(alias)
+        return httpx.Response(200, json={"data": {"user": _empty_user()}})
+
+    async with httpx.AsyncClient(transport=httpx.MockTransport(handler)) as client:
+        with pytest.raises(httpx.HTTPStatusError):
+            await load_repositories("me", "tok", client=client)
+        await asyncio.sleep(0.1)
+
+    assert finished == []
+
+
+def test_api_headers_cannot_override_auth() -> None:
+    headers = api_headers("tok", extra_headers={"Authorization": "nope", "X-Tag": "1"})
+    assert headers["Authorization"] == "Bearer tok"
+    assert headers["User-Agent"] == DEFAULT_USER_AGENT
+    assert headers["X-Tag"] == "1"
+
+
+async def test_load_repositories_lists_starred_gists_when_requested() -> None:
+    rest_pages = {
+        "1": ([{"id": "g1", "public": True, "updated_at": "2024-01-01T00:00:00Z"}], True),
+        "2": ([{"id": "g2", "public": False, "updated_at": "2024-01-02T00:00:00Z"}], False),
+    }
+
+    def handler(request: httpx.Request) -> httpx.Response:
+        if request.url.path == "/graphql":
+            return httpx.Response(200, json={"data": {"user": _empty_user()}})
+        assert request.url.path == "/gists/starred"
+        page = request.url.params.get("page", "1")
+        items, has_next = rest_pages[page]
+        headers = {"Link": '<https://api.test/gists/starred?page=2>; rel="next"'}
+        return httpx.Response(200, json=items, headers=headers if has_next else {})
+
+    async with httpx.AsyncClient(transport=httpx.MockTransport(handler)) as client:
+        without = await load_repositories(
+            "me", "tok", client=client, api_url="https://api.test"
+        )
+        repos = await load_repositories(
+            "me",
+            "tok",
+            client=client,
+            api_url="https://api.test",
+            starred_gists=True,
+        )
+
+    assert without.starred_gists == set()
+    assert repos.starred_gists == {"g1", "g2"}
+
+
+@pytest.mark.parametrize(
+    ("api_url", "rest", "graphql", "web"),
+    [
+        (
+            "https://api.github.com",
+            "https://api.github.com",
+            "https://api.github.com/graphql",
+            "https://github.com",
+        ),
+        (
+            "https://ghe.example.com/api",
+            "https://ghe.example.com/api/v3",
+            "https://ghe.example.com/api/graphql",
+            "https://ghe.example.com",
+        ),
+        (
+            "https://ghe.example.com/api/v3/",
+            "https://ghe.example.com/api/v3",
+            "https://ghe.example.com/api/graphql",
+            "https://ghe.example.com",
+        ),
+    ],
+)
+def test_endpoints_derive_from_the_api_url(api_url: str, rest: str, graphql: str, web: str) -> None:
+    assert (rest_url(api_url), graphql_url(api_url), web_url(api_url)) == (rest, graphql, web)
+
+
+async def test_load_repositories_on_github_enterprise_server() -> None:
+    paths: list[str] = []
+
+    def handler(request: httpx.Request) -> httpx.Response:
+        paths.append(request.url.path)
+        if request.url.path == "/api/graphql":
+            return httpx.Response(200, json={"data": {"user": _empty_user()}})
+        assert request.url.path == "/api/v3/gists/starred"
+        return httpx.Response(200, json=[])
+
+    async with httpx.AsyncClient(transport=httpx.MockTransport(handler)) as client:
+        await load_repositories(
+            "me", "tok", client=client, api_url="https://ghe.example.com/api", starred_gists=True
+        )
+
+    assert set(paths) == {"/api/graphql", "/api/v3/gists/starred"}
+
+
+@pytest.mark.parametrize(
+    ("status", "headers", "expected"),
+    [
+        (200, {"Retry-After": "5"}, None),
+        (404, {}, None),
+        (403, {}, None),  # a plain permission error is not throttling
+        (429, {"Retry-After": "30"}, 30.0),
+        (429, {"Retry-After": "Thu, 01 Jan 1970 00:01:40 GMT"}, 60.0),
+        (429, {}, 0.0),
+        (403, {"Retry-After": "7"}, 7.0),
+        (403, {"X-RateLimit-Remaining": "0", "X-RateLimit-Reset": "1000"}, 960.0),
+    ],
+    ids=["ok", "not-found", "forbidden", "seconds", "http-date", "no-hint", "secondary", "reset"],
+)
+def test_throttle_delay(status: int, headers: dict[str, str], expected: float | None) -> None:
+    assert throttle_delay(httpx.Response(status, headers=headers), now=40.0) == expected
+
+
+def _limiter(sleeps: list[float], **kwargs: Any) -> RateLimiter:
+    async def sleep(seconds: float) -> None:
+        sleeps.append(seconds)
+
+    return RateLimiter(sleep=sleep, clock=lambda: 1000.0, **kwargs)
+
+
+async def test_rate_limiter_waits_for_reset_when_budget_is_low() -> None:
+    sleeps: list[float] = []
+    limiter = _limiter(sleeps, reserve=10)
+    budget = {"X-RateLimit-Remaining": "11", "X-RateLimit-Reset": "1300"}
+
+    async def send() -> httpx.Response:
+        return httpx.Response(200, headers=budget)
+
+    await limiter.send(send)
+    await limiter.send(send)
+    assert sleeps == []
+
+    budget["X-RateLimit-Remaining"] = "10"
+    await limiter.send(send)
+    assert sleeps == []  # the low budget is only known after this response
+    await limiter.send(send)
+    assert sleeps == [301.0]  # until the reset, plus a second of slack
+
+
+async def test_rate_limiter_tracks_resources_separately() -> None:
+    sleeps: list[float] = []
+    limiter = _limiter(sleeps)
+    limiter.observe(
+        httpx.Response(
+            200,
+            headers={
+                "X-RateLimit-Remaining": "0",
+                "X-RateLimit-Reset": "1060",
+                "X-RateLimit-Resource": "graphql",
+            },
+        )
+    )
+    await limiter.wait("core")
+    assert sleeps == []
+    await limiter.wait("graphql")
+    assert sleeps == [61.0]
+
+
+async def test_rate_limiter_honours_graphql_query_cost() -> None:
+    sleeps: list[float] = []
+    limiter = _limiter(sleeps, reserve=5)
+    reset = "1970-01-01T00:17:00+00:00"  # epoch 1020
+    limiter.observe_graphql({"cost": 80, "remaining": 90, "resetAt": reset})
+    await limiter.wait("graphql")
+    assert sleeps == []
+    limiter.observe_graphql({"cost": 80, "remaining": 60, "resetAt": reset})
+    await limiter.wait("graphql")
+    assert sleeps == [21.0]
+    limiter.observe_graphql({"remaining": "bogus"})
+    await limiter.wait("graphql")
+    assert sleeps == [21.0]
+
+
+async def test_rate_limiter_retries_throttled_responses() -> None:
+    sleeps: list[float] = []
+    limiter = _limiter(sleeps, max_retries=3, backoff_seconds=2.0)
+    responses = [
+        httpx.Response(429, headers={"Retry-After": "9"}),
+        httpx.Response(429),
+        httpx.Response(200),
+    ]
+
+    async def send() -> httpx.Response:
+        return responses.pop(0)
+
+    response = await limiter.send(send)
+    assert response.status_code == 200
+    assert sleeps == [9.0, 4.0]  # Retry-After wins, else backoff from the attempt number
+
+    async def always_throttled() -> httpx.Response:
+        return httpx.Response(429)
+
+    assert (await limiter.send(always_throttled)).status_code == 429
+    assert sleeps[2:] == [2.0, 4.0, 8.0]
+
+
+async def test_load_repositories_pauses_on_exhausted_graphql_budget() -> None:
+    sleeps: list[float] = []
+    calls = {"n": 0}
+
+    def handler(request: httpx.Request) -> httpx.Response:
+        payload = json.loads(request.content)
+        alias = _alias(payload)
+        calls["n"] += 1
+        data: dict[str, Any] = {"user": {alias: _empty_user()[alias]}}
+        if alias == "ownedRepositories" and payload["variables"]["after"] is None:
+            data["user"][alias] = {
+                "ownedEdges": [{"cursor": "c1", "node": _REPO_NODE}],
+                "pageInfo": {"hasNextPage": True, "endCursor": "c1"},
+            }
+            data["rateLimit"] = {"cost": 1, "remaining": 0, "resetAt": "1970-01-01T00:20:00Z"}
+        return httpx.Response(200, json={"data": data})
+
+    async with httpx.AsyncClient(transport=httpx.MockTransport(handler)) as client:
+        repos = await load_repositories(
+            "me", "tok", client=client, rate_limiter=_limiter(sleeps)
+        )
+
+    assert repos.owned == {"me/r1"}
+    assert calls["n"] == 5
+    assert sleeps == [201.0]  # the next GraphQL call after the exhausted page waits
+
+
+async def test_token_pool_replays_throttled_requests_with_the_next_token() -> None:
+    seen: list[str] = []
+
+    def handler(request: httpx.Request) -> httpx.Response:
+        seen.append(request.headers["Authorization"])
+        if request.headers["Authorization"] == "Bearer tok1":
+            exhausted = {"X-RateLimit-Remaining": "0", "X-RateLimit-Reset": "1600"}
+            return httpx.Response(403, headers=exhausted)
+        return httpx.Response(200)
+
+    pool = TokenPool(["tok1", "tok2"], clock=lambda: 1000.0)
+    async with httpx.AsyncClient(transport=httpx.MockTransport(handler)) as client:
+        first = await client.get("https://api.test/user", auth=pool)
+        second = await client.get("https://api.test/user", auth=pool)
+
+    assert (first.status_code, second.status_code) == (200, 200)
+    assert seen == ["Bearer tok1", "Bearer tok2", "Bearer tok2"]
+    assert pool.token == "tok2"
+
+
+async def test_token_pool_returns_the_last_response_when_every_token_is_spent() -> None:
+    def handler(request: httpx.Request) -> httpx.Response:
+        return httpx.Response(401, json={"message": "Bad credentials"})
+
+    pool = TokenPool(["tok1", "tok2"])
+    async with httpx.AsyncClient(transport=httpx.MockTransport(handler)) as client:
+        response = await client.get("https://api.test/user", auth=pool)
+
+    assert response.status_code == 401
+    assert pool.rotate(0.0) is False  # both are retired for good
+
+
+async def test_rate_limiter_rotates_tokens_instead_of_waiting() -> None:
+    sleeps: list[float] = []
+    pool = TokenPool(["tok1", "tok2"], clock=lambda: 1000.0)
+    limiter = _limiter(sleeps, tokens=pool)
+    low = {"X-RateLimit-Remaining": "3", "X-RateLimit-Reset": "1500"}
+    limiter.observe(httpx.Response(200, headers=low))
+
+    await limiter.wait()
+    assert (sleeps, pool.token) == ([], "tok2")
+
+    limiter.observe(httpx.Response(200, headers=low))
+    await limiter.wait()
+    assert sleeps == [501.0]  # tok1 is still retired until its reset
@@ -0,0 +1,152 @@
+"""Repository settings export against an in-memory REST API (httpx MockTransport)."""
+
+from __future__ import annotations
+
+import json
+import logging
+from pathlib import Path
+
+import httpx
+import pytest
+
+from gitout.github_metadata import CommunitySnapshotExporter, RepositorySettingsExporter
+
+
+def _handler(admin: bool) -> httpx.MockTransport:
+    def handle(request: httpx.Request) -> httpx.Response:
+        assert request.headers["Authorization"] == "Bearer tok"
+        path = request.url.path
+        if path == "/repos/me/repo":
+            return httpx.Response(
+                200,
+                json={
+                    "full_name": "me/repo",
+                    "allow_squash_merge": True,
+                    "delete_branch_on_merge": False,
+                    "stargazers_count": 12,  # activity, not a setting
+                    "permissions": {"admin": admin},
+                },
+            )
+        if path == "/repos/me/repo/branches":
+            assert request.url.params["protected"] == "true"
+            return httpx.Response(200, json=[{"name": "main"}])
+        if path == "/repos/me/repo/branches/main/protection":
+            return httpx.Response(200, json={"enforce_admins": {"enabled": True}})
+        if path == "/repos/me/repo/rulesets":
+            return httpx.Response(200, json=[{"id": 7, "name": "summary"}])
+        if path == "/repos/me/repo/rulesets/7":
+            return httpx.Response(200, json={"id": 7, "name": "full", "rules": []})
+        return httpx.Response(404)
+
+    return httpx.MockTransport(handle)
+
+
+def _exporter(admin: bool) -> RepositorySettingsExporter:
+    return RepositorySettingsExporter(
+        "tok",
+        client=httpx.AsyncClient(transport=_handler(admin)),
+        api_url="https://api.test",
+        now_iso=lambda: "2024-01-01T00:00:00+00:00",
+    )
+
+
+async def test_export_collects_settings_protection_and_rulesets() -> None:
+    document = await _exporter(admin=True).export("me/repo")
+    assert document == {
+        "repository": "me/repo",
+        "exportedAt": "2024-01-01T00:00:00+00:00",
+        "settings": {"allow_squash_merge": True, "delete_branch_on_merge": False},
+        "branchProtection": {"main": {"enforce_admins": {"enabled": True}}},
+        "rulesets": [{"id": 7, "name": "full", "rules": []}],
+    }
+
+
+async def test_branch_names_are_escaped_and_unreadable_protection_is_logged(
+    caplog: pytest.LogCaptureFixture,
+) -> None:
+    def handle(request: httpx.Request) -> httpx.Response:
+        raw = request.url.raw_path.decode()
+        if raw == "/repos/me/repo":
+            return httpx.Response(200, json={"permissions": {"admin": True}})
+        if raw.startswith("/repos/me/repo/branches?"):
+            return httpx.Response(200, json=[{"name": "fix#1?a%b"}, {"name": "busy"}])
+        if raw == "/repos/me/repo/branches/fix%231%3Fa%25b/protection":
+            return httpx.Response(200, json={"enforce_admins": {"enabled": True}})
+        if raw == "/repos/me/repo/branches/busy/protection":
+            return httpx.Response(500)
+        return httpx.Response(404)
+
+    client = httpx.AsyncClient(transport=httpx.MockTransport(handle))
+    exporter = RepositorySettingsExporter("tok", client=client, api_url="https://api.test")
+    with caplog.at_level(logging.WARNING, logger="gitout.github_metadata"):
+        document = await exporter.export("me/repo")
+    assert document is not None
+    assert document["branchProtection"] == {"fix#1?a%b": {"enforce_admins": {"enabled": True}}}
+    assert "Cannot read the protection of me/repo branch busy: HTTP 500" in caplog.text
+
+
+async def test_export_skips_repos_without_admin(tmp_path: Path) -> None:
+    exporter = _exporter(admin=False)
+    assert await exporter.export("me/repo") is None
+    assert await exporter.export_to("me/repo", tmp_path) is False
+    assert list(tmp_path.iterdir()) == []
+
+
+async def test_export_to_writes_owner_repo_file(tmp_path: Path) -> None:
+    assert await _exporter(admin=True).export_to("me/repo", tmp_path) is True
+    written = json.loads((tmp_path / "me" / "repo.json").read_text())
+    assert written["branchProtection"]["main"]["enforce_admins"]["enabled"] is True
+
+
+async def test_api_errors_are_swallowed(tmp_path: Path) -> None:
+    transport = httpx.MockTransport(lambda request: httpx.Response(500))
+    exporter = RepositorySettingsExporter(
+        "tok", client=httpx.AsyncClient(transport=transport), api_url="https://api.test"
+    )
+    assert await exporter.export_to("me/repo", tmp_path) is False
+
+
+def _community_handler(request: httpx.Request) -> httpx.Response:
+    path = request.url.path
+    if path == "/repos/me/repo/stargazers":
+        assert request.headers["Accept"] == "application/vnd.github.star+json"
+        if request.url.params.get("page") == "2":
+            return httpx.Response(
+                200, json=[{"starred_at": "2024-02-01T00:00:00Z", "user": {"login": "bob"}}]
+            )
+        return httpx.Response(
+            200,
+            json=[{"starred_at": "2023-05-01T00:00:00Z", "user": {"login": "alice"}}],
+            headers={"Link": '<https://api.test/repos/me/repo/stargazers?page=2>; rel="next"'},
+        )
+    if path == "/repos/me/repo/subscribers":
+        return httpx.Response(200, json=[{"login": "me"}, {"login": "carol"}])
+    return httpx.Response(404)
+
+
+def _community_exporter() -> CommunitySnapshotExporter:
+    return CommunitySnapshotExporter(
+        "tok",
+        client=httpx.AsyncClient(transport=httpx.MockTransport(_community_handler)),
+        api_url="https://api.test",
+        now_iso=lambda: "2024-03-05T06:07:08+00:00",
+    )
+
+
+async def test_community_snapshot_collects_paginated_stargazers_and_watchers() -> None:
+    document = await _community_exporter().export("me/repo")
+    assert document == {
+        "repository": "me/repo",
+        "capturedAt": "2024-03-05T06:07:08+00:00",
+        "stargazers": [
+            {"login": "alice", "starredAt": "2023-05-01T00:00:00Z"},
+            {"login": "bob", "starredAt": "2024-02-01T00:00:00Z"},
+        ],
+        "watchers": ["me", "carol"],
+    }
+
+
+async def test_community_snapshot_writes_dated_file(tmp_path: Path) -> None:
+    assert await _community_exporter().export_to("me/repo", tmp_path) is True
+    written = json.loads((tmp_path / "me" / "repo" / "2024-03-05.json").read_text())
+    assert [s["login"] for s in written["stargazers"]] == ["alice", "bob"]
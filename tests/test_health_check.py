@@ -2,18 +2,23 @@
 
 from __future__ import annotations
 
+from pathlib import Path
+
 import httpx
 
-from gitout.health_check import HealthCheckService
+from gitout.engine import SyncOutcome, SyncTask
+from gitout.health_check import PING_BODY_LIMIT, HealthCheck, HealthCheckService, failure_log
 
 
 class Recorder:
     def __init__(self, *, fail: bool = False) -> None:
         self.fail = fail
         self.paths: list[str] = []
+        self.bodies: list[bytes] = []
 
     def __call__(self, request: httpx.Request) -> httpx.Response:
         self.paths.append(request.url.path)
+        self.bodies.append(request.content)
         if self.fail:
             raise httpx.ConnectError("boom")
         return httpx.Response(200, text="OK")
@@ -39,3 +44,22 @@ async def test_network_failure_is_swallowed() -> None:
     started = await check.start()
     await started.complete()
     assert rec.paths == ["/id/start", "/id"]
+
+
+async def test_failure_ping_carries_the_log_up_to_the_limit() -> None:
+    rec = Recorder()
+    client = httpx.AsyncClient(transport=httpx.MockTransport(rec))
+    started = await HealthCheck("https://hc.test/ping/uuid", client).start()
+    await started.fail("x" * (PING_BODY_LIMIT + 10))
+    assert rec.paths == ["/ping/uuid/start", "/ping/uuid/fail"]
+    assert len(rec.bodies[1]) == PING_BODY_LIMIT
+
+
+def test_failure_log_lists_the_failed_repositories() -> None:
+    outcomes = [
+        SyncOutcome(SyncTask("a", "https://github.com/me/a.git", Path("a")), ok=True),
+        SyncOutcome(
+            SyncTask("b", "https://github.com/me/b.git", Path("b")), ok=False, error="timeout"
+        ),
+    ]
+    assert failure_log(outcomes) == "1 of 2 repositories failed\nFAILED github.com/b: timeout"
@@ -0,0 +1,94 @@
+"""Conditional GET cache: validators out, 304s answered from the stored body."""
+
+from __future__ import annotations
+
+from pathlib import Path
+
+import httpx
+
+from gitout.github_metadata import CommunitySnapshotExporter
+from gitout.http_cache import HTTP_CACHE_FILE, HttpCache
+
+
+def _etag_handler(seen: list[httpx.Request]) -> httpx.MockTransport:
+    def handle(request: httpx.Request) -> httpx.Response:
+        seen.append(request)
+        etag = f'"{request.url.path}"'
+        if request.headers.get("If-None-Match") == etag:
+            return httpx.Response(304)
+        if request.url.path == "/repos/me/repo/stargazers" and "page" not in request.url.params:
+            return httpx.Response(
+                200,
+                json=[{"starred_at": None, "user": {"login": "alice"}}],
+                headers={
+                    "ETag": etag,
+                    "Link": '<https://api.test/repos/me/repo/stargazers?page=2>; rel="next"',
+                },
+            )
+        if request.url.path == "/repos/me/repo/stargazers":
+            return httpx.Response(200, json=[{"starred_at": None, "user": {"login": "bob"}}])
+        return httpx.Response(200, json=[{"login": "carol"}], headers={"ETag": etag})
+
+    return httpx.MockTransport(handle)
+
+
+async def test_not_modified_answers_replay_body_and_pagination(tmp_path: Path) -> None:
+    seen: list[httpx.Request] = []
+    path = tmp_path / HTTP_CACHE_FILE
+
+    async def snapshot(cache: HttpCache) -> dict[str, object]:
+        exporter = CommunitySnapshotExporter(
+            "tok",
+            client=httpx.AsyncClient(transport=_etag_handler(seen)),
+            api_url="https://api.test",
+            cache=cache,
+        )
+        return await exporter.export("me/repo")
+
+    first_cache = HttpCache(path)
+    first = await snapshot(first_cache)
+    first_cache.save()
+    assert first_cache.hits == 0
+    seen.clear()
+
+    cache = HttpCache(path)  # reloaded from disk
+    second = await snapshot(cache)
+
+    assert second["stargazers"] == first["stargazers"]
+    assert second["watchers"] == ["carol"]
+    assert cache.hits == 2  # first stargazers page and subscribers; page 2 has no ETag
+    assert [r.headers.get("If-None-Match") for r in seen] == [
+        '"/repos/me/repo/stargazers"',
+        None,
+        '"/repos/me/repo/subscribers"',
+    ]
+
+
+async def test_last_modified_is_sent_as_if_modified_since() -> None:
+    seen: list[httpx.Request] = []
+
+    def handle(request: httpx.Request) -> httpx.Response:
+        seen.append(request)
+        if request.headers.get("If-Modified-Since"):
+            return httpx.Response(304)
+        headers = {"Last-Modified": "Tue, 01 Oct 2024 00:00:00 GMT"}
+        return httpx.Response(200, json={"ok": True}, headers=headers)
+
+    cache = HttpCache()
+    async with httpx.AsyncClient(transport=httpx.MockTransport(handle)) as http:
+        for _ in range(2):
+            response = await cache.get(
+                "https://api.test/x", {}, lambda h: http.get("https://api.test/x", headers=h)
+            )
+            assert response.json() == {"ok": True}
+
+    assert seen[1].headers["If-Modified-Since"] == "Tue, 01 Oct 2024 00:00:00 GMT"
+    assert cache.hits == 1
+
+
+def test_unreadable_cache_starts_empty(tmp_path: Path) -> None:
+    path = tmp_path / HTTP_CACHE_FILE
+    path.write_text("{broken")
+    cache = HttpCache(path)
+    cache.save()
+    assert path.read_text() == '{"version": 1, "entries": {}}'
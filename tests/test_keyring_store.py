@@ -0,0 +1,41 @@
+"""OS keyring storage, against an in-memory stand-in for the optional keyring package."""
+
+from __future__ import annotations
+
+import sys
+
+import pytest
+
+from gitout.keyring_store import (
+    KEYRING_SERVICE,
+    KeyringUnavailableError,
+    delete_secret,
+    get_secret,
+    set_secret,
+)
+from tests.helpers import fake_keyring
+
+
+def test_set_get_and_delete_round_trip(monkeypatch: pytest.MonkeyPatch) -> None:
+    store = fake_keyring(monkeypatch)
+    assert get_secret("github") is None
+
+    set_secret("github", "tok")
+    assert store == {(KEYRING_SERVICE, "github"): "tok"}
+    assert get_secret("github") == "tok"
+
+    assert delete_secret("github") is True
+    assert delete_secret("github") is False
+    assert get_secret("github") is None
+
+
+def test_missing_package_or_backend(monkeypatch: pytest.MonkeyPatch) -> None:
+    monkeypatch.setitem(sys.modules, "keyring", None)  # import fails
+    assert get_secret("github") is None
+    with pytest.raises(KeyringUnavailableError, match="gitout\\[keyring\\]"):
+        set_secret("github", "tok")
+
+    fake_keyring(monkeypatch, broken=True)
+    assert get_secret("github") is None
+    with pytest.raises(KeyringUnavailableError, match="no backend available"):
+        set_secret("github", "tok")
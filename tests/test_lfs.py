@@ -62,6 +62,16 @@ def test_fetch_lfs_objects_argv_and_success(tmp_path: Path) -> None:
     assert fake.calls == [[GIT, "-C", str(tmp_path), "lfs", "fetch", "--all"]]
 
 
+def test_fetch_lfs_objects_passes_config_options(tmp_path: Path) -> None:
+    fake = FakeGit(code=0)
+    lfs = LfsSupport(run_git=fake)
+    options = ["http.sslVerify=false", "credential.helper=store --file=/tmp/c"]
+    assert lfs.fetch_lfs_objects(tmp_path, options) is True
+    assert fake.calls == [
+        [GIT, "-c", options[0], "-c", options[1], "-C", str(tmp_path), "lfs", "fetch", "--all"]
+    ]
+
+
 def test_sync_lfs_if_needed_true_for_non_lfs_repo(tmp_path: Path) -> None:
     fake = FakeGit(code=128, stdout="")  # not a git repo / no lfs
     lfs = LfsSupport(run_git=fake)
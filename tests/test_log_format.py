@@ -0,0 +1,142 @@
+"""Tests for the text and JSON log line formats."""
+
+from __future__ import annotations
+
+import asyncio
+import json
+import logging
+import sys
+
+import httpx
+import pytest
+
+from logging.handlers import RotatingFileHandler, TimedRotatingFileHandler
+from pathlib import Path
+
+from gitout.log_format import (
+    HttpUrlFilter,
+    JsonFormatter,
+    RepositoryBufferingHandler,
+    file_handler,
+    formatter_for,
+    repository_logs,
+    resolve_levels,
+)
+
+
+def _record(**extra: object) -> logging.LogRecord:
+    record = logging.LogRecord(
+        "gitout.engine", logging.WARNING, __file__, 1, "Synced %s", ("a",), None
+    )
+    record.__dict__.update(extra)
+    return record
+
+
+def test_json_lines_carry_level_logger_message_and_extra_fields() -> None:
+    entry = json.loads(JsonFormatter().format(_record(repo="me/a", attempts=2)))
+    assert entry["level"] == "WARNING"
+    assert entry["logger"] == "gitout.engine"
+    assert entry["message"] == "Synced a"
+    assert entry["repo"] == "me/a"
+    assert entry["attempts"] == 2
+    assert entry["time"].endswith("+00:00")
+    assert "exception" not in entry
+
+
+def test_json_lines_include_the_traceback() -> None:
+    try:
+        raise RuntimeError("boom")
+    except RuntimeError:
+        record = logging.LogRecord(
+            "gitout", logging.ERROR, __file__, 1, "failed", None, sys.exc_info()
+        )
+    entry = json.loads(JsonFormatter().format(record))
+    assert "RuntimeError: boom" in entry["exception"]
+
+
+def test_http_request_lines_keep_only_the_origin_of_the_url() -> None:
+    url = httpx.URL("https://api.telegram.org/bot123:s3cret/sendMessage?chat_id=1")
+    record = logging.LogRecord(
+        "httpx", logging.INFO, __file__, 1, "HTTP Request: %s %s", ("POST", url), None
+    )
+    assert HttpUrlFilter().filter(record)
+    assert record.getMessage() == "HTTP Request: POST https://api.telegram.org"
+
+
+def test_formatter_for_text_and_unknown_formats() -> None:
+    assert formatter_for("text").format(_record()).endswith("WARNING gitout.engine: Synced a")
+    with pytest.raises(ValueError, match="Unknown log format"):
+        formatter_for("xml")
+
+
+def test_resolve_levels_maps_flags_and_keeps_traces_for_vv() -> None:
+    assert resolve_levels(0, False) == (logging.INFO, logging.WARNING)
+    assert resolve_levels(1, False) == (logging.DEBUG, logging.WARNING)
+    assert resolve_levels(2, False) == (logging.DEBUG, logging.DEBUG)
+    assert resolve_levels(3, True) == (logging.WARNING, logging.WARNING)
+    assert resolve_levels(2, False, "error") == (logging.ERROR, logging.ERROR)
+    assert resolve_levels(0, True, "trace") == (logging.DEBUG, logging.DEBUG)
+    with pytest.raises(ValueError, match="Unknown log level"):
+        resolve_levels(0, False, "loud")
+
+
+def test_file_handler_rotates_by_size_and_keeps_backups(tmp_path: Path) -> None:
+    path = tmp_path / "logs" / "gitout.log"
+    handler = file_handler(path, max_bytes=64, backups=2)
+    assert isinstance(handler, RotatingFileHandler)
+    handler.setFormatter(logging.Formatter("%(message)s"))
+    try:
+        for number in range(10):
+            handler.emit(_record(args=(f"{number:02d} " + "x" * 30,)))
+    finally:
+        handler.close()
+    assert sorted(p.name for p in path.parent.iterdir()) == [
+        "gitout.log",
+        "gitout.log.1",
+        "gitout.log.2",
+    ]
+    assert "Synced 09" in path.read_text()
+
+
+def test_file_handler_rotates_by_time_when_asked(tmp_path: Path) -> None:
+    handler = file_handler(tmp_path / "gitout.log", rotate_every_seconds=86400.0, backups=3)
+    try:
+        assert isinstance(handler, TimedRotatingFileHandler)
+        assert handler.interval == 86400
+        assert handler.backupCount == 3
+    finally:
+        handler.close()
+
+
+class _Collect(logging.Handler):
+    def __init__(self) -> None:
+        super().__init__()
+        self.lines: list[str] = []
+
+    def emit(self, record: logging.LogRecord) -> None:
+        self.lines.append(f"{getattr(record, 'repository', '-')} {record.getMessage()}")
+
+
+def test_parallel_repositories_log_in_one_piece_each() -> None:
+    collected = _Collect()
+    handler = RepositoryBufferingHandler(collected)
+    root = logging.getLogger()
+    log = logging.getLogger("gitout.test_buffer")
+    log.setLevel(logging.INFO)
+    root.addHandler(handler)
+
+    async def sync(name: str, delay: float) -> None:
+        with repository_logs(name):
+            log.info("start")
+            await asyncio.sleep(delay)
+            log.info("done")
+
+    async def run() -> None:
+        log.info("run started")
+        await asyncio.gather(sync("a", 0.02), sync("b", 0.0))
+
+    try:
+        asyncio.run(run())
+    finally:
+        root.removeHandler(handler)
+    assert collected.lines == ["- run started", "b start", "b done", "a start", "a done"]
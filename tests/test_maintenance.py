@@ -3,10 +3,11 @@ plus git-argv coverage via an injected command runner)."""
 
 from __future__ import annotations
 
+import json
 from pathlib import Path
 
 from gitout.config import Maintenance
-from gitout.maintenance import RepositoryMaintenance
+from gitout.maintenance import MAINTENANCE_STATE_FILE, RepositoryMaintenance
 
 
 class RecordingRunner:
@@ -75,6 +76,18 @@ def test_gc_auto_then_commit_graph(tmp_path: Path) -> None:
     ]
 
 
+def test_multi_pack_index_before_commit_graph(tmp_path: Path) -> None:
+    m, runner = _make(
+        Maintenance(enabled=True, strategy="geometric", write_multi_pack_index=True)
+    )
+    m.run_post_sync_maintenance(tmp_path)
+    assert runner.argvs == [
+        ["git", "-C", str(tmp_path), "repack", "--geometric=2", "-d"],
+        ["git", "-C", str(tmp_path), "multi-pack-index", "write"],
+        ["git", "-C", str(tmp_path), "commit-graph", "write", "--reachable"],
+    ]
+
+
 def test_geometric_strategy(tmp_path: Path) -> None:
     m, runner = _make(Maintenance(enabled=True, strategy="geometric", write_commit_graph=False))
     m.run_post_sync_maintenance(tmp_path)
@@ -89,6 +102,67 @@ def test_unknown_strategy_still_writes_commit_graph(tmp_path: Path) -> None:
     ]
 
 
+def test_gc_runs_every_n_syncs_per_repository_and_survives_restarts(tmp_path: Path) -> None:
+    config = Maintenance(enabled=True, strategy="none", write_commit_graph=False, gc_every=2)
+    state = tmp_path / MAINTENANCE_STATE_FILE
+    a, b = tmp_path / "git" / "a", tmp_path / "git" / "b"
+    a.mkdir(parents=True)
+    b.mkdir()
+    gc_a = ["git", "-C", str(a), "gc", "--quiet", "--prune=2.weeks.ago"]
+
+    runner = RecordingRunner()
+    first = RepositoryMaintenance(config, run_git=runner, state_path=state)
+    first.run_post_sync_maintenance(a)
+    first.run_post_sync_maintenance(b)
+    first.save_state()
+    assert runner.argvs == []
+    assert json.loads(state.read_text()) == {"syncsSinceGc": {"git/a": 1, "git/b": 1}}
+
+    runner = RecordingRunner()
+    second = RepositoryMaintenance(config, run_git=runner, state_path=state)
+    second.run_post_sync_maintenance(a)
+    second.run_post_sync_maintenance(a)
+    assert runner.argvs == [gc_a]  # due on the second sync overall; the count restarts
+    second.save_state()
+    assert json.loads(state.read_text()) == {"syncsSinceGc": {"git/a": 1, "git/b": 1}}
+
+
+def test_gc_disabled_by_default_writes_no_state(tmp_path: Path) -> None:
+    state = tmp_path / MAINTENANCE_STATE_FILE
+    m = RepositoryMaintenance(
+        Maintenance(enabled=True, strategy="none", write_commit_graph=False),
+        run_git=RecordingRunner(),
+        state_path=state,
+    )
+    m.run_post_sync_maintenance(tmp_path)
+    m.save_state()
+    assert not state.exists()
+
+
+def test_git_config_options_prefix_every_command(tmp_path: Path) -> None:
+    runner = RecordingRunner()
+    m = RepositoryMaintenance(
+        Maintenance(enabled=True, strategy="geometric", write_commit_graph=False),
+        run_git=runner,
+        git_config=["pack.threads=2", "pack.windowMemory=64m"],
+    )
+    m.run_post_sync_maintenance(tmp_path)
+    assert runner.argvs == [
+        [
+            "git",
+            "-c",
+            "pack.threads=2",
+            "-c",
+            "pack.windowMemory=64m",
+            "-C",
+            str(tmp_path),
+            "repack",
+            "--geometric=2",
+            "-d",
+        ]
+    ]
+
+
 # --- full repack across discovered repos ---
 
 
@@ -0,0 +1,64 @@
+"""The generated gitout(1) man page."""
+
+from __future__ import annotations
+
+from dataclasses import dataclass, field
+
+import typer
+
+from gitout.cli import app
+from gitout.manpage import _escape, render_manpage
+
+
+def _page() -> str:
+    return render_manpage(typer.main.get_command(app), "1.2.3")
+
+
+def test_every_command_and_its_options_are_documented() -> None:
+    page = _page()
+
+    assert page.startswith('.TH GITOUT 1 "" "gitout 1.2.3" "User Commands"\n')
+    for command in ("sync", "verify", "state export", "auth set", "check\\-config", "man"):
+        assert f".SS gitout {command}\n" in page
+    assert "\\fB\\-\\-dry\\-run\\fR, \\fB\\-n\\fR\n" in page
+    assert "Print actions, do not run them (env: GITOUT_DRY_RUN)" in page
+    assert "\\fB\\-\\-timeout\\fR \\fIDURATION\\fR\n" in page
+    assert "After the sync, copy the destination to this directory too (repeatable)" in page
+
+
+def test_config_tables_list_keys_with_type_and_default() -> None:
+    page = _page()
+
+    assert ".SS Top level\n.TP\n\\fBversion\\fR\nint (required)\n" in page
+    assert ".SS [github.clone]\n" in page
+    assert "\\fBstarred\\fR\nbool (default: false)\n" in page
+    assert ".SS [[overrides]]\n" in page
+    assert ".SS [groups.<name>]\n" in page
+    assert ".SS [git.credentials" not in page
+
+
+@dataclass
+class _Inner:
+    name: str = "x"
+
+
+@dataclass
+class _Outer:
+    version: int
+    inner: _Inner = field(default_factory=_Inner)
+    items: list[_Inner] = field(default_factory=list)
+
+
+def test_nested_dataclasses_become_tables() -> None:
+    page = render_manpage(typer.main.get_command(app), "1", config_cls=_Outer)
+
+    assert '.SS [inner]\n.TP\n\\fBname\\fR\nstr (default: "x")\n' in page
+    assert ".SS [[items]]\n" in page
+
+
+def test_roff_control_characters_are_escaped() -> None:
+    assert _escape(".hidden\n'quote\nback\\slash -x") == "\\&.hidden\n\\&'quote\nback\\eslash \\-x"
+
+
+def test_output_is_reproducible() -> None:
+    assert _page() == _page()
@@ -0,0 +1,119 @@
+"""Discovery and migration archives end to end against :class:`tests.mock_github.MockGitHub`."""
+
+from __future__ import annotations
+
+import io
+import tarfile
+from pathlib import Path
+
+import httpx
+import pytest
+
+from gitout.github_archive import MigrationArchiver, MigrationRequest
+from gitout.github_client import TokenPool, load_repositories
+from tests.mock_github import API_URL, MockGitHub, gist_node, repo_node
+
+
+def _tarball() -> bytes:
+    buffer = io.BytesIO()
+    with tarfile.open(fileobj=buffer, mode="w:gz") as archive:
+        info = tarfile.TarInfo("repositories_000001.json")
+        info.size = 2
+        archive.addfile(info, io.BytesIO(b"[]"))
+    return buffer.getvalue()
+
+
+async def _no_sleep(seconds: float) -> None:
+    return None
+
+
+def _github() -> MockGitHub:
+    return MockGitHub(
+        owned=[repo_node(f"octocat/repo-{i}") for i in range(5)],
+        starred=[repo_node("other/star", isFork=True)],
+        watching=[repo_node("octocat/repo-0"), repo_node("other/watch", isArchived=True)],
+        gists=[gist_node("g1"), gist_node("g2", isPublic=False)],
+        starred_gists=[
+            {"id": f"s{i}", "public": True, "updated_at": "2024-01-01T00:00:00Z"}
+            for i in range(3)
+        ],
+        page_size=2,
+        archive=_tarball(),
+    )
+
+
+async def test_discovery_pages_every_connection_independently() -> None:
+    github = _github()
+    async with github.client() as client:
+        repos = await load_repositories(
+            "octocat", "tok", client=client, api_url=API_URL, starred_gists=True
+        )
+
+    assert repos.owned == {f"octocat/repo-{i}" for i in range(5)}
+    assert repos.starred == {"other/star"}
+    assert repos.watching == {"octocat/repo-0", "other/watch"}
+    assert repos.gists == {"g1", "g2"}
+    assert repos.starred_gists == {"s0", "s1", "s2"}
+    assert repos.metadata["other/star"].is_fork is True
+    assert repos.metadata["other/watch"].is_archived is True
+    # Three pages of owned repositories, one for each other connection; pageInfo
+    # ends every connection without an extra empty page.
+    assert github.paths("POST").count("/graphql") == 6
+    assert github.paths("GET").count("/gists/starred") == 2
+
+
+async def test_discovery_rejects_bad_token_and_unknown_user() -> None:
+    github = _github()
+    async with github.client() as client:
+        with pytest.raises(httpx.HTTPStatusError):
+            await load_repositories("octocat", "wrong", client=client, api_url=API_URL)
+        with pytest.raises(RuntimeError, match="not found"):
+            await load_repositories("nobody", "tok", client=client, api_url=API_URL)
+
+
+async def test_token_pool_rotates_past_a_rejected_token() -> None:
+    github = _github()
+    pool = TokenPool(["revoked", "tok"])
+    async with github.client() as client:
+        repos = await load_repositories(
+            "octocat", pool, client=client, api_url=API_URL, starred_gists=True
+        )
+
+    assert repos.owned == {f"octocat/repo-{i}" for i in range(5)}
+    assert repos.starred_gists == {"s0", "s1", "s2"}
+    assert pool.token == "tok"
+    # The rejected first page is replayed: one extra GraphQL request at least.
+    assert github.paths("POST").count("/graphql") > 6
+
+
+async def test_archive_retries_throttling_and_unlocks(tmp_path: Path) -> None:
+    github = _github()
+    github.export_polls = 3
+    github.throttle("/user/migrations", times=2)
+    archiver = MigrationArchiver(
+        "tok",
+        client=github.client(),
+        api_url=API_URL,
+        sleep=_no_sleep,
+        request=MigrationRequest(lock_repositories=True),
+    )
+
+    assert await archiver.archive_to("octocat/repo-1", tmp_path) is True
+
+    assert (tmp_path / "octocat" / "repo-1.tar.gz").read_bytes() == github.archive
+    assert list(github.migrations) == [1]
+    assert github.unlocked == [(1, "repo-1")]
+    assert github.paths().count("/user/migrations/1") == 3
+
+
+async def test_served_mock_backs_clients_built_from_api_url(tmp_path: Path) -> None:
+    github = _github()
+    with github.serve() as api_url:
+        repos = await load_repositories("octocat", "tok", api_url=api_url)
+        archived = await MigrationArchiver(
+            "tok", api_url=api_url, sleep=_no_sleep
+        ).archive_to("other/star", tmp_path)
+
+    assert "octocat/repo-4" in repos.owned
+    assert archived is True
+    assert (tmp_path / "other" / "star.tar.gz").read_bytes() == github.archive
@@ -0,0 +1,39 @@
+"""``.netrc`` lookups: $NETRC, host matching, default entries and broken files."""
+
+from __future__ import annotations
+
+from pathlib import Path
+
+from gitout.netrc_lookup import netrc_login, netrc_path
+
+
+def _netrc(tmp_path: Path, text: str) -> dict[str, str]:
+    path = tmp_path / "netrc"
+    path.write_text(text)
+    return {"NETRC": str(path)}
+
+
+def test_netrc_env_overrides_the_home_file(tmp_path: Path) -> None:
+    assert netrc_path({"NETRC": str(tmp_path / "n")}) == tmp_path / "n"
+    assert netrc_path({}) == Path.home() / ".netrc"
+
+
+def test_first_matching_host_wins_case_insensitively(tmp_path: Path) -> None:
+    env = _netrc(
+        tmp_path,
+        "machine Git.Example.com login backup password one\n"
+        "machine other.example login x password two\n",
+    )
+    assert netrc_login(["nope.example", "git.example.com"], env) == ("backup", "one")
+    assert netrc_login(["other.example", "git.example.com"], env) == ("x", "two")
+
+
+def test_default_entries_are_never_used(tmp_path: Path) -> None:
+    env = _netrc(tmp_path, "default login anyone password catch-all\n")
+    assert netrc_login(["git.example.com"], env) is None
+
+
+def test_missing_or_malformed_files_find_nothing(tmp_path: Path) -> None:
+    assert netrc_login(["h"], {"NETRC": str(tmp_path / "missing")}) is None
+    env = _netrc(tmp_path, "machine h login\n")
+    assert netrc_login(["h"], env) is None
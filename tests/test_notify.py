@@ -0,0 +1,163 @@
+"""Tests for the run summary notifications ([notify])."""
+
+from __future__ import annotations
+
+import json
+import smtplib
+from datetime import UTC, datetime, timedelta
+from email.message import EmailMessage
+from pathlib import Path
+from typing import Any
+
+import httpx
+import pytest
+
+from gitout import notify as notify_module
+from gitout.config import ChatNotify, EmailNotify, Notify
+from gitout.engine import SyncOutcome, SyncTask
+from gitout.notify import (
+    ChatNotifier,
+    EmailNotifier,
+    chat_body,
+    chat_notifiers,
+    email_message,
+    email_password,
+)
+from gitout.webhooks import build_payload
+
+_START = datetime(2024, 6, 15, 2, 30, tzinfo=UTC)
+_END = _START + timedelta(seconds=90)
+_EMAIL = EmailNotify(
+    server="smtp.example.com",
+    username="gitout",
+    password_env="SMTP_PASSWORD",
+    sender="gitout@example.com",
+    recipients=["ops@example.com", "me@example.com"],
+)
+
+
+def _payload(*, failed: bool) -> dict[str, Any]:
+    task = SyncTask("me/b", "https://github.com/me/b.git", Path("b"))
+    outcomes = [SyncOutcome(task, ok=not failed, error="timed out" if failed else None)]
+    return build_payload(outcomes, started_at=_START, finished_at=_END)
+
+
+def test_email_message_has_the_summary_subject_and_failures() -> None:
+    message = email_message(_EMAIL, _payload(failed=True))
+    assert message["Subject"] == "[gitout] 1 of 1 repositories failed"
+    assert message["From"] == "gitout@example.com"
+    assert message["To"] == "ops@example.com, me@example.com"
+    body = message.get_content()
+    assert "Attempted 1, succeeded 0, failed 1, skipped 0" in body
+    assert "  github.com/me/b: timed out" in body
+
+
+async def test_only_on_failure_skips_successful_runs() -> None:
+    sent: list[EmailMessage] = []
+    config = EmailNotify(**{**vars(_EMAIL), "only_on_failure": True})
+    notifier = EmailNotifier(config, sender=sent.append)
+    assert not await notifier.notify(_payload(failed=False))
+    assert await notifier.notify(_payload(failed=True))
+    assert len(sent) == 1
+
+
+async def test_send_failures_are_logged_not_raised() -> None:
+    def refuse(message: EmailMessage) -> None:
+        raise smtplib.SMTPAuthenticationError(535, b"bad credentials")
+
+    assert not await EmailNotifier(_EMAIL, sender=refuse).notify(_payload(failed=True))
+
+
+async def test_smtp_sender_upgrades_to_tls_and_logs_in(monkeypatch: pytest.MonkeyPatch) -> None:
+    calls: list[tuple[str, Any]] = []
+
+    class FakeSMTP:
+        def __init__(self, host: str, port: int, timeout: float) -> None:
+            calls.append(("connect", (host, port)))
+
+        def __enter__(self) -> FakeSMTP:
+            return self
+
+        def __exit__(self, *exc: object) -> None:
+            calls.append(("quit", None))
+
+        def starttls(self, context: object) -> None:
+            calls.append(("starttls", None))
+
+        def login(self, user: str, password: str) -> None:
+            calls.append(("login", (user, password)))
+
+        def send_message(self, message: EmailMessage) -> None:
+            calls.append(("send", message["To"]))
+
+    monkeypatch.setattr(notify_module.smtplib, "SMTP", FakeSMTP)
+    notifier = EmailNotifier(_EMAIL, environ={"SMTP_PASSWORD": " s3cret\n"})
+    assert await notifier.notify(_payload(failed=False))
+    assert calls == [
+        ("connect", ("smtp.example.com", 587)),
+        ("starttls", None),
+        ("login", ("gitout", "s3cret")),
+        ("send", "ops@example.com, me@example.com"),
+        ("quit", None),
+    ]
+
+
+def test_email_password_from_a_file(tmp_path: Path) -> None:
+    secret = tmp_path / "smtp"
+    secret.write_text("pw\n")
+    config = EmailNotify(username="gitout", password_file=str(secret))
+    assert email_password(config, {}) == "pw"
+    with pytest.raises(ValueError, match="SMTP_PASSWORD is not set"):
+        email_password(_EMAIL, {})
+
+
+def test_chat_messages_use_each_services_markup() -> None:
+    tasks = [
+        SyncTask(f"me/r{i}", f"https://github.com/me/r{i}.git", Path(f"r{i}")) for i in range(12)
+    ]
+    outcomes = [SyncOutcome(task, ok=False, error="<refused>") for task in tasks]
+    payload = build_payload(outcomes, started_at=_START, finished_at=_END)
+
+    slack = chat_body("slack", payload)["text"].splitlines()
+    assert slack[0] == "*gitout: 12 of 12 repositories failed*"
+    assert slack[1] == "Succeeded 0, failed 12, skipped 0 in 90.0s"
+    assert slack[2] == "\u2022 `github.com/me/r0`: &lt;refused&gt;"
+    assert slack[-1] == "... and 2 more"
+
+    discord = chat_body("discord", payload)["content"]
+    assert discord.startswith("**gitout: 12 of 12 repositories failed**\n")
+    assert "`github.com/me/r0`: <refused>" in discord
+
+
+async def test_chat_notifier_posts_to_the_webhook_and_honours_only_on_failure() -> None:
+    requests: list[httpx.Request] = []
+
+    def handle(request: httpx.Request) -> httpx.Response:
+        requests.append(request)
+        return httpx.Response(204 if len(requests) == 1 else 500)
+
+    client = httpx.AsyncClient(transport=httpx.MockTransport(handle))
+    config = ChatNotify(webhook_url="https://discord.test/api/webhooks/1/t", only_on_failure=True)
+    notifier = ChatNotifier("discord", config, client=client)
+    assert not await notifier.notify(_payload(failed=False))
+    assert await notifier.notify(_payload(failed=True))
+    assert not await notifier.notify(_payload(failed=True))
+    assert str(requests[0].url) == "https://discord.test/api/webhooks/1/t"
+    assert "content" in json.loads(requests[0].content)
+    assert len(requests) == 2
+
+
+async def test_chat_failures_do_not_log_the_webhook_secret(
+    caplog: pytest.LogCaptureFixture,
+) -> None:
+    client = httpx.AsyncClient(transport=httpx.MockTransport(lambda r: httpx.Response(401)))
+    config = ChatNotify(webhook_url="https://hooks.slack.test/services/T0/B0/s3cret")
+    assert not await ChatNotifier("slack", config, client=client).notify(_payload(failed=True))
+    assert "slack (https://hooks.slack.test) failed: HTTP 401" in caplog.text
+    assert "s3cret" not in caplog.text
+
+
+def test_chat_notifiers_follow_the_configured_services() -> None:
+    config = Notify(discord=ChatNotify(webhook_url="https://discord.test/x"))
+    assert [n.service for n in chat_notifiers(config)] == ["discord"]
+    assert chat_notifiers(Notify()) == []
@@ -0,0 +1,50 @@
+"""Push-mirror URLs, argv and password sources."""
+
+from __future__ import annotations
+
+from pathlib import Path
+
+import pytest
+
+from gitout.config import PushMirror
+from gitout.push_mirror import push_argv, push_password, push_url
+
+
+def test_url_template_fields() -> None:
+    template = "https://gitea.example/{owner}/{repo}.git#{name}@{path}"
+    assert push_url(template, "octocat/hello", "github/clone/octocat/hello") == (
+        "https://gitea.example/octocat/hello.git#octocat/hello@github/clone/octocat/hello"
+    )
+    assert push_url("ssh://git@h/backup/{repo}.git", "dotfiles", "git/dotfiles") == (
+        "ssh://git@h/backup/dotfiles.git"
+    )
+
+
+def test_argv_pushes_branches_and_tags_with_prune() -> None:
+    assert push_argv(["git"], "u", PushMirror()) == [
+        "git",
+        "push",
+        "--force",
+        "--porcelain",
+        "--prune",
+        "u",
+        "+refs/heads/*:refs/heads/*",
+        "+refs/tags/*:refs/tags/*",
+    ]
+    no_prune = PushMirror(prune=False, refspecs=["+refs/heads/main:refs/heads/main"])
+    assert push_argv(["git"], "u", no_prune)[3:] == [
+        "--porcelain",
+        "u",
+        "+refs/heads/main:refs/heads/main",
+    ]
+
+
+def test_password_sources(tmp_path: Path) -> None:
+    secret = tmp_path / "token"
+    secret.write_text("tok\n")
+    assert push_password(PushMirror(password_file=str(secret)), {}) == "tok"
+    assert push_password(PushMirror(password_env="PW"), {"PW": "env"}) == "env"
+    with pytest.raises(ValueError, match=r"\$PW is not set"):
+        push_password(PushMirror(password_env="PW"), {})
+    with pytest.raises(ValueError, match="no password source"):
+        push_password(PushMirror(username="u"), {})
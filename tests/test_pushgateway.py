@@ -0,0 +1,57 @@
+"""Tests for pushing run metrics to a Prometheus Pushgateway."""
+
+from __future__ import annotations
+
+from datetime import UTC, datetime, timedelta
+from pathlib import Path
+
+import httpx
+
+from gitout.engine import SyncOutcome, SyncTask
+from gitout.pushgateway import Pushgateway, render_metrics
+
+_START = datetime(2024, 6, 15, 2, 30, tzinfo=UTC)
+_END = _START + timedelta(seconds=90)
+
+
+def _outcome(name: str, *, ok: bool = True, skipped: bool = False) -> SyncOutcome:
+    task = SyncTask(name, f"https://github.com/{name}.git", Path(name))
+    return SyncOutcome(task, ok=ok, skipped=skipped, duration_seconds=1.25)
+
+
+def test_render_metrics_counts_outcomes_and_labels_repositories() -> None:
+    text = render_metrics(
+        [_outcome("me/a"), _outcome("me/b", ok=False), _outcome('me/"c"', skipped=True)],
+        started_at=_START,
+        finished_at=_END,
+    )
+    assert 'gitout_sync_repositories{status="ok"} 1' in text
+    assert 'gitout_sync_repositories{status="failed"} 1' in text
+    assert 'gitout_sync_repositories{status="skipped"} 1' in text
+    assert "gitout_sync_duration_seconds 90.000" in text
+    assert f"gitout_sync_last_run_timestamp_seconds {_END.timestamp():.0f}" in text
+    assert "gitout_sync_last_success_timestamp_seconds" not in text
+    assert 'gitout_repository_up{repository="github.com/me/b"} 0' in text
+    assert 'gitout_repository_up{repository="github.com/me/\\"c\\""} 1' in text
+    assert 'gitout_repository_sync_duration_seconds{repository="github.com/me/a"} 1.250' in text
+
+
+def test_render_metrics_marks_a_clean_run_as_the_last_success() -> None:
+    text = render_metrics([_outcome("me/a")], started_at=_START, finished_at=_END)
+    assert f"gitout_sync_last_success_timestamp_seconds {_END.timestamp():.0f}" in text
+
+
+async def test_push_replaces_the_job_group_and_swallows_failures() -> None:
+    requests: list[httpx.Request] = []
+
+    def handle(request: httpx.Request) -> httpx.Response:
+        requests.append(request)
+        return httpx.Response(200 if len(requests) == 1 else 500)
+
+    client = httpx.AsyncClient(transport=httpx.MockTransport(handle))
+    gateway = Pushgateway("http://pushgw:9091/", "nightly backup", client=client)
+    assert await gateway.push("gitout_sync_duration_seconds 1\n")
+    assert not await gateway.push("gitout_sync_duration_seconds 1\n")
+    assert requests[0].method == "PUT"
+    assert str(requests[0].url) == "http://pushgw:9091/metrics/job/nightly%20backup"
+    assert requests[0].content == b"gitout_sync_duration_seconds 1\n"
@@ -0,0 +1,43 @@
+"""Recognising and setting aside corrupt mirrors."""
+
+from __future__ import annotations
+
+from datetime import date
+from pathlib import Path
+
+import pytest
+
+from gitout.quarantine import is_corruption, is_quarantined, quarantine
+
+
+@pytest.mark.parametrize(
+    ("message", "corrupt"),
+    [
+        ("fatal: packed object 63a3 (stored in ./objects/pack/pack-1.pack) is corrupt", True),
+        ("error: object file ./objects/12/34 is empty", True),
+        ("fatal: bad object refs/heads/main", True),
+        ("error: HEAD: invalid sha1 pointer 1234", True),
+        ("fatal: not a git repository (or any of the parent directories): .git", True),
+        ("fatal: unable to access 'https://x/': Could not resolve host: x", False),
+        ("fatal: repository 'https://x/' not found", False),
+        ("fatal: 'origin' does not appear to be a git repository", False),
+    ],
+)
+def test_is_corruption(message: str, corrupt: bool) -> None:
+    assert is_corruption(message) is corrupt
+
+
+def test_quarantine_renames_with_the_date_and_never_overwrites(tmp_path: Path) -> None:
+    day = date(2026, 10, 16)
+    for _ in range(3):
+        (tmp_path / "repo").mkdir()
+        (tmp_path / "repo" / "HEAD").write_text("ref: refs/heads/main\n")
+        moved = quarantine(tmp_path / "repo", day)
+        assert is_quarantined(moved)
+    assert not (tmp_path / "repo").exists()
+    assert sorted(p.name for p in tmp_path.iterdir()) == [
+        "repo.corrupt-2026-10-16",
+        "repo.corrupt-2026-10-16-2",
+        "repo.corrupt-2026-10-16-3",
+    ]
+    assert not is_quarantined(tmp_path / "repo.corrupt-notes")
@@ -0,0 +1,36 @@
+"""Which refs a prune would delete, and where the attic keeps them."""
+
+from __future__ import annotations
+
+from gitout.ref_attic import attic_ref, forced_ref, moved_refs, parse_refs, pruned_refs
+
+
+def test_parse_refs_reads_ls_remote_and_for_each_ref_output() -> None:
+    ls_remote = "aaa\tHEAD\nbbb\trefs/heads/main\nccc\trefs/tags/v1\nddd\trefs/tags/v1^{}\n"
+    assert parse_refs(ls_remote) == {"refs/heads/main": "bbb", "refs/tags/v1": "ccc"}
+    assert parse_refs("bbb refs/heads/main\n\n") == {"refs/heads/main": "bbb"}
+
+
+def test_pruned_refs_are_local_refs_missing_upstream_outside_the_attic() -> None:
+    local = {
+        "refs/heads/main": "1",
+        "refs/heads/gone": "2",
+        "refs/tags/old": "3",
+        "refs/attic/2024-01-01/heads/older": "4",
+    }
+    remote = {"refs/heads/main": "1"}
+    assert pruned_refs(local, remote) == {"refs/heads/gone": "2", "refs/tags/old": "3"}
+    assert pruned_refs(local, remote, heads_only=True) == {"refs/heads/gone": "2"}
+
+
+def test_moved_refs_pair_old_and_new_tips() -> None:
+    before = {"refs/heads/main": "1", "refs/heads/same": "2", "refs/heads/gone": "3"}
+    after = {"refs/heads/main": "9", "refs/heads/same": "2", "refs/heads/new": "4"}
+    assert moved_refs(before, after) == {"refs/heads/main": ("1", "9")}
+
+
+def test_attic_refs_keep_the_namespace_under_the_date() -> None:
+    assert attic_ref("refs/heads/feature/x", "2024-05-01") == (
+        "refs/attic/2024-05-01/heads/feature/x"
+    )
+    assert forced_ref("refs/tags/v1", "2024-05-01") == "refs/attic/2024-05-01/forced/tags/v1"
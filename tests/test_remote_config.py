@@ -0,0 +1,141 @@
+"""Tests for loading the configuration from files, HTTPS URLs and git repositories."""
+
+from __future__ import annotations
+
+import hashlib
+import shutil
+import subprocess
+from pathlib import Path
+
+import httpx
+import pytest
+
+from gitout.remote_config import (
+    RemoteConfigError,
+    RemoteConfigLoader,
+    load_config_text,
+    parse_git_source,
+)
+
+_TOML = 'version = 0\n[git.repos]\nx = "https://e.example/x.git"\n'
+_DIGEST = hashlib.sha256(_TOML.encode()).hexdigest()
+
+
+def _loader(tmp_path: Path, handler: object) -> RemoteConfigLoader:
+    client = httpx.Client(transport=httpx.MockTransport(handler))  # type: ignore[arg-type]
+    return RemoteConfigLoader(tmp_path / "cache", client=client)
+
+
+def test_local_file_with_pin(tmp_path: Path) -> None:
+    path = tmp_path / "config.toml"
+    path.write_text(_TOML)
+    assert load_config_text(str(path), environ={}, sha256=_DIGEST.upper()) == _TOML
+    with pytest.raises(RemoteConfigError, match="SHA-256 mismatch"):
+        load_config_text(str(path), environ={}, sha256="00" * 32)
+    with pytest.raises(RemoteConfigError, match="not found"):
+        load_config_text(str(tmp_path / "missing.toml"), environ={})
+
+
+def test_https_fetch_caches_and_revalidates_with_etag(tmp_path: Path) -> None:
+    seen: list[str | None] = []
+
+    def handler(request: httpx.Request) -> httpx.Response:
+        seen.append(request.headers.get("If-None-Match"))
+        if request.headers.get("If-None-Match") == '"v1"':
+            return httpx.Response(304)
+        return httpx.Response(200, text=_TOML, headers={"ETag": '"v1"'})
+
+    loader = _loader(tmp_path, handler)
+    assert loader.load("https://cfg.example/gitout.toml", sha256=_DIGEST) == _TOML
+    assert loader.load("https://cfg.example/gitout.toml", sha256=_DIGEST) == _TOML
+    assert seen == [None, '"v1"']
+
+
+def test_https_falls_back_to_cache_when_unreachable(tmp_path: Path) -> None:
+    online = _loader(tmp_path, lambda request: httpx.Response(200, text=_TOML))
+    online.load("https://cfg.example/gitout.toml")
+
+    def down(request: httpx.Request) -> httpx.Response:
+        raise httpx.ConnectError("unreachable", request=request)
+
+    assert _loader(tmp_path, down).load("https://cfg.example/gitout.toml") == _TOML
+    with pytest.raises(RemoteConfigError, match="Failed to fetch"):
+        _loader(tmp_path, down).load("https://other.example/gitout.toml")
+
+
+def test_https_pin_mismatch_is_not_cached(tmp_path: Path) -> None:
+    loader = _loader(tmp_path, lambda request: httpx.Response(200, text="version = 9\n"))
+    with pytest.raises(RemoteConfigError, match="SHA-256 mismatch"):
+        loader.load("https://cfg.example/gitout.toml", sha256=_DIGEST)
+    assert not any((tmp_path / "cache").glob("*.toml"))
+
+
+def test_https_cannot_verify_signatures(tmp_path: Path) -> None:
+    loader = _loader(tmp_path, lambda request: httpx.Response(200, text=_TOML))
+    with pytest.raises(RemoteConfigError, match="git\\+ source"):
+        loader.load("https://cfg.example/gitout.toml", verify_signature=True)
+
+
+@pytest.mark.parametrize(
+    ("source", "expected"),
+    [
+        (
+            "git+https://g.example/fleet.git#nas.toml",
+            ("https://g.example/fleet.git", "nas.toml", "HEAD"),
+        ),
+        (
+            "git+ssh://git@g.example/fleet.git#/boxes/a.toml@v2",
+            ("ssh://git@g.example/fleet.git", "boxes/a.toml", "v2"),
+        ),
+    ],
+)
+def test_parse_git_source(source: str, expected: tuple[str, str, str]) -> None:
+    assert parse_git_source(source) == expected
+
+
+def test_parse_git_source_requires_path() -> None:
+    with pytest.raises(RemoteConfigError, match="expected git\\+"):
+        parse_git_source("git+https://g.example/fleet.git")
+
+
+def test_git_source_verifies_signature_before_reading(tmp_path: Path) -> None:
+    calls: list[list[str]] = []
+
+    def run_git(argv: list[str]) -> str:
+        calls.append(argv)
+        if "verify-commit" in argv:
+            raise RemoteConfigError("no signature found")
+        return _TOML
+
+    loader = RemoteConfigLoader(tmp_path / "cache", run_git=run_git)
+    with pytest.raises(RemoteConfigError, match="no valid signature"):
+        loader.load("git+https://g.example/fleet.git#nas.toml@main", verify_signature=True)
+    assert not any("show" in argv for argv in calls)
+
+
+@pytest.mark.skipif(shutil.which("git") is None, reason="git not installed")
+def test_git_source_reads_file_and_picks_up_new_commits(tmp_path: Path) -> None:
+    origin = tmp_path / "origin"
+    origin.mkdir()
+
+    def git(*args: str) -> None:
+        subprocess.run(  # noqa: S603
+            ["git", "-c", "user.name=t", "-c", "user.email=t@e", *args],
+            cwd=origin,
+            check=True,
+            capture_output=True,
+        )
+
+    git("init", "-q", "-b", "main")
+    (origin / "boxes").mkdir()
+    (origin / "boxes" / "nas.toml").write_text(_TOML)
+    git("add", ".")
+    git("commit", "-q", "-m", "initial")
+
+    loader = RemoteConfigLoader(tmp_path / "cache")
+    source = f"git+{origin}#boxes/nas.toml@main"
+    assert loader.load(source, sha256=_DIGEST) == _TOML
+
+    (origin / "boxes" / "nas.toml").write_text("version = 0\n")
+    git("commit", "-q", "-am", "update")
+    assert loader.load(source) == "version = 0\n"
@@ -0,0 +1,127 @@
+"""Tests for per-cycle JSON run reports."""
+
+from __future__ import annotations
+
+import json
+from datetime import UTC, datetime, timedelta
+from pathlib import Path
+
+from gitout.engine import SyncOutcome, SyncTask
+from gitout.errors import ErrorCategory
+from gitout.reports import (
+    REPORT_FORMAT,
+    build_report,
+    prune_reports,
+    write_report,
+    write_report_file,
+)
+
+_START = datetime(2024, 6, 15, 2, 30, tzinfo=UTC)
+
+
+def _outcomes(tmp_path: Path) -> list[SyncOutcome]:
+    synced = tmp_path / "git" / "a"
+    synced.mkdir(parents=True)
+    (synced / "HEAD").write_bytes(b"x" * 10)
+    return [
+        SyncOutcome(
+            SyncTask("a", "https://e.example/a.git", synced, groups=("work",)),
+            ok=True,
+            duration_seconds=1.5,
+            rewritten_refs=("refs/heads/main",),
+        ),
+        SyncOutcome(
+            SyncTask("b", "https://e.example/b.git", tmp_path / "git" / "b", groups=("work",)),
+            ok=False,
+            error="boom",
+            category=ErrorCategory.NETWORK_ERROR,
+            attempts=3,
+        ),
+        SyncOutcome(
+            SyncTask("c", "https://e.example/c.git", tmp_path / "c"), ok=True, skipped=True
+        ),
+    ]
+
+
+def test_build_report_summarizes_outcomes(tmp_path: Path) -> None:
+    report = build_report(
+        _outcomes(tmp_path), started_at=_START, finished_at=_START + timedelta(seconds=90)
+    )
+    assert report["format"] == REPORT_FORMAT
+    assert report["durationSeconds"] == 90.0
+    assert report["summary"] == {
+        "attempted": 3,
+        "succeeded": 1,
+        "failed": 1,
+        "skipped": 1,
+        "sizeBytes": 10,
+        "rewrittenRefs": 1,
+    }
+    assert report["groups"] == {"work": {"attempted": 2, "succeeded": 1, "failed": 1}}
+    first, failed, skipped = report["repositories"]
+    assert first["groups"] == ["work"]
+    assert first["outcome"] == "ok"
+    assert first["durationSeconds"] == 1.5
+    assert first["sizeBytes"] == 10
+    assert first["rewrittenRefs"] == ["refs/heads/main"]
+    assert failed["outcome"] == "failed"
+    assert failed["category"] == "NETWORK_ERROR"
+    assert failed["attempts"] == 3
+    assert failed["sizeBytes"] is None
+    assert skipped["outcome"] == "skipped"
+
+
+def test_write_report_names_file_after_start_and_prunes(tmp_path: Path) -> None:
+    reports = tmp_path / "reports"
+    for day in range(1, 4):
+        started = datetime(2024, 6, day, tzinfo=UTC)
+        write_report(
+            build_report([], started_at=started, finished_at=started), reports, retention=2
+        )
+    assert sorted(p.name for p in reports.iterdir()) == [
+        "report-20240602T000000Z.json",
+        "report-20240603T000000Z.json",
+    ]
+    latest = json.loads((reports / "report-20240603T000000Z.json").read_text())
+    assert latest["startedAt"] == "2024-06-03T00:00:00+00:00"
+
+
+def test_prune_ignores_non_report_files(tmp_path: Path) -> None:
+    (tmp_path / "README.txt").write_text("keep me")
+    (tmp_path / "20240101T000000Z.json").write_text("{}")
+    assert prune_reports(tmp_path, 1) == []
+    assert (tmp_path / "README.txt").exists()
+
+
+def test_prune_leaves_state_files_next_to_the_reports(tmp_path: Path) -> None:
+    (tmp_path / ".gitout-sync-state.json").write_text("{}")
+    (tmp_path / "settings.json").write_text("{}")
+    (tmp_path / "report-20240101T000000Z.json").write_text("{}")
+    (tmp_path / "report-20240102T000000Z.json").write_text("{}")
+    assert prune_reports(tmp_path, 1) == [tmp_path / "report-20240101T000000Z.json"]
+    assert (tmp_path / ".gitout-sync-state.json").exists()
+    assert (tmp_path / "settings.json").exists()
+
+
+def test_prune_keeps_the_fixed_report_path(tmp_path: Path) -> None:
+    fixed = tmp_path / "report-20200101T000000Z.json"
+    fixed.write_text("{}")
+    (tmp_path / "report-20240101T000000Z.json").write_text("{}")
+    (tmp_path / "report-20240102T000000Z.json").write_text("{}")
+    assert prune_reports(tmp_path, 1, keep=fixed) == [tmp_path / "report-20240101T000000Z.json"]
+    assert fixed.exists()
+
+
+def test_prune_removes_unprefixed_reports_first(tmp_path: Path) -> None:
+    (tmp_path / "20991231T000000Z.json").write_text("{}")
+    (tmp_path / "report-20240101T000000Z.json").write_text("{}")
+    assert prune_reports(tmp_path, 1) == [tmp_path / "20991231T000000Z.json"]
+
+
+def test_write_report_file_replaces_a_fixed_path(tmp_path: Path) -> None:
+    target = tmp_path / "nested" / "latest.json"
+    for day in (1, 2):
+        started = datetime(2024, 6, day, tzinfo=UTC)
+        write_report_file(build_report([], started_at=started, finished_at=started), target)
+    assert json.loads(target.read_text())["startedAt"] == "2024-06-02T00:00:00+00:00"
+    assert [p.name for p in target.parent.iterdir()] == ["latest.json"]
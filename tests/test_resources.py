@@ -0,0 +1,78 @@
+"""Tests for the per-cycle resource checks used in --cron mode."""
+
+from __future__ import annotations
+
+import logging
+
+import pytest
+
+from gitout.resources import (
+    current_rss_bytes,
+    end_cycle,
+    format_size,
+    fd_limit,
+    open_fd_count,
+    parse_size,
+)
+
+
+@pytest.mark.parametrize(
+    ("size", "expected"),
+    [(512, "512 B"), (1536, "1.5 KiB"), (5 * 1024**3, "5.0 GiB"), (2 * 1024**4, "2.0 TiB")],
+)
+def test_format_size(size: int, expected: str) -> None:
+    assert format_size(size) == expected
+
+
+@pytest.mark.parametrize(
+    ("text", "expected"),
+    [
+        ("1048576", 1048576),
+        ("512M", 512 * 1024**2),
+        ("512mb", 512 * 1024**2),
+        ("800MiB", 800 * 1024**2),
+        ("1.5G", int(1.5 * 1024**3)),
+        ("2k", 2048),
+    ],
+)
+def test_parse_size(text: str, expected: int) -> None:
+    assert parse_size(text) == expected
+
+
+@pytest.mark.parametrize("text", ["", "lots", "-1G", "0", "12X"])
+def test_parse_size_rejects_garbage(text: str) -> None:
+    with pytest.raises(ValueError):
+        parse_size(text)
+
+
+def test_end_cycle_warns_over_budget(caplog: pytest.LogCaptureFixture) -> None:
+    with caplog.at_level(logging.WARNING, logger="gitout.resources"):
+        within = end_cycle(2 * 1024**2, read_rss=lambda: 1024**2, count_fds=lambda: 9)
+        over = end_cycle(1024**2, read_rss=lambda: 3 * 1024**2, count_fds=lambda: 9)
+    assert within is True
+    assert over is False
+    assert "exceeds --max-rss" in caplog.text
+    assert "open fds: 9" in caplog.text
+
+
+def test_end_cycle_warns_near_the_open_file_limit(caplog: pytest.LogCaptureFixture) -> None:
+    def cycle(fds: int) -> bool:
+        return end_cycle(
+            None, read_rss=lambda: None, count_fds=lambda: fds, read_fd_limit=lambda: 100
+        )
+
+    with caplog.at_level(logging.WARNING, logger="gitout.resources"):
+        assert cycle(80) is True
+        assert not caplog.text
+        assert cycle(81) is False
+    assert "81 of 100 allowed file descriptors" in caplog.text
+
+
+def test_end_cycle_without_budget_or_measurement_never_warns() -> None:
+    assert end_cycle(None, read_rss=lambda: 10**12, count_fds=lambda: None) is True
+    assert end_cycle(1, read_rss=lambda: None, count_fds=lambda: None) is True
+
+
+def test_readers_return_positive_values_or_none() -> None:
+    for value in (current_rss_bytes(), open_fd_count(), fd_limit()):
+        assert value is None or value > 0
@@ -0,0 +1,189 @@
+"""Pushing mirrors, bundles, bundle chains and migration archives to a new remote."""
+
+from __future__ import annotations
+
+import shutil
+import subprocess
+import tarfile
+import zipfile
+from datetime import UTC, datetime, timedelta
+from pathlib import Path
+
+import pytest
+
+from gitout.bundle import write_incremental_bundle
+from gitout.restore import RestoreError, Restorer, source_kind
+
+pytestmark = pytest.mark.skipif(shutil.which("git") is None, reason="git not installed")
+
+
+def _git(cwd: Path, *args: str) -> str:
+    return subprocess.run(  # noqa: S603
+        ["git", "-c", "user.name=t", "-c", "user.email=t@e", *args],
+        cwd=cwd,
+        check=True,
+        capture_output=True,
+        text=True,
+    ).stdout.strip()
+
+
+async def _run(argv: list[str], cwd: Path) -> tuple[int, str]:
+    done = subprocess.run(argv, cwd=cwd, capture_output=True, text=True, check=False)  # noqa: S603
+    return done.returncode, done.stdout + done.stderr
+
+
+def _refs(repo: Path) -> dict[str, str]:
+    output = _git(repo, "for-each-ref", "--format=%(refname) %(objectname)")
+    return dict(line.split() for line in output.splitlines())
+
+
+def _mirror(tmp_path: Path, name: str = "mirror.git") -> Path:
+    work = tmp_path / "work"
+    work.mkdir()
+    _git(work, "init", "-q", "-b", "main")
+    _git(work, "commit", "-q", "--allow-empty", "-m", "one")
+    _git(work, "branch", "feature")
+    _git(work, "tag", "v1")
+    _git(tmp_path, "clone", "-q", "--mirror", str(work), name)
+    _git(tmp_path / name, "update-ref", "refs/pull/1/head", "main")
+    return tmp_path / name
+
+
+def _empty_remote(tmp_path: Path) -> Path:
+    _git(tmp_path, "init", "-q", "--bare", "remote.git")
+    return tmp_path / "remote.git"
+
+
+async def test_mirror_is_pushed_with_branches_and_tags_only(tmp_path: Path) -> None:
+    mirror = _mirror(tmp_path)
+    remote = _empty_remote(tmp_path)
+
+    result = await Restorer().restore(mirror, str(remote))
+
+    assert (result.kind, result.branches, result.tags) == ("mirror", 2, 1)
+    assert sorted(_refs(remote)) == ["refs/heads/feature", "refs/heads/main", "refs/tags/v1"]
+
+
+async def test_all_refs_pushes_every_ref(tmp_path: Path) -> None:
+    mirror = _mirror(tmp_path)
+    remote = _empty_remote(tmp_path)
+
+    await Restorer().restore(mirror, str(remote), all_refs=True)
+
+    assert _refs(remote) == _refs(mirror)
+
+
+async def test_bundle_is_fetched_and_pushed(tmp_path: Path) -> None:
+    mirror = _mirror(tmp_path)
+    bundle = tmp_path / "mirror.bundle"
+    _git(mirror, "bundle", "create", "-q", str(bundle), "--all")
+    remote = _empty_remote(tmp_path)
+
+    result = await Restorer().restore(bundle, str(remote))
+
+    assert (result.kind, result.branches, result.tags) == ("bundle", 2, 1)
+    assert _refs(remote)["refs/heads/main"] == _refs(mirror)["refs/heads/main"]
+
+
+async def test_bundle_chain_restores_the_refs_of_its_last_entry(tmp_path: Path) -> None:
+    mirror = _mirror(tmp_path)
+    chain = tmp_path / "mirror.bundles"
+    now = datetime(2026, 1, 1, tzinfo=UTC)
+    assert await write_incremental_bundle(
+        ["git"], mirror, chain, _run, verify=True, full_every=0, now=now
+    )
+    work = tmp_path / "work"
+    _git(work, "commit", "-q", "--allow-empty", "-m", "two")
+    _git(mirror, "fetch", "-q", str(work), "+main:main")
+    _git(mirror, "branch", "-D", "feature")
+    assert await write_incremental_bundle(
+        ["git"], mirror, chain, _run, verify=True, full_every=0, now=now + timedelta(hours=1)
+    )
+    remote = _empty_remote(tmp_path)
+
+    result = await Restorer().restore(chain, str(remote), all_refs=True)
+
+    assert result.kind == "chain"
+    assert _refs(remote) == _refs(mirror)
+
+
+def _archive_tree(tmp_path: Path) -> Path:
+    root = tmp_path / "archive"
+    (root / "repositories" / "octocat").mkdir(parents=True)
+    _mirror(tmp_path, "hello.git").rename(root / "repositories" / "octocat" / "hello.git")
+    _git(root / "repositories" / "octocat", "init", "-q", "--bare", "hello.wiki.git")
+    (root / "repositories.json").write_text("[]")
+    return root
+
+
+async def test_migration_tarball_restores_its_repository_and_skips_the_wiki(
+    tmp_path: Path,
+) -> None:
+    root = _archive_tree(tmp_path)
+    archive = tmp_path / "migration.tar.gz"
+    with tarfile.open(archive, "w:gz") as tarred:
+        for path in sorted(root.iterdir()):
+            tarred.add(path, arcname=path.name)
+    remote = _empty_remote(tmp_path)
+
+    result = await Restorer().restore(archive, str(remote))
+
+    assert (result.kind, result.branches, result.tags) == ("archive", 2, 1)
+    assert "refs/heads/feature" in _refs(remote)
+
+
+async def test_migration_zip_with_several_repositories_needs_a_choice(tmp_path: Path) -> None:
+    root = _archive_tree(tmp_path)
+    _git(root / "repositories" / "octocat", "init", "-q", "--bare", "other.git")
+    archive = tmp_path / "migration.zip"
+    with zipfile.ZipFile(archive, "w") as zipped:
+        for path in sorted(root.rglob("*")):
+            zipped.write(path, path.relative_to(root).as_posix())
+    remote = _empty_remote(tmp_path)
+
+    with pytest.raises(RestoreError, match="octocat/hello, octocat/hello.wiki, octocat/other"):
+        await Restorer().restore(archive, str(remote))
+    result = await Restorer().restore(archive, str(remote), repository="octocat/hello")
+
+    assert result.branches == 2
+    assert "refs/tags/v1" in _refs(remote)
+
+
+@pytest.mark.parametrize("name", ["migration.tar.gz", "migration.zip"])
+async def test_unreadable_migration_archive_is_a_restore_error(tmp_path: Path, name: str) -> None:
+    archive = tmp_path / name
+    archive.write_bytes(b"not an archive")
+
+    with pytest.raises(RestoreError, match=f"Cannot read {name}"):
+        await Restorer().restore(archive, str(tmp_path / "remote.git"))
+
+
+async def test_dry_run_counts_refs_without_pushing(tmp_path: Path) -> None:
+    mirror = _mirror(tmp_path)
+    remote = _empty_remote(tmp_path)
+
+    result = await Restorer().restore(mirror, str(remote), dry_run=True)
+
+    assert (result.branches, result.tags) == (2, 1)
+    assert _refs(remote) == {}
+
+
+async def test_diverged_remote_is_only_overwritten_with_force(tmp_path: Path) -> None:
+    mirror = _mirror(tmp_path)
+    remote = _empty_remote(tmp_path)
+    other = tmp_path / "other"
+    other.mkdir()
+    _git(other, "init", "-q", "-b", "main")
+    _git(other, "commit", "-q", "--allow-empty", "-m", "unrelated")
+    _git(other, "push", "-q", str(remote), "main")
+
+    with pytest.raises(RestoreError, match="git push failed"):
+        await Restorer().restore(mirror, str(remote))
+    await Restorer().restore(mirror, str(remote), force=True)
+
+    assert _refs(remote)["refs/heads/main"] == _refs(mirror)["refs/heads/main"]
+
+
+def test_unrecognised_source_is_rejected(tmp_path: Path) -> None:
+    with pytest.raises(RestoreError, match="is not a mirror"):
+        source_kind(tmp_path)
@@ -0,0 +1,126 @@
+"""Read-only smart-HTTP serving of the destination."""
+
+from __future__ import annotations
+
+import base64
+import os
+import shutil
+import subprocess
+import threading
+from collections.abc import Callable, Iterator
+from pathlib import Path
+
+import httpx
+import pytest
+
+from gitout.serve import GitHttpServer, authorized, normalize_prefix, parse_bind
+
+needs_git = pytest.mark.skipif(shutil.which("git") is None, reason="git not installed")
+
+
+def _git(cwd: Path, *args: str, check: bool = True) -> subprocess.CompletedProcess[str]:
+    return subprocess.run(  # noqa: S603
+        ["git", "-c", "user.name=t", "-c", "user.email=t@e", *args],
+        cwd=cwd,
+        check=check,
+        capture_output=True,
+        text=True,
+        env={**os.environ, "GIT_TERMINAL_PROMPT": "0"},
+    )
+
+
+@pytest.fixture
+def destination(tmp_path: Path) -> Path:
+    work = tmp_path / "work"
+    work.mkdir()
+    _git(work, "init", "-q", "-b", "main")
+    _git(work, "commit", "-q", "--allow-empty", "-m", "one")
+    _git(work, "tag", "v1")
+    dest = tmp_path / "dest"
+    (dest / "github" / "clone" / "octocat").mkdir(parents=True)
+    _git(dest / "github" / "clone" / "octocat", "clone", "-q", "--mirror", str(work), "hello")
+    return dest
+
+
+@pytest.fixture
+def serve(destination: Path) -> Iterator[Callable[..., GitHttpServer]]:
+    servers: list[GitHttpServer] = []
+
+    def start(token: str | None = None, prefix: str = "") -> GitHttpServer:
+        server = GitHttpServer(("127.0.0.1", 0), destination, token=token, prefix=prefix)
+        servers.append(server)
+        threading.Thread(target=server.serve_forever, daemon=True).start()
+        return server
+
+    yield start
+    for server in servers:
+        server.shutdown()
+        server.server_close()
+
+
+def test_parse_bind_accepts_ipv4_ipv6_and_bare_ports() -> None:
+    assert parse_bind("127.0.0.1:8080") == ("127.0.0.1", 8080)
+    assert parse_bind("[::]:9418") == ("::", 9418)
+    assert parse_bind(":80") == ("0.0.0.0", 80)
+    for bad in ("localhost", "host:http", "host:70000"):
+        with pytest.raises(ValueError, match="expected HOST:PORT"):
+            parse_bind(bad)
+
+
+def test_prefix_is_normalized() -> None:
+    assert normalize_prefix("git/") == "/git"
+    assert normalize_prefix("/") == ""
+
+
+def test_token_is_accepted_as_bearer_or_basic_password() -> None:
+    basic = base64.b64encode(b"anyone:s3cret").decode()
+    assert authorized(None, None)
+    assert authorized("Bearer s3cret", "s3cret")
+    assert authorized(f"Basic {basic}", "s3cret")
+    assert not authorized("Bearer wrong", "s3cret")
+    assert not authorized(None, "s3cret")
+    assert not authorized("Basic !!!", "s3cret")
+
+
+@needs_git
+def test_mirror_clones_over_http(tmp_path: Path, serve: Callable[..., GitHttpServer]) -> None:
+    server = serve()
+
+    _git(tmp_path, "clone", "-q", f"{server.url}github/clone/octocat/hello", "copy")
+
+    assert _git(tmp_path / "copy", "tag").stdout.strip() == "v1"
+
+
+@needs_git
+def test_pushes_are_refused(
+    tmp_path: Path, destination: Path, serve: Callable[..., GitHttpServer]
+) -> None:
+    server = serve()
+    mirror = destination / "github" / "clone" / "octocat" / "hello"
+    before = _git(mirror, "rev-parse", "main").stdout
+    _git(tmp_path, "clone", "-q", f"{server.url}github/clone/octocat/hello", "copy")
+    _git(tmp_path / "copy", "commit", "-q", "--allow-empty", "-m", "two")
+
+    pushed = _git(tmp_path / "copy", "push", "origin", "main", check=False)
+
+    assert pushed.returncode != 0
+    assert "403" in pushed.stderr
+    assert _git(mirror, "rev-parse", "main").stdout == before
+
+
+@needs_git
+def test_token_and_prefix_are_required(tmp_path: Path, serve: Callable[..., GitHttpServer]) -> None:
+    server = serve(token="s3cret", prefix="/git")
+    url = server.url.replace("http://", "http://gitout:s3cret@")
+
+    repo = "github/clone/octocat/hello"
+    refused = _git(tmp_path, "clone", "-q", f"{server.url}{repo}", "a", check=False)
+    assert refused.returncode != 0
+    _git(tmp_path, "clone", "-q", f"{url}{repo}", "b")
+    response = httpx.get(
+        server.url.replace("/git/", "/") + f"{repo}/info/refs",
+        headers={"Authorization": "Bearer s3cret"},
+    )
+
+    assert response.status_code == 404
+    assert (tmp_path / "b" / ".git").is_dir()
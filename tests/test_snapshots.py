@@ -0,0 +1,161 @@
+"""Snapshot scheduling, grandfather-father-son retention and snapshot contents."""
+
+from __future__ import annotations
+
+import os
+import shutil
+import subprocess
+from datetime import UTC, datetime, timedelta
+from pathlib import Path
+
+import pytest
+
+from gitout.config import Snapshots
+from gitout.snapshots import (
+    list_snapshots,
+    prune_snapshots,
+    select_kept,
+    snapshot_due,
+    take_snapshot,
+)
+
+
+def _daily(days: int, start: datetime = datetime(2026, 1, 1, 3)) -> list[datetime]:
+    return [start + timedelta(days=i) for i in range(days)]
+
+
+def test_retention_keeps_one_per_day_week_month_and_year() -> None:
+    stamps = _daily(400)  # 2026-01-01 .. 2027-02-04
+    policy = Snapshots(keep_daily=3, keep_weekly=2, keep_monthly=2, keep_yearly=2)
+
+    kept = sorted(s.date().isoformat() for s in select_kept(stamps, policy))
+
+    assert kept == [
+        "2026-12-31",  # newest of 2026
+        "2027-01-31",  # newest of January and of ISO week 2027-W04
+        "2027-02-02",
+        "2027-02-03",
+        "2027-02-04",  # also the newest of February, 2027 and week W05
+    ]
+
+
+def test_only_the_newest_of_a_day_counts_and_the_newest_always_stays() -> None:
+    morning, evening = datetime(2026, 3, 1, 8), datetime(2026, 3, 1, 20)
+    assert select_kept([morning, evening], Snapshots(keep_daily=1)) == {evening}
+    assert select_kept([morning], Snapshots(keep_daily=0, keep_weekly=0, keep_monthly=0)) == {
+        morning
+    }
+
+
+def test_prune_removes_unkept_and_partial_snapshots(tmp_path: Path) -> None:
+    for stamp in ("2026-03-01T080000Z", "2026-03-01T200000Z", "2026-03-02T080000Z"):
+        (tmp_path / stamp).mkdir()
+    (tmp_path / "2026-03-03T080000Z.partial").mkdir()
+    (tmp_path / "notes").mkdir()
+
+    removed = prune_snapshots(tmp_path, Snapshots(keep_daily=7))
+
+    assert sorted(p.name for p in removed) == ["2026-03-01T080000Z", "2026-03-03T080000Z.partial"]
+    assert [p.name for _, p in list_snapshots(tmp_path)] == [
+        "2026-03-01T200000Z",
+        "2026-03-02T080000Z",
+    ]
+    assert (tmp_path / "notes").is_dir()
+
+
+def test_snapshot_is_due_after_the_interval(tmp_path: Path) -> None:
+    now = datetime(2026, 3, 2, 9, tzinfo=UTC)
+    assert snapshot_due(tmp_path / "missing", now, 24)
+    (tmp_path / "2026-03-01T100000Z").mkdir()
+    assert not snapshot_due(tmp_path, now, 24)
+    assert snapshot_due(tmp_path, now, 23)
+    assert snapshot_due(tmp_path, now, 0)
+
+
+def _git(cwd: Path, *args: str) -> str:
+    return subprocess.run(  # noqa: S603
+        ["git", "-c", "user.name=t", "-c", "user.email=t@e", *args],
+        cwd=cwd,
+        check=True,
+        capture_output=True,
+        text=True,
+    ).stdout.strip()
+
+
+async def _run(argv: list[str], cwd: Path) -> tuple[int, str]:
+    done = subprocess.run(argv, cwd=cwd, capture_output=True, text=True, check=False)  # noqa: S603
+    return done.returncode, done.stdout + done.stderr
+
+
+@pytest.mark.skipif(shutil.which("git") is None, reason="git not installed")
+async def test_take_snapshot_bundles_every_mirror(tmp_path: Path) -> None:
+    work = tmp_path / "work"
+    work.mkdir()
+    _git(work, "init", "-q", "-b", "main")
+    _git(work, "commit", "-q", "--allow-empty", "-m", "one")
+    dest, root = tmp_path / "dest", tmp_path / "dest" / "snapshots"
+    _git(tmp_path, "clone", "-q", "--mirror", str(work), str(dest / "git" / "x"))
+    now = datetime(2026, 3, 2, 9, 30, tzinfo=UTC)
+
+    taken = await take_snapshot(["git"], dest, root, _run, now=now, verify=True, workers=2)
+
+    assert taken == root / "2026-03-02T093000Z"
+    assert (taken / "git" / "x.bundle").is_file()
+    assert [p.name for p in root.iterdir()] == ["2026-03-02T093000Z"]
+
+
+@pytest.mark.skipif(shutil.which("git") is None, reason="git not installed")
+async def test_a_timed_out_bundle_discards_the_snapshot(tmp_path: Path) -> None:
+    dest, root = tmp_path / "dest", tmp_path / "dest" / "snapshots"
+    _git(tmp_path, "init", "-q", "--bare", str(dest / "git" / "x"))
+    now = datetime(2026, 3, 2, 9, 30, tzinfo=UTC)
+
+    async def run(argv: list[str], cwd: Path) -> tuple[int, str]:
+        raise RuntimeError("git bundle timed out after 60s")
+
+    assert await take_snapshot(["git"], dest, root, run, now=now, verify=True, workers=1) is None
+    assert list(root.iterdir()) == []
+
+
+def _write(path: Path, text: str = "x") -> None:
+    path.parent.mkdir(parents=True, exist_ok=True)
+    path.write_text(text)
+
+
+async def test_hardlink_snapshots_share_unchanged_files(tmp_path: Path) -> None:
+    dest = tmp_path / "dest"
+    mirror = dest / "git" / "x"
+    pack = mirror / "objects" / "pack" / ("pack-" + "a" * 40 + ".pack")
+    _write(pack, "pack")
+    _write(mirror / "objects" / "ab" / ("c" * 38), "loose")
+    _write(mirror / "packed-refs", "one")
+    _write(mirror / "HEAD", "ref: refs/heads/main\n")
+    (mirror / "refs" / "heads").mkdir(parents=True)
+    _write(dest / "bundles" / "git" / "x.bundle.tmp", "half written")
+    root = dest / "snapshots"
+
+    async def no_git(argv: list[str], cwd: Path) -> tuple[int, str]:
+        raise AssertionError("hardlink snapshots do not run git")
+
+    first = await take_snapshot(
+        [], dest, root, no_git, now=datetime(2026, 3, 1), verify=True, workers=1, mode="hardlink"
+    )
+    assert first is not None
+    _write(mirror / "packed-refs", "two")
+    os.utime(mirror / "packed-refs", ns=(1, 10**18))
+    second = await take_snapshot(
+        [], dest, root, no_git, now=datetime(2026, 3, 2), verify=True, workers=1, mode="hardlink"
+    )
+    assert second is not None
+
+    def inode(path: Path) -> int:
+        return path.stat().st_ino
+
+    snap_pack = Path("git/x/objects/pack") / pack.name
+    assert inode(first / snap_pack) == inode(second / snap_pack) == inode(pack)
+    assert inode(first / "git/x/HEAD") == inode(second / "git/x/HEAD") != inode(mirror / "HEAD")
+    assert (first / "git/x/packed-refs").read_text() == "one"
+    assert (second / "git/x/packed-refs").read_text() == "two"
+    assert (second / "git/x/refs/heads").is_dir()
+    assert not (second / "bundles/git/x.bundle.tmp").exists()
+    assert not (second / "snapshots").exists()
@@ -0,0 +1,109 @@
+"""SSH transport options: URL detection, core.sshCommand and the askpass helper."""
+
+from __future__ import annotations
+
+import os
+import subprocess
+from pathlib import Path
+
+import pytest
+
+from gitout.config import Ssh
+from gitout.ssh import default_login, is_ssh_url, ssh_command, ssh_login, write_askpass
+
+
+@pytest.mark.parametrize(
+    ("url", "expected"),
+    [
+        ("git@github.com:me/repo.git", True),
+        ("backup@nas.lan:repos/x.git", True),
+        ("ssh://git@host:2222/x.git", True),
+        ("git+ssh://host/x.git", True),
+        ("https://github.com/me/repo.git", False),
+        ("https://user@host:8443/x.git", False),
+        ("file:///srv/x.git", False),
+        ("/srv/x.git", False),
+    ],
+)
+def test_is_ssh_url(url: str, expected: bool) -> None:
+    assert is_ssh_url(url) is expected
+
+
+def test_no_ssh_options_leave_ssh_alone() -> None:
+    assert ssh_command(Ssh()) is None
+
+
+def test_key_file_is_quoted_and_batch_mode_forbids_prompts() -> None:
+    # Agent keys are offered first; the key file is the fallback.
+    command = ssh_command(Ssh(key_file="/keys/my key"))
+    assert command == "ssh -i '/keys/my key' -o BatchMode=yes"
+
+
+def test_without_the_agent_only_the_key_file_is_offered() -> None:
+    command = ssh_command(Ssh(key_file="/keys/k", agent=False))
+    assert command == (
+        "ssh -o IdentityAgent=none -i /keys/k -o IdentitiesOnly=yes -o BatchMode=yes"
+    )
+
+
+def test_agent_socket_points_ssh_at_an_agent() -> None:
+    command = ssh_command(Ssh(agent_socket="/run/user/1000/agent.sock"))
+    assert command == "ssh -o IdentityAgent=/run/user/1000/agent.sock -o BatchMode=yes"
+
+
+def test_passphrase_goes_through_askpass() -> None:
+    ssh = Ssh(key_file="/keys/k", passphrase_file="/run/secrets/pass")
+    assert ssh_command(ssh, "/tmp/helper") == (
+        "SSH_ASKPASS=/tmp/helper SSH_ASKPASS_REQUIRE=force ssh -i /keys/k"
+    )
+    assert ssh_command(ssh) == "ssh -i /keys/k"  # dry run
+
+
+@pytest.mark.parametrize(
+    ("url", "login", "forced"),
+    [
+        ("git@github.com:me/repo.git", ("git", "github.com"), None),
+        ("backup@NAS.lan:repos/x.git", ("backup", "nas.lan"), None),
+        ("ssh://GitHub.com/me/repo.git", (None, "github.com"), "git"),
+        ("ssh://nas.lan:2222/x.git", (None, "nas.lan"), None),
+        ("ssh://me@gitlab.com/me/x.git", ("me", "gitlab.com"), None),
+    ],
+)
+def test_login_comes_from_the_url_with_a_forge_default(
+    url: str, login: tuple[str | None, str | None], forced: str | None
+) -> None:
+    assert ssh_login(url) == login
+    assert default_login(url) == forced
+
+
+def test_forced_login_alone_still_needs_a_command() -> None:
+    assert ssh_command(Ssh(), login="git") == "ssh -l git"
+
+
+def test_askpass_helper_prints_the_passphrase_file(tmp_path: Path) -> None:
+    secret = tmp_path / "pass"
+    secret.write_text("hunter2\n")
+    helper = write_askpass(Ssh(passphrase_file=str(secret)))
+    assert helper is not None
+    try:
+        assert os.stat(helper).st_mode & 0o777 == 0o700
+        assert "hunter2" not in helper.read_text()
+        output = subprocess.run([str(helper)], capture_output=True, text=True, check=True)
+        assert output.stdout == "hunter2\n"
+    finally:
+        helper.unlink()
+
+
+def test_askpass_helper_prints_the_passphrase_env(monkeypatch: pytest.MonkeyPatch) -> None:
+    monkeypatch.setenv("GITOUT_SSH_PASSPHRASE", "s3cret")
+    helper = write_askpass(Ssh(passphrase_env="GITOUT_SSH_PASSPHRASE"))
+    assert helper is not None
+    try:
+        output = subprocess.run([str(helper)], capture_output=True, text=True, check=True)
+        assert output.stdout == "s3cret\n"
+    finally:
+        helper.unlink()
+
+
+def test_no_passphrase_means_no_helper() -> None:
+    assert write_askpass(Ssh(key_file="/keys/k")) is None
@@ -0,0 +1,83 @@
+"""Tests for portable state export/import."""
+
+from __future__ import annotations
+
+import json
+from pathlib import Path
+
+import pytest
+
+from gitout.config import Config, FailureTrackingConfig
+from gitout.state_export import EXPORT_FORMAT, export_state, import_state
+
+
+def _seed(destination: Path) -> None:
+    (destination / "github").mkdir(parents=True)
+    (destination / "github" / ".gitout-state.json").write_text(
+        json.dumps({"version": 1, "repositories": {}, "excludedRepos": {"a/b": {"name": "a/b"}}})
+    )
+    (destination / ".gitout-failures.json").write_text(
+        json.dumps({"version": 1, "repositories": {"x/y": {"name": "x/y"}}})
+    )
+
+
+def test_export_collects_existing_state_files(tmp_path: Path) -> None:
+    _seed(tmp_path)
+    payload = export_state(Config(version=0), tmp_path, now_ms=lambda: 42)
+    assert payload["format"] == EXPORT_FORMAT
+    assert payload["exportedAt"] == 42
+    assert set(payload["files"]) == {"github/.gitout-state.json", ".gitout-failures.json"}
+    assert payload["files"]["github/.gitout-state.json"]["excludedRepos"]["a/b"]["name"] == "a/b"
+
+
+def test_export_skips_missing_files_and_honours_custom_failure_file(tmp_path: Path) -> None:
+    (tmp_path / "custom.json").write_text("{}")
+    cfg = Config(version=0, failure_tracking=FailureTrackingConfig(state_file="custom.json"))
+    assert set(export_state(cfg, tmp_path)["files"]) == {"custom.json"}
+
+
+def test_export_includes_the_maintenance_state(tmp_path: Path) -> None:
+    (tmp_path / ".gitout-maintenance.json").write_text('{"me/a": 1700000000}')
+    files = export_state(Config(version=0), tmp_path)["files"]
+    assert files == {".gitout-maintenance.json": {"me/a": 1700000000}}
+
+
+def test_export_rejects_corrupt_state(tmp_path: Path) -> None:
+    (tmp_path / ".gitout-failures.json").write_text("{not json")
+    with pytest.raises(ValueError, match="not valid JSON"):
+        export_state(Config(version=0), tmp_path)
+
+
+def test_round_trip_into_new_destination(tmp_path: Path) -> None:
+    source = tmp_path / "old"
+    _seed(source)
+    target = tmp_path / "new"
+    target.mkdir()
+    written = import_state(export_state(Config(version=0), source), target)
+    assert sorted(written) == [".gitout-failures.json", "github/.gitout-state.json"]
+    for relative in written:
+        assert json.loads((target / relative).read_text()) == json.loads(
+            (source / relative).read_text()
+        )
+
+
+def test_import_refuses_to_overwrite_without_force(tmp_path: Path) -> None:
+    _seed(tmp_path)
+    payload = export_state(Config(version=0), tmp_path)
+    with pytest.raises(ValueError, match="--force"):
+        import_state(payload, tmp_path)
+    assert import_state(payload, tmp_path, force=True)
+
+
+@pytest.mark.parametrize("path", ["../escape.json", "/etc/passwd", ""])
+def test_import_rejects_paths_outside_destination(tmp_path: Path, path: str) -> None:
+    payload = {"format": EXPORT_FORMAT, "version": 1, "files": {path: {}}}
+    with pytest.raises(ValueError, match="outside the destination"):
+        import_state(payload, tmp_path)
+
+
+def test_import_rejects_foreign_documents(tmp_path: Path) -> None:
+    with pytest.raises(ValueError, match="format"):
+        import_state({"files": {}}, tmp_path)
+    with pytest.raises(ValueError, match="version"):
+        import_state({"format": EXPORT_FORMAT, "version": 99, "files": {}}, tmp_path)
@@ -0,0 +1,124 @@
+"""Per-repository sync state persistence and the unchanged check."""
+
+from __future__ import annotations
+
+from pathlib import Path
+
+from gitout.engine import SyncOutcome, SyncTask
+from gitout.sync_state import (
+    SYNC_STATE_FILE,
+    RepoSyncState,
+    RepoVerifyState,
+    SyncStateStore,
+    read_heads,
+)
+from gitout.verify import VerifyResult
+
+
+def _task(tmp_path: Path, pushed_at: str | None = "2024-01-01T00:00:00Z") -> SyncTask:
+    return SyncTask(
+        name="me/repo",
+        url="https://github.com/me/repo.git",
+        destination=tmp_path / "github" / "clone" / "me" / "repo",
+        pushed_at=pushed_at,
+    )
+
+
+def test_records_outcomes_and_round_trips(tmp_path: Path) -> None:
+    path = tmp_path / SYNC_STATE_FILE
+    store = SyncStateStore(path, now_iso=lambda: "2024-03-01T00:00:00+00:00")
+    ok = _task(tmp_path)
+    ok.destination.mkdir(parents=True)
+    (ok.destination / "packed-refs").write_text("aaaa refs/heads/main\n")
+    failed = SyncTask(name="x", url="https://example.com/x.git", destination=tmp_path / "x")
+    skipped = SyncTask(name="y", url="https://example.com/y.git", destination=tmp_path / "y")
+    store.record(
+        [
+            SyncOutcome(task=ok, ok=True),
+            SyncOutcome(task=failed, ok=False, error="boom"),
+            SyncOutcome(task=skipped, ok=True, skipped=True),
+        ]
+    )
+    store.save()
+
+    reloaded = SyncStateStore(path)
+    assert reloaded.repositories == {
+        "github.com/me/repo": RepoSyncState(
+            last_success="2024-03-01T00:00:00+00:00",
+            last_attempt="2024-03-01T00:00:00+00:00",
+            pushed_at="2024-01-01T00:00:00Z",
+            heads={"main": "aaaa"},
+            size_bytes=len("aaaa refs/heads/main\n"),
+        ),
+        "example.com/x": RepoSyncState(
+            last_attempt="2024-03-01T00:00:00+00:00", last_error="boom"
+        ),
+    }
+
+
+def test_verification_results_are_kept_beside_the_sync_state(tmp_path: Path) -> None:
+    path = tmp_path / SYNC_STATE_FILE
+    store = SyncStateStore(path, now_iso=lambda: "2024-03-01T00:00:00+00:00")
+    store.record([SyncOutcome(task=_task(tmp_path), ok=False, error="boom")])
+    store.record_verification(
+        [
+            VerifyResult("git/good", tmp_path / "git" / "good", ok=True, output="ignored"),
+            VerifyResult("git/bad", tmp_path / "git" / "bad", ok=False, output="missing blob 12"),
+        ]
+    )
+    store.save()
+
+    reloaded = SyncStateStore(path)
+    assert reloaded.verification == {
+        "git/bad": RepoVerifyState("2024-03-01T00:00:00+00:00", ok=False, error="missing blob 12"),
+        "git/good": RepoVerifyState("2024-03-01T00:00:00+00:00", ok=True),
+    }
+    assert list(reloaded.repositories) == ["github.com/me/repo"]
+
+
+def test_failure_keeps_last_good_state(tmp_path: Path) -> None:
+    times = iter(["2024-03-01T00:00:00+00:00", "2024-03-02T00:00:00+00:00"])
+    store = SyncStateStore(tmp_path / SYNC_STATE_FILE, now_iso=lambda: next(times))
+    task = _task(tmp_path)
+    store.record([SyncOutcome(task=task, ok=True)])
+    store.record([SyncOutcome(task=task, ok=False, error="timeout")])
+
+    state = store.repositories[task.identity]
+    assert state.last_success == "2024-03-01T00:00:00+00:00"
+    assert state.last_attempt == "2024-03-02T00:00:00+00:00"
+    assert state.last_error == "timeout"
+    assert state.pushed_at == "2024-01-01T00:00:00Z"
+
+
+def test_read_heads_prefers_loose_refs(tmp_path: Path) -> None:
+    (tmp_path / "packed-refs").write_text(
+        "# pack-refs with: peeled fully-peeled sorted\n"
+        "1111 refs/heads/main\n"
+        "2222 refs/heads/feature/x\n"
+        "3333 refs/tags/v1\n"
+        "^4444\n"
+    )
+    (tmp_path / "refs" / "heads").mkdir(parents=True)
+    (tmp_path / "refs" / "heads" / "main").write_text("5555\n")
+    (tmp_path / "refs" / "heads" / "alias").write_text("ref: refs/heads/main\n")
+
+    assert read_heads(tmp_path) == {"feature/x": "2222", "main": "5555"}
+    assert read_heads(tmp_path / "missing") == {}
+
+
+def test_unchanged_requires_matching_push_and_existing_mirror(tmp_path: Path) -> None:
+    store = SyncStateStore(tmp_path / SYNC_STATE_FILE)
+    task = _task(tmp_path)
+    store.record([SyncOutcome(task=task, ok=True)])
+
+    assert store.is_unchanged(task) is False  # mirror directory missing
+    task.destination.mkdir(parents=True)
+    assert store.is_unchanged(task) is True
+    assert store.is_unchanged(_task(tmp_path, "2024-02-01T00:00:00Z")) is False
+    assert store.is_unchanged(_task(tmp_path, None)) is False
+
+
+def test_unreadable_state_is_empty(tmp_path: Path) -> None:
+    path = tmp_path / SYNC_STATE_FILE
+    path.write_text("not json")
+    assert SyncStateStore(path).repositories == {}
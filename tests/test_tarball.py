@@ -0,0 +1,103 @@
+"""Tarball exports of the default branch."""
+
+from __future__ import annotations
+
+import os
+import shutil
+import subprocess
+import tarfile
+from pathlib import Path
+
+import pytest
+
+from gitout.tarball import tarball_dir, write_tarball
+
+
+def _git(cwd: Path, *args: str) -> str:
+    return subprocess.run(  # noqa: S603
+        ["git", "-c", "user.name=t", "-c", "user.email=t@e", *args],
+        cwd=cwd,
+        check=True,
+        capture_output=True,
+        text=True,
+    ).stdout.strip()
+
+
+async def _run(argv: list[str], cwd: Path) -> tuple[int, str]:
+    done = subprocess.run(argv, cwd=cwd, capture_output=True, text=True, check=False)  # noqa: S603
+    return done.returncode, done.stdout + done.stderr
+
+
+def test_tarballs_follow_the_destination_layout(tmp_path: Path) -> None:
+    mirror = tmp_path / "github" / "clone" / "octocat" / "hello"
+    assert tarball_dir(tmp_path / "tarballs", tmp_path, mirror) == (
+        tmp_path / "tarballs" / "github" / "clone" / "octocat"
+    )
+
+
+@pytest.mark.skipif(shutil.which("git") is None, reason="git not installed")
+async def test_head_is_exported_and_older_tarballs_pruned(tmp_path: Path) -> None:
+    repo = tmp_path / "hello"
+    repo.mkdir()
+    _git(repo, "init", "-q", "-b", "main")
+    (repo / "README.md").write_text("one\n")
+    _git(repo, "add", "README.md")
+    _git(repo, "commit", "-q", "-m", "one")
+    out = tmp_path / "tarballs"
+    other = out / f"hello-world-{'a' * 40}.tar.gz"  # another repository's tarball
+    out.mkdir()
+    other.write_text("")
+
+    first = await write_tarball(["git"], repo, out, _run, keep=1, zstd=False)
+    assert first is not None
+    assert first.name == f"hello-{_git(repo, 'rev-parse', 'HEAD')}.tar.gz"
+    with tarfile.open(first) as archive:
+        member = archive.extractfile("README.md")
+        assert member is not None and member.read() == b"one\n"
+    os.utime(first, (1, 1))
+    assert await write_tarball(["git"], repo, out, _run, keep=1, zstd=False) == first
+
+    (repo / "README.md").write_text("two\n")
+    _git(repo, "commit", "-q", "-am", "two")
+    second = await write_tarball(["git"], repo, out, _run, keep=1, zstd=False)
+
+    assert second is not None and second != first
+    assert sorted(p.name for p in out.iterdir()) == sorted([second.name, other.name])
+
+
+@pytest.mark.skipif(shutil.which("git") is None, reason="git not installed")
+async def test_empty_repository_has_nothing_to_export(tmp_path: Path) -> None:
+    _git(tmp_path, "init", "-q", "--bare", "empty")
+    out = tmp_path / "tarballs"
+    assert await write_tarball(["git"], tmp_path / "empty", out, _run, keep=1, zstd=False) is None
+    assert not out.exists()
+
+
+async def test_zstd_compression_goes_through_git_archive(tmp_path: Path) -> None:
+    sha = "b" * 40
+    calls: list[list[str]] = []
+
+    async def run(argv: list[str], cwd: Path) -> tuple[int, str]:
+        calls.append(argv)
+        if "archive" in argv:
+            Path(argv[argv.index("-o") + 1]).write_bytes(b"zst")
+        return 0, sha + "\n"
+
+    target = await write_tarball(["git"], tmp_path / "x", tmp_path, run, keep=0, zstd=True)
+
+    assert target == tmp_path / f"x-{sha}.tar.zst"
+    assert calls[1][:3] == ["git", "-c", "tar.tar.zst.command=zstd -q -c"]
+    assert calls[1][3:6] == ["archive", "--format=tar.zst", "-o"]
+    assert calls[1][-1] == sha
+
+
+async def test_a_timed_out_export_leaves_no_partial_file(tmp_path: Path) -> None:
+    async def run(argv: list[str], cwd: Path) -> tuple[int, str]:
+        if "archive" in argv:
+            Path(argv[argv.index("-o") + 1]).write_bytes(b"half")
+            raise RuntimeError("git archive timed out after 60s")
+        return 0, "c" * 40 + "\n"
+
+    with pytest.raises(RuntimeError, match="timed out"):
+        await write_tarball(["git"], tmp_path / "x", tmp_path / "out", run, keep=0, zstd=False)
+    assert list((tmp_path / "out").iterdir()) == []
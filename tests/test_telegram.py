@@ -98,6 +98,33 @@ def test_enabled_service_sends_start_and_completion() -> None:
     assert any("Sync Completed" in m and "1h 1m 1s" in m for m in sent)
 
 
+def test_completion_lists_failed_repositories() -> None:
+    sent: list[str] = []
+    service = _service(Telegram(chat_id="1", token="t", enabled=True), sent)
+    failures = [f"github.com/me/r{i}: <timeout>" for i in range(12)]
+    service.notify_sync_completion(1, 12, 60, failures)
+    assert "  github.com/me/r0: &lt;timeout&gt;" in sent[-1]
+    assert "github.com/me/r10" not in sent[-1]
+    assert sent[-1].endswith("... and 2 more")
+
+
+def test_verification_lists_failed_mirrors() -> None:
+    sent: list[str] = []
+    service = _service(Telegram(chat_id="1", token="t", enabled=True), sent)
+    service.notify_verification(3, [])
+    service.notify_verification(1, [f"git/r{i}" for i in range(12)])
+    assert "Verification Passed" in sent[0] and "Failed: 0" in sent[0]
+    assert "Verification Failed" in sent[1] and "git/r9" in sent[1]
+    assert "git/r10" not in sent[1] and "... and 2 more" in sent[1]
+
+
+def test_replication_failure_lists_errors() -> None:
+    sent: list[str] = []
+    service = _service(Telegram(chat_id="1", token="t", enabled=True), sent)
+    service.notify_replication_failure("b2:x", 1, [f"git/r{i}: <denied>" for i in range(11)])
+    assert "Replication Failed" in sent[0] and "Remote: b2:x" in sent[0]
+    assert "git/r0: &lt;denied&gt;" in sent[0] and "... and 1 more" in sent[0]
+
 def test_progress_respects_step_threshold() -> None:
     sent: list[str] = []
     config = Telegram(chat_id="1", token="t", enabled=True, notify_progress_step_percent=10)
@@ -0,0 +1,95 @@
+"""Startup token check against an in-memory ``/user`` endpoint (no network)."""
+
+from __future__ import annotations
+
+import httpx
+import pytest
+
+from gitout.config import GitHubArchive, GitHubClone, GitHubConfig, GitHubMetadata
+from gitout.token_check import (
+    TokenCheckError,
+    check_token,
+    missing_scopes,
+    recommended_scopes,
+    required_scopes,
+    tokens_page,
+)
+
+
+def _user_endpoint(status: int, scopes: str | None) -> httpx.MockTransport:
+    def handle(request: httpx.Request) -> httpx.Response:
+        assert request.url.path.endswith("/user")
+        assert request.headers["Authorization"] == "Bearer tok"
+        headers = {} if scopes is None else {"X-OAuth-Scopes": scopes}
+        return httpx.Response(status, json={"login": "me"}, headers=headers)
+
+    return httpx.MockTransport(handle)
+
+
+async def _check(status: int, scopes: str | None, required: dict[str, str]) -> str:
+    async with httpx.AsyncClient(transport=_user_endpoint(status, scopes)) as client:
+        return await check_token("tok", required, client=client)
+
+
+def test_required_scopes_follow_enabled_features() -> None:
+    plain = GitHubConfig(user="me", clone=GitHubClone(gists=False))
+    assert required_scopes(plain) == {}
+    assert list(recommended_scopes(plain)) == ["repo"]
+
+    gists = GitHubConfig(user="me")
+    assert required_scopes(gists) == {}
+    assert list(recommended_scopes(gists)) == ["repo", "gist"]
+
+    others = GitHubConfig(
+        user="Me",
+        clone=GitHubClone(gists=False, repos=["me/own"]),
+        archive=GitHubArchive(repos=["acme/tool"]),
+        metadata=GitHubMetadata(settings=True),
+    )
+    assert required_scopes(others) == {"repo": "migration archives and settings exports"}
+    assert list(recommended_scopes(others)) == ["read:org"]
+
+
+def test_broader_scopes_imply_narrower_ones() -> None:
+    assert missing_scopes(["repo", " admin:org"], ["repo", "read:org"]) == []
+    assert missing_scopes(["public_repo"], ["repo", "gist"]) == ["repo", "gist"]
+    assert missing_scopes([""], ["public_repo"]) == ["public_repo"]
+
+
+def test_tokens_page_points_at_the_web_host() -> None:
+    assert tokens_page("https://api.github.com/") == "https://github.com/settings/tokens"
+    assert tokens_page("https://ghe.example/api/v3") == "https://ghe.example/settings/tokens"
+    assert tokens_page("https://ghe.example/api") == "https://ghe.example/settings/tokens"
+
+
+async def test_valid_token_returns_its_login() -> None:
+    assert await _check(200, "repo, gist", {"repo": "private", "gist": "gists"}) == "me"
+
+
+async def test_rejected_token_raises_an_actionable_error() -> None:
+    with pytest.raises(TokenCheckError, match="401 Bad credentials.*settings/tokens"):
+        await _check(401, None, {"repo": "private"})
+
+
+async def test_missing_scopes_are_named_with_their_feature() -> None:
+    with pytest.raises(TokenCheckError, match=r"lacks scope\(s\): gist \(secret gists\)"):
+        await _check(200, "repo", {"repo": "private", "gist": "secret gists"})
+
+
+async def test_missing_recommended_scopes_are_only_logged(
+    caplog: pytest.LogCaptureFixture,
+) -> None:
+    async with httpx.AsyncClient(transport=_user_endpoint(200, "public_repo")) as client:
+        login = await check_token("tok", {}, recommended={"gist": "secret gists"}, client=client)
+    assert login == "me"
+    assert "lacks scope(s) gist, so the backup leaves out: secret gists" in caplog.text
+
+
+async def test_tokens_without_a_scope_header_are_only_checked_for_validity() -> None:
+    # Fine-grained tokens list no scopes; their permissions cannot be checked up front.
+    assert await _check(200, None, {"repo": "private"}) == "me"
+
+
+async def test_other_errors_propagate() -> None:
+    with pytest.raises(httpx.HTTPStatusError):
+        await _check(502, None, {})
@@ -0,0 +1,82 @@
+"""Tests for OTLP span recording and export."""
+
+from __future__ import annotations
+
+import asyncio
+import json
+
+import httpx
+import pytest
+
+from gitout.tracing import Tracer
+
+
+def test_spans_nest_per_task_and_record_errors() -> None:
+    tracer = Tracer("http://collector:4318")
+
+    async def repository(name: str) -> None:
+        with tracer.span("repository", **{"gitout.repository": name}):
+            await asyncio.sleep(0)
+            with tracer.span("git", **{"gitout.attempt": 1}):
+                await asyncio.sleep(0)
+
+    async def run() -> None:
+        with tracer.span("sync"):
+            await asyncio.gather(repository("a"), repository("b"))
+
+    asyncio.run(run())
+    with pytest.raises(RuntimeError), tracer.span("failing"):
+        raise RuntimeError("boom")
+
+    spans = {(s.name, s.attributes.get("gitout.repository")): s for s in tracer.finished}
+    root = spans[("sync", None)]
+    assert root.parent_span_id is None
+    for name in ("a", "b"):
+        repo = spans[("repository", name)]
+        assert repo.parent_span_id == root.span_id
+        assert repo.trace_id == root.trace_id
+    gits = [s for s in tracer.finished if s.name == "git"]
+    assert {g.parent_span_id for g in gits} == {
+        spans[("repository", "a")].span_id,
+        spans[("repository", "b")].span_id,
+    }
+    failing = spans[("failing", None)]
+    assert failing.error == "boom"
+    assert failing.trace_id != root.trace_id
+
+
+async def test_export_posts_otlp_json_and_clears_the_spans() -> None:
+    requests: list[httpx.Request] = []
+
+    def handle(request: httpx.Request) -> httpx.Response:
+        requests.append(request)
+        return httpx.Response(200 if len(requests) == 1 else 503)
+
+    tracer = Tracer(
+        "http://collector:4318/",
+        service_name="nas-backup",
+        headers={"x-api-key": "k"},
+        client=httpx.AsyncClient(transport=httpx.MockTransport(handle)),
+    )
+    with tracer.span("sync", **{"gitout.repositories": 2, "gitout.dry": False}):
+        pass
+    assert await tracer.export()
+    assert tracer.finished == []
+    assert await tracer.export()  # nothing to send
+    with tracer.span("sync"):
+        pass
+    assert not await tracer.export()
+
+    request = requests[0]
+    assert str(request.url) == "http://collector:4318/v1/traces"
+    assert request.headers["x-api-key"] == "k"
+    (resource_spans,) = json.loads(request.content)["resourceSpans"]
+    assert {"key": "service.name", "value": {"stringValue": "nas-backup"}} in resource_spans[
+        "resource"
+    ]["attributes"]
+    (span,) = resource_spans["scopeSpans"][0]["spans"]
+    assert span["name"] == "sync"
+    assert "parentSpanId" not in span
+    assert span["status"] == {"code": 1}
+    assert {"key": "gitout.repositories", "value": {"intValue": "2"}} in span["attributes"]
+    assert {"key": "gitout.dry", "value": {"boolValue": False}} in span["attributes"]
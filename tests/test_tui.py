@@ -0,0 +1,68 @@
+"""Live dashboard state and rendering, driven by synthetic sync events."""
+
+from __future__ import annotations
+
+import logging
+from io import StringIO
+from pathlib import Path
+
+from rich.console import Console
+
+from gitout.engine import SyncOutcome, SyncTask
+from gitout.events import RepoFinished, RepoStarted, RunFinished, RunStarted
+from gitout.tui import Dashboard, DashboardLogHandler
+
+
+class FakeClock:
+    def __init__(self) -> None:
+        self.now = 100.0
+
+    def __call__(self) -> float:
+        return self.now
+
+
+def _task(name: str) -> SyncTask:
+    return SyncTask(name=name, url=f"https://x/{name}.git", destination=Path("/b") / name)
+
+
+def _text(dashboard: Dashboard) -> str:
+    console = Console(file=StringIO(), width=100, color_system=None)
+    console.print(dashboard)
+    return console.file.getvalue()  # type: ignore[attr-defined]
+
+
+def test_dashboard_tracks_workers_events_and_failures() -> None:
+    clock = FakeClock()
+    dashboard = Dashboard(clock=clock)
+    dashboard.handle(RunStarted(total=3, workers=2))
+    dashboard.handle(RepoStarted(task=_task("me/big"), worker=1))
+    dashboard.handle(RepoStarted(task=_task("me/small"), worker=2))
+    clock.now += 42
+    dashboard.handle(RepoFinished(SyncOutcome(task=_task("me/small"), ok=True), worker=2))
+    dashboard.handle(
+        RepoFinished(SyncOutcome(task=_task("me/gone"), ok=False, error="repository not found"))
+    )
+
+    text = _text(dashboard)
+    assert "2/3 repositories, 1 ok, 1 failed" in text
+    assert "me/big" in text and "42s" in text
+    assert "idle" in text  # worker 2 is free again
+    assert "repository not found" in text
+    assert dashboard.failures[0].task.name == "me/gone"
+
+    dashboard.handle(RunFinished([]))
+    assert "gitout done" in _text(dashboard)
+
+
+def test_event_log_scrolls_and_receives_log_records() -> None:
+    dashboard = Dashboard(log_lines=2)
+    handler = DashboardLogHandler(dashboard)
+    logger = logging.getLogger("gitout.test_tui")
+    logger.addHandler(handler)
+    try:
+        logger.warning("first")
+        logger.warning("second")
+        logger.warning("third")
+    finally:
+        logger.removeHandler(handler)
+    assert list(dashboard.log) == ["WARNING second", "WARNING third"]
@@ -0,0 +1,141 @@
+"""``gitout verify``: fsck over the mirrors of a destination."""
+
+from __future__ import annotations
+
+import asyncio
+import shutil
+import subprocess
+from pathlib import Path
+
+import pytest
+
+from gitout.config import Config, Fetch, Telegram
+from gitout.sync_state import SYNC_STATE_FILE, SyncStateStore
+from gitout.telegram import TelegramNotificationService
+from gitout.verify import fsck_argv, run_verification_pass, verify_destination
+
+
+def _bare(path: Path) -> None:
+    (path / "objects").mkdir(parents=True)
+    (path / "refs").mkdir()
+    (path / "HEAD").write_text("ref: refs/heads/main\n")
+
+
+def _git(cwd: Path, *args: str) -> str:
+    return subprocess.run(
+        ["git", *args], cwd=cwd, check=True, capture_output=True, text=True
+    ).stdout.strip()
+
+
+def test_fsck_argv() -> None:
+    assert fsck_argv() == ["git", "fsck", "--no-progress", "--no-dangling"]
+    assert fsck_argv(connectivity_only=True)[-1] == "--connectivity-only"
+
+
+async def test_every_mirror_is_checked_within_the_worker_limit(tmp_path: Path) -> None:
+    for name in ("a", "b", "c"):
+        _bare(tmp_path / "git" / name)
+    _bare(tmp_path / "github" / "clone" / "me" / "broken")
+    running = 0
+    peak = 0
+    calls: list[tuple[list[str], Path]] = []
+
+    async def fake_git(argv: list[str], cwd: Path, timeout: float) -> tuple[int, str]:
+        nonlocal running, peak
+        calls.append((argv, cwd))
+        running += 1
+        peak = max(peak, running)
+        await asyncio.sleep(0)
+        running -= 1
+        if cwd.name == "broken":
+            return 1, "error: HEAD: invalid sha1 pointer\n"
+        return 0, ""
+
+    results = await verify_destination(tmp_path, workers=2, git_runner=fake_git)
+
+    assert [(r.name, r.ok) for r in results] == [
+        ("git/a", True),
+        ("git/b", True),
+        ("git/c", True),
+        ("github/clone/me/broken", False),
+    ]
+    assert results[-1].output == "error: HEAD: invalid sha1 pointer"
+    assert results[-1].corrupt
+    assert peak <= 2
+    assert {argv[1] for argv, _ in calls} == {"fsck"}
+
+
+async def test_runner_errors_fail_the_repository(tmp_path: Path) -> None:
+    _bare(tmp_path / "git" / "slow")
+
+    async def timing_out(argv: list[str], cwd: Path, timeout: float) -> tuple[int, str]:
+        raise RuntimeError(f"git operation timed out after {timeout}s")
+
+    results = await verify_destination(tmp_path, timeout_seconds=5, git_runner=timing_out)
+    assert not results[0].ok and not results[0].corrupt
+    assert "timed out after 5" in results[0].output
+
+
+async def test_missing_destination_is_rejected(tmp_path: Path) -> None:
+    with pytest.raises(ValueError, match="does not exist"):
+        await verify_destination(tmp_path / "missing")
+
+
+@pytest.mark.parametrize("reclone", [True, False])
+async def test_scheduled_pass_records_quarantines_and_notifies(
+    tmp_path: Path, reclone: bool
+) -> None:
+    _bare(tmp_path / "git" / "good")
+    _bare(tmp_path / "git" / "bad")
+    sent: list[str] = []
+    telegram = TelegramNotificationService(
+        Telegram(chat_id="1", token="t"), environ={}, sender=sent.append
+    )
+
+    async def fake_git(argv: list[str], cwd: Path, timeout: float) -> tuple[int, str]:
+        return (1, "missing tree 12") if cwd.name == "bad" else (0, "")
+
+    results = await run_verification_pass(
+        Config(version=0, fetch=Fetch(reclone_corrupt=reclone)),
+        tmp_path,
+        telegram=telegram,
+        git_runner=fake_git,
+    )
+
+    assert [r.ok for r in results] == [False, True]
+    state = SyncStateStore(tmp_path / SYNC_STATE_FILE).verification
+    assert state["git/bad"].error == "missing tree 12" and state["git/good"].ok
+    assert (tmp_path / "git" / "bad").exists() is not reclone
+    assert "Verification Failed" in sent[0] and "git/bad" in sent[0]
+
+
+async def test_scheduled_pass_keeps_mirrors_whose_check_timed_out(tmp_path: Path) -> None:
+    _bare(tmp_path / "git" / "slow")
+
+    async def timing_out(argv: list[str], cwd: Path, timeout: float) -> tuple[int, str]:
+        raise RuntimeError(f"git operation timed out after {timeout}s")
+
+    config = Config(version=0, fetch=Fetch(reclone_corrupt=True))
+    [result] = await run_verification_pass(config, tmp_path, git_runner=timing_out)
+
+    assert not result.ok
+    assert (tmp_path / "git" / "slow").is_dir()
+
+
+@pytest.mark.skipif(shutil.which("git") is None, reason="git not installed")
+async def test_real_fsck_catches_a_missing_object(tmp_path: Path) -> None:
+    work = tmp_path / "work"
+    work.mkdir()
+    _git(work, "init", "--quiet", "-b", "main")
+    (work / "file.txt").write_text("hello\n")
+    _git(work, "add", "file.txt")
+    _git(work, "-c", "user.name=t", "-c", "user.email=t@t", "commit", "--quiet", "-m", "one")
+    dest = tmp_path / "dest"
+    for name in ("good", "bad"):
+        _git(tmp_path, "clone", "--quiet", "--mirror", str(work), str(dest / "git" / name))
+    blob = _git(work, "rev-parse", "HEAD:file.txt")
+    # A local clone copies (or hardlinks) the loose objects; drop the blob from one mirror.
+    (dest / "git" / "bad" / "objects" / blob[:2] / blob[2:]).unlink()
+    results = await verify_destination(dest)
+    assert [(r.name, r.ok) for r in results] == [("git/bad", False), ("git/good", True)]
+    assert blob in results[0].output
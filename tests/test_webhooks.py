@@ -0,0 +1,95 @@
+"""Tests for the generic webhook notifications."""
+
+from __future__ import annotations
+
+import json
+from datetime import UTC, datetime, timedelta
+from pathlib import Path
+
+import httpx
+import pytest
+
+from gitout.config import Webhook
+from gitout.engine import SyncOutcome, SyncTask
+from gitout.errors import ErrorCategory
+from gitout.webhooks import WebhookNotifier, build_payload, redacted_url, render_body
+
+_START = datetime(2024, 6, 15, 2, 30, tzinfo=UTC)
+_END = _START + timedelta(seconds=90)
+
+
+def _outcome(name: str, *, ok: bool = True, error: str | None = None) -> SyncOutcome:
+    task = SyncTask(name, f"https://github.com/{name}.git", Path(name))
+    category = ErrorCategory.TIMEOUT if error else None
+    return SyncOutcome(task, ok=ok, error=error, category=category)
+
+
+def test_build_payload_summarizes_the_run_and_lists_failures() -> None:
+    payload = build_payload(
+        [_outcome("me/a"), _outcome("me/b", ok=False, error="timed out")],
+        started_at=_START,
+        finished_at=_END,
+    )
+    assert payload["event"] == "failure"
+    assert payload["durationSeconds"] == 90.0
+    assert payload["summary"] == {"attempted": 2, "succeeded": 1, "failed": 1, "skipped": 0}
+    assert payload["error"] is None
+    assert payload["failures"] == [
+        {"identity": "github.com/me/b", "error": "timed out", "category": "TIMEOUT"}
+    ]
+    assert build_payload([_outcome("me/a")], started_at=_START, finished_at=_END)["event"] == (
+        "success"
+    )
+    raised = build_payload([], started_at=_START, finished_at=_END, error="disk full")
+    assert raised["event"] == "failure"
+
+
+def test_render_body_fills_templates_escaping_for_json() -> None:
+    payload = build_payload(
+        [_outcome("me/a"), _outcome("me/b", ok=False, error='refused "x"')],
+        started_at=_START,
+        finished_at=_END,
+    )
+    hook = Webhook(url="https://chat.example", template='{"text": "$summary\\n$failures"}')
+    body = json.loads(render_body(hook, payload))
+    assert body == {"text": '1 of 2 repositories failed\ngithub.com/me/b: refused "x"'}
+
+    plain = Webhook(url="https://x.example", template="$event: $failed", content_type="text/plain")
+    assert render_body(plain, payload) == b"failure: 1"
+    assert json.loads(render_body(Webhook(url="https://x.example"), payload)) == payload
+
+
+async def test_notify_posts_to_subscribed_hooks_and_swallows_failures() -> None:
+    requests: list[httpx.Request] = []
+
+    def handle(request: httpx.Request) -> httpx.Response:
+        requests.append(request)
+        return httpx.Response(500 if request.url.host == "down.example" else 204)
+
+    client = httpx.AsyncClient(transport=httpx.MockTransport(handle))
+    notifier = WebhookNotifier(
+        [
+            Webhook(url="https://all.example/hook", headers={"Authorization": "Bearer t"}),
+            Webhook(url="https://failures.example/hook", events=["failure"]),
+            Webhook(url="https://down.example/hook"),
+        ],
+        client=client,
+    )
+    payload = build_payload([_outcome("me/a")], started_at=_START, finished_at=_END)
+    assert await notifier.notify(payload) == 1
+    assert [r.url.host for r in requests] == ["all.example", "down.example"]
+    assert requests[0].headers["Authorization"] == "Bearer t"
+    assert requests[0].headers["Content-Type"] == "application/json"
+    assert json.loads(requests[0].content)["event"] == "success"
+
+
+async def test_failed_deliveries_log_the_host_not_the_secret_url(
+    caplog: pytest.LogCaptureFixture,
+) -> None:
+    client = httpx.AsyncClient(transport=httpx.MockTransport(lambda r: httpx.Response(403)))
+    notifier = WebhookNotifier([Webhook(url="https://hooks.example/T0/s3cret")], client=client)
+    payload = build_payload([_outcome("me/a")], started_at=_START, finished_at=_END)
+    assert await notifier.notify(payload) == 0
+    assert "Webhook https://hooks.example failed: HTTP 403" in caplog.text
+    assert "s3cret" not in caplog.text
+    assert redacted_url("https://discord.test/api/webhooks/1/t?wait=1") == "https://discord.test"